@@ -0,0 +1,699 @@
+//! Session agent that keeps unlocked safe passwords in memory.
+//!
+//! `skit agent` runs a small daemon listening on a Unix domain socket, or a named pipe on
+//! Windows. Commands ask it for a safe's password by UUID before falling back to saved keys
+//! or a prompt, and hand it passwords they had to prompt for. Entries expire after a fixed
+//! TTL or when unused for the idle timeout, and are never written to disk.
+
+use crate::error::SkitError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+/// Environment variable overriding the agent socket location
+pub const SOCKET_ENV: &str = "SKIT_AGENT_SOCK";
+
+/// One request per connection, sent as a single JSON line
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    Get {
+        uuid: String,
+    },
+    Add {
+        uuid: String,
+        safe_path: String,
+        password: String,
+    },
+    Lock,
+    Status,
+    Stop,
+}
+
+/// An unlocked safe as reported by `status`, without its password
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentEntry {
+    pub uuid: String,
+    pub safe_path: String,
+    /// Seconds until the entry expires regardless of use
+    pub expires_in: u64,
+    /// Seconds until the entry expires if it isn't used
+    pub idle_expires_in: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Response {
+    Ok,
+    Password { password: String },
+    NotFound,
+    Entries { entries: Vec<AgentEntry> },
+    Error { message: String },
+}
+
+/// `$SKIT_AGENT_SOCK`, else `$XDG_RUNTIME_DIR/skit/agent.sock`, else
+/// `~/.config/skit/agent.sock`. On Windows the default is the pipe
+/// `\\.\pipe\skit-agent-<USERNAME>`.
+pub fn socket_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(SOCKET_ENV).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    #[cfg(windows)]
+    {
+        // Pipe names share one machine-wide namespace, so keep users apart by name
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        Some(PathBuf::from(format!(r"\\.\pipe\skit-agent-{}", user)))
+    }
+
+    #[cfg(not(windows))]
+    {
+        if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR").filter(|d| !d.is_empty()) {
+            return Some(PathBuf::from(dir).join("skit").join("agent.sock"));
+        }
+        dirs::home_dir().map(|home| home.join(".config").join("skit").join("agent.sock"))
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn unsupported() -> SkitError {
+    SkitError::Io(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "skit agent is only supported on Unix and Windows",
+    ))
+}
+
+/// Write one request line and read the one-line reply
+fn round_trip(
+    mut stream: impl std::io::Read + std::io::Write,
+    request: &Request,
+) -> Result<Response, SkitError> {
+    use std::io::{BufRead, BufReader};
+
+    let mut line = Zeroizing::new(serde_json::to_string(request)?);
+    line.push('\n');
+    stream.write_all(line.as_bytes()).map_err(SkitError::Io)?;
+
+    let mut reply = Zeroizing::new(String::new());
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .map_err(SkitError::Io)?;
+    Ok(serde_json::from_str(&reply)?)
+}
+
+/// Send a request to the running agent. Fails with `NotFound` when no agent is listening.
+pub fn send(request: &Request) -> Result<Response, SkitError> {
+    #[cfg(any(unix, windows))]
+    {
+        let path = socket_path().ok_or_else(|| {
+            SkitError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not determine the agent socket path",
+            ))
+        })?;
+        let no_agent = |e: std::io::Error| {
+            SkitError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No agent listening on {}: {}", path.display(), e),
+            ))
+        };
+
+        #[cfg(unix)]
+        {
+            let stream = std::os::unix::net::UnixStream::connect(&path).map_err(no_agent)?;
+            stream
+                .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+                .map_err(SkitError::Io)?;
+            round_trip(stream, request)
+        }
+
+        #[cfg(windows)]
+        {
+            let pipe = pipe::connect(&path).map_err(no_agent)?;
+            round_trip(&pipe, request)
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = request;
+        Err(unsupported())
+    }
+}
+
+/// Whether an agent is answering on the socket
+pub fn is_running() -> bool {
+    matches!(send(&Request::Status), Ok(Response::Entries { .. }))
+}
+
+/// Ask a running agent for a safe's password. Any failure to reach it counts as a miss.
+pub fn get_password(uuid: &str) -> Option<Zeroizing<String>> {
+    match send(&Request::Get {
+        uuid: uuid.to_string(),
+    }) {
+        Ok(Response::Password { password }) => Some(Zeroizing::new(password)),
+        _ => None,
+    }
+}
+
+/// Hand a verified password to the running agent
+pub fn add_password(uuid: &str, safe_path: &str, password: &str) -> Result<(), SkitError> {
+    let mut request = Request::Add {
+        uuid: uuid.to_string(),
+        safe_path: crate::fs_utils::display_path(std::path::Path::new(safe_path)),
+        password: password.to_string(),
+    };
+    let response = send(&request);
+    if let Request::Add { password, .. } = &mut request {
+        zeroize::Zeroize::zeroize(password);
+    }
+    match response? {
+        Response::Ok => Ok(()),
+        Response::Error { message } => Err(SkitError::Io(std::io::Error::other(message))),
+        _ => Err(SkitError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Unexpected response from agent",
+        ))),
+    }
+}
+
+#[cfg(any(unix, windows))]
+mod server {
+    use super::{AgentEntry, Request, Response};
+    use crate::error::SkitError;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    #[cfg(unix)]
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use zeroize::{Zeroize, Zeroizing};
+
+    struct Entry {
+        safe_path: String,
+        password: Zeroizing<String>,
+        added: Instant,
+        last_used: Instant,
+    }
+
+    /// Unlocked safes by UUID; dropping an entry zeroes its password
+    pub(super) struct Store {
+        entries: HashMap<String, Entry>,
+        ttl: Duration,
+        idle: Duration,
+    }
+
+    impl Store {
+        pub(super) fn new(ttl: Duration, idle: Duration) -> Self {
+            Store {
+                entries: HashMap::new(),
+                ttl,
+                idle,
+            }
+        }
+
+        fn expires_at(&self, entry: &Entry) -> (Instant, Instant) {
+            (entry.added + self.ttl, entry.last_used + self.idle)
+        }
+
+        pub(super) fn purge(&mut self, now: Instant) {
+            let (ttl, idle) = (self.ttl, self.idle);
+            self.entries
+                .retain(|_, entry| now < entry.added + ttl && now < entry.last_used + idle);
+        }
+
+        pub(super) fn handle(&mut self, request: Request, now: Instant) -> Response {
+            self.purge(now);
+            match request {
+                Request::Get { uuid } => match self.entries.get_mut(&uuid) {
+                    Some(entry) => {
+                        entry.last_used = now;
+                        Response::Password {
+                            password: entry.password.to_string(),
+                        }
+                    }
+                    None => Response::NotFound,
+                },
+                Request::Add {
+                    uuid,
+                    safe_path,
+                    password,
+                } => {
+                    let password = Zeroizing::new(password);
+                    self.entries.insert(
+                        uuid,
+                        Entry {
+                            safe_path,
+                            password,
+                            added: now,
+                            last_used: now,
+                        },
+                    );
+                    Response::Ok
+                }
+                Request::Lock => {
+                    self.entries.clear();
+                    Response::Ok
+                }
+                Request::Status => {
+                    let mut entries: Vec<AgentEntry> = self
+                        .entries
+                        .iter()
+                        .map(|(uuid, entry)| {
+                            let (expires, idle_expires) = self.expires_at(entry);
+                            AgentEntry {
+                                uuid: uuid.clone(),
+                                safe_path: entry.safe_path.clone(),
+                                expires_in: expires.saturating_duration_since(now).as_secs(),
+                                idle_expires_in: idle_expires
+                                    .saturating_duration_since(now)
+                                    .as_secs(),
+                            }
+                        })
+                        .collect();
+                    entries.sort_by(|a, b| a.safe_path.cmp(&b.safe_path));
+                    Response::Entries { entries }
+                }
+                Request::Stop => Response::Ok,
+            }
+        }
+    }
+
+    /// Answer one connection. Returns true when the agent was asked to stop.
+    fn serve_connection<S>(stream: &S, store: &Mutex<Store>) -> bool
+    where
+        for<'a> &'a S: Read + Write,
+    {
+        let mut line = Zeroizing::new(String::new());
+        if BufReader::new(stream).read_line(&mut line).is_err() {
+            return false;
+        }
+
+        let (response, stop) = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                let stop = matches!(request, Request::Stop);
+                let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+                (store.handle(request, Instant::now()), stop)
+            }
+            Err(e) => (
+                Response::Error {
+                    message: format!("Invalid request: {}", e),
+                },
+                false,
+            ),
+        };
+
+        if let Ok(reply) = serde_json::to_string(&response) {
+            let mut reply = Zeroizing::new(reply);
+            reply.push('\n');
+            let mut writer = stream;
+            let _ = writer.write_all(reply.as_bytes());
+        }
+        if let Response::Password { mut password } = response {
+            password.zeroize();
+        }
+        stop
+    }
+
+    /// A fresh store, with a thread expiring its entries once a second
+    fn start_store(ttl: Duration, idle: Duration) -> Arc<Mutex<Store>> {
+        let store = Arc::new(Mutex::new(Store::new(ttl, idle)));
+        let purger = Arc::clone(&store);
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(Duration::from_secs(1));
+                purger
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .purge(Instant::now());
+            }
+        });
+        store
+    }
+
+    fn forget_all(store: &Mutex<Store>) {
+        store
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .clear();
+    }
+
+    /// Bind the socket owner-only, refusing to replace an agent that is still answering
+    #[cfg(unix)]
+    fn bind(path: &Path) -> Result<UnixListener, SkitError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        if let Some(parent) = path.parent() {
+            crate::fs_utils::create_private_dir(parent)?;
+        }
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(SkitError::Io(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    format!("An agent is already running on {}", path.display()),
+                )));
+            }
+            // Left behind by an agent that didn't shut down cleanly
+            std::fs::remove_file(path).map_err(SkitError::Io)?;
+        }
+
+        // Create the socket as 0600 rather than chmod it after binding, which would leave a
+        // window where other users could connect. The umask is process-wide, so restore it.
+        let previous = unsafe { libc::umask(0o177) };
+        let bound = UnixListener::bind(path);
+        unsafe { libc::umask(previous) };
+        let listener = bound.map_err(SkitError::Io)?;
+        let mode = std::fs::metadata(path)
+            .map_err(SkitError::Io)?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(SkitError::Io(std::io::Error::other(format!(
+                "Agent socket {} is accessible to other users",
+                path.display()
+            ))));
+        }
+        Ok(listener)
+    }
+
+    /// Run the agent until it is stopped. Each connection is answered on its own thread, so a
+    /// client that connects and stalls only holds up itself.
+    #[cfg(unix)]
+    pub fn serve(path: &Path, ttl: Duration, idle: Duration) -> Result<(), SkitError> {
+        let listener = bind(path)?;
+        let store = start_store(ttl, idle);
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        for stream in listener.incoming() {
+            if stopping.load(Ordering::SeqCst) {
+                break;
+            }
+            let Ok(stream) = stream else { continue };
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+            let store = Arc::clone(&store);
+            let stopping = Arc::clone(&stopping);
+            let path = path.to_path_buf();
+            std::thread::spawn(move || {
+                if serve_connection(&stream, &store) {
+                    stopping.store(true, Ordering::SeqCst);
+                    // Wake the accept loop so it sees the flag
+                    let _ = UnixStream::connect(&path);
+                }
+            });
+        }
+
+        forget_all(&store);
+        let _ = std::fs::remove_file(path);
+        Ok(())
+    }
+
+    /// Run the agent on a named pipe until it is stopped, one thread per connection as on Unix
+    #[cfg(windows)]
+    pub fn serve(path: &Path, ttl: Duration, idle: Duration) -> Result<(), SkitError> {
+        use super::pipe;
+
+        let mut listening = pipe::create(path, true).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => SkitError::Io(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("An agent is already running on {}", path.display()),
+            )),
+            _ => SkitError::Io(e),
+        })?;
+        let store = start_store(ttl, idle);
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        loop {
+            let connected = pipe::accept(&listening);
+            // Keep an instance listening at all times, so the name can't be claimed by another
+            // process while this one is busy
+            let next = pipe::create(path, false).map_err(SkitError::Io)?;
+            let connection = std::mem::replace(&mut listening, next);
+            if stopping.load(Ordering::SeqCst) {
+                break;
+            }
+            if connected.is_err() {
+                continue;
+            }
+
+            let store = Arc::clone(&store);
+            let stopping = Arc::clone(&stopping);
+            let path = path.to_path_buf();
+            std::thread::spawn(move || {
+                let stop = serve_connection(&connection, &store);
+                // Wait for the client to read the reply before the handle closes
+                let _ = connection.sync_all();
+                if stop {
+                    stopping.store(true, Ordering::SeqCst);
+                    // Wake the accept loop so it sees the flag
+                    let _ = pipe::connect(&path);
+                }
+            });
+        }
+
+        forget_all(&store);
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod pipe {
+    use crate::fs_utils::windows_acl::{OwnerOnlyAcl, is_current_user_process};
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::{AsRawHandle, FromRawHandle};
+    use std::path::Path;
+    use std::ptr;
+    use std::time::Duration;
+    use windows_sys::Win32::Foundation::{
+        ERROR_PIPE_BUSY, ERROR_PIPE_CONNECTED, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Security::{
+        InitializeSecurityDescriptor, SECURITY_ATTRIBUTES, SECURITY_DESCRIPTOR,
+        SetSecurityDescriptorDacl,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX, SECURITY_IDENTIFICATION,
+    };
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, GetNamedPipeServerProcessId, PIPE_READMODE_BYTE,
+        PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+    use windows_sys::Win32::System::SystemServices::SECURITY_DESCRIPTOR_REVISION;
+
+    const BUFFER_SIZE: u32 = 4096;
+
+    /// Create one instance of the pipe that only the current user can open. With `first`, fail
+    /// with `PermissionDenied` if the pipe already exists, whoever created it.
+    pub(super) fn create(path: &Path, first: bool) -> io::Result<File> {
+        let name: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let acl = OwnerOnlyAcl::new()?;
+
+        unsafe {
+            let mut descriptor: SECURITY_DESCRIPTOR = std::mem::zeroed();
+            let descriptor_ptr = (&mut descriptor as *mut SECURITY_DESCRIPTOR).cast();
+            if InitializeSecurityDescriptor(descriptor_ptr, SECURITY_DESCRIPTOR_REVISION) == 0
+                || SetSecurityDescriptorDacl(descriptor_ptr, 1, acl.as_ptr(), 0) == 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+            let attributes = SECURITY_ATTRIBUTES {
+                nLength: size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: descriptor_ptr,
+                bInheritHandle: 0,
+            };
+
+            let mut open_mode = PIPE_ACCESS_DUPLEX;
+            if first {
+                open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+            }
+            let handle = CreateNamedPipeW(
+                name.as_ptr(),
+                open_mode,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                &attributes,
+            );
+            if handle == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(File::from_raw_handle(handle))
+        }
+    }
+
+    /// Wait for a client to connect to this instance
+    pub(super) fn accept(pipe: &File) -> io::Result<()> {
+        if unsafe { ConnectNamedPipe(pipe.as_raw_handle(), ptr::null_mut()) } == 0 {
+            let err = io::Error::last_os_error();
+            // A client that connected before this call is still a connection
+            if err.raw_os_error() != Some(ERROR_PIPE_CONNECTED as i32) {
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Open the agent's pipe, refusing one served by another user's process
+    pub(super) fn connect(path: &Path) -> io::Result<File> {
+        let mut attempts = 0;
+        let pipe = loop {
+            let opened = OpenOptions::new()
+                .read(true)
+                .write(true)
+                // The server may learn who we are, but can't act as us
+                .security_qos_flags(SECURITY_IDENTIFICATION)
+                .open(path);
+            match opened {
+                // Briefly true while the agent creates its next instance
+                Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32) && attempts < 20 => {
+                    attempts += 1;
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                opened => break opened?,
+            }
+        };
+
+        let mut server_pid = 0;
+        if unsafe { GetNamedPipeServerProcessId(pipe.as_raw_handle(), &mut server_pid) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if !is_current_user_process(server_pid)? {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is served by another user's process", path.display()),
+            ));
+        }
+        Ok(pipe)
+    }
+}
+
+#[cfg(any(unix, windows))]
+pub use server::serve;
+
+#[cfg(not(any(unix, windows)))]
+pub fn serve(
+    _path: &std::path::Path,
+    _ttl: std::time::Duration,
+    _idle: std::time::Duration,
+) -> Result<(), SkitError> {
+    Err(unsupported())
+}
+
+#[cfg(all(test, any(unix, windows)))]
+mod tests {
+    use super::server::Store;
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn add(store: &mut Store, uuid: &str, now: Instant) {
+        store.handle(
+            Request::Add {
+                uuid: uuid.to_string(),
+                safe_path: format!("{}.safe", uuid),
+                password: format!("pw-{}", uuid),
+            },
+            now,
+        );
+    }
+
+    fn get(store: &mut Store, uuid: &str, now: Instant) -> Option<String> {
+        match store.handle(
+            Request::Get {
+                uuid: uuid.to_string(),
+            },
+            now,
+        ) {
+            Response::Password { password } => Some(password),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_store_expires_by_ttl_and_idle() {
+        let start = Instant::now();
+        let mut store = Store::new(Duration::from_secs(100), Duration::from_secs(30));
+        add(&mut store, "a", start);
+
+        // Each use pushes back the idle timeout, but not the TTL
+        assert_eq!(
+            get(&mut store, "a", start + Duration::from_secs(20)).as_deref(),
+            Some("pw-a")
+        );
+        assert!(get(&mut store, "a", start + Duration::from_secs(45)).is_some());
+        assert!(get(&mut store, "a", start + Duration::from_secs(70)).is_some());
+        assert!(get(&mut store, "a", start + Duration::from_secs(100)).is_none());
+
+        add(&mut store, "b", start);
+        assert!(get(&mut store, "b", start + Duration::from_secs(31)).is_none());
+    }
+
+    #[test]
+    fn test_store_lock_and_status_hide_passwords() {
+        let now = Instant::now();
+        let mut store = Store::new(Duration::from_secs(100), Duration::from_secs(30));
+        add(&mut store, "a", now);
+
+        let status = serde_json::to_string(&store.handle(Request::Status, now)).unwrap();
+        assert!(status.contains("a.safe"));
+        assert!(!status.contains("pw-a"));
+
+        store.handle(Request::Lock, now);
+        assert!(get(&mut store, "a", now).is_none());
+    }
+
+    #[test]
+    fn test_stalled_client_does_not_block_others() {
+        #[cfg(unix)]
+        let dir = tempfile::TempDir::new().unwrap();
+        #[cfg(unix)]
+        let path = dir.path().join("agent.sock");
+        #[cfg(windows)]
+        let path = PathBuf::from(format!(
+            r"\\.\pipe\skit-agent-test-{}",
+            std::process::id()
+        ));
+        let server_path = path.clone();
+        let server = std::thread::spawn(move || {
+            server::serve(
+                &server_path,
+                Duration::from_secs(60),
+                Duration::from_secs(60),
+            )
+        });
+
+        let connect = || {
+            for _ in 0..100 {
+                #[cfg(unix)]
+                let stream = std::os::unix::net::UnixStream::connect(&path);
+                #[cfg(windows)]
+                let stream = pipe::connect(&path);
+                if let Ok(stream) = stream {
+                    return stream;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            panic!("agent did not start listening");
+        };
+
+        // Connects but never sends a request
+        let _stalled = connect();
+
+        let started = Instant::now();
+        let response = round_trip(connect(), &Request::Status).unwrap();
+        assert!(matches!(response, Response::Entries { .. }));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let response = round_trip(connect(), &Request::Stop).unwrap();
+        assert!(matches!(response, Response::Ok));
+        server.join().unwrap().unwrap();
+        #[cfg(unix)]
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::display::print_warning;
+use crate::error::SkitError;
+use crate::types::{Safe, SafeItem};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// One line of a safe's audit log. Secret values are never recorded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+/// Path of the append-only audit log for a safe: `~/.config/skit/audit/<uuid>.log`
+pub fn log_path(uuid: &str) -> Option<PathBuf> {
+    dirs::home_dir().map(|home| {
+        home.join(".config")
+            .join("skit")
+            .join("audit")
+            .join(format!("{}.log", uuid))
+    })
+}
+
+/// Log every key added, changed or removed since `before`
+pub fn record_changes(safe: &Safe, before: &IndexMap<String, SafeItem>, action: &str) {
+    let entries = changed_entries(&safe.items, before, action, Utc::now());
+    append_or_warn(&safe.uuid, &entries);
+}
+
+/// Added and changed keys are logged under `action` and removed keys as `rm`. If no key
+/// changed, `action` is logged once without a key.
+fn changed_entries(
+    items: &IndexMap<String, SafeItem>,
+    before: &IndexMap<String, SafeItem>,
+    action: &str,
+    timestamp: DateTime<Utc>,
+) -> Vec<AuditEntry> {
+    let entry = |action: &str, key: Option<&str>| AuditEntry {
+        timestamp,
+        action: action.to_string(),
+        key: key.map(|k| k.to_string()),
+    };
+
+    let mut keys: Vec<&String> = items.keys().chain(before.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut entries = Vec::new();
+    for key in keys {
+        match (before.get(key), items.get(key)) {
+            (Some(_), None) => entries.push(entry("rm", Some(key))),
+            (None, Some(_)) => entries.push(entry(action, Some(key))),
+            (Some(old), Some(new))
+                if old.value != new.value || old.is_encrypted != new.is_encrypted =>
+            {
+                entries.push(entry(action, Some(key)))
+            }
+            _ => {}
+        }
+    }
+    if entries.is_empty() {
+        entries.push(entry(action, None));
+    }
+    entries
+}
+
+/// Log a safe-wide action that is not tied to a single key, such as a password rotation
+pub fn record(safe: &Safe, action: &str) {
+    append_or_warn(
+        &safe.uuid,
+        &[AuditEntry {
+            timestamp: Utc::now(),
+            action: action.to_string(),
+            key: None,
+        }],
+    );
+}
+
+/// The safe has already been saved by the time the log is written, so a log failure
+/// is reported rather than failing the command
+fn append_or_warn(uuid: &str, entries: &[AuditEntry]) {
+    if let Err(e) = append(uuid, entries) {
+        print_warning(&format!("Failed to write audit log: {}", e));
+    }
+}
+
+fn append(uuid: &str, entries: &[AuditEntry]) -> Result<(), SkitError> {
+    let Some(path) = log_path(uuid) else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(SkitError::Io)?;
+    }
+
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+    }
+
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path).map_err(SkitError::Io)?;
+    file.write_all(lines.as_bytes()).map_err(SkitError::Io)
+}
+
+/// Read all entries of a safe's audit log, oldest first. A missing log has no entries.
+pub fn read_log(uuid: &str) -> Result<Vec<AuditEntry>, SkitError> {
+    let Some(path) = log_path(uuid) else {
+        return Ok(Vec::new());
+    };
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(SkitError::Io(e)),
+    };
+    parse_log(&content)
+}
+
+fn parse_log(content: &str) -> Result<Vec<AuditEntry>, SkitError> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                SkitError::ParseError(format!("Invalid audit log line {}: {}", i + 1, e))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(key: &str, value: &str) -> SafeItem {
+        SafeItem {
+            key: key.to_string(),
+            value: value.to_string(),
+            is_encrypted: false,
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_log_round_trip() {
+        let entries = vec![
+            AuditEntry {
+                timestamp: "2024-05-01T10:00:00Z".parse().unwrap(),
+                action: "set".to_string(),
+                key: Some("API_KEY".to_string()),
+            },
+            AuditEntry {
+                timestamp: "2024-05-02T10:00:00Z".parse().unwrap(),
+                action: "rotate".to_string(),
+                key: None,
+            },
+        ];
+        let content: String = entries
+            .iter()
+            .map(|e| format!("{}\n", serde_json::to_string(e).unwrap()))
+            .collect();
+
+        assert!(!content.contains("\"key\":null"));
+        assert_eq!(parse_log(&content).unwrap(), entries);
+        assert!(parse_log("not json\n").is_err());
+    }
+
+    #[test]
+    fn test_changed_entries() {
+        let items = |pairs: &[(&str, &str)]| -> IndexMap<String, SafeItem> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), item(k, v)))
+                .collect()
+        };
+        let before = items(&[("KEEP", "1"), ("CHANGE", "old"), ("DROP", "x")]);
+        let after = items(&[("KEEP", "1"), ("CHANGE", "new"), ("ADD", "y")]);
+
+        let logged: Vec<(String, Option<String>)> =
+            changed_entries(&after, &before, "set", Utc::now())
+                .into_iter()
+                .map(|e| (e.action, e.key))
+                .collect();
+        assert_eq!(
+            logged,
+            vec![
+                ("set".to_string(), Some("ADD".to_string())),
+                ("set".to_string(), Some("CHANGE".to_string())),
+                ("rm".to_string(), Some("DROP".to_string())),
+            ]
+        );
+
+        let unchanged = changed_entries(&after, &after, "migrate", Utc::now());
+        assert_eq!(unchanged.len(), 1);
+        assert_eq!(unchanged[0].action, "migrate");
+        assert_eq!(unchanged[0].key, None);
+    }
+}
@@ -1,24 +1,105 @@
 use crate::error::SkitError;
+use aws_config::SdkConfig;
 use aws_sdk_ssm::Client;
+use aws_sdk_ssm::config::ProvideCredentials;
+use aws_sdk_ssm::error::DisplayErrorContext;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, mpsc};
 
-/// Initialize AWS SSM client with the default credential provider chain
+type ConfigKey = (Option<String>, Option<String>);
+
+/// Loaded configuration per (region, profile), so clients created later in the same run
+/// don't resolve credentials again
+static CONFIG_CACHE: OnceLock<Mutex<HashMap<ConfigKey, SdkConfig>>> = OnceLock::new();
+
+/// Load shared AWS configuration, using `region` and `profile` when given
 ///
-/// This will try to load credentials from:
+/// Credentials come from the default provider chain:
 /// 1. Environment variables (AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY)
-/// 2. ~/.aws/credentials file
+/// 2. ~/.aws/credentials file (the `profile` section, or AWS_PROFILE / default)
 /// 3. IAM role (when running on EC2, ECS, Lambda, etc.)
-pub async fn create_ssm_client(region: Option<String>) -> Result<Client, SkitError> {
-    let config = if let Some(region) = region {
-        // Use explicit region if provided
-        let region_provider = aws_sdk_ssm::config::Region::new(region);
-        aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(region_provider)
-            .load()
-            .await
-    } else {
-        // Use default region from AWS config or environment
-        aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await
-    };
+async fn load_config(
+    region: Option<String>,
+    profile: Option<String>,
+) -> Result<SdkConfig, SkitError> {
+    let cache = CONFIG_CACHE.get_or_init(Default::default);
+    let key = (region.clone(), profile.clone());
+    if let Some(config) = cache.lock().ok().and_then(|c| c.get(&key).cloned()) {
+        return Ok(config);
+    }
+
+    let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+    if let Some(region) = region {
+        // Use explicit region if provided, otherwise the AWS config or environment
+        loader = loader.region(aws_config::Region::new(region));
+    }
+    if let Some(profile) = &profile {
+        loader = loader.profile_name(profile);
+    }
+    let config = loader.load().await;
+
+    // A missing or broken profile only surfaces on the first request; check it up front
+    if let Some(profile) = &profile {
+        let provider = config.credentials_provider().ok_or_else(|| {
+            SkitError::AwsError(format!(
+                "No credentials available for AWS profile '{}'",
+                profile
+            ))
+        })?;
+        provider.provide_credentials().await.map_err(|e| {
+            SkitError::AwsError(format!(
+                "Failed to load AWS profile '{}': {}",
+                profile,
+                DisplayErrorContext(&e)
+            ))
+        })?;
+    }
+
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(key, config.clone());
+    }
+    Ok(config)
+}
 
-    Ok(Client::new(&config))
+/// Initialize AWS SSM client with the default credential provider chain
+pub async fn create_ssm_client(
+    region: Option<String>,
+    profile: Option<String>,
+) -> Result<Client, SkitError> {
+    Ok(Client::new(&load_config(region, profile).await?))
+}
+
+/// Initialize AWS Secrets Manager client with the default credential provider chain
+pub async fn create_secrets_manager_client(
+    region: Option<String>,
+) -> Result<aws_sdk_secretsmanager::Client, SkitError> {
+    Ok(aws_sdk_secretsmanager::Client::new(
+        &load_config(region, None).await?,
+    ))
+}
+
+/// Run an AWS future to completion from synchronous command code
+pub fn run_async_blocking<T, F>(future: F) -> Result<T, SkitError>
+where
+    T: Send + 'static,
+    F: std::future::Future<Output = Result<T, SkitError>> + Send + 'static,
+{
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        let (tx, rx) = mpsc::channel();
+        handle.spawn(async move {
+            let _ = tx.send(future.await);
+        });
+
+        match rx.recv() {
+            Ok(result) => result,
+            Err(e) => Err(SkitError::AwsError(format!(
+                "Failed to receive result from async task: {}",
+                e
+            ))),
+        }
+    } else {
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| SkitError::AwsError(format!("Failed to create async runtime: {}", e)))?;
+        runtime.block_on(future)
+    }
 }
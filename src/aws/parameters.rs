@@ -1,5 +1,8 @@
 use crate::error::SkitError;
+use crate::validation::{check_key_collisions, is_valid_env_key, sanitize_env_key};
 use aws_sdk_ssm::{Client, types::ParameterType};
+use serde::Serialize;
+use std::collections::HashSet;
 
 /// Represents a pulled SSM parameter with its key, value, and encryption status
 #[derive(Debug, Clone)]
@@ -7,6 +10,8 @@ pub struct SsmParameter {
     pub key: String,
     pub value: String,
     pub is_encrypted: bool,
+    /// `String`, `SecureString` or `StringList`
+    pub parameter_type: String,
 }
 
 /// Fetch all parameters under a given prefix from AWS SSM Parameter Store
@@ -15,9 +20,12 @@ pub struct SsmParameter {
 /// * `client` - AWS SSM client
 /// * `prefix` - Parameter path prefix (e.g., "/myapp/dev/")
 /// * `strip_prefix` - Whether to strip the prefix from parameter names
+/// * `decrypt` - Whether to request SecureString plaintext; without it values are dropped, so
+///   listing names and types never brings a secret into this process
 ///
 /// # Returns
-/// Vector of SsmParameter structs with key, value, and encryption status
+/// Vector of SsmParameter structs with key, value, and encryption status; empty if nothing
+/// exists under the prefix
 ///
 /// # SSM Type Mapping
 /// - `String` → plain text (is_encrypted = false)
@@ -27,6 +35,7 @@ pub async fn fetch_parameters(
     client: &Client,
     prefix: &str,
     strip_prefix: bool,
+    decrypt: bool,
 ) -> Result<Vec<SsmParameter>, SkitError> {
     let mut parameters = Vec::new();
     let mut next_token: Option<String> = None;
@@ -41,7 +50,8 @@ pub async fn fetch_parameters(
         let mut request = client
             .get_parameters_by_path()
             .path(&normalized_prefix)
-            .with_decryption(true);
+            .recursive(true)
+            .with_decryption(decrypt);
 
         if let Some(token) = next_token {
             request = request.next_token(token);
@@ -55,18 +65,16 @@ pub async fn fetch_parameters(
         if let Some(params) = response.parameters {
             for param in params {
                 let param_name = param.name().unwrap_or("");
-                let param_value = param.value().unwrap_or("");
+                let param_value = if decrypt {
+                    param.value().unwrap_or("")
+                } else {
+                    ""
+                };
                 let param_type = param.r#type();
 
                 let is_encrypted = matches!(param_type, Some(ParameterType::SecureString));
 
-                let key = if strip_prefix && param_name.starts_with(&normalized_prefix) {
-                    param_name[normalized_prefix.len()..]
-                        .trim_start_matches('/')
-                        .to_string()
-                } else {
-                    param_name.to_string()
-                };
+                let key = relative_name(param_name, &normalized_prefix, strip_prefix);
 
                 if key.is_empty() {
                     continue;
@@ -76,6 +84,9 @@ pub async fn fetch_parameters(
                     key,
                     value: param_value.to_string(),
                     is_encrypted,
+                    parameter_type: param_type
+                        .map(|t| t.as_str().to_string())
+                        .unwrap_or_default(),
                 });
             }
         }
@@ -87,31 +98,367 @@ pub async fn fetch_parameters(
         }
     }
 
-    if parameters.is_empty() {
-        return Err(SkitError::AwsError(format!(
-            "No parameters found under prefix: {}",
-            normalized_prefix
-        )));
+    Ok(parameters)
+}
+
+/// Name of a parameter relative to the (normalized) prefix, so the nested
+/// `/myapp/dev/database/host` under `/myapp/dev/` becomes `database/host`
+pub fn relative_name(name: &str, normalized_prefix: &str, strip_prefix: bool) -> String {
+    if strip_prefix && name.starts_with(normalized_prefix) {
+        name[normalized_prefix.len()..]
+            .trim_start_matches('/')
+            .to_string()
+    } else {
+        name.to_string()
     }
+}
 
+/// Name and type of an SSM parameter, listed without its value
+#[derive(Debug, Clone, Serialize)]
+pub struct ParameterInfo {
+    pub name: String,
+    /// `String`, `SecureString` or `StringList`
+    #[serde(rename = "type")]
+    pub parameter_type: String,
+}
+
+/// List the parameters `fetch_parameters` would pull from a prefix, without decrypting anything
+///
+/// # Arguments
+/// * `client` - AWS SSM client
+/// * `prefix` - Parameter path prefix (e.g., "/myapp/dev/")
+///
+/// # Returns
+/// Name and type of every parameter, sorted by name
+pub async fn list_parameters(
+    client: &Client,
+    prefix: &str,
+) -> Result<Vec<ParameterInfo>, SkitError> {
+    let mut parameters: Vec<ParameterInfo> = fetch_parameters(client, prefix, false, false)
+        .await?
+        .into_iter()
+        .map(|param| ParameterInfo {
+            name: param.key,
+            parameter_type: param.parameter_type,
+        })
+        .collect();
+    parameters.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(parameters)
 }
 
+/// How pulled parameter names (relative to the prefix) become safe keys
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyTransform {
+    /// Replace `/`, `-` and `.` with `_`, so `database/host` becomes `database_host`
+    #[default]
+    Sanitize,
+    /// Keep names as they are, e.g. `database/host`
+    None,
+}
+
+/// Map a parameter name relative to the prefix to a safe key
+pub fn transform_key(name: &str, transform: KeyTransform, upper: bool) -> String {
+    let key = match transform {
+        KeyTransform::Sanitize => sanitize_env_key(name),
+        KeyTransform::None => name.to_string(),
+    };
+    if upper { key.to_uppercase() } else { key }
+}
+
+/// Rename pulled parameters to safe keys before anything is written.
+///
+/// With `Sanitize`, parameters whose key is still not a valid env key are skipped and returned
+/// by name, or fail the pull with `fail_on_invalid`. Two parameters mapping to the same key
+/// always fail, listing every collision.
+pub fn apply_key_transform(
+    parameters: Vec<SsmParameter>,
+    transform: KeyTransform,
+    upper: bool,
+    fail_on_invalid: bool,
+) -> Result<(Vec<SsmParameter>, Vec<String>), SkitError> {
+    let mut kept = Vec::new();
+    let mut renames = Vec::new();
+    let mut invalid = Vec::new();
+
+    for param in parameters {
+        let key = transform_key(&param.key, transform, upper);
+        if transform == KeyTransform::Sanitize && !is_valid_env_key(&key) {
+            invalid.push(param.key);
+            continue;
+        }
+        renames.push((param.key.clone(), key.clone()));
+        kept.push(SsmParameter { key, ..param });
+    }
+
+    if fail_on_invalid && !invalid.is_empty() {
+        return Err(SkitError::ParseError(format!(
+            "Parameters that don't map to valid keys: {}",
+            invalid.join(", ")
+        )));
+    }
+
+    check_key_collisions(
+        "Parameters",
+        renames
+            .iter()
+            .map(|(name, key)| (name.as_str(), key.as_str())),
+    )?;
+
+    Ok((kept, invalid))
+}
+
+/// Outcome of pushing a batch of parameters to SSM
+#[derive(Debug, Default)]
+pub struct PutSummary {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Build the full SSM parameter name for a safe key under the given prefix
+///
+/// Keys containing `/` (e.g. `database/host` from a nested pull) map back to nested paths.
+pub fn parameter_name(prefix: &str, key: &str) -> String {
+    let trimmed = prefix.trim().trim_end_matches('/');
+    let key = key.trim_start_matches('/');
+    if trimmed.starts_with('/') || trimmed.is_empty() {
+        format!("{}/{}", trimmed, key)
+    } else {
+        format!("/{}/{}", trimmed, key)
+    }
+}
+
+/// List the names of all parameters that currently exist under a prefix
+///
+/// Unlike a pull, values are not decrypted and an empty prefix is not an error.
+pub async fn fetch_parameter_names(
+    client: &Client,
+    prefix: &str,
+) -> Result<HashSet<String>, SkitError> {
+    Ok(fetch_parameters(client, prefix, false, false)
+        .await?
+        .into_iter()
+        .map(|param| param.key)
+        .collect())
+}
+
+/// Write a single parameter to SSM
+///
+/// Encrypted values are stored as `SecureString`, plain values as `String`.
+pub async fn put_parameter(
+    client: &Client,
+    name: &str,
+    value: &str,
+    is_encrypted: bool,
+    overwrite: bool,
+) -> Result<(), SkitError> {
+    let param_type = if is_encrypted {
+        ParameterType::SecureString
+    } else {
+        ParameterType::String
+    };
+
+    client
+        .put_parameter()
+        .name(name)
+        .value(value)
+        .r#type(param_type)
+        .overwrite(overwrite)
+        .send()
+        .await
+        .map_err(|e| SkitError::AwsError(format!("Failed to put parameter {}: {}", name, e)))?;
+
+    Ok(())
+}
+
+/// Push safe entries to SSM Parameter Store under a prefix
+///
+/// # Arguments
+/// * `client` - AWS SSM client
+/// * `prefix` - Parameter path prefix the safe keys are appended to
+/// * `parameters` - Decrypted safe entries keyed by their safe key name
+/// * `existing` - Full names of parameters already present under the prefix
+/// * `overwrite` - Whether existing parameters may be replaced (otherwise they are skipped)
+/// * `dry_run` - Classify parameters without writing anything
+pub async fn put_parameters(
+    client: &Client,
+    prefix: &str,
+    parameters: Vec<SsmParameter>,
+    existing: &HashSet<String>,
+    overwrite: bool,
+    dry_run: bool,
+) -> Result<PutSummary, SkitError> {
+    let mut summary = PutSummary::default();
+
+    for param in parameters {
+        let name = parameter_name(prefix, &param.key);
+        let exists = existing.contains(&name);
+
+        if exists && !overwrite {
+            summary.skipped.push(name);
+            continue;
+        }
+
+        if !dry_run {
+            put_parameter(client, &name, &param.value, param.is_encrypted, exists).await?;
+        }
+
+        if exists {
+            summary.updated.push(name);
+        } else {
+            summary.created.push(name);
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Delete parameters from SSM (batched to respect the 10-name API limit)
+pub async fn delete_parameters(client: &Client, names: &[String]) -> Result<(), SkitError> {
+    for chunk in names.chunks(10) {
+        client
+            .delete_parameters()
+            .set_names(Some(chunk.to_vec()))
+            .send()
+            .await
+            .map_err(|e| SkitError::AwsError(format!("Failed to delete parameters: {}", e)))?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parameter_name() {
+        assert_eq!(
+            parameter_name("/myapp/dev/", "database/host"),
+            "/myapp/dev/database/host"
+        );
+        assert_eq!(
+            parameter_name("/myapp/dev/", "API_KEY"),
+            "/myapp/dev/API_KEY"
+        );
+        assert_eq!(
+            parameter_name("/myapp/dev", "API_KEY"),
+            "/myapp/dev/API_KEY"
+        );
+        assert_eq!(
+            parameter_name("myapp/dev/", "API_KEY"),
+            "/myapp/dev/API_KEY"
+        );
+    }
+
+    #[test]
+    fn test_nested_parameter_names_become_keys() {
+        let cases = [
+            ("/myapp/dev/API_KEY", "/myapp/dev/", "API_KEY"),
+            ("/myapp/dev/database/host", "/myapp/dev/", "database_host"),
+            (
+                "/myapp/dev/database/tls/mode",
+                "/myapp/dev",
+                "database_tls_mode",
+            ),
+        ];
+        for (name, prefix, expected) in cases {
+            let relative = relative_name(name, prefix, true);
+            assert_eq!(
+                transform_key(&relative, KeyTransform::Sanitize, false),
+                expected
+            );
+        }
+        assert_eq!(
+            relative_name("/myapp/dev/database/host", "/myapp/dev/", false),
+            "/myapp/dev/database/host"
+        );
+    }
+
+    #[test]
+    fn test_transform_key_mapping() {
+        let cases = [
+            ("API_KEY", KeyTransform::Sanitize, false, "API_KEY"),
+            (
+                "database/host",
+                KeyTransform::Sanitize,
+                false,
+                "database_host",
+            ),
+            (
+                "database/host",
+                KeyTransform::Sanitize,
+                true,
+                "DATABASE_HOST",
+            ),
+            (
+                "feature-flags/new-ui",
+                KeyTransform::Sanitize,
+                true,
+                "FEATURE_FLAGS_NEW_UI",
+            ),
+            (
+                "app.v2/token",
+                KeyTransform::Sanitize,
+                false,
+                "app_v2_token",
+            ),
+            ("database/host", KeyTransform::None, false, "database/host"),
+            ("database/host", KeyTransform::None, true, "DATABASE/HOST"),
+        ];
+        for (name, transform, upper, expected) in cases {
+            assert_eq!(transform_key(name, transform, upper), expected, "{}", name);
+        }
+    }
+
+    fn param(key: &str) -> SsmParameter {
+        SsmParameter {
+            key: key.to_string(),
+            value: "v".to_string(),
+            is_encrypted: false,
+            parameter_type: "String".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_key_transform_skips_or_fails_on_invalid() {
+        let params = || vec![param("db/host"), param("1st/key")];
+
+        let (kept, invalid) =
+            apply_key_transform(params(), KeyTransform::Sanitize, false, false).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].key, "db_host");
+        assert_eq!(invalid, ["1st/key"]);
+
+        assert!(apply_key_transform(params(), KeyTransform::Sanitize, false, true).is_err());
+
+        // `none` keeps the old behavior: names are not checked
+        let (kept, invalid) =
+            apply_key_transform(params(), KeyTransform::None, false, true).unwrap();
+        assert_eq!(kept.len(), 2);
+        assert!(invalid.is_empty());
+    }
+
+    #[test]
+    fn test_apply_key_transform_reports_collisions() {
+        let params = vec![param("db/host"), param("db-host"), param("other")];
+        let err = apply_key_transform(params, KeyTransform::Sanitize, false, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("db/host, db-host -> db_host"), "{}", err);
+
+        let params = vec![param("Key"), param("KEY")];
+        assert!(apply_key_transform(params, KeyTransform::None, true, false).is_err());
+    }
+
     #[test]
     fn test_key_stripping() {
         let prefix = "/myapp/dev/";
         let param_name = "/myapp/dev/API_KEY";
 
-        let stripped = if param_name.starts_with(prefix) {
-            param_name[prefix.len()..]
-                .trim_start_matches('/')
-                .to_string()
-        } else {
-            param_name.to_string()
+        let stripped = match param_name.strip_prefix(prefix) {
+            Some(rest) => rest.trim_start_matches('/').to_string(),
+            None => param_name.to_string(),
         };
 
         assert_eq!(stripped, "API_KEY");
@@ -122,12 +469,9 @@ mod tests {
         let prefix = "/myapp/dev/";
         let param_name = "/myapp/dev/database/host";
 
-        let stripped = if param_name.starts_with(prefix) {
-            param_name[prefix.len()..]
-                .trim_start_matches('/')
-                .to_string()
-        } else {
-            param_name.to_string()
+        let stripped = match param_name.strip_prefix(prefix) {
+            Some(rest) => rest.trim_start_matches('/').to_string(),
+            None => param_name.to_string(),
         };
 
         assert_eq!(stripped, "database/host");
@@ -0,0 +1,234 @@
+use crate::error::SkitError;
+use crate::validation::{check_key_collisions, is_valid_env_key, sanitize_env_key};
+use aws_sdk_secretsmanager::{
+    Client,
+    types::{Filter, FilterNameStringType},
+};
+
+/// A value pulled from AWS Secrets Manager, keyed by its safe key name
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManagedSecret {
+    pub key: String,
+    pub value: String,
+}
+
+/// Fetch all secrets whose name starts with `prefix` from AWS Secrets Manager
+///
+/// # Arguments
+/// * `client` - AWS Secrets Manager client
+/// * `prefix` - Secret name prefix (e.g., "myapp/")
+/// * `flatten` - Whether JSON object payloads become one key per field
+///
+/// # Returns
+/// Vector of ManagedSecret structs, keyed by the secret name without the prefix
+///
+/// Only `SecretString` values are pulled; binary secrets are skipped with a warning.
+pub async fn fetch_secrets(
+    client: &Client,
+    prefix: &str,
+    flatten: bool,
+) -> Result<Vec<ManagedSecret>, SkitError> {
+    let mut names = Vec::new();
+    let mut next_token: Option<String> = None;
+
+    // The name filter matches by prefix
+    let filter = Filter::builder()
+        .key(FilterNameStringType::Name)
+        .values(prefix)
+        .build();
+
+    loop {
+        let mut request = client.list_secrets().filters(filter.clone());
+
+        if let Some(token) = next_token {
+            request = request.next_token(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SkitError::AwsError(format!("Failed to list secrets: {}", e)))?;
+
+        names.extend(
+            response
+                .secret_list()
+                .iter()
+                .filter_map(|secret| secret.name())
+                .filter(|name| name.starts_with(prefix))
+                .map(|name| name.to_string()),
+        );
+
+        if response.next_token.is_some() {
+            next_token = response.next_token;
+        } else {
+            break;
+        }
+    }
+
+    if names.is_empty() {
+        return Err(SkitError::AwsError(format!(
+            "No secrets found under prefix: {}",
+            prefix
+        )));
+    }
+    names.sort();
+
+    let mut secrets = Vec::new();
+    for name in names {
+        let response = client
+            .get_secret_value()
+            .secret_id(&name)
+            .send()
+            .await
+            .map_err(|e| SkitError::AwsError(format!("Failed to fetch secret {}: {}", name, e)))?;
+
+        match response.secret_string() {
+            Some(value) => {
+                secrets.extend(secret_entries(&secret_key(prefix, &name), value, flatten))
+            }
+            None => tracing::warn!("Skipping binary secret {}", name),
+        }
+    }
+
+    Ok(secrets)
+}
+
+/// Safe key for a secret: its name without the prefix, or the full name if nothing is left
+pub fn secret_key(prefix: &str, name: &str) -> String {
+    let key = name
+        .strip_prefix(prefix)
+        .unwrap_or(name)
+        .trim_start_matches('/');
+    if key.is_empty() {
+        name.to_string()
+    } else {
+        key.to_string()
+    }
+}
+
+/// Split a secret into safe entries
+///
+/// With `flatten`, a JSON object payload becomes one `key/field` entry per field
+/// (nested values are kept as JSON text). Anything else is stored as a single entry.
+pub fn secret_entries(key: &str, value: &str, flatten: bool) -> Vec<ManagedSecret> {
+    let fields = match serde_json::from_str::<serde_json::Value>(value) {
+        Ok(serde_json::Value::Object(fields)) if flatten && !fields.is_empty() => fields,
+        _ => {
+            return vec![ManagedSecret {
+                key: key.to_string(),
+                value: value.to_string(),
+            }];
+        }
+    };
+
+    fields
+        .into_iter()
+        .map(|(field, field_value)| ManagedSecret {
+            key: format!("{}/{}", key, field),
+            value: match field_value {
+                serde_json::Value::String(s) => s,
+                serde_json::Value::Null => String::new(),
+                other => other.to_string(),
+            },
+        })
+        .collect()
+}
+
+/// Turn secret names and flattened fields into env keys, so `database/host` becomes
+/// `database_host`. Returns the secrets with a valid key and the names of those without one.
+/// Two secrets mapping to the same key fail the pull before anything is written.
+pub fn sanitize_secret_keys(
+    secrets: Vec<ManagedSecret>,
+) -> Result<(Vec<ManagedSecret>, Vec<String>), SkitError> {
+    let mut kept = Vec::new();
+    let mut renames = Vec::new();
+    let mut invalid = Vec::new();
+
+    for secret in secrets {
+        let key = sanitize_env_key(&secret.key);
+        if !is_valid_env_key(&key) {
+            invalid.push(secret.key);
+            continue;
+        }
+        renames.push((secret.key, key.clone()));
+        kept.push(ManagedSecret { key, ..secret });
+    }
+
+    check_key_collisions(
+        "Secrets",
+        renames
+            .iter()
+            .map(|(name, key)| (name.as_str(), key.as_str())),
+    )?;
+    Ok((kept, invalid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, value: &str) -> ManagedSecret {
+        ManagedSecret {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_secret_key() {
+        assert_eq!(secret_key("myapp/", "myapp/database"), "database");
+        assert_eq!(secret_key("myapp", "myapp/prod/api"), "prod/api");
+        assert_eq!(
+            secret_key("myapp/database", "myapp/database"),
+            "myapp/database"
+        );
+    }
+
+    #[test]
+    fn test_secret_entries_flatten() {
+        let payload = r#"{"host":"db.local","port":5432,"tls":{"mode":"require"},"note":null}"#;
+
+        assert_eq!(
+            secret_entries("database", payload, true),
+            vec![
+                entry("database/host", "db.local"),
+                entry("database/note", ""),
+                entry("database/port", "5432"),
+                entry("database/tls", r#"{"mode":"require"}"#),
+            ]
+        );
+        assert_eq!(
+            secret_entries("database", payload, false),
+            vec![entry("database", payload)]
+        );
+
+        // Plain strings and non-object JSON are never split
+        assert_eq!(
+            secret_entries("API_KEY", "sk-123", true),
+            vec![entry("API_KEY", "sk-123")]
+        );
+        assert_eq!(
+            secret_entries("LIST", "[1,2]", true),
+            vec![entry("LIST", "[1,2]")]
+        );
+    }
+
+    #[test]
+    fn test_sanitize_secret_keys() {
+        let (kept, invalid) = sanitize_secret_keys(vec![
+            entry("database/host", "db.local"),
+            entry("api-key", "k"),
+            entry("9lives", "x"),
+        ])
+        .unwrap();
+        assert_eq!(
+            kept,
+            vec![entry("database_host", "db.local"), entry("api_key", "k")]
+        );
+        assert_eq!(invalid, vec!["9lives"]);
+
+        let error =
+            sanitize_secret_keys(vec![entry("db/host", "a"), entry("db-host", "b")]).unwrap_err();
+        assert!(error.to_string().contains("db/host, db-host -> db_host"));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::SkitError;
+#[cfg(any(feature = "clipboard", test))]
+use sha2::{Digest, Sha256};
+
+/// Environment variable carrying the digest of the copied value to the clearing process
+pub const CLEAR_DIGEST_ENV: &str = "SKIT_CLIPBOARD_DIGEST";
+
+/// Hex SHA-256 of a clipboard value, so the clearing process never sees the secret itself
+#[cfg(any(feature = "clipboard", test))]
+pub fn digest(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(feature = "clipboard")]
+fn clipboard_error(e: arboard::Error) -> SkitError {
+    SkitError::ParseError(format!("Clipboard unavailable: {}", e))
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn unsupported() -> SkitError {
+    SkitError::ParseError(
+        "This build of skit was compiled without clipboard support (enable the 'clipboard' feature)"
+            .to_string(),
+    )
+}
+
+/// Place a value on the system clipboard and schedule it to be cleared.
+///
+/// The clear runs in a detached `skit` process that sleeps for `clear_after_secs`
+/// and then empties the clipboard, but only if it still holds the copied value.
+/// A timeout of 0 leaves the value on the clipboard.
+pub fn copy_with_clear(value: &str, clear_after_secs: u64) -> Result<(), SkitError> {
+    #[cfg(feature = "clipboard")]
+    {
+        let mut clipboard = arboard::Clipboard::new().map_err(clipboard_error)?;
+        clipboard.set_text(value).map_err(clipboard_error)?;
+
+        if clear_after_secs > 0 {
+            let exe = std::env::current_exe().map_err(SkitError::Io)?;
+            std::process::Command::new(exe)
+                .arg("clear-clipboard")
+                .arg("--after")
+                .arg(clear_after_secs.to_string())
+                .env(CLEAR_DIGEST_ENV, digest(value))
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .map_err(SkitError::Io)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    {
+        let _ = (value, clear_after_secs);
+        Err(unsupported())
+    }
+}
+
+/// Wait, then clear the clipboard if it still holds the value with the given digest
+pub fn clear_after(secs: u64, expected_digest: &str) -> Result<(), SkitError> {
+    #[cfg(feature = "clipboard")]
+    {
+        std::thread::sleep(std::time::Duration::from_secs(secs));
+
+        let mut clipboard = arboard::Clipboard::new().map_err(clipboard_error)?;
+        if let Ok(current) = clipboard.get_text()
+            && digest(&current) == expected_digest
+        {
+            clipboard.clear().map_err(clipboard_error)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    {
+        let _ = (secs, expected_digest);
+        Err(unsupported())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_is_stable_hex() {
+        let d = digest("secret");
+        assert_eq!(d.len(), 64);
+        assert_eq!(d, digest("secret"));
+        assert_ne!(d, digest("secret2"));
+    }
+}
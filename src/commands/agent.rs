@@ -0,0 +1,220 @@
+use crate::OutputFormat;
+use crate::agent::{self, Request, Response};
+use crate::display::{format_json_output, format_yaml_output, print_info, print_success};
+use crate::error::SkitError;
+use crate::types::{AgentStatusOutput, Safe};
+use std::time::Duration;
+
+/// Longest accepted `--ttl`/`--idle`, keeping `Instant` arithmetic in the agent from overflowing
+const MAX_DURATION_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Parse a duration such as `90`, `90s`, `15m`, `8h` or `1d` (bare numbers are seconds), up to
+/// 30 days
+pub fn parse_duration(value: &str) -> Result<Duration, SkitError> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => 0,
+    };
+    let secs = number
+        .parse::<u64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|secs| *secs > 0 && *secs <= MAX_DURATION_SECS);
+    match secs {
+        Some(secs) => Ok(Duration::from_secs(secs)),
+        None => Err(SkitError::ParseError(format!(
+            "Invalid duration '{}': use a positive number with s, m, h or d, at most 30d, e.g. 8h",
+            value
+        ))),
+    }
+}
+
+/// Render seconds compactly, e.g. `1h 5m` or `42s`
+fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s >= 3600 => format!("{}h {}m", s / 3600, (s % 3600) / 60),
+        s if s >= 60 => format!("{}m {}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
+
+fn socket_display() -> String {
+    agent::socket_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default()
+}
+
+/// Start the agent in the background, or with `foreground` in this process
+pub fn agent_start(ttl: &str, idle: &str, foreground: bool) -> Result<(), SkitError> {
+    if cfg!(not(any(unix, windows))) {
+        return Err(SkitError::Io(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "skit agent is only supported on Unix and Windows",
+        )));
+    }
+    let ttl_duration = parse_duration(ttl)?;
+    let idle_duration = parse_duration(idle)?;
+    let path = agent::socket_path().ok_or_else(|| {
+        SkitError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Could not determine the agent socket path",
+        ))
+    })?;
+
+    if foreground {
+        return agent::serve(&path, ttl_duration, idle_duration);
+    }
+
+    if agent::is_running() {
+        print_info(&format!("Agent is already running on {}", path.display()));
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe().map_err(SkitError::Io)?;
+    let mut command = std::process::Command::new(exe);
+    command
+        .args([
+            "agent",
+            "start",
+            "--foreground",
+            "--ttl",
+            ttl,
+            "--idle",
+            idle,
+        ])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    // Keep the agent alive when the terminal that started it closes
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    #[cfg(windows)]
+    std::os::windows::process::CommandExt::creation_flags(
+        &mut command,
+        windows_sys::Win32::System::Threading::DETACHED_PROCESS
+            | windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP,
+    );
+    command.spawn().map_err(SkitError::Io)?;
+
+    for _ in 0..50 {
+        if agent::is_running() {
+            print_success(&format!(
+                "Agent started on {} (TTL {}, idle timeout {})",
+                path.display(),
+                ttl,
+                idle
+            ));
+            print_info("Passwords you enter are now remembered for this session");
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err(SkitError::Io(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        format!("Agent did not start listening on {}", path.display()),
+    )))
+}
+
+/// Unlock the safe and hand its password to the running agent
+pub fn agent_add(safe_path: &str) -> Result<(), SkitError> {
+    if !agent::is_running() {
+        return Err(SkitError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "No agent is running; start one with 'skit agent start'",
+        )));
+    }
+    let safe = Safe::load(safe_path)?;
+    let password = zeroize::Zeroizing::new(crate::password::get_password_with_auth_chain(
+        &safe,
+        safe_path,
+        "Enter password to unlock the safe in the agent: ",
+    )?);
+    agent::add_password(&safe.uuid, safe_path, &password)?;
+    print_success(&format!("Safe {} unlocked in the agent", safe_path));
+    Ok(())
+}
+
+/// Forget every password the agent holds
+pub fn agent_lock() -> Result<(), SkitError> {
+    agent::send(&Request::Lock)?;
+    print_success("Agent locked; all cached passwords were forgotten");
+    Ok(())
+}
+
+/// Stop the agent, forgetting every password it holds
+pub fn agent_stop() -> Result<(), SkitError> {
+    agent::send(&Request::Stop)?;
+    print_success("Agent stopped");
+    Ok(())
+}
+
+/// Show whether an agent is running and which safes it holds unlocked
+pub fn agent_status(format: &OutputFormat) -> Result<(), SkitError> {
+    let output = match agent::send(&Request::Status) {
+        Ok(Response::Entries { entries }) => AgentStatusOutput {
+            running: true,
+            socket: socket_display(),
+            entries,
+        },
+        _ => AgentStatusOutput {
+            running: false,
+            socket: socket_display(),
+            entries: Vec::new(),
+        },
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", format_json_output(&output)?),
+        OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+        _ => {
+            if !output.running {
+                print_info(&format!("No agent running on {}", output.socket));
+                return Ok(());
+            }
+            println!("Agent running on {}", output.socket);
+            if output.entries.is_empty() {
+                println!("No safes unlocked");
+            }
+            for entry in &output.entries {
+                println!(
+                    "  {}  expires in {} (idle {})",
+                    entry.safe_path,
+                    format_duration(entry.expires_in),
+                    format_duration(entry.idle_expires_in)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("15m").unwrap(), Duration::from_secs(900));
+        assert_eq!(parse_duration("8h").unwrap(), Duration::from_secs(28800));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("8 hours").is_err());
+        assert!(parse_duration("h").is_err());
+        assert_eq!(
+            parse_duration("30d").unwrap(),
+            Duration::from_secs(2_592_000)
+        );
+        assert!(parse_duration("31d").is_err());
+        assert!(parse_duration("99999999999999999d").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::aws::client::{self, run_async_blocking};
+use crate::aws::secrets;
+use crate::commands::pull::{PullOutcome, PulledEntry, apply_pulled};
+use crate::commands::template::{CommandTemplate, MessageOutput};
+use crate::error::SkitError;
+use crate::types::Safe;
+
+/// Arguments for the Secrets Manager pull command
+#[derive(Debug)]
+pub struct AsmPullArgs {
+    pub prefix: Option<String>,
+    pub region: Option<String>,
+    pub flatten: bool,
+    pub replace: bool,
+    pub no_overwrite: bool,
+    pub dry_run: bool,
+}
+
+/// Template-based implementation of the Secrets Manager pull command
+pub struct AsmPullCommand;
+
+impl CommandTemplate for AsmPullCommand {
+    type Args = AsmPullArgs;
+    type Output = MessageOutput;
+
+    fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
+        if let Some(prefix) = &args.prefix
+            && prefix.trim().is_empty()
+        {
+            return Err(SkitError::ParseError(
+                "Secrets Manager prefix cannot be empty when provided".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn requires_authentication(&self, _safe: &Safe, _args: &Self::Args) -> bool {
+        true // Every pulled secret is stored encrypted
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let AsmPullArgs {
+            prefix,
+            region,
+            flatten,
+            replace,
+            no_overwrite,
+            dry_run,
+        } = args;
+
+        let resolved_prefix = resolve_prefix(prefix.as_deref(), safe)?;
+
+        let prefix_for_fetch = resolved_prefix.clone();
+        let managed_secrets = run_async_blocking(async move {
+            let asm_client = client::create_secrets_manager_client(region).await?;
+            secrets::fetch_secrets(&asm_client, &prefix_for_fetch, flatten).await
+        })?;
+        // Rename and check every key before anything is written to the safe
+        let (managed_secrets, invalid) = secrets::sanitize_secret_keys(managed_secrets)?;
+        if !invalid.is_empty() {
+            crate::display::print_warning(&format!(
+                "Skipping secrets that don't map to valid keys: {}",
+                invalid.join(", ")
+            ));
+        }
+
+        let total = managed_secrets.len();
+        let entries = managed_secrets
+            .into_iter()
+            .map(|secret| PulledEntry {
+                key: secret.key,
+                value: secret.value,
+                encrypt: true,
+            })
+            .collect();
+        let counts = match apply_pulled(
+            safe,
+            entries,
+            password.as_deref(),
+            replace,
+            no_overwrite,
+            dry_run,
+        )? {
+            PullOutcome::DryRun(listing) => {
+                return Ok(MessageOutput {
+                    message: format!(
+                        "Dry run: Would pull {} secrets from Secrets Manager prefix '{}'\n\n{}",
+                        total, resolved_prefix, listing
+                    ),
+                });
+            }
+            PullOutcome::Applied(counts) => counts,
+        };
+
+        safe.asm_prefix = Some(resolved_prefix.clone());
+
+        let message = format!(
+            "Successfully pulled {} secrets from Secrets Manager prefix '{}'\n\
+             Added: {}, Updated: {}, Skipped: {}",
+            counts.added + counts.updated,
+            resolved_prefix,
+            counts.added,
+            counts.updated,
+            counts.skipped
+        );
+
+        Ok(MessageOutput { message })
+    }
+
+    fn modifies_safe(&self) -> bool {
+        true
+    }
+
+    fn audit_action(&self) -> &'static str {
+        "asm-pull"
+    }
+
+    fn format_output(
+        &self,
+        output: Self::Output,
+        _format: &crate::OutputFormat,
+    ) -> Result<(), SkitError> {
+        crate::display::print_success(&output.message);
+        Ok(())
+    }
+}
+
+pub fn asm_pull(
+    safe_path: &str,
+    prefix: Option<&str>,
+    region: Option<String>,
+    flatten: bool,
+    replace: bool,
+    no_overwrite: bool,
+    dry_run: bool,
+) -> Result<(), SkitError> {
+    use crate::display::print_info;
+
+    print_info("Pulling secrets from AWS Secrets Manager...\n");
+
+    let command = AsmPullCommand;
+    let args = AsmPullArgs {
+        prefix: prefix.map(|p| p.to_string()),
+        region,
+        flatten,
+        replace,
+        no_overwrite,
+        dry_run,
+    };
+
+    command.execute(safe_path, &crate::OutputFormat::Table, args)
+}
+
+/// Resolve the Secrets Manager prefix from the CLI flag, falling back to the safe's stored prefix
+fn resolve_prefix(prefix: Option<&str>, safe: &Safe) -> Result<String, SkitError> {
+    match prefix {
+        Some(prefix) => {
+            let trimmed = prefix.trim();
+            if trimmed.is_empty() {
+                return Err(SkitError::ParseError(
+                    "Secrets Manager prefix cannot be empty when provided".to_string(),
+                ));
+            }
+            Ok(trimmed.to_string())
+        }
+        None => safe
+            .asm_prefix
+            .as_ref()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| {
+                SkitError::ParseError(
+                    "No Secrets Manager prefix available. Provide --prefix or run a prior `skit asm pull --prefix ...`."
+                        .to_string(),
+                )
+            }),
+    }
+}
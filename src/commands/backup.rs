@@ -0,0 +1,135 @@
+use crate::display::{print_info, print_success};
+use crate::error::SkitError;
+use crate::types::Safe;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Copy the raw safe file to `backup_path` without decrypting anything.
+/// The content must parse as a safe, and an existing file is never overwritten.
+pub fn backup_safe_to(safe_path: &str, backup_path: &Path) -> Result<(), SkitError> {
+    let content = fs::read_to_string(safe_path).map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            SkitError::SafeNotFound(safe_path.to_string())
+        } else {
+            SkitError::Io(e)
+        }
+    })?;
+
+    // Don't preserve a file that couldn't be restored from
+    Safe::parse(&content)?;
+
+    crate::fs_utils::write_secret_file_secure(backup_path, &content)
+}
+
+/// UTC timestamp used in backup file names, e.g. `20250821T161500Z`
+pub fn backup_timestamp() -> String {
+    chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Backup location `<dir>/<safe file name>.<timestamp>.bak`
+fn backup_path(safe_path: &str, dir: &Path) -> Result<PathBuf, SkitError> {
+    let file_name = Path::new(safe_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| SkitError::ParseError(format!("Invalid safe path: {}", safe_path)))?;
+    Ok(dir.join(format!("{}.{}.bak", file_name, backup_timestamp())))
+}
+
+/// Create a timestamped copy of the safe in `dir` (default: current directory)
+pub fn backup(safe_path: &str, dir: Option<&str>) -> Result<(), SkitError> {
+    let path = backup_path(safe_path, Path::new(dir.unwrap_or(".")))?;
+    backup_safe_to(safe_path, &path)?;
+
+    print_success(&format!("Backed up {} to {}", safe_path, path.display()));
+    Ok(())
+}
+
+/// Swap numbered backup `n` (`<safe>.bak.N`) back in after confirming. The replaced safe takes
+/// the backup's place, so running the same restore again undoes it.
+pub fn restore(safe_path: &str, from_backup: u32) -> Result<(), SkitError> {
+    let path = Path::new(safe_path);
+    let backup = crate::fs_utils::numbered_backup_path(path, from_backup);
+    let content = fs::read_to_string(&backup).map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            SkitError::SafeNotFound(backup.display().to_string())
+        } else {
+            SkitError::Io(e)
+        }
+    })?;
+    let restored = Safe::parse(&content)?;
+
+    if !crate::input::confirm(&format!(
+        "Replace {} with {} (last updated {})? [y/N]: ",
+        safe_path,
+        backup.display(),
+        restored.updated
+    ))? {
+        print_info("Restore cancelled");
+        return Ok(());
+    }
+
+    let current = match fs::read_to_string(path) {
+        Ok(current) => Some(current),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(SkitError::Io(e)),
+    };
+    crate::fs_utils::write_file_atomic(path, &content)?;
+    if let Some(ref current) = current {
+        crate::fs_utils::write_file_atomic(&backup, current)?;
+    }
+
+    match current.and_then(|current| Safe::parse(&current).ok()) {
+        Some(replaced) if replaced.uuid == restored.uuid => {
+            crate::audit::record_changes(&restored, &replaced.items, "restore")
+        }
+        _ => crate::audit::record(&restored, "restore"),
+    }
+
+    print_success(&format!("Restored {} from {}", safe_path, backup.display()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SAFE: &str = "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n\
+                        TOKEN=ENC~v1~abc\n";
+
+    #[test]
+    fn test_backup_copies_raw_safe() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let safe_path = temp_dir.path().join(".env.safe");
+        fs::write(&safe_path, SAFE).unwrap();
+        let safe_path = safe_path.to_str().unwrap();
+
+        let path = backup_path(safe_path, temp_dir.path()).unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with(".env.safe.") && name.ends_with("Z.bak"));
+
+        backup_safe_to(safe_path, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), SAFE);
+
+        // Never overwrite an existing backup
+        assert!(backup_safe_to(safe_path, &path).is_err());
+    }
+
+    #[test]
+    fn test_backup_rejects_invalid_safe() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let safe_path = temp_dir.path().join(".env.safe");
+        fs::write(&safe_path, "not a safe").unwrap();
+        let backup = temp_dir.path().join("backup.bak");
+
+        assert!(backup_safe_to(safe_path.to_str().unwrap(), &backup).is_err());
+        assert!(!backup.exists());
+
+        let missing = temp_dir.path().join("missing.safe");
+        assert!(matches!(
+            backup_safe_to(missing.to_str().unwrap(), &backup),
+            Err(SkitError::SafeNotFound(_))
+        ));
+    }
+}
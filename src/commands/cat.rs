@@ -0,0 +1,51 @@
+use crate::OutputFormat;
+use crate::commands::get::{GetArgs, GetCommand, GetOutput};
+use crate::commands::template::CommandTemplate;
+use crate::error::SkitError;
+use crate::types::Safe;
+use std::io::Write;
+
+/// Template-based implementation of the cat command: `get` without the trailing newline
+pub struct CatCommand;
+
+impl CommandTemplate for CatCommand {
+    type Args = GetArgs;
+    type Output = GetOutput;
+
+    fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
+        GetCommand.validate_args(args)
+    }
+
+    fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
+        GetCommand.requires_authentication(safe, args)
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        GetCommand.execute_operation(safe, password, args)
+    }
+
+    fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(output.value.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// Write a secret value to stdout exactly as stored, with no trailing newline.
+/// A missing key surfaces as `KeyNotFound`, which main maps to exit 3.
+pub fn cat(safe_path: &str, key: &str) -> Result<(), SkitError> {
+    let command = CatCommand;
+    let args = GetArgs {
+        key: key.to_string(),
+        copy_timeout: None,
+    };
+
+    // Quiet keeps auth-chain info logs off stdout; the output ignores the format
+    command.execute_with(safe_path, &OutputFormat::Table, args, true)
+}
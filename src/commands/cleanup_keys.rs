@@ -1,9 +1,27 @@
 use crate::display::{print_error, print_info, print_success, print_warning};
 use crate::error::SkitError;
+use crate::key_index::{self, KeyIndex};
 use std::fs;
-use std::io::{self, Write};
+use std::path::Path;
 use std::time::{Duration, SystemTime};
 
+fn is_keychain_marker(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "keyring")
+}
+
+/// Remove a saved key, which is either a plaintext key file or a keychain entry marker
+fn remove_saved_key(path: &Path) -> Result<(), SkitError> {
+    if is_keychain_marker(path) {
+        let uuid = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        crate::keychain::delete_password(uuid)
+    } else {
+        fs::remove_file(path).map_err(SkitError::Io)
+    }
+}
+
 fn format_days_ago(days: u64) -> String {
     match days {
         0 => "today".to_string(),
@@ -12,102 +30,160 @@ fn format_days_ago(days: u64) -> String {
     }
 }
 
-pub fn cleanup_keys(older_than_days: u64, dry_run: bool) -> Result<(), SkitError> {
-    let home_dir = dirs::home_dir().ok_or_else(|| {
+/// A saved key found in the keys directory
+struct SavedKey {
+    path: std::path::PathBuf,
+    uuid: String,
+    /// File name plus the safe description when the index knows it
+    label: String,
+    days_ago: u64,
+    last_used: SystemTime,
+    orphan: bool,
+}
+
+impl SavedKey {
+    fn describe(&self) -> String {
+        if self.orphan {
+            format!("{} (safe no longer exists)", self.label)
+        } else {
+            format!(
+                "{} (accessed {})",
+                self.label,
+                format_days_ago(self.days_ago)
+            )
+        }
+    }
+}
+
+/// Remove saved keys not used for `older_than_days`, and with `orphans` keys whose safe is gone
+pub fn cleanup_keys(
+    older_than_days: Option<u64>,
+    orphans: bool,
+    dry_run: bool,
+) -> Result<(), SkitError> {
+    let skit_keys_dir = key_index::keys_dir().ok_or_else(|| {
         SkitError::Io(std::io::Error::new(
             std::io::ErrorKind::NotFound,
             "Could not find home directory",
         ))
     })?;
 
-    let skit_keys_dir = home_dir.join(".config").join("skit").join("keys");
-
     if !skit_keys_dir.exists() {
         print_info("No saved keys directory found - nothing to clean up");
         return Ok(());
     }
 
-    let cutoff_time = SystemTime::now()
-        .checked_sub(Duration::from_secs(older_than_days * 24 * 60 * 60))
-        .ok_or_else(|| SkitError::ParseError("Invalid days value - too large".to_string()))?;
+    let cutoff_time = match older_than_days {
+        Some(days) => Some(
+            SystemTime::now()
+                .checked_sub(Duration::from_secs(days * 24 * 60 * 60))
+                .ok_or_else(|| {
+                    SkitError::ParseError("Invalid days value - too large".to_string())
+                })?,
+        ),
+        None => None,
+    };
 
+    let mut index = KeyIndex::load(&skit_keys_dir);
     let entries = fs::read_dir(&skit_keys_dir).map_err(SkitError::Io)?;
-    let mut found_keys = false;
-    let mut old_keys = Vec::new();
-    let mut recent_keys = Vec::new();
+    let mut saved_keys = Vec::new();
     let now = SystemTime::now();
 
-    // First pass: categorize keys by age
+    // First pass: collect keys with their last use, from the index or the file's mtime
     for entry in entries {
         let entry = entry.map_err(SkitError::Io)?;
         let path = entry.path();
 
-        // Only process .key files
-        if path.extension().is_none_or(|ext| ext != "key") {
+        // Only process .key files and OS keychain entry markers
+        if path
+            .extension()
+            .is_none_or(|ext| ext != "key" && ext != "keyring")
+        {
             continue;
         }
 
-        found_keys = true;
-
-        // Check the modification time
-        let metadata = fs::metadata(&path).map_err(SkitError::Io)?;
-        let modified_time = metadata.modified().map_err(SkitError::Io)?;
-
-        let file_name = path
-            .file_name()
+        let uuid = path
+            .file_stem()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown")
             .to_string();
+        let indexed = index.entries.get(&uuid);
 
-        // Calculate days since last access
-        let days_ago = match now.duration_since(modified_time) {
-            Ok(duration) => duration.as_secs() / (24 * 60 * 60),
-            Err(_) => 0, // File is in the future somehow, treat as recent
+        let last_used = match indexed {
+            Some(indexed) => SystemTime::from(indexed.last_used),
+            // Keys saved before the index existed
+            None => fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .map_err(SkitError::Io)?,
         };
 
-        if modified_time < cutoff_time {
-            old_keys.push((path, file_name, days_ago));
+        let mut label = if is_keychain_marker(&path) {
+            format!("{} (OS keychain)", uuid)
         } else {
-            recent_keys.push((file_name, days_ago));
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string()
+        };
+        if let Some(indexed) = indexed.filter(|i| !i.description.is_empty()) {
+            label = format!("{} - {}", label, indexed.description);
         }
+
+        // Calculate days since last access
+        let days_ago = match now.duration_since(last_used) {
+            Ok(duration) => duration.as_secs() / (24 * 60 * 60),
+            Err(_) => 0, // Used in the future somehow, treat as recent
+        };
+
+        saved_keys.push(SavedKey {
+            orphan: orphans && indexed.is_some_and(|i| i.is_orphan(&uuid)),
+            path,
+            uuid,
+            label,
+            days_ago,
+            last_used,
+        });
     }
 
-    if !found_keys {
+    if saved_keys.is_empty() {
         print_info("No key files found in the keys directory");
         return Ok(());
     }
+    saved_keys.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let (old_keys, recent_keys): (Vec<SavedKey>, Vec<SavedKey>) = saved_keys
+        .into_iter()
+        .partition(|key| key.orphan || cutoff_time.is_some_and(|cutoff| key.last_used < cutoff));
+
+    let reason = match older_than_days {
+        Some(days) if orphans => format!("not accessed for {}+ days, or orphaned", days),
+        Some(days) => format!("not accessed for {}+ days", days),
+        None => "their safe no longer exists".to_string(),
+    };
 
     // Show recent keys being kept
     if !recent_keys.is_empty() {
-        print_info(&format!("📂 Keeping {} recent key(s):", recent_keys.len()));
-        for (key_name, days_ago) in &recent_keys {
-            print_info(&format!(
-                "  ├─ {} (accessed {})",
-                key_name,
-                format_days_ago(*days_ago)
-            ));
+        print_info(&format!("📂 Keeping {} key(s):", recent_keys.len()));
+        for key in &recent_keys {
+            print_info(&format!("  ├─ {}", key.describe()));
         }
         println!(); // Add spacing
     }
 
     // Show old keys that will be/would be removed
     if old_keys.is_empty() {
-        print_info("No old keys found to remove");
+        print_info("No keys found to remove");
         return Ok(());
     }
 
     if dry_run {
         print_warning(&format!(
-            "🗑️  Would remove {} old key(s) (not accessed for {}+ days):",
+            "🗑️  Would remove {} key(s) ({}):",
             old_keys.len(),
-            older_than_days
+            reason
         ));
-        for (_, key_name, days_ago) in &old_keys {
-            print_warning(&format!(
-                "  ├─ {} (accessed {})",
-                key_name,
-                format_days_ago(*days_ago)
-            ));
+        for key in &old_keys {
+            print_warning(&format!("  ├─ {}", key.describe()));
         }
         println!();
         print_info("Run without --dry-run to actually remove these keys");
@@ -116,16 +192,12 @@ pub fn cleanup_keys(older_than_days: u64, dry_run: bool) -> Result<(), SkitError
 
     // Show keys to be removed and ask for confirmation
     print_warning(&format!(
-        "⚠️  Found {} old key(s) to remove (not accessed for {}+ days):",
+        "⚠️  Found {} key(s) to remove ({}):",
         old_keys.len(),
-        older_than_days
+        reason
     ));
-    for (_, key_name, days_ago) in &old_keys {
-        print_warning(&format!(
-            "  ├─ {} (accessed {})",
-            key_name,
-            format_days_ago(*days_ago)
-        ));
+    for key in &old_keys {
+        print_warning(&format!("  ├─ {}", key.describe()));
     }
     println!();
     print_error("🚨 WARNING: This operation is IRREVERSIBLE!");
@@ -134,38 +206,31 @@ pub fn cleanup_keys(older_than_days: u64, dry_run: bool) -> Result<(), SkitError
     );
     println!();
 
-    print!("Continue with deletion? [y/N]: ");
-    io::stdout().flush().map_err(SkitError::Io)?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).map_err(SkitError::Io)?;
-
-    let input = input.trim().to_lowercase();
-    if input != "y" && input != "yes" {
+    if !crate::input::confirm("Continue with deletion? [y/N]: ")? {
         print_info("Cleanup cancelled");
         return Ok(());
     }
 
     // Proceed with deletion
     let mut removed_count = 0;
-    for (path, key_name, days_ago) in old_keys {
-        match fs::remove_file(&path) {
+    for key in old_keys {
+        match remove_saved_key(&key.path) {
             Ok(()) => {
-                print_success(&format!(
-                    "Removed old key: {} (was accessed {})",
-                    key_name,
-                    format_days_ago(days_ago)
-                ));
+                print_success(&format!("Removed key: {}", key.describe()));
+                index.entries.remove(&key.uuid);
                 removed_count += 1;
             }
             Err(e) => {
-                print_error(&format!("Failed to remove key {}: {}", key_name, e));
+                print_error(&format!("Failed to remove key {}: {}", key.label, e));
             }
         }
     }
+    if removed_count > 0 {
+        index.save(&skit_keys_dir)?;
+    }
 
     print_success(&format!(
-        "✅ Cleanup completed - removed {} old key(s)",
+        "✅ Cleanup completed - removed {} key(s)",
         removed_count
     ));
     Ok(())
@@ -173,7 +238,9 @@ pub fn cleanup_keys(older_than_days: u64, dry_run: bool) -> Result<(), SkitError
 
 #[cfg(test)]
 mod tests {
+    use super::is_keychain_marker;
     use std::fs;
+    use std::path::Path;
     use tempfile::TempDir;
 
     #[test]
@@ -193,4 +260,11 @@ mod tests {
         // Note: Testing the actual cleanup logic would require manipulating file timestamps
         // which is complex and platform-dependent. This test verifies basic structure.
     }
+
+    #[test]
+    fn test_is_keychain_marker() {
+        assert!(is_keychain_marker(Path::new("keys/test-uuid.keyring")));
+        assert!(!is_keychain_marker(Path::new("keys/test-uuid.key")));
+        assert!(!is_keychain_marker(Path::new("keys/test-uuid")));
+    }
 }
@@ -0,0 +1,160 @@
+use crate::error::SkitError;
+use clap::ValueEnum;
+use clap_complete::Shell;
+use std::io::Write;
+
+/// Shells `skit completions` can generate scripts for
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl CompletionShell {
+    fn clap_shell(self) -> Shell {
+        match self {
+            CompletionShell::Bash => Shell::Bash,
+            CompletionShell::Zsh => Shell::Zsh,
+            CompletionShell::Fish => Shell::Fish,
+            CompletionShell::Powershell => Shell::PowerShell,
+        }
+    }
+}
+
+// The hooks below complete key names for `get` and `rm` by asking skit itself via the
+// hidden `keys --complete-keys`, which reads the safe without authenticating.
+
+const BASH_KEYS_HOOK: &str = r#"
+# Complete secret key names for `skit get` and `skit rm`
+_skit_keys() {
+    local i word cmd="" safe="" positional=0
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        word="${COMP_WORDS[i]}"
+        case "$word" in
+            -s|--safe) safe="${COMP_WORDS[i + 1]}"; ((i++)) ;;
+            --safe=*) safe="${word#--safe=}" ;;
+            -o|--format|--timeout|--clear-after) ((i++)) ;;
+            -*) ;;
+            *) if [[ -z "$cmd" ]]; then cmd="$word"; else ((positional++)); fi ;;
+        esac
+    done
+
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    if [[ ("$cmd" == get || "$cmd" == rm) && $positional -eq 0 && "$cur" != -* ]]; then
+        local -a args=(keys --complete-keys)
+        [[ -n "$safe" ]] && args=(--safe "$safe" "${args[@]}")
+        COMPREPLY=($(compgen -W "$(skit "${args[@]}" 2>/dev/null)" -- "$cur"))
+        return 0
+    fi
+    _skit "$@"
+}
+
+if [[ "${BASH_VERSINFO[0]}" -eq 4 && "${BASH_VERSINFO[1]}" -ge 4 || "${BASH_VERSINFO[0]}" -gt 4 ]]; then
+    complete -F _skit_keys -o nosort -o bashdefault -o default skit
+else
+    complete -F _skit_keys -o bashdefault -o default skit
+fi
+"#;
+
+const ZSH_KEYS_HOOK: &str = r#"
+# Complete secret key names for `skit get` and `skit rm`
+_skit_keys() {
+    local i word cmd="" safe="" positional=0
+    for ((i = 2; i < CURRENT; i++)); do
+        word="${words[i]}"
+        case "$word" in
+            -s|--safe) safe="${words[i + 1]}"; ((i++)) ;;
+            --safe=*) safe="${word#--safe=}" ;;
+            -o|--format|--timeout|--clear-after) ((i++)) ;;
+            -*) ;;
+            *) if [[ -z "$cmd" ]]; then cmd="$word"; else ((positional++)); fi ;;
+        esac
+    done
+
+    if [[ ("$cmd" == get || "$cmd" == rm) && $positional -eq 0 && "${words[CURRENT]}" != -* ]]; then
+        local -a args keys
+        args=(keys --complete-keys)
+        [[ -n "$safe" ]] && args=(--safe "$safe" $args)
+        keys=(${(f)"$(skit $args 2>/dev/null)"})
+        compadd -a keys
+        return
+    fi
+    _skit "$@"
+}
+
+compdef _skit_keys skit
+"#;
+
+const FISH_KEYS_HOOK: &str = r#"
+# Complete secret key names for `skit get` and `skit rm`
+function __skit_keys
+    set -l tokens (commandline -opc)
+    set -l args keys --complete-keys
+    set -l i 2
+    while test $i -le (count $tokens)
+        switch $tokens[$i]
+            case -s --safe
+                set i (math $i + 1)
+                set args --safe $tokens[$i] $args
+            case '--safe=*'
+                set args --safe (string replace -- '--safe=' '' $tokens[$i]) $args
+        end
+        set i (math $i + 1)
+    end
+    skit $args 2>/dev/null
+end
+
+complete -c skit -n "__fish_seen_subcommand_from get rm" -f -a "(__skit_keys)"
+"#;
+
+const POWERSHELL_KEYS_HOOK: &str = r#"
+# Complete secret key names for `skit get` and `skit rm`
+function __skit_keys($commandElements) {
+    $keysArgs = @('keys', '--complete-keys')
+    for ($i = 1; $i -lt $commandElements.Count; $i++) {
+        $element = $commandElements[$i].ToString()
+        if (($element -eq '-s' -or $element -eq '--safe') -and $i + 1 -lt $commandElements.Count) {
+            $keysArgs = @('--safe', $commandElements[$i + 1].ToString()) + $keysArgs
+        }
+    }
+    skit @keysArgs 2>$null | ForEach-Object {
+        [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_)
+    }
+}
+"#;
+
+/// Build the completion script for a shell, including key name completion
+pub fn completion_script(shell: CompletionShell, cmd: &mut clap::Command) -> String {
+    let mut buffer = Vec::new();
+    clap_complete::generate(shell.clap_shell(), cmd, "skit", &mut buffer);
+    let mut script = String::from_utf8_lossy(&buffer).into_owned();
+
+    match shell {
+        CompletionShell::Bash => script.push_str(BASH_KEYS_HOOK),
+        CompletionShell::Zsh => script.push_str(ZSH_KEYS_HOOK),
+        CompletionShell::Fish => script.push_str(FISH_KEYS_HOOK),
+        CompletionShell::Powershell => {
+            // Offer key names alongside the static completions of the get and rm arms
+            for subcommand in ["get", "rm"] {
+                let arm = format!("'skit;{}' {{\n", subcommand);
+                script = script.replace(
+                    &arm,
+                    &format!("{}            __skit_keys $commandElements\n", arm),
+                );
+            }
+            script.push_str(POWERSHELL_KEYS_HOOK);
+        }
+    }
+
+    script
+}
+
+/// Print a shell completion script to stdout
+pub fn completions(shell: CompletionShell, cmd: &mut clap::Command) -> Result<(), SkitError> {
+    let script = completion_script(shell, cmd);
+    std::io::stdout()
+        .write_all(script.as_bytes())
+        .map_err(SkitError::Io)
+}
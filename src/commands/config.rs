@@ -0,0 +1,81 @@
+use crate::OutputFormat;
+use crate::config::{Config, Resolved};
+use crate::display::{format_json_output, format_yaml_output};
+use crate::error::SkitError;
+use crate::types::{ConfigOutput, ConfigSetting};
+
+fn setting<T: std::fmt::Display>(name: &str, resolved: &Resolved<T>) -> ConfigSetting {
+    ConfigSetting {
+        name: name.to_string(),
+        value: resolved.value.to_string(),
+        source: resolved.source.to_string(),
+    }
+}
+
+/// Print the effective configuration and where each setting came from
+pub fn config_show(
+    config: &Config,
+    safe_path: &Resolved<String>,
+    resolved_format: &Resolved<OutputFormat>,
+    backups: &Resolved<u32>,
+    format: &OutputFormat,
+) -> Result<(), SkitError> {
+    let format_name = Resolved {
+        value: format!("{:?}", resolved_format.value).to_lowercase(),
+        source: resolved_format.source.clone(),
+    };
+    let output = ConfigOutput {
+        files: config.files().map(|p| p.display().to_string()).collect(),
+        settings: vec![
+            setting("safe", safe_path),
+            setting("format", &format_name),
+            setting("no_color", &config.no_color()),
+            setting("auto_remember", &config.auto_remember()),
+            setting("password_attempts", &config.password_attempts()),
+            setting("discover", &config.discover()),
+            setting("backups", backups),
+        ],
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", format_json_output(&output)?),
+        OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+        _ => print_config_table(&output),
+    }
+    Ok(())
+}
+
+fn print_config_table(output: &ConfigOutput) {
+    if output.files.is_empty() {
+        println!("Config files: none found");
+    } else {
+        println!("Config files (later files take precedence):");
+        for file in &output.files {
+            println!("  {}", file);
+        }
+    }
+    println!();
+
+    let name_width = output
+        .settings
+        .iter()
+        .map(|s| s.name.len())
+        .max()
+        .unwrap_or(0);
+    let value_width = output
+        .settings
+        .iter()
+        .map(|s| s.value.len())
+        .max()
+        .unwrap_or(0);
+    for setting in &output.settings {
+        println!(
+            "{:name_width$}  {:value_width$}  ({})",
+            setting.name,
+            setting.value,
+            setting.source,
+            name_width = name_width,
+            value_width = value_width
+        );
+    }
+}
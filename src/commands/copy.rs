@@ -1,18 +1,28 @@
+use crate::audit;
 use crate::crypto;
 use crate::error::SkitError;
 use crate::input;
+use crate::key_filter::KeyFilter;
 use crate::password::{
-    generate_secure_password, get_password_with_auth_chain, validate_password_strength,
+    PasswordSource, SAFE_PASSPHRASE_WORDS, generate_safe_passphrase, generate_secure_password,
+    get_password_with_auth_chain, validate_password_strength,
 };
 use crate::types::Safe;
 use std::fs;
 use std::io::{self, Write};
 
+/// Copy the safe to `dest_path` under a new password, keeping only the keys `filter` allows.
+/// Every `--keys` entry must match a key in the source safe.
+#[allow(clippy::too_many_arguments)]
 pub fn copy(
     source_path: &str,
     dest_path: &str,
     remember: bool,
+    use_keyring: bool,
     description: Option<&str>,
+    filter: &KeyFilter,
+    password_source: &PasswordSource,
+    passphrase: bool,
 ) -> Result<(), SkitError> {
     // Check if destination already exists
     if fs::metadata(dest_path).is_ok() {
@@ -25,6 +35,16 @@ pub fn copy(
     // Load the source safe
     let source_safe = Safe::load(source_path)?;
 
+    // Fail on a mistyped key before asking for any passwords
+    let missing = filter.unmatched_only(source_safe.items.keys());
+    if !missing.is_empty() {
+        return Err(SkitError::ParseError(format!(
+            "No key in {} matches: {}",
+            source_path,
+            missing.join(", ")
+        )));
+    }
+
     // Get source password to decrypt secrets
     let source_password = get_password_with_auth_chain(
         &source_safe,
@@ -34,42 +54,23 @@ pub fn copy(
     source_safe.verify_password(&source_password)?;
 
     println!("\n📋 Copying safe from {} to {}", source_path, dest_path);
-    println!("\nPassword requirements for new safe:");
-    println!("  - At least 12 characters");
-    println!("  - At least one uppercase letter");
-    println!("  - At least one lowercase letter");
-    println!("  - At least one digit");
-    println!("  - At least one special character. Allowed special characters: . _ @ # -");
 
     // Get new password for destination safe
-    let dest_password = loop {
-        let password = input::prompt_password_with_fallback(
-            "Enter password for the new safe (or hit enter to generate one automatically): ",
-        )
-        .map_err(SkitError::Io)?;
-
-        if password.is_empty() {
-            let gen_password = generate_secure_password();
-            println!("Generated password (keep this safe!): {}", gen_password);
-            break gen_password;
-        } else {
-            match validate_password_strength(&password) {
-                Ok(()) => {
-                    let confirm = input::prompt_password_with_fallback("Confirm password: ")
-                        .map_err(SkitError::Io)?;
-
-                    if password == confirm {
-                        println!();
-                        break password;
-                    } else {
-                        eprintln!("Error: Passwords do not match. Please try again.");
-                        continue;
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error: {}", e);
-                    continue;
-                }
+    let dest_password = if passphrase {
+        let generated = generate_safe_passphrase(SAFE_PASSPHRASE_WORDS);
+        println!("Generated passphrase (keep this safe!): {}", generated);
+        generated
+    } else {
+        match password_source.read()? {
+            Some(password) => {
+                validate_password_strength(&password)?;
+                password
+            }
+            None => {
+                input::require_interactive(
+                    "pass --password-env, --password-file or --passphrase to set the new safe password",
+                )?;
+                prompt_new_password()?
             }
         }
     };
@@ -77,6 +78,8 @@ pub fn copy(
     // Get description for new safe
     let new_description = if let Some(desc) = description {
         desc.to_string()
+    } else if input::is_non_interactive() {
+        source_safe.description.clone()
     } else {
         print!(
             "\nEnter a description for the new safe (optional, press enter to use source description):"
@@ -98,21 +101,36 @@ pub fn copy(
 
     // Create new safe with new password and UUID
     let mut dest_safe = Safe::new_with_password(&dest_password, &new_description)?;
+    dest_safe.kdf_params = source_safe.kdf_params;
+    dest_safe.cipher = source_safe.cipher;
+    dest_safe.create_master_key(&dest_password)?;
 
-    // Copy and re-encrypt all items
+    // Copy and re-encrypt the selected items
     let mut copied_encrypted = 0;
     let mut copied_plain = 0;
+    let mut skipped = Vec::new();
 
     for (key, item) in &source_safe.items {
+        if !filter.allows(key) {
+            skipped.push(key.as_str());
+            continue;
+        }
         if item.is_encrypted {
             // Decrypt with source password and re-encrypt with destination password
             let decrypted_value = crypto::DecryptBuilder::new()
+                .kdf_params(source_safe.kdf_params)
+                .master_key(source_safe.master_key.as_deref())
                 .password(&source_password)
                 .ciphertext(&item.value)
+                .context(key)
                 .decrypt()?;
             let encrypted_value = crypto::EncryptBuilder::new()
+                .kdf_params(dest_safe.kdf_params)
+                .master_key(dest_safe.master_key.as_deref())
+                .cipher(dest_safe.cipher)
                 .password(&dest_password)
                 .plaintext(&decrypted_value)
+                .context(key)
                 .encrypt()?;
             dest_safe.add_or_update_item(key.clone(), encrypted_value, true);
             copied_encrypted += 1;
@@ -125,6 +143,7 @@ pub fn copy(
 
     // Save the new safe
     dest_safe.save(dest_path)?;
+    audit::record_changes(&dest_safe, &Default::default(), "copy");
 
     tracing::info!(
         "✓ Copied safe to {} ({} encrypted, {} plain text)",
@@ -132,10 +151,19 @@ pub fn copy(
         copied_encrypted,
         copied_plain
     );
+    if !skipped.is_empty() {
+        let mut copied: Vec<&str> = dest_safe.items.keys().map(|k| k.as_str()).collect();
+        copied.sort();
+        skipped.sort();
+        println!("Copied keys: {}", copied.join(", "));
+        println!("Skipped keys: {}", skipped.join(", "));
+    }
 
     // Save the safe key if requested or if user chooses to
     let should_save = if remember {
         true
+    } else if input::is_non_interactive() {
+        false
     } else {
         print!("\nWould you like to save the safe key for automatic authentication? (y/N):");
         let _ = io::stdout().flush();
@@ -147,14 +175,26 @@ pub fn copy(
         input == "y" || input == "yes"
     };
 
-    if should_save {
+    if should_save && use_keyring {
+        let location = crate::commands::remember_safekey_with_password_quiet(
+            &dest_safe,
+            dest_path,
+            &dest_password,
+            true,
+            true,
+        )?;
+        tracing::info!(
+            "✓ Safe key saved for automatic authentication in {}",
+            location
+        );
+    } else if should_save {
         let home_dir = dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from("~"));
         let key_file = home_dir
             .join(".config")
             .join("skit")
             .join("keys")
             .join(format!("{}.key", dest_safe.uuid));
-        save_safe_key(&dest_safe, &dest_password)?;
+        save_safe_key(&dest_safe, dest_path, &dest_password)?;
         tracing::info!(
             "✓ Safe key saved for automatic authentication at {}",
             key_file.display()
@@ -169,7 +209,51 @@ pub fn copy(
     Ok(())
 }
 
-fn save_safe_key(safe: &Safe, password: &str) -> Result<(), SkitError> {
+/// Prompt for the destination safe password, generating one if the user just hits enter
+fn prompt_new_password() -> Result<String, SkitError> {
+    println!("\nPassword requirements for new safe:");
+    println!("  - At least 12 characters");
+    println!("  - At least one uppercase letter");
+    println!("  - At least one lowercase letter");
+    println!("  - At least one digit");
+    println!("  - At least one special character. Allowed special characters: . _ @ # -");
+
+    let password = loop {
+        let password = input::prompt_password_with_fallback(
+            "Enter password for the new safe (or hit enter to generate one automatically): ",
+        )
+        .map_err(SkitError::Io)?;
+
+        if password.is_empty() {
+            let gen_password = generate_secure_password();
+            println!("Generated password (keep this safe!): {}", gen_password);
+            break gen_password;
+        } else {
+            match validate_password_strength(&password) {
+                Ok(()) => {
+                    let confirm = input::prompt_password_with_fallback("Confirm password: ")
+                        .map_err(SkitError::Io)?;
+
+                    if password == confirm {
+                        println!();
+                        break password;
+                    } else {
+                        eprintln!("Error: Passwords do not match. Please try again.");
+                        continue;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    continue;
+                }
+            }
+        }
+    };
+
+    Ok(password)
+}
+
+fn save_safe_key(safe: &Safe, safe_path: &str, password: &str) -> Result<(), SkitError> {
     // Create the ~/.config/skit/keys directory
     let home_dir = dirs::home_dir().ok_or_else(|| {
         SkitError::Io(std::io::Error::new(
@@ -179,11 +263,12 @@ fn save_safe_key(safe: &Safe, password: &str) -> Result<(), SkitError> {
     })?;
 
     let skit_keys_dir = home_dir.join(".config").join("skit").join("keys");
-    fs::create_dir_all(&skit_keys_dir).map_err(SkitError::Io)?;
+    crate::fs_utils::create_private_dir(&skit_keys_dir)?;
 
     // Save the password to ~/.config/skit/keys/<uuid>.key with secure permissions
     let key_file = skit_keys_dir.join(format!("{}.key", safe.uuid));
     crate::fs_utils::write_secret_file_secure(&key_file, password)?;
+    crate::key_index::record_use(safe, Some(safe_path));
 
     Ok(())
 }
@@ -0,0 +1,149 @@
+use crate::display::{print_info, print_success, print_warning};
+use crate::error::SkitError;
+use crate::key_index::{self, KeyIndex};
+use crate::types::Safe;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Overwrite a plaintext key file before unlinking it, so the password doesn't linger in
+/// the freed blocks on filesystems that write in place
+fn shred_file(path: &Path) -> Result<(), SkitError> {
+    // No truncate: truncating first would free the old blocks before they are overwritten
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(SkitError::Io)?;
+    let len = file.metadata().map_err(SkitError::Io)?.len();
+    file.write_all(&vec![0u8; len as usize])
+        .map_err(SkitError::Io)?;
+    file.sync_all().map_err(SkitError::Io)?;
+    drop(file);
+    fs::remove_file(path).map_err(SkitError::Io)
+}
+
+/// Remove the saved key file, keychain entry and key index entry for `uuid`, returning a
+/// description of each thing removed
+pub fn forget_key(uuid: &str) -> Result<Vec<String>, SkitError> {
+    let mut removed = Vec::new();
+    let Some(dir) = key_index::keys_dir() else {
+        return Ok(removed);
+    };
+
+    let key_file = dir.join(format!("{}.key", uuid));
+    if key_file.exists() {
+        shred_file(&key_file)?;
+        removed.push(format!("key file {}", key_file.display()));
+    }
+
+    if crate::keychain::marker_path(uuid).is_some_and(|marker| marker.exists()) {
+        crate::keychain::delete_password(uuid)?;
+        removed.push("OS keychain entry".to_string());
+    }
+
+    if dir.is_dir() {
+        let mut index = KeyIndex::load(&dir);
+        if index.entries.remove(uuid).is_some() {
+            index.save(&dir)?;
+        }
+    }
+    Ok(removed)
+}
+
+/// Numbered backups kept next to the safe by `--backup`, which still hold its secrets
+fn leftover_backups(path: &Path) -> Vec<PathBuf> {
+    (1..)
+        .map(|n| crate::fs_utils::numbered_backup_path(path, n))
+        .take_while(|backup| backup.exists())
+        .collect()
+}
+
+/// Delete a safe and forget its remembered key, after the user types the safe's file name.
+/// With `keep_safe` only the key is forgotten. A file that isn't a valid safe is only
+/// deleted with `force`.
+pub fn destroy(safe_path: &str, keep_safe: bool, force: bool) -> Result<(), SkitError> {
+    let path = Path::new(safe_path);
+    let content = fs::read_to_string(path).map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            SkitError::SafeNotFound(safe_path.to_string())
+        } else {
+            SkitError::Io(e)
+        }
+    })?;
+    let safe = match Safe::parse(&content) {
+        Ok(safe) => Some(safe),
+        Err(_) if force && !keep_safe => None,
+        Err(e) => {
+            return Err(SkitError::ParseError(format!(
+                "{} is not a valid safe ({}); use --force to delete it anyway",
+                safe_path, e
+            )));
+        }
+    };
+
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(safe_path);
+    let action = if keep_safe {
+        format!("Forget the remembered key for {}", safe_path)
+    } else {
+        format!("Permanently delete {} and its remembered key", safe_path)
+    };
+    if !crate::input::confirm_typed(&format!("{}. Type '{}' to confirm: ", action, name), name)? {
+        print_info("Nothing was removed");
+        return Ok(());
+    }
+
+    let mut removed = match &safe {
+        Some(safe) => forget_key(&safe.uuid)?,
+        None => Vec::new(),
+    };
+    if !keep_safe {
+        fs::remove_file(path).map_err(SkitError::Io)?;
+        removed.insert(0, format!("safe {}", safe_path));
+        if let Some(ref safe) = safe {
+            crate::audit::record(safe, "destroy");
+        }
+    }
+
+    if removed.is_empty() {
+        print_info(&format!("No remembered key found for {}", safe_path));
+    }
+    for item in &removed {
+        print_success(&format!("Removed {}", item));
+    }
+
+    if !keep_safe {
+        let backups = leftover_backups(path);
+        if !backups.is_empty() {
+            let names: Vec<String> = backups.iter().map(|b| b.display().to_string()).collect();
+            print_warning(&format!(
+                "Backups of the safe were left in place: {}",
+                names.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shred_file_overwrites_in_place() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("safe.key");
+        fs::write(&path, "hunter2").unwrap();
+        // A second name for the same inode sees what was written to the original blocks
+        let link = temp_dir.path().join("link");
+        fs::hard_link(&path, &link).unwrap();
+
+        shred_file(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(fs::read(&link).unwrap(), vec![0u8; 7]);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::OutputFormat;
+use crate::crypto;
+use crate::display::{format_json_output, format_yaml_output, print_success};
+use crate::error::SkitError;
+use crate::password::get_password_with_auth_chain_formatted;
+use crate::types::{DiffOutput, Safe};
+use std::collections::{BTreeSet, HashMap};
+
+/// Compare two safes key-by-key without revealing secret values
+pub fn diff(safe_path: &str, other_path: &str, format: &OutputFormat) -> Result<(), SkitError> {
+    let safe = Safe::load(safe_path)?;
+    let other = Safe::load(other_path)?;
+
+    let values_a = decrypt_all(&safe, safe_path, format)?;
+    let values_b = decrypt_all(&other, other_path, format)?;
+
+    let keys_a: BTreeSet<&String> = values_a.keys().collect();
+    let keys_b: BTreeSet<&String> = values_b.keys().collect();
+
+    let output = DiffOutput {
+        only_a: keys_a.difference(&keys_b).map(|k| k.to_string()).collect(),
+        only_b: keys_b.difference(&keys_a).map(|k| k.to_string()).collect(),
+        changed: keys_a
+            .intersection(&keys_b)
+            .filter(|k| values_a[**k] != values_b[**k])
+            .map(|k| k.to_string())
+            .collect(),
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", format_json_output(&output)?),
+        OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+        _ => print_diff_table(safe_path, other_path, &output),
+    }
+
+    Ok(())
+}
+
+/// Decrypt every value in a safe, prompting for its password only when needed
+fn decrypt_all(
+    safe: &Safe,
+    safe_path: &str,
+    format: &OutputFormat,
+) -> Result<HashMap<String, String>, SkitError> {
+    let password = if safe.items.values().any(|item| item.is_encrypted) {
+        Some(
+            get_password_with_auth_chain_formatted(
+                safe,
+                safe_path,
+                &format!("Enter password for {}: ", safe_path),
+                Some(format),
+            )?
+            .0,
+        )
+    } else {
+        None
+    };
+
+    let mut values = HashMap::new();
+    for item in safe.items.values() {
+        let value = match (&password, item.is_encrypted) {
+            (Some(password), true) => crypto::DecryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .ciphertext(&item.value)
+                .context(&item.key)
+                .password(password)
+                .decrypt()
+                .map_err(SkitError::Crypto)?,
+            _ => item.value.clone(),
+        };
+        values.insert(item.key.clone(), value);
+    }
+
+    Ok(values)
+}
+
+fn print_diff_table(safe_path: &str, other_path: &str, output: &DiffOutput) {
+    if output.only_a.is_empty() && output.only_b.is_empty() && output.changed.is_empty() {
+        print_success(&format!(
+            "No differences between {} and {}",
+            safe_path, other_path
+        ));
+        return;
+    }
+
+    let sections = [
+        (format!("Only in {}", safe_path), &output.only_a),
+        (format!("Only in {}", other_path), &output.only_b),
+        ("Changed values".to_string(), &output.changed),
+    ];
+
+    let mut first = true;
+    for (title, keys) in sections {
+        if keys.is_empty() {
+            continue;
+        }
+        if !first {
+            println!();
+        }
+        first = false;
+
+        println!("{} ({})", title, keys.len());
+        for (i, key) in keys.iter().enumerate() {
+            let prefix = if i == keys.len() - 1 {
+                "└─"
+            } else {
+                "├─"
+            };
+            println!("{} {}", prefix, key);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use crate::OutputFormat;
+use crate::display::{format_json_output, format_yaml_output};
+use crate::error::SkitError;
+use crate::key_index;
+use crate::types::{DoctorCheck, DoctorOutput, Safe};
+use std::io::IsTerminal;
+use std::path::Path;
+
+pub const STATUS_PASS: &str = "pass";
+pub const STATUS_WARN: &str = "warn";
+pub const STATUS_FAIL: &str = "fail";
+pub const STATUS_SKIP: &str = "skip";
+
+fn check(name: &str, status: &str, critical: bool, detail: impl Into<String>) -> DoctorCheck {
+    DoctorCheck {
+        name: name.to_string(),
+        status: status.to_string(),
+        critical,
+        detail: detail.into(),
+    }
+}
+
+/// The safe file exists and parses
+fn check_safe(safe_path: &str) -> (DoctorCheck, Option<Safe>) {
+    const NAME: &str = "Safe file";
+    if !Path::new(safe_path).exists() {
+        return (
+            check(
+                NAME,
+                STATUS_FAIL,
+                true,
+                format!(
+                    "{} not found; create it with 'skit init' or pick another with --safe",
+                    safe_path
+                ),
+            ),
+            None,
+        );
+    }
+    match Safe::load(safe_path) {
+        Ok(safe) => (
+            check(
+                NAME,
+                STATUS_PASS,
+                true,
+                format!("{} ({} secrets)", safe_path, safe.items.len()),
+            ),
+            Some(safe),
+        ),
+        Err(e) => (
+            check(NAME, STATUS_FAIL, true, format!("{}: {}", safe_path, e)),
+            None,
+        ),
+    }
+}
+
+/// `SKIT_SAFEKEY`, when set, must unlock the safe: commands fail outright if it doesn't
+fn check_env_password(safe_path: &str, safe: Option<&Safe>) -> DoctorCheck {
+    const NAME: &str = "SKIT_SAFEKEY";
+    let Some(password) = crate::password::try_get_password_from_env(safe_path) else {
+        return check(NAME, STATUS_SKIP, false, "Not set");
+    };
+    let password = zeroize::Zeroizing::new(password);
+    match safe {
+        None => check(
+            NAME,
+            STATUS_SKIP,
+            false,
+            "Set, but there is no safe to check it against",
+        ),
+        Some(safe) if safe.verify_password(&password).is_ok() => {
+            check(NAME, STATUS_PASS, true, "Set and unlocks the safe")
+        }
+        Some(_) => check(
+            NAME,
+            STATUS_FAIL,
+            true,
+            "Set but does not unlock the safe; every command will fail until it is fixed or unset",
+        ),
+    }
+}
+
+/// A saved key file, when present, must unlock the safe
+fn check_key_file(safe: Option<&Safe>) -> DoctorCheck {
+    const NAME: &str = "Saved key";
+    let Some(safe) = safe else {
+        return check(NAME, STATUS_SKIP, false, "No safe to look up a key for");
+    };
+    let Some(key_file) = key_index::keys_dir().map(|dir| dir.join(format!("{}.key", safe.uuid)))
+    else {
+        return check(NAME, STATUS_SKIP, false, "No home directory");
+    };
+    if !key_file.exists() {
+        return check(
+            NAME,
+            STATUS_SKIP,
+            false,
+            "No key file saved for this safe ('skit remember-safekey' saves one)",
+        );
+    }
+    let password = match std::fs::read_to_string(&key_file) {
+        Ok(content) => zeroize::Zeroizing::new(content.trim().to_string()),
+        Err(e) => {
+            return check(
+                NAME,
+                STATUS_FAIL,
+                true,
+                format!("Can't read {}: {}", key_file.display(), e),
+            );
+        }
+    };
+    if safe.verify_password(&password).is_ok() {
+        check(
+            NAME,
+            STATUS_PASS,
+            true,
+            format!("{} unlocks the safe", key_file.display()),
+        )
+    } else {
+        check(
+            NAME,
+            STATUS_FAIL,
+            true,
+            format!(
+                "{} does not unlock the safe; remove it with 'skit destroy --keep-safe' and remember the key again",
+                key_file.display()
+            ),
+        )
+    }
+}
+
+/// Saved key files and their directory should only be accessible to their owner
+fn check_key_permissions() -> DoctorCheck {
+    const NAME: &str = "Key permissions";
+    let Some(dir) = key_index::keys_dir().filter(|dir| dir.is_dir()) else {
+        return check(NAME, STATUS_SKIP, false, "No saved keys directory");
+    };
+    match crate::commands::fix_perms::exposed_paths(&dir) {
+        Ok(exposed) if exposed.is_empty() => check(
+            NAME,
+            STATUS_PASS,
+            false,
+            format!("{} is owner-only", dir.display()),
+        ),
+        Ok(exposed) => check(
+            NAME,
+            STATUS_WARN,
+            false,
+            format!(
+                "Accessible to other users: {}; run 'skit fix-perms'",
+                exposed.join(", ")
+            ),
+        ),
+        Err(e) => check(NAME, STATUS_WARN, false, e.to_string()),
+    }
+}
+
+/// Where the AWS SDK would find credentials for `ssm` and `asm`, without contacting AWS
+fn aws_credential_source() -> Option<String> {
+    let set = |name: &str| std::env::var(name).is_ok_and(|v| !v.is_empty());
+    if set("AWS_ACCESS_KEY_ID") && set("AWS_SECRET_ACCESS_KEY") {
+        return Some("AWS_ACCESS_KEY_ID environment variable".to_string());
+    }
+    if set("AWS_PROFILE") {
+        return Some(format!(
+            "AWS_PROFILE={}",
+            std::env::var("AWS_PROFILE").unwrap_or_default()
+        ));
+    }
+    if set("AWS_WEB_IDENTITY_TOKEN_FILE") {
+        return Some("web identity token".to_string());
+    }
+    if set("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI") || set("AWS_CONTAINER_CREDENTIALS_FULL_URI") {
+        return Some("container credentials".to_string());
+    }
+    let aws_dir = dirs::home_dir()?.join(".aws");
+    ["credentials", "config"]
+        .iter()
+        .map(|name| aws_dir.join(name))
+        .find(|path| path.exists())
+        .map(|path| path.display().to_string())
+}
+
+fn check_aws(safe: Option<&Safe>) -> DoctorCheck {
+    const NAME: &str = "AWS credentials";
+    let uses_aws = safe.is_some_and(|safe| safe.ssm_prefix.is_some() || safe.asm_prefix.is_some());
+    match aws_credential_source() {
+        Some(source) => check(NAME, STATUS_PASS, false, format!("Found {}", source)),
+        // Instance roles can still work, so this is never more than a warning
+        None if uses_aws => check(
+            NAME,
+            STATUS_WARN,
+            false,
+            "None found, but the safe has an SSM/Secrets Manager prefix; ssm and asm commands need credentials unless an instance role provides them",
+        ),
+        None => check(
+            NAME,
+            STATUS_SKIP,
+            false,
+            "None found (only needed for ssm and asm)",
+        ),
+    }
+}
+
+/// Masked password prompts need an interactive terminal that supports raw mode
+fn check_terminal() -> DoctorCheck {
+    const NAME: &str = "Terminal";
+    if crate::input::is_non_interactive() {
+        return check(
+            NAME,
+            STATUS_SKIP,
+            false,
+            "Prompts are disabled (--no-input or CI); passwords must come from SKIT_SAFEKEY, --password-file or a saved key",
+        );
+    }
+    if !std::io::stdin().is_terminal() {
+        return check(
+            NAME,
+            STATUS_WARN,
+            false,
+            "stdin is not a terminal, so password prompts can't be masked; use SKIT_SAFEKEY or --password-file",
+        );
+    }
+    match crossterm::terminal::enable_raw_mode() {
+        Ok(()) => {
+            let _ = crossterm::terminal::disable_raw_mode();
+            check(NAME, STATUS_PASS, false, "Supports masked password input")
+        }
+        Err(e) => check(
+            NAME,
+            STATUS_WARN,
+            false,
+            format!(
+                "Raw mode unavailable ({}); passwords will be read unmasked",
+                e
+            ),
+        ),
+    }
+}
+
+fn print_checks(output: &DoctorOutput) {
+    for check in &output.checks {
+        let mark = match check.status.as_str() {
+            STATUS_PASS => "✅",
+            STATUS_WARN => "⚠️ ",
+            STATUS_FAIL => "❌",
+            _ => "➖",
+        };
+        println!("{} {:16} {}", mark, check.name, check.detail);
+    }
+    println!();
+    if output.ok {
+        println!("No critical problems found");
+    } else {
+        println!("Critical problems found; fix the ❌ items above");
+    }
+}
+
+/// Check the safe, the ways of unlocking it and the environment for common setup problems.
+/// Fails if any critical check fails.
+pub fn doctor(safe_path: &str, format: &OutputFormat) -> Result<(), SkitError> {
+    let (safe_check, safe) = check_safe(safe_path);
+    let checks = vec![
+        safe_check,
+        check_env_password(safe_path, safe.as_ref()),
+        check_key_file(safe.as_ref()),
+        check_key_permissions(),
+        check_aws(safe.as_ref()),
+        check_terminal(),
+    ];
+    let failed = checks
+        .iter()
+        .filter(|check| check.critical && check.status == STATUS_FAIL)
+        .count();
+    let output = DoctorOutput {
+        ok: failed == 0,
+        checks,
+    };
+
+    match format {
+        OutputFormat::Json => println!("{}", format_json_output(&output)?),
+        OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+        _ => print_checks(&output),
+    }
+
+    if failed > 0 {
+        return Err(SkitError::VerificationFailed(format!(
+            "{} critical check(s) failed",
+            failed
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_safe_reports_missing_and_invalid_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path_str = path.to_str().unwrap();
+
+        let (missing, safe) = check_safe(path_str);
+        assert_eq!(missing.status, STATUS_FAIL);
+        assert!(missing.detail.contains("skit init"));
+        assert!(safe.is_none());
+
+        std::fs::write(&path, "not a safe\n").unwrap();
+        assert_eq!(check_safe(path_str).0.status, STATUS_FAIL);
+
+        std::fs::write(
+            &path,
+            "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\nA=1\n",
+        )
+        .unwrap();
+        let (ok, safe) = check_safe(path_str);
+        assert_eq!(ok.status, STATUS_PASS);
+        assert!(safe.is_some());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::audit;
+use crate::commands::import::{parse_env_file, parse_key_list};
+use crate::crypto;
+use crate::display::{print_info, print_success};
+use crate::dotenv::quote_value;
+use crate::error::SkitError;
+use crate::password::get_password_with_auth_chain;
+use crate::types::Safe;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use zeroize::Zeroizing;
+
+const PLAIN_MARKER: &str = "# plain";
+
+/// Removes the decrypted temp file when dropped, including on early returns
+struct TempSecretFile {
+    path: PathBuf,
+}
+
+impl Drop for TempSecretFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub fn edit(safe_path: &str, key: Option<&str>, plain_keys: Option<&str>) -> Result<(), SkitError> {
+    match key {
+        Some(key) => edit_value(safe_path, key),
+        None => edit_all(safe_path, plain_keys),
+    }
+}
+
+/// Edit a single (possibly multi-line) value in the editor
+fn edit_value(safe_path: &str, key: &str) -> Result<(), SkitError> {
+    let mut safe = Safe::load(safe_path)?;
+    let before = safe.items.clone();
+    let item = safe
+        .find_item(key)
+        .ok_or_else(|| safe.key_not_found(key))?
+        .clone();
+
+    let password = if item.is_encrypted {
+        Some(Zeroizing::new(get_password_with_auth_chain(
+            &safe,
+            safe_path,
+            "Enter safe password: ",
+        )?))
+    } else {
+        None
+    };
+
+    let original = match &password {
+        Some(password) => Zeroizing::new(
+            crypto::DecryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .ciphertext(&item.value)
+                .context(&item.key)
+                .password(password)
+                .decrypt()
+                .map_err(SkitError::Crypto)?,
+        ),
+        None => Zeroizing::new(item.value.clone()),
+    };
+
+    let temp = TempSecretFile {
+        path: std::env::temp_dir().join(format!("skit-edit-{}.txt", uuid::Uuid::new_v4())),
+    };
+    crate::fs_utils::write_secret_file_secure(&temp.path, &original)?;
+    launch_editor(&temp.path)?;
+    let mut edited = Zeroizing::new(fs::read_to_string(&temp.path).map_err(SkitError::Io)?);
+    drop(temp);
+
+    // Most editors append a final newline; drop it unless the original had one
+    if !original.ends_with('\n') && edited.ends_with('\n') {
+        edited.pop();
+        if edited.ends_with('\r') {
+            edited.pop();
+        }
+    }
+
+    if *edited == *original {
+        print_info("No changes made");
+        return Ok(());
+    }
+
+    let stored_value = match &password {
+        Some(password) => crypto::EncryptBuilder::new()
+            .kdf_params(safe.kdf_params)
+            .master_key(safe.master_key.as_deref())
+            .cipher(safe.cipher)
+            .plaintext(&edited)
+            .context(&item.key)
+            .password(password)
+            .encrypt()
+            .map_err(SkitError::Crypto)?,
+        None => edited.to_string(),
+    };
+
+    safe.add_or_update_item(item.key.clone(), stored_value, item.is_encrypted);
+    safe.save(safe_path)?;
+    audit::record_changes(&safe, &before, "edit");
+    print_success(&format!("Updated {} in safe", item.key));
+
+    Ok(())
+}
+
+/// Edit every secret in the safe as a dotenv buffer
+fn edit_all(safe_path: &str, plain_keys: Option<&str>) -> Result<(), SkitError> {
+    let mut safe = Safe::load(safe_path)?;
+    let before = safe.items.clone();
+    let forced_plain: HashSet<String> = plain_keys.map(parse_key_list).unwrap_or_default();
+
+    // New or changed values are encrypted by default, so a password is always needed
+    let password = get_password_with_auth_chain(&safe, safe_path, "Enter safe password: ")?;
+
+    let mut keys: Vec<_> = safe.items.keys().cloned().collect();
+    keys.sort();
+
+    let mut original: HashMap<String, (String, bool)> = HashMap::new();
+    for key in &keys {
+        let item = &safe.items[key];
+        let value = if item.is_encrypted {
+            crypto::DecryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .ciphertext(&item.value)
+                .context(key)
+                .password(&password)
+                .decrypt()
+                .map_err(SkitError::Crypto)?
+        } else {
+            item.value.clone()
+        };
+        original.insert(key.clone(), (value, !item.is_encrypted));
+    }
+
+    let mut content = String::new();
+    content.push_str("# Editing secrets for ");
+    content.push_str(safe_path);
+    content.push_str("\n# Values are encrypted on save. Append '");
+    content.push_str(PLAIN_MARKER);
+    content.push_str("' to a line to store it as plain text.\n");
+    content
+        .push_str("# Delete a line to remove the secret. Lines starting with '#' are ignored.\n\n");
+    for key in &keys {
+        let (value, is_plain) = &original[key];
+        let value = quote_value(value);
+        if *is_plain {
+            content.push_str(&format!("{}={} {}\n", key, value, PLAIN_MARKER));
+        } else {
+            content.push_str(&format!("{}={}\n", key, value));
+        }
+    }
+
+    let temp = TempSecretFile {
+        path: std::env::temp_dir().join(format!("skit-edit-{}.env", uuid::Uuid::new_v4())),
+    };
+    crate::fs_utils::write_secret_file_secure(&temp.path, &content)?;
+
+    let edited = loop {
+        launch_editor(&temp.path)?;
+
+        let edited_content = fs::read_to_string(&temp.path).map_err(SkitError::Io)?;
+        match parse_edited(&edited_content) {
+            Ok(entries) => break entries,
+            Err(e) if crate::input::is_non_interactive() => return Err(e),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                if !prompt_reopen()? {
+                    print_info("Edit aborted - safe left unchanged");
+                    return Ok(());
+                }
+            }
+        }
+    };
+    drop(temp);
+
+    let mut added = 0;
+    let mut updated = 0;
+    let mut seen = HashSet::new();
+
+    for (key, value, marked_plain) in edited {
+        let is_plain = marked_plain || forced_plain.contains(&key);
+        seen.insert(key.clone());
+
+        match original.get(&key) {
+            Some((old_value, old_plain)) if *old_value == value && *old_plain == is_plain => {
+                continue; // Unchanged: keep existing ciphertext
+            }
+            Some(_) => updated += 1,
+            None => added += 1,
+        }
+
+        let stored_value = if is_plain {
+            value
+        } else {
+            crypto::EncryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .cipher(safe.cipher)
+                .plaintext(&value)
+                .context(&key)
+                .password(&password)
+                .encrypt()
+                .map_err(SkitError::Crypto)?
+        };
+        safe.add_or_update_item(key, stored_value, !is_plain);
+    }
+
+    let removed: Vec<String> = keys.into_iter().filter(|k| !seen.contains(k)).collect();
+    for key in &removed {
+        safe.items.shift_remove(key);
+    }
+
+    if added == 0 && updated == 0 && removed.is_empty() {
+        print_info("No changes made");
+        return Ok(());
+    }
+
+    safe.save(safe_path)?;
+    audit::record_changes(&safe, &before, "edit");
+    print_success(&format!(
+        "Saved changes: {} added, {} updated, {} removed",
+        added,
+        updated,
+        removed.len()
+    ));
+
+    Ok(())
+}
+
+/// Parse the edited buffer, honouring the trailing plain-text marker
+fn parse_edited(content: &str) -> Result<Vec<(String, String, bool)>, SkitError> {
+    let mut plain = HashSet::new();
+    let mut stripped = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim_end();
+        match trimmed.strip_suffix(PLAIN_MARKER) {
+            Some(rest) if !trimmed.trim_start().starts_with('#') => {
+                if let Some(eq_pos) = rest.find('=') {
+                    plain.insert(rest[..eq_pos].trim().to_string());
+                }
+                stripped.push_str(rest.trim_end());
+            }
+            _ => stripped.push_str(line),
+        }
+        stripped.push('\n');
+    }
+
+    let mut entries = Vec::new();
+    let mut keys = HashSet::new();
+    for (key, value) in parse_env_file(&stripped)? {
+        if !keys.insert(key.clone()) {
+            return Err(SkitError::ParseError(format!("Duplicate key '{}'", key)));
+        }
+        let is_plain = plain.contains(&key);
+        entries.push((key, value, is_plain));
+    }
+
+    Ok(entries)
+}
+
+fn launch_editor(path: &Path) -> Result<(), SkitError> {
+    let editor = std::env::var("VISUAL")
+        .ok()
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+
+    // Allow editors configured with arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| {
+            SkitError::Io(io::Error::new(
+                e.kind(),
+                format!("Failed to launch editor '{}': {}", program, e),
+            ))
+        })?;
+
+    if !status.success() {
+        return Err(SkitError::ParseError(format!(
+            "Editor '{}' exited with {} - safe left unchanged",
+            program, status
+        )));
+    }
+
+    Ok(())
+}
+
+fn prompt_reopen() -> Result<bool, SkitError> {
+    print!("Re-open the editor to fix it? (Y/n): ");
+    io::stdout().flush().map_err(SkitError::Io)?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).map_err(SkitError::Io)?;
+
+    let input = input.trim().to_lowercase();
+    Ok(input.is_empty() || input == "y" || input == "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_edited_plain_marker() {
+        let content = "# header # plain\nAPI_KEY=secret\nPORT=3000 # plain\n";
+        let entries = parse_edited(content).unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("API_KEY".to_string(), "secret".to_string(), false),
+                ("PORT".to_string(), "3000".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_edited_rejects_duplicates() {
+        assert!(parse_edited("A=1\nA=2\n").is_err());
+    }
+}
@@ -1,15 +1,21 @@
 use crate::OutputFormat;
 use crate::commands::template::CommandTemplate;
-use crate::crypto;
 use crate::display::shell_quote;
 use crate::error::SkitError;
-use crate::shell::detect_shell;
+use crate::key_filter::KeyFilter;
+use crate::shell::{ShellInfo, detect_shell};
 use crate::types::Safe;
 use crate::validation::is_valid_env_key;
 
-/// Arguments for the env command (no arguments needed)
+/// Arguments for the env command
 #[derive(Debug)]
-pub struct EnvArgs;
+pub struct EnvArgs {
+    pub filter: KeyFilter,
+    /// Shell syntax to emit; detected from the environment when not given
+    pub shell: Option<String>,
+    /// Fail instead of skipping values that can't be decrypted
+    pub strict: bool,
+}
 
 /// Output for the env command
 #[derive(Debug)]
@@ -25,18 +31,26 @@ impl CommandTemplate for EnvCommand {
     type Args = EnvArgs;
     type Output = EnvOutput;
 
-    fn requires_authentication(&self, safe: &Safe, _args: &Self::Args) -> bool {
-        // Need authentication if there are any encrypted items
-        safe.items.values().any(|item| item.is_encrypted)
+    fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
+        // Need authentication if any item that will be output is encrypted
+        safe.items
+            .values()
+            .any(|item| item.is_encrypted && args.filter.allows_item(item))
     }
 
     fn execute_operation(
         &self,
         safe: &mut Safe,
         password: Option<String>,
-        _args: Self::Args,
+        args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
-        let shell = detect_shell();
+        let shell = match args.shell {
+            Some(name) => ShellInfo { name },
+            None => detect_shell(),
+        };
+
+        // Drop filtered-out keys before decrypting
+        safe.items.retain(|_, item| args.filter.allows_item(item));
 
         if safe.items.is_empty() {
             return Ok(EnvOutput {
@@ -49,36 +63,34 @@ impl CommandTemplate for EnvCommand {
         let mut keys: Vec<_> = safe.items.keys().collect();
         keys.sort();
 
-        let mut entries = Vec::new();
+        let items: Vec<_> = keys.iter().map(|key| &safe.items[*key]).collect();
+        let decrypted = safe.decrypt_selected(&items, password.as_deref());
 
-        for key in keys {
-            let item = &safe.items[key];
-
-            let value = if item.is_encrypted {
-                if let Some(ref pwd) = password {
-                    match crypto::DecryptBuilder::new()
-                        .ciphertext(&item.value)
-                        .password(pwd)
-                        .decrypt()
-                    {
-                        Ok(v) => v,
-                        Err(_) => {
-                            eprintln!("# Warning: Failed to decrypt '{}'", item.key);
-                            continue;
-                        }
-                    }
-                } else {
+        let mut entries = Vec::new();
+        let mut failed = Vec::new();
+
+        for (item, result) in items.into_iter().zip(decrypted) {
+            let value = match result {
+                Some(Ok(v)) => v,
+                Some(Err(_)) => {
+                    eprintln!("# Warning: Failed to decrypt '{}'", item.key);
+                    failed.push(item.key.clone());
+                    continue;
+                }
+                None => {
                     eprintln!(
                         "# Warning: No password provided for encrypted key '{}'",
                         item.key
                     );
                     continue;
                 }
-            } else {
-                item.value.clone()
             };
 
-            entries.push((item.key.clone(), value));
+            entries.push((args.filter.output_name(&item.key).to_string(), value));
+        }
+
+        if args.strict && !failed.is_empty() {
+            return Err(SkitError::DecryptionFailed(failed));
         }
 
         Ok(EnvOutput {
@@ -94,35 +106,132 @@ impl CommandTemplate for EnvCommand {
                 eprintln!("# Warning: Skipping invalid environment key: {}", key);
                 continue;
             }
-            match output.shell_name.as_str() {
-                "fish" => {
-                    println!("set -x {} {}", key, shell_quote(&value));
-                }
-                "powershell" => {
-                    println!("$env:{} = {}", key, shell_quote(&value));
-                }
-                "cmd" => {
-                    println!("set {}={}", key, value); // cmd doesn't need quoting like Unix
-                }
-                "csh" | "tcsh" => {
-                    println!("setenv {} {}", key, shell_quote(&value));
-                }
-                "nu" => {
-                    println!("let-env {} = {}", key, shell_quote(&value));
-                }
-                _ => {
-                    println!("export {}={}", key, shell_quote(&value));
-                }
-            }
+            println!("{}", format_env_line(&output.shell_name, &key, &value));
         }
         Ok(())
     }
 }
 
+/// One line setting `key` to `value` in the given shell's syntax and quoting
+pub fn format_env_line(shell_name: &str, key: &str, value: &str) -> String {
+    match shell_name {
+        "fish" => format!("set -x {} {}", key, fish_quote(value)),
+        "powershell" => format!("$env:{} = {}", key, powershell_quote(value)),
+        // The quotes around the whole assignment keep spaces and trailing characters literal
+        "cmd" => format!("set \"{}={}\"", key, value),
+        "csh" | "tcsh" => format!("setenv {} {}", key, shell_quote(value)),
+        "nu" => format!("$env.{} = {}", key, nu_quote(value)),
+        _ => format!("export {}={}", key, shell_quote(value)),
+    }
+}
+
+/// fish single quotes only treat `\\` and `\'` as escapes
+fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// PowerShell single quotes are literal apart from `''`, which stands for one quote
+fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Nushell double-quoted string with backslash escapes
+fn nu_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 /// Output secrets for shell sourcing
-pub fn env(safe_path: &str) -> Result<(), SkitError> {
+pub fn env(
+    safe_path: &str,
+    filter: KeyFilter,
+    shell: Option<&str>,
+    strict: bool,
+) -> Result<(), SkitError> {
     let command = EnvCommand;
-    let args = EnvArgs;
+    let args = EnvArgs {
+        filter,
+        shell: shell.map(|s| s.to_string()),
+        strict,
+    };
 
     command.execute(safe_path, &OutputFormat::Env, args)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUE: &str = "it's a \"quoted\" value";
+
+    #[test]
+    fn test_format_env_line_posix() {
+        for shell in ["bash", "zsh", "sh"] {
+            assert_eq!(format_env_line(shell, "KEY", "plain"), "export KEY=plain");
+            assert_eq!(
+                format_env_line(shell, "KEY", VALUE),
+                "export KEY='it'\"'\"'s a \"quoted\" value'"
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_env_line_fish() {
+        assert_eq!(
+            format_env_line("fish", "KEY", VALUE),
+            "set -x KEY 'it\\'s a \"quoted\" value'"
+        );
+        assert_eq!(
+            format_env_line("fish", "KEY", "a\\b"),
+            "set -x KEY 'a\\\\b'"
+        );
+    }
+
+    #[test]
+    fn test_format_env_line_nu() {
+        assert_eq!(
+            format_env_line("nu", "KEY", VALUE),
+            "$env.KEY = \"it's a \\\"quoted\\\" value\""
+        );
+        assert_eq!(
+            format_env_line("nu", "KEY", "a\\b\nc"),
+            "$env.KEY = \"a\\\\b\\nc\""
+        );
+    }
+
+    #[test]
+    fn test_format_env_line_powershell() {
+        assert_eq!(
+            format_env_line("powershell", "KEY", VALUE),
+            "$env:KEY = 'it''s a \"quoted\" value'"
+        );
+    }
+
+    #[test]
+    fn test_format_env_line_cmd() {
+        assert_eq!(
+            format_env_line("cmd", "KEY", VALUE),
+            "set \"KEY=it's a \"quoted\" value\""
+        );
+    }
+
+    #[test]
+    fn test_format_env_line_csh() {
+        assert_eq!(
+            format_env_line("csh", "KEY", "two words"),
+            "setenv KEY 'two words'"
+        );
+    }
+}
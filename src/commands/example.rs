@@ -0,0 +1,201 @@
+use crate::OutputFormat;
+use crate::commands::import::parse_env_file;
+use crate::commands::template::CommandTemplate;
+use crate::display::print_success;
+use crate::error::SkitError;
+use crate::fs_utils;
+use crate::types::Safe;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Example file checked by `--check` when no path is given
+pub const DEFAULT_EXAMPLE_FILE: &str = ".env.example";
+
+/// Arguments for the example command
+#[derive(Debug)]
+pub struct ExampleArgs {
+    /// Write to this file instead of stdout
+    pub output: Option<String>,
+    /// Add `# encrypted` / `# plain` and description comments above each key
+    pub comments: bool,
+    /// Compare this example file against the safe instead of writing one
+    pub check: Option<String>,
+}
+
+/// Output for the example command
+#[derive(Debug)]
+pub enum ExampleOutput {
+    Generated {
+        content: String,
+        count: usize,
+        output: Option<String>,
+    },
+    InSync {
+        file: String,
+        count: usize,
+    },
+}
+
+/// Template-based implementation of the example command
+pub struct ExampleCommand;
+
+impl CommandTemplate for ExampleCommand {
+    type Args = ExampleArgs;
+    type Output = ExampleOutput;
+
+    fn requires_authentication(&self, _safe: &Safe, _args: &Self::Args) -> bool {
+        // Only key names, types and descriptions are used, never values
+        false
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        _password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        if let Some(file) = args.check {
+            let content = fs::read_to_string(&file).map_err(|e| {
+                SkitError::ParseError(format!("Failed to read example file '{}': {}", file, e))
+            })?;
+            let (missing, extra) = compare_example(safe, &content)?;
+            if !missing.is_empty() || !extra.is_empty() {
+                let mut message = format!("{} is out of sync with the safe", file);
+                if !missing.is_empty() {
+                    message.push_str(&format!("\n  Missing keys: {}", missing.join(", ")));
+                }
+                if !extra.is_empty() {
+                    message.push_str(&format!("\n  Extra keys: {}", extra.join(", ")));
+                }
+                message.push_str(&format!(
+                    "\nRun `skit example --output {}` to regenerate it",
+                    file
+                ));
+                return Err(SkitError::ParseError(message));
+            }
+            return Ok(ExampleOutput::InSync {
+                file,
+                count: safe.items.len(),
+            });
+        }
+
+        Ok(ExampleOutput::Generated {
+            content: render_example(safe, args.comments),
+            count: safe.items.len(),
+            output: args.output,
+        })
+    }
+
+    fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
+        match output {
+            ExampleOutput::Generated {
+                content,
+                count,
+                output: Some(file),
+            } => {
+                fs_utils::write_file_atomic(Path::new(&file), &content)?;
+                print_success(&format!("Wrote {} keys to {}", count, file));
+            }
+            ExampleOutput::Generated { content, .. } => print!("{}", content),
+            ExampleOutput::InSync { file, count } => {
+                print_success(&format!("{} matches the safe ({} keys)", file, count));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Render `KEY=` lines in key order, optionally preceded by a comment with the type and description
+pub fn render_example(safe: &Safe, comments: bool) -> String {
+    let mut keys: Vec<_> = safe.items.keys().collect();
+    keys.sort();
+
+    let mut content = String::new();
+    for key in keys {
+        let item = &safe.items[key];
+        if comments {
+            let kind = if item.is_encrypted {
+                "encrypted"
+            } else {
+                "plain"
+            };
+            match item.metadata.description.as_deref() {
+                Some(description) => content.push_str(&format!("# {}: {}\n", kind, description)),
+                None => content.push_str(&format!("# {}\n", kind)),
+            }
+        }
+        content.push_str(&format!("{}=\n", item.key));
+    }
+    content
+}
+
+/// Keys the example file lacks and keys it has that the safe does not, each sorted
+pub fn compare_example(
+    safe: &Safe,
+    content: &str,
+) -> Result<(Vec<String>, Vec<String>), SkitError> {
+    let example_keys: BTreeSet<String> = parse_env_file(content)?
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    let safe_keys: BTreeSet<String> = safe.items.keys().cloned().collect();
+
+    let missing = safe_keys.difference(&example_keys).cloned().collect();
+    let extra = example_keys.difference(&safe_keys).cloned().collect();
+    Ok((missing, extra))
+}
+
+/// Print a `.env.example` of the safe's keys without values, write it to a file, or check one
+pub fn example(
+    safe_path: &str,
+    output: Option<&str>,
+    comments: bool,
+    check: Option<&str>,
+) -> Result<(), SkitError> {
+    let command = ExampleCommand;
+    let args = ExampleArgs {
+        output: output.map(|o| o.to_string()),
+        comments,
+        check: check.map(|c| c.to_string()),
+    };
+
+    command.execute(safe_path, &OutputFormat::Env, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safe_with_items() -> Safe {
+        let content = "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n\
+                       PORT=8080\nAPI_KEY=ENC~v1~abc\n";
+        let mut safe = Safe::parse(content).unwrap();
+        safe.items.get_mut("API_KEY").unwrap().metadata.description =
+            Some("Billing webhooks".to_string());
+        safe
+    }
+
+    #[test]
+    fn test_render_example() {
+        let safe = safe_with_items();
+
+        assert_eq!(render_example(&safe, false), "API_KEY=\nPORT=\n");
+        assert_eq!(
+            render_example(&safe, true),
+            "# encrypted: Billing webhooks\nAPI_KEY=\n# plain\nPORT=\n"
+        );
+    }
+
+    #[test]
+    fn test_compare_example() {
+        let safe = safe_with_items();
+
+        let (missing, extra) = compare_example(&safe, &render_example(&safe, true)).unwrap();
+        assert!(missing.is_empty() && extra.is_empty());
+
+        let (missing, extra) = compare_example(&safe, "# app\nPORT=3000\nOLD_TOKEN=\n").unwrap();
+        assert_eq!(missing, vec!["API_KEY".to_string()]);
+        assert_eq!(extra, vec!["OLD_TOKEN".to_string()]);
+    }
+}
@@ -2,12 +2,92 @@ use crate::crypto;
 #[cfg(windows)]
 use crate::display::print_warning;
 use crate::error::SkitError;
+use crate::key_filter::KeyFilter;
 use crate::password::get_password_with_auth_chain;
 use crate::types::Safe;
+use crate::validation::is_valid_env_key;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::process::Command;
 
-pub fn exec(safe_path: &str, command_args: &[String]) -> Result<(), SkitError> {
+/// Host variables an `--isolated` child keeps when `--keep` is not given
+#[cfg(not(windows))]
+pub const DEFAULT_KEEP: &[&str] = &["PATH", "HOME", "USER", "LANG", "TERM"];
+/// Host variables an `--isolated` child keeps when `--keep` is not given
+#[cfg(windows)]
+pub const DEFAULT_KEEP: &[&str] = &[
+    "PATH",
+    "PATHEXT",
+    "SYSTEMROOT",
+    "USERPROFILE",
+    "TEMP",
+    "TMP",
+];
+
+/// Which host variables the child sees next to the safe's variables
+#[derive(Debug, Default)]
+pub struct ExecEnvironment {
+    /// Host variables passed through; `None` inherits the whole environment
+    pub keep: Option<Vec<String>>,
+    /// Ad-hoc `--env KEY=VALUE` assignments, applied after the safe's variables
+    pub extra: Vec<(String, String)>,
+}
+
+impl ExecEnvironment {
+    pub fn new(isolated: bool, keep: Option<&str>, env: &[String]) -> Result<Self, SkitError> {
+        let keep = isolated.then(|| match keep {
+            Some(list) => list
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+            None => DEFAULT_KEEP.iter().map(|name| name.to_string()).collect(),
+        });
+        let extra = env
+            .iter()
+            .map(|assignment| parse_assignment(assignment))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { keep, extra })
+    }
+
+    /// Whether a host variable is passed to the child
+    fn keeps(&self, name: &str) -> bool {
+        match &self.keep {
+            None => true,
+            // Variable names are case-insensitive on Windows
+            Some(keep) if cfg!(windows) => keep.iter().any(|k| k.eq_ignore_ascii_case(name)),
+            Some(keep) => keep.iter().any(|k| k == name),
+        }
+    }
+}
+
+/// Parse a `KEY=VALUE` assignment given with `--env`
+fn parse_assignment(assignment: &str) -> Result<(String, String), SkitError> {
+    let (key, value) = assignment.split_once('=').ok_or_else(|| {
+        SkitError::ParseError(format!(
+            "Invalid --env '{}' (expected KEY=VALUE)",
+            assignment
+        ))
+    })?;
+    if !is_valid_env_key(key) {
+        return Err(SkitError::ParseError(format!(
+            "Invalid --env key '{}' (must match [A-Za-z_][A-Za-z0-9_]*)",
+            key
+        )));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Run a command with the safe's secrets in its environment. Unless `strict` is off, a value
+/// that fails to decrypt aborts before the command starts.
+pub fn exec(
+    safe_path: &str,
+    command_args: &[String],
+    filter: &KeyFilter,
+    environment: &ExecEnvironment,
+    strict: bool,
+) -> Result<(), SkitError> {
     if command_args.is_empty() {
         return Err(SkitError::EmptyCommand);
     }
@@ -20,21 +100,36 @@ pub fn exec(safe_path: &str, command_args: &[String]) -> Result<(), SkitError> {
         print_warning("  Command: for /f \"tokens=*\" %i in ('skit env') do %i");
     }
 
-    let env_vars = prepare_environment(safe_path)?;
+    let env_vars = prepare_environment(safe_path, filter, strict)?;
 
     #[cfg(unix)]
     {
-        exec_replace_process(command_args, &env_vars); // Never returns
+        exec_replace_process(command_args, &env_vars, environment); // Never returns
     }
 
     #[cfg(not(unix))]
     {
-        exec_spawn_and_wait(command_args, &env_vars); // Never returns
+        exec_spawn_and_wait(command_args, &env_vars, environment); // Never returns
     }
 }
 
-fn prepare_environment(safe_path: &str) -> Result<HashMap<String, String>, SkitError> {
-    let safe = Safe::load(safe_path)?;
+fn prepare_environment(
+    safe_path: &str,
+    filter: &KeyFilter,
+    strict: bool,
+) -> Result<HashMap<String, String>, SkitError> {
+    let mut safe = Safe::load(safe_path)?;
+
+    let unmatched = filter.unmatched_only(safe.items.keys());
+    if !unmatched.is_empty() {
+        eprintln!(
+            "Warning: --only matched no keys in the safe: {}",
+            unmatched.join(", ")
+        );
+    }
+
+    // Drop filtered-out keys before decrypting so they never need the password
+    safe.items.retain(|_, item| filter.allows_item(item));
 
     if safe.items.is_empty() {
         return Ok(HashMap::new());
@@ -63,15 +158,23 @@ fn prepare_environment(safe_path: &str) -> Result<HashMap<String, String>, SkitE
     };
 
     // Second pass: decrypt and collect all values
+    let mut failed = Vec::new();
     for item in safe.items.values() {
         let value = if item.is_encrypted {
             if let Some(ref pwd) = password {
                 match crypto::DecryptBuilder::new()
+                    .kdf_params(safe.kdf_params)
+                    .master_key(safe.master_key.as_deref())
                     .ciphertext(&item.value)
+                    .context(&item.key)
                     .password(pwd)
                     .decrypt()
                 {
                     Ok(v) => v,
+                    Err(_) if strict => {
+                        failed.push(item.key.clone());
+                        continue;
+                    }
                     Err(_) => {
                         eprintln!("Warning: Failed to decrypt '{}', skipping", item.key);
                         continue;
@@ -87,31 +190,91 @@ fn prepare_environment(safe_path: &str) -> Result<HashMap<String, String>, SkitE
         } else {
             item.value.clone()
         };
-        env_vars.insert(item.key.clone(), value);
+        let name = filter.output_name(&item.key);
+        if !is_valid_env_key(name) {
+            eprintln!("Warning: Skipping invalid environment key: {}", name);
+            continue;
+        }
+        env_vars.insert(name.to_string(), value);
+    }
+
+    if !failed.is_empty() {
+        failed.sort();
+        return Err(SkitError::DecryptionFailed(failed));
     }
 
     Ok(env_vars)
 }
 
-#[cfg(unix)]
-fn exec_replace_process(command_args: &[String], env_vars: &HashMap<String, String>) -> ! {
-    use std::os::unix::process::CommandExt;
-
-    let program = &command_args[0];
-    let args = &command_args[1..];
-
+/// Build the child command from the host environment (all of it, or only the kept variables
+/// when isolated), the safe variables and any `--env` extras, in that order of precedence
+fn build_command(
+    command_args: &[String],
+    env_vars: &HashMap<String, String>,
+    environment: &ExecEnvironment,
+) -> Command {
+    let program = if environment.keep.is_some() {
+        resolve_program(&command_args[0])
+    } else {
+        PathBuf::from(&command_args[0])
+    };
     let mut cmd = Command::new(program);
-    cmd.args(args);
+    cmd.args(&command_args[1..]);
+    if environment.keep.is_some() {
+        cmd.env_clear();
+    }
 
-    // Inherit current environment and add/override with safe variables
+    // Inherit the current environment (or its kept part) and add/override with safe variables
     for (key, value) in std::env::vars() {
-        cmd.env(key, value);
+        if environment.keeps(&key) {
+            cmd.env(key, value);
+        }
     }
 
     for (key, value) in env_vars {
         cmd.env(key, value);
     }
 
+    for (key, value) in &environment.extra {
+        cmd.env(key, value);
+    }
+
+    cmd
+}
+
+/// Look a bare program name up on skit's own PATH. An isolated child may not keep PATH, and
+/// the program should still be found the same way it would be without `--isolated`.
+fn resolve_program(program: &str) -> PathBuf {
+    let bare = PathBuf::from(program);
+    if bare.components().count() != 1 {
+        return bare;
+    }
+    let Some(path) = std::env::var_os("PATH") else {
+        return bare;
+    };
+
+    let names = if cfg!(windows) && bare.extension().is_none() {
+        vec![format!("{}.exe", program), program.to_string()]
+    } else {
+        vec![program.to_string()]
+    };
+    std::env::split_paths(&path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+        .unwrap_or(bare)
+}
+
+#[cfg(unix)]
+fn exec_replace_process(
+    command_args: &[String],
+    env_vars: &HashMap<String, String>,
+    environment: &ExecEnvironment,
+) -> ! {
+    use std::os::unix::process::CommandExt;
+
+    let program = &command_args[0];
+    let mut cmd = build_command(command_args, env_vars, environment);
+
     // Use exec() to replace the current process entirely
     let err = cmd.exec(); // This never returns on success
 
@@ -121,21 +284,13 @@ fn exec_replace_process(command_args: &[String], env_vars: &HashMap<String, Stri
 }
 
 #[cfg(not(unix))]
-fn exec_spawn_and_wait(command_args: &[String], env_vars: &HashMap<String, String>) -> ! {
+fn exec_spawn_and_wait(
+    command_args: &[String],
+    env_vars: &HashMap<String, String>,
+    environment: &ExecEnvironment,
+) -> ! {
     let program = &command_args[0];
-    let args = &command_args[1..];
-
-    let mut cmd = Command::new(program);
-    cmd.args(args);
-
-    // Inherit current environment and add/override with safe variables
-    for (key, value) in std::env::vars() {
-        cmd.env(key, value);
-    }
-
-    for (key, value) in env_vars {
-        cmd.env(key, value);
-    }
+    let mut cmd = build_command(command_args, env_vars, environment);
 
     match cmd.status() {
         Ok(status) => {
@@ -148,3 +303,58 @@ fn exec_spawn_and_wait(command_args: &[String], env_vars: &HashMap<String, Strin
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_build_command_injects_env_vars() {
+        // Shared by the unix exec path and the spawn path
+        let env_vars = HashMap::from([("API_KEY".to_string(), "secret".to_string())]);
+        let cmd = build_command(
+            &["printenv".to_string(), "API_KEY".to_string()],
+            &env_vars,
+            &ExecEnvironment::default(),
+        );
+
+        assert_eq!(cmd.get_program(), "printenv");
+        assert_eq!(cmd.get_args().collect::<Vec<_>>(), vec!["API_KEY"]);
+        assert!(
+            cmd.get_envs()
+                .any(|(k, v)| k == "API_KEY" && v == Some(OsStr::new("secret")))
+        );
+    }
+
+    #[test]
+    fn test_isolated_environment_keeps_only_allowlist() {
+        let environment =
+            ExecEnvironment::new(true, Some("PATH, LANG"), &["EXTRA=a=b".to_string()]).unwrap();
+        assert!(environment.keeps("PATH"));
+        assert!(environment.keeps("LANG"));
+        assert!(!environment.keeps("HOME"));
+        assert_eq!(
+            environment.extra,
+            vec![("EXTRA".to_string(), "a=b".to_string())]
+        );
+
+        let defaults = ExecEnvironment::new(true, None, &[]).unwrap();
+        assert!(defaults.keeps("PATH"));
+        assert!(
+            ExecEnvironment::new(false, None, &[])
+                .unwrap()
+                .keeps("ANYTHING")
+        );
+        assert!(ExecEnvironment::new(false, None, &["NOEQUALS".to_string()]).is_err());
+        assert!(ExecEnvironment::new(false, None, &["1BAD=x".to_string()]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_program_uses_parent_path() {
+        let resolved = resolve_program("sh");
+        assert!(resolved.is_absolute(), "{}", resolved.display());
+        assert_eq!(resolve_program("./script.sh"), PathBuf::from("./script.sh"));
+    }
+}
@@ -1,17 +1,28 @@
 use crate::OutputFormat;
 use crate::commands::template::CommandTemplate;
-use crate::crypto;
+use crate::dotenv::quote_value;
 use crate::error::SkitError;
-use crate::types::Safe;
+use crate::fs_utils;
+use crate::types::{Safe, SortOrder};
+use std::path::Path;
+use zeroize::Zeroizing;
 
 /// Arguments for the export command
 #[derive(Debug)]
-pub struct ExportArgs;
+pub struct ExportArgs {
+    /// Write to this file instead of stdout
+    pub file: Option<String>,
+    pub force: bool,
+    /// Fail instead of skipping values that can't be decrypted
+    pub strict: bool,
+    pub sort: SortOrder,
+}
 
 /// Output for the export command
 #[derive(Debug)]
 pub struct ExportOutput {
     pub entries: Vec<(String, String)>, // (key, value) pairs
+    pub file: Option<String>,
 }
 
 /// Template-based implementation of the export command
@@ -21,6 +32,19 @@ impl CommandTemplate for ExportCommand {
     type Args = ExportArgs;
     type Output = ExportOutput;
 
+    fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
+        if let Some(file) = &args.file
+            && !args.force
+            && Path::new(file).exists()
+        {
+            return Err(SkitError::ParseError(format!(
+                "File '{}' already exists (use --force to overwrite)",
+                file
+            )));
+        }
+        Ok(())
+    }
+
     fn requires_authentication(&self, safe: &Safe, _args: &Self::Args) -> bool {
         // Need authentication if there are any encrypted items
         safe.items.values().any(|item| item.is_encrypted)
@@ -30,64 +54,89 @@ impl CommandTemplate for ExportCommand {
         &self,
         safe: &mut Safe,
         password: Option<String>,
-        _args: Self::Args,
+        args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
         if safe.items.is_empty() {
-            return Ok(ExportOutput { entries: vec![] });
+            return Ok(ExportOutput {
+                entries: vec![],
+                file: args.file,
+            });
         }
 
-        // Sort keys for consistent output
-        let mut keys: Vec<_> = safe.items.keys().collect();
-        keys.sort();
+        let keys = safe.sorted_keys(args.sort);
+
+        let items: Vec<_> = keys.iter().map(|key| &safe.items[*key]).collect();
+        let decrypted = safe.decrypt_selected(&items, password.as_deref());
 
         let mut entries = Vec::new();
+        let mut failed = Vec::new();
 
-        for key in keys {
-            let item = &safe.items[key];
-
-            let value = if item.is_encrypted {
-                if let Some(ref pwd) = password {
-                    match crypto::DecryptBuilder::new()
-                        .ciphertext(&item.value)
-                        .password(pwd)
-                        .decrypt()
-                    {
-                        Ok(v) => v,
-                        Err(_) => {
-                            eprintln!("# Warning: Failed to decrypt '{}'", item.key);
-                            continue;
-                        }
-                    }
-                } else {
+        for (item, result) in items.into_iter().zip(decrypted) {
+            let value = match result {
+                Some(Ok(v)) => v,
+                Some(Err(_)) => {
+                    eprintln!("# Warning: Failed to decrypt '{}'", item.key);
+                    failed.push(item.key.clone());
+                    continue;
+                }
+                None => {
                     eprintln!(
                         "# Warning: No password provided for encrypted key '{}'",
                         item.key
                     );
                     continue;
                 }
-            } else {
-                item.value.clone()
             };
 
             entries.push((item.key.clone(), value));
         }
 
-        Ok(ExportOutput { entries })
+        if args.strict && !failed.is_empty() {
+            return Err(SkitError::DecryptionFailed(failed));
+        }
+
+        Ok(ExportOutput {
+            entries,
+            file: args.file,
+        })
     }
 
     fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
-        // Output simple KEY=value format for piping to external commands
-        for (key, value) in output.entries {
-            println!("{}={}", key, value);
+        // KEY=value lines, quoted like `write-env` where a value would otherwise misparse
+        let content = Zeroizing::new(
+            output
+                .entries
+                .iter()
+                .map(|(key, value)| format!("{}={}\n", key, quote_value(value)))
+                .collect::<String>(),
+        );
+
+        match output.file {
+            Some(file) => {
+                fs_utils::write_secret_file_atomic(Path::new(&file), &content)?;
+                tracing::info!("✓ Exported {} secrets to {}", output.entries.len(), file);
+            }
+            None => print!("{}", content.as_str()),
         }
         Ok(())
     }
 }
 
-/// Output secrets in KEY=value format for piping to external commands
-pub fn export(safe_path: &str) -> Result<(), SkitError> {
+/// Output secrets in KEY=value format for piping to external commands, or write them to a file
+pub fn export(
+    safe_path: &str,
+    file: Option<&str>,
+    force: bool,
+    strict: bool,
+    sort: SortOrder,
+) -> Result<(), SkitError> {
     let command = ExportCommand;
-    let args = ExportArgs;
+    let args = ExportArgs {
+        file: file.map(|f| f.to_string()),
+        force,
+        strict,
+        sort,
+    };
 
     command.execute(safe_path, &OutputFormat::Env, args)
 }
@@ -0,0 +1,228 @@
+use crate::display::{print_info, print_success, print_warning};
+use crate::error::SkitError;
+use crate::key_index;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A key file other users may be able to read
+#[derive(Debug)]
+struct Violation {
+    path: PathBuf,
+    /// What is wrong, e.g. `mode 644`
+    detail: String,
+}
+
+/// Saved key files (`*.key`) in `dir`, sorted by name
+fn key_files(dir: &Path) -> Result<Vec<PathBuf>, SkitError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(SkitError::Io)? {
+        let path = entry.map_err(SkitError::Io)?.path();
+        if path.extension().is_some_and(|ext| ext == "key") && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(unix)]
+fn check(path: &Path) -> Result<Option<String>, SkitError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)
+        .map_err(SkitError::Io)?
+        .permissions()
+        .mode()
+        & 0o777;
+    Ok((mode != 0o600).then(|| format!("mode {:o}", mode)))
+}
+
+#[cfg(unix)]
+fn repair(path: &Path) -> Result<(), SkitError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(SkitError::Io)
+}
+
+/// The keys directory itself should not let other users in (0o700)
+#[cfg(unix)]
+fn check_dir(dir: &Path) -> Result<Option<String>, SkitError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(dir)
+        .map_err(SkitError::Io)?
+        .permissions()
+        .mode()
+        & 0o777;
+    Ok((mode & 0o077 != 0).then(|| format!("mode {:o}", mode)))
+}
+
+#[cfg(unix)]
+fn repair_dir(dir: &Path) -> Result<(), SkitError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700)).map_err(SkitError::Io)
+}
+
+#[cfg(windows)]
+fn check(path: &Path) -> Result<Option<String>, SkitError> {
+    Ok((!crate::fs_utils::is_owner_only(path)?).then(|| "accessible to other users".to_string()))
+}
+
+#[cfg(windows)]
+fn repair(path: &Path) -> Result<(), SkitError> {
+    crate::fs_utils::restrict_to_owner(path)
+}
+
+/// Key files in `dir` that aren't owner-only (and on Unix `dir` itself), fixing each one
+/// unless `check_only`
+#[cfg(any(unix, windows))]
+fn scan(dir: &Path, check_only: bool) -> Result<(usize, Vec<Violation>), SkitError> {
+    let files = key_files(dir)?;
+    let mut violations = Vec::new();
+
+    #[cfg(unix)]
+    if let Some(detail) = check_dir(dir)? {
+        if !check_only {
+            repair_dir(dir)?;
+        }
+        violations.push(Violation {
+            path: dir.to_path_buf(),
+            detail,
+        });
+    }
+    for path in &files {
+        if let Some(detail) = check(path)? {
+            if !check_only {
+                repair(path)?;
+            }
+            violations.push(Violation {
+                path: path.clone(),
+                detail,
+            });
+        }
+    }
+    Ok((files.len(), violations))
+}
+
+/// Key files in `dir` (and `dir` itself) that other users may be able to access, described as
+/// `path (detail)`, without changing anything
+pub fn exposed_paths(dir: &Path) -> Result<Vec<String>, SkitError> {
+    #[cfg(any(unix, windows))]
+    {
+        let (_, violations) = scan(dir, true)?;
+        Ok(violations
+            .iter()
+            .map(|v| format!("{} ({})", v.path.display(), v.detail))
+            .collect())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = dir;
+        Ok(Vec::new())
+    }
+}
+
+/// Make every saved key file in `~/.config/skit/keys` readable by its owner only (0o600),
+/// and the directory itself owner-only (0o700).
+/// With `check_only` nothing is changed, and any violation fails the command.
+pub fn fix_perms(check_only: bool) -> Result<(), SkitError> {
+    let Some(dir) = key_index::keys_dir().filter(|dir| dir.is_dir()) else {
+        print_info("No saved keys directory found - nothing to check");
+        return Ok(());
+    };
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = check_only;
+        print_warning(&format!(
+            "Key file permissions can't be enforced on this platform; check {} by hand",
+            dir.display()
+        ));
+        Ok(())
+    }
+
+    #[cfg(any(unix, windows))]
+    {
+        let (total, violations) = scan(&dir, check_only)?;
+        if violations.is_empty() {
+            print_success(&format!("All {} key file(s) are owner-only", total));
+            return Ok(());
+        }
+
+        for violation in &violations {
+            if check_only {
+                print_warning(&format!(
+                    "{} is not owner-only ({})",
+                    violation.path.display(),
+                    violation.detail
+                ));
+            } else {
+                print_success(&format!(
+                    "Restricted {} to its owner (was {})",
+                    violation.path.display(),
+                    violation.detail
+                ));
+            }
+        }
+
+        if check_only {
+            print_info("Run without --check to fix them");
+            return Err(SkitError::VerificationFailed(format!(
+                "{} path(s) in {} are not owner-only",
+                violations.len(),
+                dir.display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn write_key(dir: &Path, name: &str, mode: u32) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, "password").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        path
+    }
+
+    fn mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    fn test_scan_reports_and_repairs_open_key_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let dir = temp_dir.path();
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700)).unwrap();
+        let open = write_key(dir, "a.key", 0o644);
+        let fine = write_key(dir, "b.key", 0o600);
+        let other = write_key(dir, "index.json", 0o644);
+
+        let (total, violations) = scan(dir, true).unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, open);
+        assert_eq!(violations[0].detail, "mode 644");
+        assert_eq!(mode(&open), 0o644);
+
+        let (_, violations) = scan(dir, false).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(mode(&open), 0o600);
+        assert_eq!(mode(&fine), 0o600);
+        assert_eq!(mode(&other), 0o644);
+
+        assert!(scan(dir, true).unwrap().1.is_empty());
+
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o755)).unwrap();
+        let (_, violations) = scan(dir, false).unwrap();
+        assert_eq!(violations[0].path, dir);
+        assert_eq!(mode(dir), 0o700);
+    }
+}
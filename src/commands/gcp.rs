@@ -0,0 +1,157 @@
+use crate::commands::pull::{PullOutcome, PulledEntry, apply_pulled};
+use crate::commands::template::{CommandTemplate, MessageOutput};
+use crate::error::SkitError;
+use crate::gcp;
+use crate::types::Safe;
+
+/// Arguments for the Secret Manager pull command
+#[derive(Debug)]
+pub struct GcpPullArgs {
+    pub project: Option<String>,
+    pub prefix: String,
+    pub replace: bool,
+    pub no_overwrite: bool,
+    pub dry_run: bool,
+}
+
+/// Template-based implementation of the Google Secret Manager pull command
+pub struct GcpPullCommand;
+
+impl CommandTemplate for GcpPullCommand {
+    type Args = GcpPullArgs;
+    type Output = MessageOutput;
+
+    fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
+        if let Some(project) = &args.project
+            && project.trim().is_empty()
+        {
+            return Err(SkitError::ParseError(
+                "GCP project cannot be empty when provided".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn requires_authentication(&self, _safe: &Safe, args: &Self::Args) -> bool {
+        !args.dry_run // Every pulled secret is stored encrypted
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let GcpPullArgs {
+            project,
+            prefix,
+            replace,
+            no_overwrite,
+            dry_run,
+        } = args;
+
+        let project = resolve_project(project.as_deref(), safe)?;
+        let secrets = gcp::fetch_secrets(&project, &prefix)?;
+
+        let total = secrets.len();
+        let entries = secrets
+            .into_iter()
+            .map(|secret| PulledEntry {
+                key: secret.key,
+                value: secret.value,
+                encrypt: true,
+            })
+            .collect();
+        let counts = match apply_pulled(
+            safe,
+            entries,
+            password.as_deref(),
+            replace,
+            no_overwrite,
+            dry_run,
+        )? {
+            PullOutcome::DryRun(listing) => {
+                return Ok(MessageOutput {
+                    message: format!(
+                        "Dry run: Would pull {} secrets from GCP project '{}'\n\n{}",
+                        total, project, listing
+                    ),
+                });
+            }
+            PullOutcome::Applied(counts) => counts,
+        };
+
+        safe.gcp_project = Some(project.clone());
+
+        let message = format!(
+            "Successfully pulled {} secrets from GCP project '{}'\n\
+             Added: {}, Updated: {}, Skipped: {}",
+            counts.added + counts.updated,
+            project,
+            counts.added,
+            counts.updated,
+            counts.skipped
+        );
+
+        Ok(MessageOutput { message })
+    }
+
+    fn modifies_safe(&self) -> bool {
+        true
+    }
+
+    fn audit_action(&self) -> &'static str {
+        "gcp-pull"
+    }
+
+    fn format_output(
+        &self,
+        output: Self::Output,
+        _format: &crate::OutputFormat,
+    ) -> Result<(), SkitError> {
+        crate::display::print_success(&output.message);
+        Ok(())
+    }
+}
+
+pub fn gcp_pull(
+    safe_path: &str,
+    project: Option<&str>,
+    prefix: Option<&str>,
+    replace: bool,
+    no_overwrite: bool,
+    dry_run: bool,
+) -> Result<(), SkitError> {
+    use crate::display::print_info;
+
+    print_info("Pulling secrets from Google Secret Manager...\n");
+
+    let command = GcpPullCommand;
+    let args = GcpPullArgs {
+        project: project.map(|p| p.to_string()),
+        prefix: prefix.unwrap_or_default().to_string(),
+        replace,
+        no_overwrite,
+        dry_run,
+    };
+
+    command.execute(safe_path, &crate::OutputFormat::Table, args)
+}
+
+/// Resolve the GCP project from the CLI flag, falling back to the safe's stored project
+fn resolve_project(project: Option<&str>, safe: &Safe) -> Result<String, SkitError> {
+    match project {
+        Some(project) => Ok(project.trim().to_string()),
+        None => safe
+            .gcp_project
+            .as_ref()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| {
+                SkitError::ParseError(
+                    "No GCP project available. Provide --project or run a prior `skit gcp pull --project ...`."
+                        .to_string(),
+                )
+            }),
+    }
+}
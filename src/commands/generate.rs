@@ -0,0 +1,132 @@
+use crate::OutputFormat;
+use crate::commands::set::{SetArgs, SetCommand};
+use crate::commands::template::CommandTemplate;
+use crate::error::SkitError;
+use crate::password::{Charset, generate_passphrase, generate_random_string};
+use crate::types::Safe;
+use zeroize::Zeroizing;
+
+/// How the secret value is generated
+#[derive(Debug, Clone, Copy)]
+pub enum GenerateKind {
+    Characters { length: usize, charset: Charset },
+    Passphrase { words: usize },
+}
+
+/// Arguments for the generate command
+#[derive(Debug)]
+pub struct GenerateArgs {
+    pub key: String,
+    pub kind: GenerateKind,
+    pub force: bool,
+    pub quiet: bool,
+}
+
+/// Output for the generate command
+#[derive(Debug)]
+pub struct GenerateOutput {
+    pub key: String,
+    pub value: Zeroizing<String>,
+    pub quiet: bool,
+}
+
+/// Template-based implementation of the generate command
+pub struct GenerateCommand;
+
+impl CommandTemplate for GenerateCommand {
+    type Args = GenerateArgs;
+    type Output = GenerateOutput;
+
+    fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
+        match args.kind {
+            GenerateKind::Characters { length: 0, .. } => Err(SkitError::ParseError(
+                "Length must be at least 1".to_string(),
+            )),
+            GenerateKind::Passphrase { words: 0 } => Err(SkitError::ParseError(
+                "Word count must be at least 1".to_string(),
+            )),
+            _ => SetCommand.validate_args(&set_args(&args.key, String::new())),
+        }
+    }
+
+    fn requires_authentication(&self, _safe: &Safe, _args: &Self::Args) -> bool {
+        // Generated secrets are always stored encrypted
+        true
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        if safe.find_item(&args.key).is_some() && !args.force {
+            return Err(SkitError::ParseError(format!(
+                "Key '{}' already exists in safe (use --force to overwrite)",
+                args.key
+            )));
+        }
+
+        let value = Zeroizing::new(match args.kind {
+            GenerateKind::Characters { length, charset } => generate_random_string(length, charset),
+            GenerateKind::Passphrase { words } => generate_passphrase(words),
+        });
+
+        // Store through the same path as `skit set`
+        SetCommand.execute_operation(safe, password, set_args(&args.key, value.to_string()))?;
+
+        Ok(GenerateOutput {
+            key: args.key,
+            value,
+            quiet: args.quiet,
+        })
+    }
+
+    fn modifies_safe(&self) -> bool {
+        true
+    }
+
+    fn audit_action(&self) -> &'static str {
+        "generate"
+    }
+
+    fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
+        tracing::info!("✓ Generated {} (encrypted) in safe", output.key);
+        if !output.quiet {
+            println!("{}", output.value.as_str());
+        }
+        Ok(())
+    }
+}
+
+fn set_args(key: &str, value: String) -> SetArgs {
+    SetArgs {
+        key: key.to_string(),
+        value,
+        is_plain: false,
+        description: None,
+        tags: Vec::new(),
+        if_not_exists: false,
+        stable: false,
+    }
+}
+
+/// Generate a random secret and store it encrypted in the safe
+pub fn generate(
+    safe_path: &str,
+    key: &str,
+    kind: GenerateKind,
+    force: bool,
+    quiet: bool,
+) -> Result<(), SkitError> {
+    let command = GenerateCommand;
+    let args = GenerateArgs {
+        key: key.to_string(),
+        kind,
+        force,
+        quiet,
+    };
+
+    // Use Table format as default (format doesn't matter for generate command output)
+    command.execute(safe_path, &OutputFormat::Table, args)
+}
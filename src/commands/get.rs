@@ -1,4 +1,5 @@
 use crate::OutputFormat;
+use crate::clipboard;
 use crate::commands::template::CommandTemplate;
 use crate::crypto;
 use crate::error::SkitError;
@@ -8,12 +9,16 @@ use crate::types::Safe;
 #[derive(Debug)]
 pub struct GetArgs {
     pub key: String,
+    /// Copy to the clipboard, clearing it after this many seconds, instead of printing
+    pub copy_timeout: Option<u64>,
 }
 
 /// Output for the get command
 #[derive(Debug)]
 pub struct GetOutput {
+    pub key: String,
     pub value: String,
+    pub copy_timeout: Option<u64>,
 }
 
 /// Template-based implementation of the get command
@@ -45,7 +50,9 @@ impl CommandTemplate for GetCommand {
         password: Option<String>,
         args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
-        let item = safe.find_item(&args.key).ok_or(SkitError::KeyNotFound)?;
+        let item = safe
+            .find_item(&args.key)
+            .ok_or_else(|| safe.key_not_found(&args.key))?;
 
         let value = if item.is_encrypted {
             // For encrypted values, we must have a password at this point
@@ -53,7 +60,10 @@ impl CommandTemplate for GetCommand {
                 SkitError::InvalidPassword("Password required for encrypted values".to_string())
             })?;
             crypto::DecryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
                 .ciphertext(&item.value)
+                .context(&item.key)
                 .password(&password)
                 .decrypt()
                 .map_err(SkitError::Crypto)?
@@ -61,20 +71,39 @@ impl CommandTemplate for GetCommand {
             item.value.clone()
         };
 
-        Ok(GetOutput { value })
+        Ok(GetOutput {
+            key: args.key,
+            value,
+            copy_timeout: args.copy_timeout,
+        })
     }
 
     fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
-        println!("{}", output.value);
+        match output.copy_timeout {
+            Some(timeout) => {
+                clipboard::copy_with_clear(&output.value, timeout)?;
+                if timeout > 0 {
+                    tracing::info!(
+                        "✓ Copied {} to clipboard (clears in {}s)",
+                        output.key,
+                        timeout
+                    );
+                } else {
+                    tracing::info!("✓ Copied {} to clipboard", output.key);
+                }
+            }
+            None => println!("{}", output.value),
+        }
         Ok(())
     }
 }
 
 /// Get a secret value from the safe
-pub fn get(safe_path: &str, key: &str) -> Result<(), SkitError> {
+pub fn get(safe_path: &str, key: &str, copy_timeout: Option<u64>) -> Result<(), SkitError> {
     let command = GetCommand;
     let args = GetArgs {
         key: key.to_string(),
+        copy_timeout,
     };
 
     // Use Table format as default (format doesn't matter for get command output)
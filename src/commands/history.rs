@@ -0,0 +1,124 @@
+use crate::OutputFormat;
+use crate::audit::{self, AuditEntry};
+use crate::commands::template::CommandTemplate;
+use crate::display::{format_json_output, format_yaml_output};
+use crate::error::SkitError;
+use crate::types::{HistoryOutput, Safe};
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Arguments for the history command
+#[derive(Debug)]
+pub struct HistoryArgs {
+    pub key: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Template-based implementation of the history command
+pub struct HistoryCommand;
+
+impl CommandTemplate for HistoryCommand {
+    type Args = HistoryArgs;
+    type Output = HistoryOutput;
+
+    fn requires_authentication(&self, _safe: &Safe, _args: &Self::Args) -> bool {
+        // The audit log holds no secret values
+        false
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        _password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let entries = audit::read_log(&safe.uuid)?
+            .into_iter()
+            .filter(|entry| match &args.key {
+                Some(key) => entry.key.as_deref() == Some(key.as_str()),
+                None => true,
+            })
+            .filter(|entry| match args.since {
+                Some(since) => entry.timestamp >= since,
+                None => true,
+            })
+            .collect();
+
+        Ok(HistoryOutput { entries })
+    }
+
+    fn format_output(&self, output: Self::Output, format: &OutputFormat) -> Result<(), SkitError> {
+        match format {
+            OutputFormat::Json => println!("{}", format_json_output(&output)?),
+            OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+            _ => print_history_table(&output.entries),
+        }
+        Ok(())
+    }
+}
+
+fn print_history_table(entries: &[AuditEntry]) {
+    if entries.is_empty() {
+        println!("No history recorded for this safe");
+        return;
+    }
+
+    let action_width = entries.iter().map(|e| e.action.len()).max().unwrap_or(0);
+    for entry in entries {
+        println!(
+            "{}  {:width$}  {}",
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            entry.action,
+            entry.key.as_deref().unwrap_or("-"),
+            width = action_width
+        );
+    }
+}
+
+/// Parse `--since` as a date (`2024-05-01`, midnight UTC) or an RFC 3339 timestamp
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, SkitError> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| {
+            SkitError::ParseError(format!(
+                "Invalid --since value '{}'. Use YYYY-MM-DD or an RFC 3339 timestamp",
+                value
+            ))
+        })
+}
+
+/// Show the audit log of changes made to a safe
+pub fn history(
+    safe_path: &str,
+    format: &OutputFormat,
+    key: Option<&str>,
+    since: Option<&str>,
+) -> Result<(), SkitError> {
+    let command = HistoryCommand;
+    let args = HistoryArgs {
+        key: key.map(|k| k.to_string()),
+        since: since.map(parse_since).transpose()?,
+    };
+
+    command.execute(safe_path, format, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(
+            parse_since("2024-05-01").unwrap(),
+            "2024-05-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            parse_since("2024-05-01T12:30:00+02:00").unwrap(),
+            "2024-05-01T10:30:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert!(parse_since("yesterday").is_err());
+    }
+}
@@ -1,21 +1,54 @@
 use std::collections::HashSet;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::path::Path;
 
+use clap::ValueEnum;
+
 use crate::OutputFormat;
+use crate::audit;
 use crate::commands::template::{CommandTemplate, MessageOutput};
 use crate::crypto;
 use crate::display::{print_info, print_success};
+use crate::dotenv::{find_closing_quote, unquote_value};
 use crate::error::SkitError;
+use crate::password::PasswordSource;
 use crate::types::Safe;
 use crate::validation::is_valid_env_key;
 
+/// File path that means stdin (or stdout, for output paths)
+pub const STDIN_PATH: &str = "-";
+
+/// Joins nested JSON/YAML keys unless `--flatten-separator` says otherwise
+pub const DEFAULT_FLATTEN_SEPARATOR: &str = "_";
+
+/// Syntax of the file being imported
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    Env,
+    Json,
+    Yaml,
+}
+
+/// What to do when an imported key already exists in the safe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    /// Ask for each key; keep the existing value when running non-interactively
+    Prompt,
+    Overwrite,
+    NoOverwrite,
+}
+
 /// Arguments for the import command
 #[derive(Debug)]
 pub struct ImportArgs {
     pub file_path: String,
     pub plain_keys: Option<HashSet<String>>,
+    /// Key-value pairs already parsed from the input, which may be stdin and so only readable once
+    pub vars: Vec<(String, String)>,
+    pub conflict: ConflictPolicy,
+    /// Leave keys whose value is unchanged untouched instead of re-encrypting them
+    pub stable: bool,
 }
 
 /// Template-based implementation of the import command
@@ -26,7 +59,7 @@ impl CommandTemplate for ImportCommand {
     type Output = MessageOutput;
 
     fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
-        if !Path::new(&args.file_path).exists() {
+        if args.file_path != STDIN_PATH && !Path::new(&args.file_path).exists() {
             return Err(SkitError::ParseError(format!(
                 "Input file '{}' does not exist",
                 args.file_path
@@ -35,10 +68,6 @@ impl CommandTemplate for ImportCommand {
         Ok(())
     }
 
-    fn requires_safe_loading(&self) -> bool {
-        false
-    }
-
     fn requires_authentication(&self, _safe: &Safe, args: &Self::Args) -> bool {
         match &args.plain_keys {
             Some(_) => true,
@@ -52,19 +81,14 @@ impl CommandTemplate for ImportCommand {
         password: Option<String>,
         args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
-        let file_content = fs::read_to_string(&args.file_path)
-            .map_err(|e| SkitError::ParseError(format!("Failed to read file: {}", e)))?;
-
-        let parsed_vars = parse_env_file(&file_content)?;
-
-        if parsed_vars.is_empty() {
+        if args.vars.is_empty() {
             return Err(SkitError::ParseError(
                 "No valid key-value pairs found in input file".to_string(),
             ));
         }
 
         if let Some(plain_keys) = &args.plain_keys {
-            let file_keys: HashSet<String> = parsed_vars.iter().map(|(k, _)| k.clone()).collect();
+            let file_keys: HashSet<String> = args.vars.iter().map(|(k, _)| k.clone()).collect();
             let missing_keys: Vec<&String> = plain_keys.difference(&file_keys).collect();
             if !missing_keys.is_empty() {
                 crate::display::print_info(&format!(
@@ -78,68 +102,156 @@ impl CommandTemplate for ImportCommand {
             }
         }
 
-        let mut encrypted_count = 0;
-        let mut plain_count = 0;
+        let mut added_count = 0;
+        let mut updated_count = 0;
+        let mut skipped_count = 0;
+        let mut unchanged_count = 0;
 
-        for (key, value) in parsed_vars {
+        for (key, value) in args.vars {
             let should_encrypt = determine_encryption(&key, &args.plain_keys);
+            if args.stable && safe.stores_value(&key, &value, should_encrypt, password.as_deref()) {
+                unchanged_count += 1;
+                continue;
+            }
+
+            let exists = safe.find_item(&key).is_some();
+            if exists && !should_overwrite(&key, args.conflict)? {
+                skipped_count += 1;
+                continue;
+            }
 
             if should_encrypt {
                 let password = password.as_ref().ok_or_else(|| {
                     SkitError::InvalidPassword("Password required for encrypted values".to_string())
                 })?;
                 let encrypted_value = crypto::EncryptBuilder::new()
+                    .kdf_params(safe.kdf_params)
+                    .master_key(safe.master_key.as_deref())
+                    .cipher(safe.cipher)
                     .plaintext(&value)
+                    .context(&key)
                     .password(password)
                     .encrypt()
                     .map_err(SkitError::Crypto)?;
                 safe.add_or_update_item(key, encrypted_value, true);
-                encrypted_count += 1;
             } else {
                 safe.add_or_update_item(key, value, false);
-                plain_count += 1;
+            }
+
+            if exists {
+                updated_count += 1;
+            } else {
+                added_count += 1;
             }
         }
 
-        Ok(MessageOutput::new(format!(
-            "Imported {} secrets: {} encrypted, {} plain text",
-            encrypted_count + plain_count,
-            encrypted_count,
-            plain_count
-        )))
+        let mut message = format!(
+            "Merged {} secrets into the safe\nAdded: {}, Updated: {}, Skipped: {}",
+            added_count + updated_count,
+            added_count,
+            updated_count,
+            skipped_count
+        );
+        if args.stable {
+            message.push_str(&format!(", Unchanged: {}", unchanged_count));
+        }
+        Ok(MessageOutput::new(message))
     }
 
     fn modifies_safe(&self) -> bool {
         true
     }
 
+    fn audit_action(&self) -> &'static str {
+        "import"
+    }
+
     fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
         print_success(&output.message);
         Ok(())
     }
 }
 
-/// Parse a .env style file into key-value pairs
-fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, SkitError> {
+/// Strip an inline comment from an unquoted value: `#` at the start or after whitespace
+/// begins a comment, any other `#` is part of the value
+fn strip_inline_comment(raw: &str) -> &str {
+    let mut previous = None;
+    for (i, c) in raw.char_indices() {
+        if c == '#' && previous.is_none_or(char::is_whitespace) {
+            return raw[..i].trim_end();
+        }
+        previous = Some(c);
+    }
+    raw
+}
+
+/// Check that only whitespace or a comment follows a closing quote
+fn check_after_quote(rest: &str, line_num: usize) -> Result<(), SkitError> {
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        return Ok(());
+    }
+    Err(SkitError::ParseError(format!(
+        "Unexpected text after quoted value on line {}",
+        line_num + 1
+    )))
+}
+
+/// Parse a .env style file into key-value pairs.
+///
+/// Follows common dotenv conventions: a leading `export ` is ignored, `#` outside quotes starts
+/// a comment when it begins the value or follows whitespace, and `#` inside quotes is kept.
+/// `KEY=`, `KEY=""` and `KEY= # comment` all give an empty value.
+pub fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, SkitError> {
     let mut vars = Vec::new();
+    let mut lines = content.lines().enumerate();
 
-    for (line_num, line) in content.lines().enumerate() {
+    while let Some((line_num, line)) = lines.next() {
         let line = line.trim();
 
         if line.is_empty() || line.starts_with('#') {
             continue;
         }
+        let line = line
+            .strip_prefix("export")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .map_or(line, str::trim_start);
 
         if let Some(eq_pos) = line.find('=') {
             let key = line[..eq_pos].trim().to_string();
-            let value = line[eq_pos + 1..].trim();
-
-            let value = if (value.starts_with('"') && value.ends_with('"'))
-                || (value.starts_with('\'') && value.ends_with('\''))
+            let raw = line[eq_pos + 1..].trim();
+
+            let value = if raw.starts_with('"') {
+                let mut quoted = raw.to_string();
+                // Multi-line double-quoted value: keep reading until the closing quote
+                while find_closing_quote(&quoted).is_none() {
+                    let (_, next) = lines.next().ok_or_else(|| {
+                        SkitError::ParseError(format!(
+                            "Unterminated quoted value on line {}",
+                            line_num + 1
+                        ))
+                    })?;
+                    quoted.push('\n');
+                    quoted.push_str(next);
+                }
+                let end = find_closing_quote(&quoted).unwrap_or_default();
+                check_after_quote(&quoted[end + 1..], line_num)?;
+                unquote_value(&quoted[..=end]).ok_or_else(|| {
+                    SkitError::ParseError(format!(
+                        "Invalid quoted value starting on line {}",
+                        line_num + 1
+                    ))
+                })?
+            } else if let Some(end) = raw
+                .get(1..)
+                .filter(|_| raw.starts_with('\''))
+                .and_then(|inner| inner.find('\''))
             {
-                value[1..value.len() - 1].to_string()
+                // Single quotes are literal: no escapes, and nothing inside is a comment
+                check_after_quote(&raw[end + 2..], line_num)?;
+                raw[1..end + 1].to_string()
             } else {
-                value.to_string()
+                strip_inline_comment(raw).to_string()
             };
 
             if key.is_empty() {
@@ -168,6 +280,150 @@ fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, SkitError> {
     Ok(vars)
 }
 
+/// Read the input file, or all of stdin when the path is `-`
+pub fn read_input(file_path: &str) -> Result<String, SkitError> {
+    if file_path == STDIN_PATH {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|e| SkitError::ParseError(format!("Failed to read stdin: {}", e)))?;
+        // Later prompts must come from the terminal, not the exhausted stdin
+        crate::input::mark_stdin_consumed();
+        return Ok(content);
+    }
+
+    fs::read_to_string(file_path)
+        .map_err(|e| SkitError::ParseError(format!("Failed to read file: {}", e)))
+}
+
+/// Parse an input file. Without an explicit `format` it is picked from the extension (.json,
+/// .yaml/.yml, else dotenv); stdin has no extension, so it is read as JSON when it looks like
+/// an object. Nested JSON/YAML keys are joined with `separator`.
+pub fn parse_input_file(
+    path: &str,
+    content: &str,
+    format: Option<InputFormat>,
+    separator: &str,
+) -> Result<Vec<(String, String)>, SkitError> {
+    let format = format.unwrap_or_else(|| detect_format(path, content));
+    match format {
+        InputFormat::Json => parse_json_file(content, separator),
+        InputFormat::Yaml => parse_yaml_file(content, separator),
+        InputFormat::Env => parse_env_file(content),
+    }
+}
+
+fn detect_format(path: &str, content: &str) -> InputFormat {
+    if path == STDIN_PATH && content.trim_start().starts_with('{') {
+        return InputFormat::Json;
+    }
+
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+
+    match extension.as_deref() {
+        Some("json") => InputFormat::Json,
+        Some("yaml") | Some("yml") => InputFormat::Yaml,
+        _ => InputFormat::Env,
+    }
+}
+
+/// Parse a JSON object into key-value pairs, flattening nested objects
+pub fn parse_json_file(content: &str, separator: &str) -> Result<Vec<(String, String)>, SkitError> {
+    let document: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| SkitError::ParseError(format!("Invalid JSON: {}", e)))?;
+    flatten_document(document, separator)
+}
+
+/// Parse a YAML mapping into key-value pairs, flattening nested mappings
+pub fn parse_yaml_file(content: &str, separator: &str) -> Result<Vec<(String, String)>, SkitError> {
+    let document: serde_json::Value = serde_yaml::from_str(content)
+        .map_err(|e| SkitError::ParseError(format!("Invalid YAML: {}", e)))?;
+    flatten_document(document, separator)
+}
+
+/// Flatten a top-level object into key-value pairs.
+/// Nested objects join their keys with `separator` and are upper-cased (`db.host` becomes
+/// `DB_HOST` with the default `_`).
+fn flatten_document(
+    document: serde_json::Value,
+    separator: &str,
+) -> Result<Vec<(String, String)>, SkitError> {
+    let serde_json::Value::Object(map) = document else {
+        return Err(SkitError::ParseError(
+            "Expected a top-level object of keys to values".to_string(),
+        ));
+    };
+
+    let mut vars = Vec::new();
+    flatten_object(None, map, separator, &mut vars)?;
+
+    let mut seen = HashSet::new();
+    for (key, _) in &vars {
+        if !is_valid_env_key(key) {
+            return Err(SkitError::ParseError(format!(
+                "Invalid key '{}' (must match [A-Za-z_][A-Za-z0-9_]*)",
+                key
+            )));
+        }
+        if !seen.insert(key.as_str()) {
+            return Err(SkitError::ParseError(format!(
+                "Duplicate key '{}' after flattening",
+                key
+            )));
+        }
+    }
+
+    Ok(vars)
+}
+
+fn flatten_object(
+    prefix: Option<&str>,
+    map: serde_json::Map<String, serde_json::Value>,
+    separator: &str,
+    vars: &mut Vec<(String, String)>,
+) -> Result<(), SkitError> {
+    for (key, value) in map {
+        let key = match prefix {
+            Some(prefix) => format!("{}{}{}", prefix, separator, key).to_uppercase(),
+            None => key,
+        };
+
+        let value = match value {
+            serde_json::Value::Object(nested) => {
+                flatten_object(Some(&key), nested, separator, vars)?;
+                continue;
+            }
+            serde_json::Value::String(s) => s,
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::Bool(b) => b.to_string(),
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::Array(_) => {
+                return Err(SkitError::ParseError(format!(
+                    "Unsupported list value for key '{}'",
+                    key
+                )));
+            }
+        };
+        vars.push((key, value));
+    }
+    Ok(())
+}
+
+/// Decide whether an imported key replaces the existing secret of the same name
+fn should_overwrite(key: &str, conflict: ConflictPolicy) -> Result<bool, SkitError> {
+    match conflict {
+        ConflictPolicy::Overwrite => Ok(true),
+        ConflictPolicy::NoOverwrite => Ok(false),
+        ConflictPolicy::Prompt => prompt_yes_no(
+            &format!("'{}' already exists in the safe. Overwrite? (y/N): ", key),
+            false,
+        ),
+    }
+}
+
 /// Determine if a key should be encrypted based on the command options
 fn determine_encryption(key: &str, plain_keys: &Option<HashSet<String>>) -> bool {
     match plain_keys {
@@ -177,7 +433,7 @@ fn determine_encryption(key: &str, plain_keys: &Option<HashSet<String>>) -> bool
 }
 
 /// Parse a comma-separated string into a HashSet
-fn parse_key_list(keys_str: &str) -> HashSet<String> {
+pub fn parse_key_list(keys_str: &str) -> HashSet<String> {
     keys_str
         .split(',')
         .map(|s| s.trim().to_string())
@@ -185,32 +441,60 @@ fn parse_key_list(keys_str: &str) -> HashSet<String> {
         .collect()
 }
 
-/// Import secrets from an existing cleartext file into a safe
-pub fn import(safe_path: &str, file_path: &str, plain_keys: Option<&str>) -> Result<(), SkitError> {
+/// Import secrets from an existing cleartext file into a new safe, or with `into` merge
+/// them into an existing one
+#[allow(clippy::too_many_arguments)]
+pub fn import(
+    safe_path: &str,
+    file_path: &str,
+    input_format: Option<InputFormat>,
+    flatten_separator: &str,
+    plain_keys: Option<&str>,
+    password_source: &PasswordSource,
+    into: bool,
+    conflict: ConflictPolicy,
+    stable: bool,
+) -> Result<(), SkitError> {
     println!("skit (Security Kit) - Finally safe to commit your secrets!");
-    println!("Let's convert your cleartext secrets to a secure safe.\n");
+    if into {
+        println!("Let's add your cleartext secrets to an existing safe.\n");
+    } else {
+        println!("Let's convert your cleartext secrets to a secure safe.\n");
+    }
 
     let command = ImportCommand;
 
     let plain_keys_set = plain_keys.map(parse_key_list);
 
-    let args = ImportArgs {
+    let mut args = ImportArgs {
         file_path: file_path.to_string(),
         plain_keys: plain_keys_set,
+        vars: Vec::new(),
+        conflict,
+        stable,
     };
 
     command.validate_args(&args)?;
 
-    let file_content = fs::read_to_string(&args.file_path)
-        .map_err(|e| SkitError::ParseError(format!("Failed to read file: {}", e)))?;
-    let parsed_vars = parse_env_file(&file_content)?;
+    let file_content = read_input(&args.file_path)?;
+    let parsed_vars = parse_input_file(
+        &args.file_path,
+        &file_content,
+        input_format,
+        flatten_separator,
+    )?;
     if parsed_vars.is_empty() {
         return Err(SkitError::ParseError(
             "No valid key-value pairs found in input file".to_string(),
         ));
     }
 
-    println!("📂 Found {} secrets in {}", parsed_vars.len(), file_path);
+    let source = if file_path == STDIN_PATH {
+        "stdin"
+    } else {
+        file_path
+    };
+    println!("📂 Found {} secrets in {}", parsed_vars.len(), source);
 
     if let Some(plain_keys) = &args.plain_keys {
         let keys_list: Vec<&String> = plain_keys.iter().collect();
@@ -225,27 +509,41 @@ pub fn import(safe_path: &str, file_path: &str, plain_keys: Option<&str>) -> Res
         );
     }
 
+    if into {
+        args.vars = parsed_vars;
+        return command.execute(safe_path, &OutputFormat::Table, args);
+    }
+
     if Path::new(safe_path).exists() {
         if safe_path == ".env.safe" {
             return Err(SkitError::ParseError(format!(
-                "Safe file '{}' already exists.\nOptions:\n  • Use a different name: skit --safe myproject import -f {}\n  • Or remove existing file: rm {}",
-                safe_path, args.file_path, safe_path
+                "Safe file '{}' already exists.\nOptions:\n  • Merge into it: skit import --into -f {}\n  • Use a different name: skit --safe myproject import -f {}\n  • Or remove existing file: rm {}",
+                safe_path, args.file_path, args.file_path, safe_path
             )));
         } else {
             return Err(SkitError::ParseError(format!(
-                "Safe file '{}' already exists.\nOptions:\n  • Choose a different name: skit --safe newname import -f {}\n  • Or remove existing file: rm {}",
-                safe_path, args.file_path, safe_path
+                "Safe file '{}' already exists.\nOptions:\n  • Merge into it: skit --safe {} import --into -f {}\n  • Choose a different name: skit --safe newname import -f {}\n  • Or remove existing file: rm {}",
+                safe_path, safe_path, args.file_path, args.file_path, safe_path
             )));
         }
     }
 
     println!("\n🔑 Creating your secure safe...");
 
-    let password = crate::input::prompt_password_with_fallback(
-        "Enter password for new safe (or hit enter to generate one automatically): ",
-    )
-    .map_err(SkitError::Io)?;
-    println!();
+    let password = match password_source.read()? {
+        Some(password) => password,
+        None => {
+            crate::input::require_interactive(
+                "pass --password-env or --password-file to set the safe password",
+            )?;
+            let password = crate::input::prompt_password_with_fallback(
+                "Enter password for new safe (or hit enter to generate one automatically): ",
+            )
+            .map_err(SkitError::Io)?;
+            println!();
+            password
+        }
+    };
 
     let password = if password.trim().is_empty() {
         let generated_password = crate::password::generate_secure_password();
@@ -259,6 +557,7 @@ pub fn import(safe_path: &str, file_path: &str, plain_keys: Option<&str>) -> Res
     };
 
     let mut safe = Safe::new_with_password(&password, "Imported from file")?;
+    safe.create_master_key(&password)?;
 
     let mut encrypted_count = 0;
     let mut plain_count = 0;
@@ -268,7 +567,11 @@ pub fn import(safe_path: &str, file_path: &str, plain_keys: Option<&str>) -> Res
 
         if should_encrypt {
             let encrypted_value = crypto::EncryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .cipher(safe.cipher)
                 .plaintext(&value)
+                .context(&key)
                 .password(&password)
                 .encrypt()
                 .map_err(SkitError::Crypto)?;
@@ -281,6 +584,7 @@ pub fn import(safe_path: &str, file_path: &str, plain_keys: Option<&str>) -> Res
     }
 
     safe.save(safe_path)?;
+    audit::record_changes(&safe, &Default::default(), "import");
 
     println!();
     print_success("✅ Import complete!");
@@ -295,8 +599,9 @@ pub fn import(safe_path: &str, file_path: &str, plain_keys: Option<&str>) -> Res
     println!();
     let save_key = prompt_yes_no("Save safe key for easy access? (y/N): ", false)?;
     if save_key {
-        let key_path =
-            crate::commands::remember_safekey_with_password_quiet(&safe, &password, true)?;
+        let key_path = crate::commands::remember_safekey_with_password_quiet(
+            &safe, safe_path, &password, true, false,
+        )?;
         println!(
             "✅ Safe key saved to {}! No more password prompts needed.",
             key_path
@@ -323,13 +628,17 @@ pub fn import(safe_path: &str, file_path: &str, plain_keys: Option<&str>) -> Res
     Ok(())
 }
 
-/// Simple yes/no prompt
+/// Simple yes/no prompt, answered with the default when running non-interactively
 fn prompt_yes_no(prompt: &str, default: bool) -> Result<bool, SkitError> {
+    if crate::input::is_non_interactive() {
+        return Ok(default);
+    }
+
     print!("{}", prompt);
     io::stdout().flush().map_err(SkitError::Io)?;
 
     let mut input = String::new();
-    io::stdin().read_line(&mut input).map_err(SkitError::Io)?;
+    crate::input::read_prompt_line(&mut input).map_err(SkitError::Io)?;
 
     let input = input.trim().to_lowercase();
     match input.as_str() {
@@ -339,3 +648,163 @@ fn prompt_yes_no(prompt: &str, default: bool) -> Result<bool, SkitError> {
         _ => Ok(default),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file_quoted_values() {
+        let content = "PEM=\"-----BEGIN KEY-----\nabc=\n-----END KEY-----\"\nESCAPED=\"a\\nb \\\"q\\\"\"\nSINGLE='raw\\n'\n";
+        let vars = parse_env_file(content).unwrap();
+
+        assert_eq!(
+            vars,
+            vec![
+                (
+                    "PEM".to_string(),
+                    "-----BEGIN KEY-----\nabc=\n-----END KEY-----".to_string()
+                ),
+                ("ESCAPED".to_string(), "a\nb \"q\"".to_string()),
+                ("SINGLE".to_string(), "raw\\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_fixture_lines() {
+        let cases = [
+            ("KEY=value", "value"),
+            ("KEY = value", "value"),
+            ("export KEY=value", "value"),
+            ("export   KEY=value # note", "value"),
+            ("KEY=value # primary", "value"),
+            ("KEY=value\t# tab comment", "value"),
+            ("KEY=a#b", "a#b"),
+            ("KEY=https://host/path#anchor", "https://host/path#anchor"),
+            ("KEY=", ""),
+            ("KEY= # only a comment", ""),
+            ("KEY=#not-a-value", ""),
+            ("KEY=\"\"", ""),
+            ("KEY=''", ""),
+            ("KEY=\"a # b\"", "a # b"),
+            ("KEY=\"a # b\" # comment", "a # b"),
+            ("KEY='a # b' # comment", "a # b"),
+            ("KEY='raw\\n'", "raw\\n"),
+            ("KEY=\"esc\\\"aped\\n\"", "esc\"aped\n"),
+            ("KEY=\"hash#inside\"#comment", "hash#inside"),
+            ("KEY=say \"hi\"", "say \"hi\""),
+        ];
+        for (line, expected) in cases {
+            let vars = parse_env_file(line).unwrap_or_else(|e| panic!("{}: {}", line, e));
+            assert_eq!(
+                vars,
+                vec![("KEY".to_string(), expected.to_string())],
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_env_file_export_needs_whitespace() {
+        assert_eq!(
+            parse_env_file("exported=1\nexport_X=2\n").unwrap(),
+            vec![
+                ("exported".to_string(), "1".to_string()),
+                ("export_X".to_string(), "2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_rejects_text_after_quotes() {
+        assert!(parse_env_file("KEY=\"a\" b\n").is_err());
+        assert!(parse_env_file("KEY='a' b\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_env_file_multiline_value_with_comment() {
+        assert_eq!(
+            parse_env_file("KEY=\"line1\nline2\" # trailing\n").unwrap(),
+            vec![("KEY".to_string(), "line1\nline2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_unterminated_quote() {
+        assert!(parse_env_file("KEY=\"never closed\nOTHER=1\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_file_flattens_nested_objects() {
+        let content = r#"{"API_KEY": "secret", "db": {"host": "localhost", "port": 5432}}"#;
+        let vars = parse_json_file(content, "_").unwrap();
+
+        assert_eq!(
+            vars,
+            vec![
+                ("API_KEY".to_string(), "secret".to_string()),
+                ("DB_HOST".to_string(), "localhost".to_string()),
+                ("DB_PORT".to_string(), "5432".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml_file_multiline_value() {
+        let content = "PEM: |\n  line1\n  line2\ndb:\n  user: admin\n";
+        let vars = parse_yaml_file(content, "_").unwrap();
+
+        assert_eq!(
+            vars,
+            vec![
+                ("PEM".to_string(), "line1\nline2\n".to_string()),
+                ("DB_USER".to_string(), "admin".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_structured_file_rejects_bad_keys() {
+        assert!(parse_json_file(r#"{"bad-key": "x"}"#, "_").is_err());
+        assert!(parse_json_file(r#"{"DB_HOST": "a", "db": {"host": "b"}}"#, "_").is_err());
+        assert!(parse_json_file(r#"["not", "an", "object"]"#, "_").is_err());
+        assert!(parse_yaml_file("LIST:\n  - a\n", "_").is_err());
+    }
+
+    #[test]
+    fn test_parse_input_file_detects_format() {
+        assert_eq!(
+            parse_input_file("secrets.YML", "KEY: value\n", None, "_").unwrap(),
+            vec![("KEY".to_string(), "value".to_string())]
+        );
+        assert_eq!(
+            parse_input_file(".env", "KEY=value\n", None, "_").unwrap(),
+            vec![("KEY".to_string(), "value".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_input_file_explicit_format_and_separator() {
+        // A JSON file without a .json extension
+        let content = r#"{"db": {"host": "localhost"}}"#;
+        assert_eq!(
+            parse_input_file("secrets.txt", content, Some(InputFormat::Json), "__").unwrap(),
+            vec![("DB__HOST".to_string(), "localhost".to_string())]
+        );
+        assert!(parse_input_file("x.json", content, Some(InputFormat::Json), "-").is_err());
+    }
+
+    #[test]
+    fn test_parse_input_file_stdin_sniffs_json() {
+        assert_eq!(
+            parse_input_file(STDIN_PATH, "  {\"KEY\": \"value\"}", None, "_").unwrap(),
+            vec![("KEY".to_string(), "value".to_string())]
+        );
+        assert_eq!(
+            parse_input_file(STDIN_PATH, "KEY=value\n", None, "_").unwrap(),
+            vec![("KEY".to_string(), "value".to_string())]
+        );
+    }
+}
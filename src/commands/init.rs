@@ -1,62 +1,91 @@
+use crate::audit;
+use crate::crypto::{Cipher, EncryptBuilder, KdfParams};
 use crate::error::SkitError;
-use crate::password::{generate_secure_password, validate_password_strength};
+use crate::password::{
+    PasswordSource, SAFE_PASSPHRASE_WORDS, generate_safe_passphrase, generate_secure_password,
+    validate_password_strength_min,
+};
 use crate::types::Safe;
+use crate::validation::is_valid_env_key;
 use std::fs;
 use std::io::{self, Write};
 
+/// Read the key names listed in a `--from-template` file, one per line. Blank lines and `#`
+/// comments are skipped, and `KEY=value` lines contribute their key, so a `.env.example` works.
+pub fn read_template_keys(path: &str) -> Result<Vec<String>, SkitError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        SkitError::ParseError(format!("Failed to read template file {}: {}", path, e))
+    })?;
+
+    let mut keys: Vec<String> = Vec::new();
+    for (line_num, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let key = line.split_once('=').map_or(line, |(key, _)| key).trim();
+        let key = key.strip_prefix("export ").map_or(key, str::trim_start);
+        if !is_valid_env_key(key) {
+            return Err(SkitError::ParseError(format!(
+                "Invalid key '{}' on line {} of {} (must match [A-Za-z_][A-Za-z0-9_]*)",
+                key,
+                line_num + 1,
+                path
+            )));
+        }
+        if !keys.iter().any(|existing| existing == key) {
+            keys.push(key.to_string());
+        }
+    }
+    Ok(keys)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn init(
     safe_path: &str,
     remember: bool,
+    use_keyring: bool,
     description: Option<&str>,
     ssm_prefix: Option<&str>,
+    kdf_params: KdfParams,
+    cipher: Cipher,
+    password_source: &PasswordSource,
+    min_strength: u8,
+    passphrase: bool,
+    from_template: Option<&str>,
 ) -> Result<(), SkitError> {
     if fs::metadata(safe_path).is_ok() {
         tracing::info!("Safe already exists at {}", safe_path);
         return Ok(());
     }
 
-    println!("Creating new safe.");
-    println!("\nPassword requirements for new safe:");
-    println!("  - At least 12 characters");
-    println!("  - At least one uppercase letter");
-    println!("  - At least one lowercase letter");
-    println!("  - At least one digit");
-    println!("  - At least one special character. Allowed special characters: . _ @ # -");
-
-    let password = loop {
-        let password = crate::input::prompt_password_with_fallback(
-            "Enter password for the safe (or hit enter to generate one automatically): ",
-        )
-        .map_err(SkitError::Io)?;
+    kdf_params.validate().map_err(SkitError::ParseError)?;
+    // Read the template before asking for a password, so a bad file fails fast
+    let template_keys = from_template.map(read_template_keys).transpose()?;
 
-        if password.is_empty() {
-            let gen_password = generate_secure_password();
-            println!("Generated password (keep this safe!): {}", gen_password);
-            break gen_password;
-        } else {
-            match validate_password_strength(&password) {
-                Ok(()) => {
-                    let confirm = crate::input::prompt_password_with_fallback("Confirm password: ")
-                        .map_err(SkitError::Io)?;
-
-                    if password == confirm {
-                        println!();
-                        break password;
-                    } else {
-                        eprintln!("Error: Passwords do not match. Please try again.");
-                        continue;
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error: {}", e);
-                    continue;
-                }
+    let password = if passphrase {
+        let generated = generate_safe_passphrase(SAFE_PASSPHRASE_WORDS);
+        println!("Generated passphrase (keep this safe!): {}", generated);
+        generated
+    } else {
+        match password_source.read()? {
+            Some(password) => {
+                validate_password_strength_min(&password, min_strength)?;
+                password
+            }
+            None => {
+                crate::input::require_interactive(
+                    "pass --password-env, --password-file or --passphrase to set the safe password",
+                )?;
+                prompt_new_password(min_strength)?
             }
         }
     };
 
     let description = if let Some(desc) = description {
         desc.to_string()
+    } else if crate::input::is_non_interactive() {
+        "Default safe".to_string()
     } else {
         print!("\nEnter a description for this safe (optional):");
         let _ = io::stdout().flush();
@@ -75,6 +104,9 @@ pub fn init(
     };
 
     let mut safe = Safe::new_with_password(&password, &description)?;
+    safe.kdf_params = kdf_params;
+    safe.cipher = cipher;
+    safe.create_master_key(&password)?;
 
     if let Some(prefix) = ssm_prefix {
         let normalized_prefix = prefix.trim();
@@ -97,11 +129,33 @@ pub fn init(
             normalized_prefix
         );
     }
+
+    if let Some(keys) = &template_keys {
+        for key in keys {
+            let encrypted = EncryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .cipher(safe.cipher)
+                .plaintext("")
+                .context(key)
+                .password(&password)
+                .encrypt()?;
+            safe.add_or_update_item(key.clone(), encrypted, true);
+        }
+        println!(
+            "Added {} empty keys from {} for you to fill in",
+            keys.len(),
+            from_template.unwrap_or_default()
+        );
+    }
     safe.save(safe_path)?;
+    audit::record(&safe, "init");
     tracing::info!("✓ Created new safe at {}", safe_path);
 
     let should_save = if remember {
         true
+    } else if crate::input::is_non_interactive() {
+        false
     } else {
         print!("\nWould you like to save the safe key for automatic authentication? (y/N):");
         let _ = io::stdout().flush();
@@ -113,14 +167,22 @@ pub fn init(
         input == "y" || input == "yes"
     };
 
-    if should_save {
+    if should_save && use_keyring {
+        let location = crate::commands::remember_safekey_with_password_quiet(
+            &safe, safe_path, &password, true, true,
+        )?;
+        tracing::info!(
+            "✓ Safe key saved for automatic authentication in {}",
+            location
+        );
+    } else if should_save {
         let home_dir = dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from("~"));
         let key_file = home_dir
             .join(".config")
             .join("skit")
             .join("keys")
             .join(format!("{}.key", safe.uuid));
-        save_safe_key(&safe, &password)?;
+        save_safe_key(&safe, safe_path, &password)?;
         tracing::info!(
             "✓ Safe key saved for automatic authentication at {}",
             key_file.display()
@@ -134,7 +196,56 @@ pub fn init(
     Ok(())
 }
 
-fn save_safe_key(safe: &Safe, password: &str) -> Result<(), SkitError> {
+/// Prompt for a new safe password, generating one if the user just hits enter
+fn prompt_new_password(min_strength: u8) -> Result<String, SkitError> {
+    println!("Creating new safe.");
+    println!("\nPassword requirements for new safe:");
+    println!("  - At least 12 characters");
+    println!("  - At least one uppercase letter");
+    println!("  - At least one lowercase letter");
+    println!("  - At least one digit");
+    println!("  - At least one special character. Allowed special characters: . _ @ # -");
+    println!(
+        "  - Hard to guess: strength {}/4 or better (no dictionary words or obvious patterns)",
+        min_strength
+    );
+
+    let password = loop {
+        let password = crate::input::prompt_password_with_fallback(
+            "Enter password for the safe (or hit enter to generate one automatically): ",
+        )
+        .map_err(SkitError::Io)?;
+
+        if password.is_empty() {
+            let gen_password = generate_secure_password();
+            println!("Generated password (keep this safe!): {}", gen_password);
+            break gen_password;
+        } else {
+            match validate_password_strength_min(&password, min_strength) {
+                Ok(()) => {
+                    let confirm = crate::input::prompt_password_with_fallback("Confirm password: ")
+                        .map_err(SkitError::Io)?;
+
+                    if password == confirm {
+                        println!();
+                        break password;
+                    } else {
+                        eprintln!("Error: Passwords do not match. Please try again.");
+                        continue;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    continue;
+                }
+            }
+        }
+    };
+
+    Ok(password)
+}
+
+fn save_safe_key(safe: &Safe, safe_path: &str, password: &str) -> Result<(), SkitError> {
     let home_dir = dirs::home_dir().ok_or_else(|| {
         SkitError::Io(std::io::Error::new(
             std::io::ErrorKind::NotFound,
@@ -143,10 +254,42 @@ fn save_safe_key(safe: &Safe, password: &str) -> Result<(), SkitError> {
     })?;
 
     let skit_keys_dir = home_dir.join(".config").join("skit").join("keys");
-    fs::create_dir_all(&skit_keys_dir).map_err(SkitError::Io)?;
+    crate::fs_utils::create_private_dir(&skit_keys_dir)?;
 
     let key_file = skit_keys_dir.join(format!("{}.key", safe.uuid));
     crate::fs_utils::write_secret_file_secure(&key_file, password)?;
+    crate::key_index::record_use(safe, Some(safe_path));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_template_keys() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("keys.txt");
+        let path = path.to_str().unwrap();
+
+        fs::write(
+            path,
+            "# Required everywhere\nDATABASE_URL\n\nexport API_KEY=\nSENTRY_DSN=https://example\nDATABASE_URL\n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_template_keys(path).unwrap(),
+            vec!["DATABASE_URL", "API_KEY", "SENTRY_DSN"]
+        );
+
+        fs::write(path, "GOOD\nnot-valid\n").unwrap();
+        let err = read_template_keys(path).unwrap_err().to_string();
+        assert!(
+            err.contains("not-valid") && err.contains("line 2"),
+            "{}",
+            err
+        );
+    }
+}
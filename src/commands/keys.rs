@@ -1,17 +1,41 @@
 use crate::OutputFormat;
 use crate::commands::template::CommandTemplate;
-use crate::display::{format_json_output, print_keys_table};
+use crate::display::{format_json_output, format_yaml_output, print_keys_table};
 use crate::error::SkitError;
-use crate::types::{KeyItem, KeysOutput, Safe};
+use crate::key_filter::has_tags;
+use crate::types::{ItemMetadata, KeyItem, KeysOutput, KeysSummary, Safe, SortOrder};
 
-/// Arguments for the keys command (no arguments needed)
+/// Arguments for the keys command
 #[derive(Debug)]
-pub struct KeysArgs;
+pub struct KeysArgs {
+    /// List only keys carrying all of these tags
+    pub tags: Vec<String>,
+    /// Include the stored length of each value
+    pub size: bool,
+    pub sort: SortOrder,
+    /// With `--format env`, follow each key with a tab and its type
+    pub types: bool,
+    /// Print only the totals
+    pub count: bool,
+}
 
 /// Output for the keys command
 #[derive(Debug)]
 pub struct KeysCommandOutput {
-    pub items: Vec<(String, bool)>, // (key, is_encrypted)
+    pub items: Vec<(String, bool, ItemMetadata, Option<usize>)>, // (key, is_encrypted, metadata, size)
+    pub types: bool,
+    pub count: bool,
+}
+
+impl KeysCommandOutput {
+    fn summary(&self) -> KeysSummary {
+        let encrypted = self.items.iter().filter(|item| item.1).count();
+        KeysSummary {
+            total: self.items.len(),
+            encrypted,
+            plain: self.items.len() - encrypted,
+        }
+    }
 }
 
 /// Template-based implementation of the keys command
@@ -30,59 +54,79 @@ impl CommandTemplate for KeysCommand {
         &self,
         safe: &mut Safe,
         _password: Option<String>,
-        _args: Self::Args,
+        args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
-        if safe.items.is_empty() {
-            return Ok(KeysCommandOutput { items: vec![] });
-        }
-
-        // Sort keys for consistent output
-        let mut keys: Vec<_> = safe.items.keys().collect();
-        keys.sort();
-
         let mut items = Vec::new();
-        for key in keys {
+        for key in safe.sorted_keys(args.sort) {
             let item = &safe.items[key];
-            items.push((item.key.clone(), item.is_encrypted));
+            if !has_tags(item, &args.tags) {
+                continue;
+            }
+            // Stored bytes, so encrypted values count their ciphertext and need no password
+            let size = args.size.then_some(item.value.len());
+            items.push((
+                item.key.clone(),
+                item.is_encrypted,
+                item.metadata.clone(),
+                size,
+            ));
         }
 
-        Ok(KeysCommandOutput { items })
+        Ok(KeysCommandOutput {
+            items,
+            types: args.types,
+            count: args.count,
+        })
     }
 
     fn format_output(&self, output: Self::Output, format: &OutputFormat) -> Result<(), SkitError> {
-        if output.items.is_empty() {
+        let summary = output.summary();
+        if output.count {
             match format {
-                OutputFormat::Json => {
-                    let keys_output = KeysOutput { keys: vec![] };
-                    println!("{}", format_json_output(&keys_output)?);
-                }
-                _ => {
-                    print_keys_table(&[]);
+                OutputFormat::Json => println!("{}", format_json_output(&summary)?),
+                OutputFormat::Yaml => print!("{}", format_yaml_output(&summary)?),
+                OutputFormat::Env => {
+                    println!("TOTAL={}", summary.total);
+                    println!("ENCRYPTED={}", summary.encrypted);
+                    println!("PLAIN={}", summary.plain);
                 }
+                _ => println!(
+                    "Total: {}, Encrypted: {}, Plain: {}",
+                    summary.total, summary.encrypted, summary.plain
+                ),
             }
             return Ok(());
         }
 
         match format {
             OutputFormat::Json => {
-                let keys: Vec<KeyItem> = output
-                    .items
-                    .iter()
-                    .map(|(key, is_encrypted)| KeyItem {
-                        key: key.clone(),
-                        item_type: if *is_encrypted {
-                            "ENC".to_string()
-                        } else {
-                            "PLAIN".to_string()
-                        },
-                    })
-                    .collect();
+                let keys: Vec<KeyItem> = output.items.iter().map(key_item).collect();
 
-                let keys_output = KeysOutput { keys };
+                let keys_output = KeysOutput { keys, summary };
                 println!("{}", format_json_output(&keys_output)?);
             }
+            OutputFormat::Yaml => {
+                let keys: Vec<KeyItem> = output.items.iter().map(key_item).collect();
+
+                print!("{}", format_yaml_output(&keys)?);
+            }
+            OutputFormat::Env => {
+                // One key per line, for `grep`, `wc -l` and `while read` loops
+                for (key, is_encrypted, _, _) in &output.items {
+                    if output.types {
+                        println!("{}\t{}", key, item_type(*is_encrypted));
+                    } else {
+                        println!("{}", key);
+                    }
+                }
+            }
             _ => {
-                print_keys_table(&output.items);
+                let items: Vec<(String, bool, Option<usize>)> = output
+                    .items
+                    .into_iter()
+                    .map(|(key, is_encrypted, _, size)| (key, is_encrypted, size))
+                    .collect();
+                print_keys_table(&items);
             }
         }
 
@@ -90,10 +134,56 @@ impl CommandTemplate for KeysCommand {
     }
 }
 
-/// List all secret keys with their types
-pub fn keys(safe_path: &str, format: &OutputFormat) -> Result<(), SkitError> {
+fn item_type(is_encrypted: bool) -> &'static str {
+    if is_encrypted { "ENC" } else { "PLAIN" }
+}
+
+fn key_item(
+    (key, is_encrypted, metadata, size): &(String, bool, ItemMetadata, Option<usize>),
+) -> KeyItem {
+    KeyItem {
+        key: key.clone(),
+        item_type: item_type(*is_encrypted).to_string(),
+        created: metadata.created.clone(),
+        updated: metadata.updated.clone(),
+        description: metadata.description.clone(),
+        tags: metadata.tags.clone(),
+        size: *size,
+    }
+}
+
+/// List all secret keys with their types, and with `size` the stored length of each value.
+/// With `count`, print only how many keys there are of each type.
+pub fn keys(
+    safe_path: &str,
+    format: &OutputFormat,
+    tags: &[String],
+    size: bool,
+    sort: SortOrder,
+    types: bool,
+    count: bool,
+) -> Result<(), SkitError> {
     let command = KeysCommand;
-    let args = KeysArgs;
+    let args = KeysArgs {
+        tags: tags.to_vec(),
+        size,
+        sort,
+        types,
+        count,
+    };
 
     command.execute(safe_path, format, args)
 }
+
+/// Print key names one per line for shell completion. No authentication is needed, and a
+/// missing or unreadable safe simply prints nothing.
+pub fn complete_keys(safe_path: &str) -> Result<(), SkitError> {
+    if let Ok(safe) = Safe::load(safe_path) {
+        let mut keys: Vec<_> = safe.items.keys().collect();
+        keys.sort();
+        for key in keys {
+            println!("{}", key);
+        }
+    }
+    Ok(())
+}
@@ -1,29 +1,101 @@
-use crate::display::{format_json_output, print_info};
+use crate::display::{format_json_output, format_yaml_output, print_info};
 use crate::error::SkitError;
 use crate::types::{Safe, SafeInfo, SafeStatistics, SafesListOutput};
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub fn ls(format: &crate::OutputFormat) -> Result<(), SkitError> {
-    // Find all .safe files in current directory
-    let current_dir = std::env::current_dir().map_err(SkitError::Io)?;
+/// Status of a safe file that could not be loaded
+const STATUS_ERROR: &str = "Error";
 
-    let entries = fs::read_dir(&current_dir).map_err(SkitError::Io)?;
+/// Directories never searched by `ls --recursive`
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target"];
 
-    let mut safe_files = Vec::new();
+/// Where and how deep `ls` looks for safes
+#[derive(Debug, Default)]
+pub struct LsOptions {
+    /// Directory to search (default: the current directory)
+    pub path: Option<String>,
+    pub recursive: bool,
+    /// Deepest subdirectory level searched with `recursive`; the start directory is 0
+    pub max_depth: Option<usize>,
+    /// Only list safes that fail to load
+    pub errors_only: bool,
+}
+
+/// Find `.safe` files under `root`, returned as `root`-prefixed paths sorted by path.
+/// Directories are each visited once, so symlink cycles end the walk instead of looping.
+pub fn find_safes(
+    root: &Path,
+    recursive: bool,
+    max_depth: Option<usize>,
+) -> Result<Vec<PathBuf>, SkitError> {
+    let mut found = Vec::new();
+    let mut visited = HashSet::new();
+    let max_depth = if recursive { max_depth } else { Some(0) };
+    walk(root, 0, max_depth, &mut visited, &mut found)?;
+    found.sort();
+    Ok(found)
+}
+
+fn walk(
+    dir: &Path,
+    depth: usize,
+    max_depth: Option<usize>,
+    visited: &mut HashSet<PathBuf>,
+    found: &mut Vec<PathBuf>,
+) -> Result<(), SkitError> {
+    let read_from = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    if let Ok(canonical) = fs::canonicalize(read_from)
+        && !visited.insert(canonical)
+    {
+        return Ok(());
+    }
+
+    let entries = match fs::read_dir(read_from) {
+        Ok(entries) => entries,
+        // The start directory must be readable; unreadable subdirectories are skipped
+        Err(e) if depth == 0 => return Err(SkitError::Io(e)),
+        Err(_) => return Ok(()),
+    };
 
     for entry in entries {
         let entry = entry.map_err(SkitError::Io)?;
-        let path = entry.path();
-
-        if let Some(filename_str) = path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .filter(|name| name.ends_with(".safe"))
-        {
-            safe_files.push(filename_str.to_string());
+        let name = entry.file_name();
+        let path = dir.join(&name);
+        // Follows symlinks, so linked directories are searched too
+        let is_dir = fs::metadata(&path).is_ok_and(|metadata| metadata.is_dir());
+
+        if is_dir {
+            let skipped = name
+                .to_str()
+                .is_some_and(|name| SKIPPED_DIRS.contains(&name));
+            if !skipped && max_depth.is_none_or(|max| depth < max) {
+                walk(&path, depth + 1, max_depth, visited, found)?;
+            }
+        } else if name.to_str().is_some_and(|name| name.ends_with(".safe")) {
+            found.push(path);
         }
     }
+    Ok(())
+}
+
+/// List safes in the current directory, or under `options.path`
+pub fn ls(format: &crate::OutputFormat, options: &LsOptions) -> Result<(), SkitError> {
+    let root = PathBuf::from(options.path.as_deref().unwrap_or(""));
+    let location = match &options.path {
+        Some(path) => path.clone(),
+        None => "current directory".to_string(),
+    };
+
+    let safe_files: Vec<String> = find_safes(&root, options.recursive, options.max_depth)?
+        .into_iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
 
     if safe_files.is_empty() {
         match format {
@@ -31,22 +103,22 @@ pub fn ls(format: &crate::OutputFormat) -> Result<(), SkitError> {
                 let output = SafesListOutput { safes: vec![] };
                 println!("{}", format_json_output(&output)?);
             }
+            crate::OutputFormat::Yaml => {
+                let output = SafesListOutput { safes: vec![] };
+                print!("{}", format_yaml_output(&output)?);
+            }
             _ => {
-                println!("No safes found in current directory");
+                println!("No safes found in {}", location);
             }
         }
         return Ok(());
     }
 
-    safe_files.sort();
-
     // Collect safe information
     let mut safe_infos = Vec::new();
 
     for safe_file in &safe_files {
-        let safe_path = Path::new(&safe_file);
-
-        match safe_path.to_str().and_then(|path| Safe::load(path).ok()) {
+        match Safe::load(safe_file).ok() {
             Some(safe) => {
                 let total = safe.items.len();
                 let encrypted = safe.items.values().filter(|item| item.is_encrypted).count();
@@ -58,7 +130,7 @@ pub fn ls(format: &crate::OutputFormat) -> Result<(), SkitError> {
                 };
 
                 safe_infos.push(SafeInfo {
-                    file: safe_file.clone(),
+                    file: crate::fs_utils::display_path(Path::new(safe_file)),
                     description: safe.description,
                     statistics: SafeStatistics {
                         total,
@@ -71,7 +143,7 @@ pub fn ls(format: &crate::OutputFormat) -> Result<(), SkitError> {
             }
             None => {
                 safe_infos.push(SafeInfo {
-                    file: safe_file.clone(),
+                    file: crate::fs_utils::display_path(Path::new(safe_file)),
                     description: "Error loading safe".to_string(),
                     statistics: SafeStatistics {
                         total: 0,
@@ -79,21 +151,30 @@ pub fn ls(format: &crate::OutputFormat) -> Result<(), SkitError> {
                         plain: 0,
                     },
                     updated: "?".to_string(),
-                    status: "Error".to_string(),
+                    status: STATUS_ERROR.to_string(),
                 });
             }
         }
     }
 
+    if options.errors_only {
+        safe_infos.retain(|info| info.status == STATUS_ERROR);
+    }
+
     match format {
         crate::OutputFormat::Json => {
             let output = SafesListOutput { safes: safe_infos };
             println!("{}", format_json_output(&output)?);
         }
+        crate::OutputFormat::Yaml => {
+            let output = SafesListOutput { safes: safe_infos };
+            print!("{}", format_yaml_output(&output)?);
+        }
         _ => {
             print_info(&format!(
-                "Found {} safe(s) in current directory:",
-                safe_files.len()
+                "Found {} safe(s) in {}:",
+                safe_infos.len(),
+                location
             ));
             println!();
 
@@ -118,3 +199,75 @@ pub fn ls(format: &crate::OutputFormat) -> Result<(), SkitError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_safes_depth_and_skipped_dirs() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+        for dir in [
+            "deploy/prod",
+            "deploy/staging/eu",
+            "node_modules/pkg",
+            ".git",
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            ".env.safe",
+            "deploy/prod/.env.safe",
+            "deploy/staging/eu/app.safe",
+            "node_modules/pkg/x.safe",
+            ".git/y.safe",
+            "deploy/notes.txt",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        let relative = |paths: Vec<PathBuf>| -> Vec<String> {
+            paths
+                .iter()
+                .map(|p| {
+                    p.strip_prefix(root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            relative(find_safes(root, false, None).unwrap()),
+            [".env.safe"]
+        );
+        assert_eq!(
+            relative(find_safes(root, true, None).unwrap()),
+            [
+                ".env.safe",
+                "deploy/prod/.env.safe",
+                "deploy/staging/eu/app.safe"
+            ]
+        );
+        assert_eq!(
+            relative(find_safes(root, true, Some(2)).unwrap()),
+            [".env.safe", "deploy/prod/.env.safe"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_safes_survives_symlink_cycle() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+        fs::create_dir(root.join("a")).unwrap();
+        fs::write(root.join("a/app.safe"), "").unwrap();
+        std::os::unix::fs::symlink(root, root.join("a/loop")).unwrap();
+
+        let found = find_safes(root, true, None).unwrap();
+        assert_eq!(found, [root.join("a/app.safe")]);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::audit;
+use crate::crypto;
+use crate::display::print_success;
+use crate::error::SkitError;
+use crate::password::get_password_with_auth_chain;
+use crate::types::{Safe, SortOrder};
+use zeroize::Zeroizing;
+
+/// Merge secrets from another safe into this one, re-encrypting under this safe's password
+pub fn merge(safe_path: &str, source_path: &str, no_overwrite: bool) -> Result<(), SkitError> {
+    let mut dest_safe = Safe::load(safe_path)?;
+    let before = dest_safe.items.clone();
+    let source_safe = Safe::load(source_path)?;
+
+    let source_has_encrypted = source_safe.items.values().any(|item| item.is_encrypted);
+
+    // Passwords are only needed when encrypted values have to be moved across
+    let (source_password, dest_password) = if source_has_encrypted {
+        let source_password = Zeroizing::new(get_password_with_auth_chain(
+            &source_safe,
+            source_path,
+            "Enter password for source safe: ",
+        )?);
+        let dest_password = Zeroizing::new(get_password_with_auth_chain(
+            &dest_safe,
+            safe_path,
+            "Enter password for destination safe: ",
+        )?);
+        (Some(source_password), Some(dest_password))
+    } else {
+        (None, None)
+    };
+
+    let mut added_count = 0;
+    let mut updated_count = 0;
+    let mut skipped_count = 0;
+
+    for key in source_safe.sorted_keys(SortOrder::Asc) {
+        let item = &source_safe.items[key];
+        let exists = dest_safe.find_item(key).is_some();
+
+        if exists && no_overwrite {
+            skipped_count += 1;
+            continue;
+        }
+
+        match (&source_password, &dest_password) {
+            (Some(source_password), Some(dest_password)) if item.is_encrypted => {
+                let decrypted_value = Zeroizing::new(
+                    crypto::DecryptBuilder::new()
+                        .kdf_params(source_safe.kdf_params)
+                        .master_key(source_safe.master_key.as_deref())
+                        .password(source_password)
+                        .ciphertext(&item.value)
+                        .context(key)
+                        .decrypt()?,
+                );
+                let encrypted_value = crypto::EncryptBuilder::new()
+                    .kdf_params(dest_safe.kdf_params)
+                    .master_key(dest_safe.master_key.as_deref())
+                    .cipher(dest_safe.cipher)
+                    .password(dest_password)
+                    .plaintext(&decrypted_value)
+                    .context(key)
+                    .encrypt()?;
+                dest_safe.add_or_update_item(key.clone(), encrypted_value, true);
+            }
+            _ => {
+                dest_safe.add_or_update_item(key.clone(), item.value.clone(), false);
+            }
+        }
+
+        if exists {
+            updated_count += 1;
+        } else {
+            added_count += 1;
+        }
+    }
+
+    if added_count + updated_count > 0 {
+        dest_safe.save(safe_path)?;
+        audit::record_changes(&dest_safe, &before, "merge");
+    }
+
+    print_success(&format!(
+        "Merged {} secrets from {} into {}\n\
+         Added: {}, Updated: {}, Skipped: {}",
+        added_count + updated_count,
+        source_path,
+        safe_path,
+        added_count,
+        updated_count,
+        skipped_count
+    ));
+
+    Ok(())
+}
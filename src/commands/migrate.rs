@@ -0,0 +1,105 @@
+use crate::OutputFormat;
+use crate::commands::template::{CommandTemplate, MessageOutput};
+use crate::crypto;
+use crate::display::print_success;
+use crate::error::SkitError;
+use crate::types::Safe;
+
+/// Arguments for the migrate command (no arguments needed)
+#[derive(Debug)]
+pub struct MigrateArgs;
+
+/// Template-based implementation of the migrate command
+pub struct MigrateCommand;
+
+impl CommandTemplate for MigrateCommand {
+    type Args = MigrateArgs;
+    type Output = MessageOutput;
+
+    fn requires_authentication(&self, _safe: &Safe, _args: &Self::Args) -> bool {
+        // The password is needed to wrap the new master key
+        true
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        _args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let password = password.ok_or_else(|| {
+            SkitError::InvalidPassword("Password required to migrate safe".to_string())
+        })?;
+
+        let mut legacy_keys: Vec<String> = safe
+            .items
+            .values()
+            .filter(|item| item.is_encrypted && !crypto::is_master_key_value(&item.value))
+            .map(|item| item.key.clone())
+            .collect();
+        legacy_keys.sort();
+
+        if safe.master_key.is_some() && legacy_keys.is_empty() {
+            return Ok(MessageOutput::new(
+                "Safe already uses a master key - nothing to migrate",
+            ));
+        }
+
+        // Decrypt everything before the master key changes
+        let mut decrypted = Vec::with_capacity(legacy_keys.len());
+        for key in &legacy_keys {
+            let value = crypto::DecryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .ciphertext(&safe.items[key].value)
+                .context(key)
+                .password(&password)
+                .decrypt()
+                .map_err(SkitError::Crypto)?;
+            decrypted.push((key.clone(), value));
+        }
+
+        if safe.master_key.is_none() {
+            safe.create_master_key(&password)?;
+        }
+
+        for (key, value) in decrypted {
+            let encrypted = crypto::EncryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .cipher(safe.cipher)
+                .plaintext(&value)
+                .context(&key)
+                .password(&password)
+                .encrypt()
+                .map_err(SkitError::Crypto)?;
+            safe.add_or_update_item(key, encrypted, true);
+        }
+
+        Ok(MessageOutput::new(format!(
+            "Migrated safe to format {} ({} secrets re-encrypted under the master key)",
+            safe.version,
+            legacy_keys.len()
+        )))
+    }
+
+    fn modifies_safe(&self) -> bool {
+        true
+    }
+
+    fn audit_action(&self) -> &'static str {
+        "migrate"
+    }
+
+    fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
+        print_success(&output.message);
+        Ok(())
+    }
+}
+
+/// Upgrade a safe to the master key format, re-encrypting older values
+pub fn migrate(safe_path: &str) -> Result<(), SkitError> {
+    let command = MigrateCommand;
+    let args = MigrateArgs;
+
+    command.execute(safe_path, &OutputFormat::Table, args)
+}
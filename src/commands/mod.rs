@@ -1,37 +1,89 @@
+pub mod agent;
+pub mod asm;
+pub mod backup;
+pub mod cat;
 pub mod cleanup_keys;
+pub mod completions;
+pub mod config;
 pub mod copy;
+pub mod destroy;
+pub mod diff;
+pub mod doctor;
+pub mod edit;
 pub mod env;
+pub mod example;
 pub mod exec;
 pub mod export;
+pub mod fix_perms;
+pub mod gcp;
+pub mod generate;
 pub mod get;
+pub mod history;
 pub mod import;
 pub mod init;
 pub mod keys;
+pub mod lint;
 pub mod ls;
+pub mod merge;
+pub mod migrate;
+pub mod passwd;
 pub mod print;
+pub mod pull;
 pub mod remember_safekey;
+pub mod rename;
+pub mod render;
 pub mod rm;
 pub mod rotate;
+pub mod schema;
+pub mod search;
 pub mod set;
 pub mod ssm;
 pub mod status;
 pub mod template;
+pub mod vault;
+pub mod verify;
+pub mod write_env;
 
 // Re-export all command functions
+pub use asm::asm_pull;
+pub use backup::{backup, restore};
+pub use cat::cat;
 pub use cleanup_keys::cleanup_keys;
+pub use completions::completions;
+pub use config::config_show;
 pub use copy::copy;
+pub use destroy::destroy;
+pub use diff::diff;
+pub use doctor::doctor;
+pub use edit::edit;
 pub use env::env;
+pub use example::example;
 pub use exec::exec;
 pub use export::export;
+pub use fix_perms::fix_perms;
+pub use gcp::gcp_pull;
+pub use generate::generate;
 pub use get::get;
+pub use history::history;
 pub use import::import;
 pub use init::init;
 pub use keys::keys;
+pub use lint::lint;
 pub use ls::ls;
+pub use merge::merge;
+pub use migrate::migrate;
+pub use passwd::passwd;
 pub use print::print;
 pub use remember_safekey::{remember_safekey, remember_safekey_with_password_quiet};
+pub use rename::rename;
+pub use render::render;
 pub use rm::rm;
 pub use rotate::rotate;
+pub use schema::{schema_check, schema_export};
+pub use search::search;
 pub use set::set;
-pub use ssm::ssm_pull;
+pub use ssm::{ssm_pull, ssm_push};
 pub use status::status;
+pub use vault::vault_pull;
+pub use verify::verify;
+pub use write_env::write_env;
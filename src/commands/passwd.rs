@@ -0,0 +1,46 @@
+use crate::audit;
+use crate::commands::rotate::{prompt_new_password, rekey};
+use crate::display::{print_info, print_success};
+use crate::error::SkitError;
+use crate::password::{
+    DEFAULT_MIN_STRENGTH, PasswordSource, get_password_with_auth_chain, validate_password_strength,
+};
+use crate::types::Safe;
+
+/// Change the safe password without confirmation prompts, so it can run from scripts
+pub fn passwd(safe_path: &str, new_password_source: &PasswordSource) -> Result<(), SkitError> {
+    let mut safe = Safe::load(safe_path)?;
+
+    // Always prove knowledge of the current password, even for a safe without secrets
+    let old_password = get_password_with_auth_chain(&safe, safe_path, "Enter current password: ")?;
+
+    let new_password = match new_password_source.read()? {
+        Some(password) => {
+            validate_password_strength(&password)?;
+            password
+        }
+        None => {
+            crate::input::require_interactive(
+                "pass --new-password-env or --new-password-file to set the new password",
+            )?;
+            prompt_new_password(DEFAULT_MIN_STRENGTH)?
+        }
+    };
+
+    if new_password == old_password {
+        return Err(SkitError::ParseError(
+            "New password must differ from the current password".to_string(),
+        ));
+    }
+
+    rekey(&mut safe, Some(&old_password), &new_password)?;
+    safe.save(safe_path)?;
+    audit::record(&safe, "passwd");
+
+    print_success("Safe password changed");
+    print_info(
+        "💡 Tip: Update SKIT_SAFEKEY and re-run 'skit remember-safekey' wherever the old password was saved",
+    );
+
+    Ok(())
+}
@@ -1,10 +1,59 @@
 use crate::OutputFormat;
 use crate::commands::template::CommandTemplate;
-use crate::crypto;
-use crate::display::{format_json_output, print_grouped, print_terraform_output};
+use crate::display::{
+    format_json_output, format_toml_output, format_yaml_output, print_grouped,
+    print_terraform_output,
+};
 use crate::error::SkitError;
-use crate::types::{PrintItem, PrintOutput, Safe};
+use crate::key_filter::has_tags;
+use crate::types::{ItemMetadata, PrintItem, PrintOutput, Safe, SortOrder};
+use crate::validation::{is_valid_k8s_data_key, is_valid_k8s_name};
+use base64::{Engine as _, engine::general_purpose};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Shown in place of an encrypted value that was not decrypted
+const HIDDEN_VALUE: &str = "<Value hidden - encrypted>";
+/// Shown in place of an encrypted value that failed to decrypt
+const DECRYPTION_FAILED: &str = "[DECRYPTION_FAILED]";
+
+/// A value as `print` has it: the text to show, or why an encrypted one has none
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrintValue {
+    /// Plain or decrypted value, or the stored ciphertext with `--raw`
+    Shown(String),
+    /// Encrypted and not decrypted, as no password was given
+    Hidden,
+    /// Encrypted and failed to decrypt
+    Failed,
+}
+
+impl PrintValue {
+    /// The value, if there is one to show
+    fn shown(&self) -> Option<&str> {
+        match self {
+            PrintValue::Shown(value) => Some(value),
+            PrintValue::Hidden | PrintValue::Failed => None,
+        }
+    }
+
+    /// The value, or a placeholder for formats that list every key
+    fn display(&self) -> &str {
+        match self {
+            PrintValue::Shown(value) => value,
+            PrintValue::Hidden => HIDDEN_VALUE,
+            PrintValue::Failed => DECRYPTION_FAILED,
+        }
+    }
+}
+
+/// Items with placeholders filled in, for the table and terraform printers
+fn displayed(items: &[(String, PrintValue, bool)]) -> Vec<(String, String, bool)> {
+    items
+        .iter()
+        .map(|(key, value, is_encrypted)| (key.clone(), value.display().to_string(), *is_encrypted))
+        .collect()
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 struct PostmanEnvironmentVariable {
@@ -28,17 +77,61 @@ struct PostmanEnvironment {
     postman_exported_using: String,
 }
 
+/// Secret name used for `-o k8s-secret` when `--k8s-name` is not given
+pub const DEFAULT_K8S_SECRET_NAME: &str = "skit-secrets";
+
+#[derive(Debug, Serialize)]
+struct K8sObjectMeta {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct K8sSecret {
+    api_version: String,
+    kind: String,
+    metadata: K8sObjectMeta,
+    #[serde(rename = "type")]
+    secret_type: String,
+    data: BTreeMap<String, String>,
+}
+
+/// Name and namespace of the Secret emitted by `-o k8s-secret`
+#[derive(Debug, Clone, Default)]
+pub struct K8sSecretTarget {
+    pub name: String,
+    pub namespace: Option<String>,
+}
+
 /// Arguments for the print command
 #[derive(Debug)]
 pub struct PrintArgs {
     pub plain_only: bool,
     pub enc_only: bool,
+    pub verbose: bool,
+    /// Show only items carrying all of these tags
+    pub tags: Vec<String>,
+    /// Show stored values as-is, ciphertext included, without authenticating
+    pub raw: bool,
+    /// Fail instead of showing a placeholder when a value can't be decrypted
+    pub strict: bool,
+    pub sort: SortOrder,
+    pub k8s: K8sSecretTarget,
 }
 
 /// Output for the print command
 #[derive(Debug)]
 pub struct PrintCommandOutput {
-    pub items: Vec<(String, String, bool)>, // (key, value, is_encrypted)
+    pub items: Vec<(String, PrintValue, bool)>, // (key, value, is_encrypted)
+    /// Per-key metadata, only collected for `--verbose`
+    pub metadata: HashMap<String, ItemMetadata>,
+    /// Encrypted values are the stored ciphertext
+    pub raw: bool,
+    /// Keys whose value failed to decrypt and shows a placeholder
+    pub failed: Vec<String>,
+    pub k8s: K8sSecretTarget,
 }
 
 /// Template-based implementation of the print command
@@ -54,13 +147,30 @@ impl CommandTemplate for PrintCommand {
                 "Cannot use both --plain and --enc flags together".to_string(),
             ));
         }
+        if !is_valid_k8s_name(&args.k8s.name) {
+            return Err(SkitError::ParseError(format!(
+                "Invalid --k8s-name '{}': use lowercase letters, digits, '-' and '.'",
+                args.k8s.name
+            )));
+        }
+        if let Some(namespace) = &args.k8s.namespace
+            && (namespace.len() > 63 || namespace.contains('.') || !is_valid_k8s_name(namespace))
+        {
+            return Err(SkitError::ParseError(format!(
+                "Invalid --k8s-namespace '{}': use lowercase letters, digits and '-'",
+                namespace
+            )));
+        }
         Ok(())
     }
 
     fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
         // Only need password if we have encrypted items and we're not showing plain-only
-        let has_encrypted = safe.items.values().any(|item| item.is_encrypted);
-        has_encrypted && !args.plain_only
+        let has_encrypted = safe
+            .items
+            .values()
+            .any(|item| item.is_encrypted && has_tags(item, &args.tags));
+        has_encrypted && !args.plain_only && !args.raw
     }
 
     fn execute_operation(
@@ -70,47 +180,66 @@ impl CommandTemplate for PrintCommand {
         args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
         if safe.items.is_empty() {
-            return Ok(PrintCommandOutput { items: vec![] });
+            return Ok(PrintCommandOutput {
+                items: vec![],
+                metadata: HashMap::new(),
+                raw: args.raw,
+                failed: vec![],
+                k8s: args.k8s,
+            });
         }
 
-        // Sort keys for consistent output
-        let mut keys: Vec<_> = safe.items.keys().collect();
-        keys.sort();
+        // Filter based on flags
+        let items: Vec<_> = safe
+            .sorted_keys(args.sort)
+            .into_iter()
+            .map(|key| &safe.items[key])
+            .filter(|item| !args.plain_only || !item.is_encrypted)
+            .filter(|item| !args.enc_only || item.is_encrypted)
+            .filter(|item| has_tags(item, &args.tags))
+            .collect();
 
-        let mut output_data = Vec::new();
+        // Raw output shows ciphertext as stored, so nothing is decrypted
+        let decrypted = if args.raw {
+            items
+                .iter()
+                .map(|item| Some(Ok(item.value.clone())))
+                .collect()
+        } else {
+            safe.decrypt_selected(&items, password.as_deref())
+        };
 
-        for key in keys {
-            let item = &safe.items[key];
+        let mut output_data = Vec::new();
+        let mut metadata = HashMap::new();
+        let mut failed = Vec::new();
 
-            // Filter based on flags
-            if args.plain_only && item.is_encrypted {
-                continue; // Skip encrypted items when --plain is used
-            }
-            if args.enc_only && !item.is_encrypted {
-                continue; // Skip plain items when --enc is used
+        for (item, result) in items.into_iter().zip(decrypted) {
+            if args.verbose {
+                metadata.insert(item.key.clone(), item.metadata.clone());
             }
-
-            let value = if item.is_encrypted {
-                if let Some(ref pwd) = password {
-                    match crypto::DecryptBuilder::new()
-                        .ciphertext(&item.value)
-                        .password(pwd)
-                        .decrypt()
-                    {
-                        Ok(v) => v,
-                        Err(_) => "[DECRYPTION_FAILED]".to_string(),
-                    }
-                } else {
-                    "<Value hidden - encrypted>".to_string()
+            let value = match result {
+                Some(Ok(v)) => PrintValue::Shown(v),
+                Some(Err(_)) => {
+                    failed.push(item.key.clone());
+                    PrintValue::Failed
                 }
-            } else {
-                item.value.clone()
+                None => PrintValue::Hidden,
             };
 
             output_data.push((item.key.clone(), value, item.is_encrypted));
         }
 
-        Ok(PrintCommandOutput { items: output_data })
+        if args.strict && !failed.is_empty() {
+            return Err(SkitError::DecryptionFailed(failed));
+        }
+
+        Ok(PrintCommandOutput {
+            items: output_data,
+            metadata,
+            raw: args.raw,
+            failed,
+            k8s: args.k8s,
+        })
     }
 
     fn format_output(&self, output: Self::Output, format: &OutputFormat) -> Result<(), SkitError> {
@@ -120,6 +249,9 @@ impl CommandTemplate for PrintCommand {
                     let print_output = PrintOutput { items: vec![] };
                     println!("{}", format_json_output(&print_output)?);
                 }
+                OutputFormat::Yaml => {
+                    print!("{}", format_yaml_output(&serde_yaml::Mapping::new())?);
+                }
                 OutputFormat::Env => {
                     // No output for empty safe in env format
                 }
@@ -127,7 +259,13 @@ impl CommandTemplate for PrintCommand {
                     println!("No items in safe");
                 }
                 OutputFormat::Terraform => {
-                    print_terraform_output(&output.items);
+                    print_terraform_output(&[]);
+                }
+                OutputFormat::Toml => {
+                    // An empty TOML document
+                }
+                OutputFormat::K8sSecret => {
+                    print!("{}", format_k8s_secret(&output.items, &output.k8s)?);
                 }
                 OutputFormat::Postman => {
                     let postman_env = PostmanEnvironment {
@@ -151,25 +289,33 @@ impl CommandTemplate for PrintCommand {
                     .iter()
                     .map(|(key, value, is_encrypted)| PrintItem {
                         key: key.clone(),
-                        value: value.clone(),
+                        value: value.display().to_string(),
                         item_type: if *is_encrypted {
                             "ENC".to_string()
                         } else {
                             "PLAIN".to_string()
                         },
+                        decrypt_failed: output.failed.contains(key),
                     })
                     .collect();
 
                 let print_output = PrintOutput { items };
                 println!("{}", format_json_output(&print_output)?);
             }
+            OutputFormat::Yaml => {
+                let mut mapping = serde_yaml::Mapping::new();
+                for (key, value, _) in output.items {
+                    mapping.insert(key.into(), value.display().into());
+                }
+                print!("{}", format_yaml_output(&mapping)?);
+            }
             OutputFormat::Env => {
                 for (key, value, _) in output.items {
-                    println!("{}={}", key, value);
+                    println!("{}={}", key, value.display());
                 }
             }
             OutputFormat::Table => {
-                print_grouped(&output.items);
+                print_grouped(&displayed(&output.items), &output.metadata);
                 let has_encrypted = output
                     .items
                     .iter()
@@ -177,13 +323,38 @@ impl CommandTemplate for PrintCommand {
                 if has_encrypted {
                     use crate::display::print_info;
                     println!();
-                    print_info(
-                        "Encrypted values are stored securely in the file - only decrypted for display",
-                    );
+                    if output.raw {
+                        print_info(
+                            "Raw mode: encrypted values are shown as stored ciphertext, not decrypted",
+                        );
+                    } else {
+                        print_info(
+                            "Encrypted values are stored securely in the file - only decrypted for display",
+                        );
+                    }
                 }
             }
             OutputFormat::Terraform => {
-                print_terraform_output(&output.items);
+                print_terraform_output(&displayed(&output.items));
+            }
+            OutputFormat::Toml => {
+                // Placeholders for undecrypted values would read as real strings, so leave
+                // those keys out and name them in a comment instead
+                let mut items = Vec::new();
+                let mut hidden = Vec::new();
+                for (key, value, _) in &output.items {
+                    match value.shown() {
+                        Some(value) => items.push((key.clone(), value.to_string())),
+                        None => hidden.push(key.as_str()),
+                    }
+                }
+                if !hidden.is_empty() {
+                    println!("# Omitted (not decrypted): {}\n", hidden.join(", "));
+                }
+                print!("{}", format_toml_output(&items)?);
+            }
+            OutputFormat::K8sSecret => {
+                print!("{}", format_k8s_secret(&output.items, &output.k8s)?);
             }
             OutputFormat::Postman => {
                 let values: Vec<PostmanEnvironmentVariable> = output
@@ -191,7 +362,7 @@ impl CommandTemplate for PrintCommand {
                     .iter()
                     .map(|(key, value, is_encrypted)| PostmanEnvironmentVariable {
                         key: key.clone(),
-                        value: value.clone(),
+                        value: value.display().to_string(),
                         var_type: if *is_encrypted {
                             "secret".to_string()
                         } else {
@@ -218,18 +389,108 @@ impl CommandTemplate for PrintCommand {
     }
 }
 
+/// Render a `v1/Secret` manifest with base64 `data`. Values that were not decrypted and keys
+/// Kubernetes does not accept are skipped with a warning on stderr, keeping stdout applyable.
+fn format_k8s_secret(
+    items: &[(String, PrintValue, bool)],
+    target: &K8sSecretTarget,
+) -> Result<String, SkitError> {
+    let mut data = BTreeMap::new();
+    for (key, value, _) in items {
+        let Some(value) = value.shown() else {
+            eprintln!("Warning: Skipping '{}': value was not decrypted", key);
+            continue;
+        };
+        if !is_valid_k8s_data_key(key) {
+            eprintln!(
+                "Warning: Skipping '{}': not a valid Kubernetes Secret key (letters, digits, '-', '_', '.')",
+                key
+            );
+        } else {
+            data.insert(key.clone(), general_purpose::STANDARD.encode(value));
+        }
+    }
+
+    let secret = K8sSecret {
+        api_version: "v1".to_string(),
+        kind: "Secret".to_string(),
+        metadata: K8sObjectMeta {
+            name: target.name.clone(),
+            namespace: target.namespace.clone(),
+        },
+        secret_type: "Opaque".to_string(),
+        data,
+    };
+    format_yaml_output(&secret)
+}
+
 /// Display all secrets in organized format
+#[allow(clippy::too_many_arguments)]
 pub fn print(
     safe_path: &str,
     format: &OutputFormat,
     plain_only: bool,
     enc_only: bool,
+    verbose: bool,
+    tags: &[String],
+    raw: bool,
+    strict: bool,
+    sort: SortOrder,
+    k8s: K8sSecretTarget,
 ) -> Result<(), SkitError> {
+    // The Secret would carry base64-encoded ciphertext that the cluster can't use
+    if raw && matches!(format, OutputFormat::K8sSecret) {
+        return Err(SkitError::ParseError(
+            "Cannot use --raw with -o k8s-secret: encrypted values would be stored as ciphertext"
+                .to_string(),
+        ));
+    }
+
     let command = PrintCommand;
     let args = PrintArgs {
         plain_only,
         enc_only,
+        verbose,
+        tags: tags.to_vec(),
+        raw,
+        strict,
+        sort,
+        k8s,
     };
 
     command.execute(safe_path, format, args)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(value: &str) -> PrintValue {
+        PrintValue::Shown(value.to_string())
+    }
+
+    #[test]
+    fn test_format_k8s_secret_encodes_and_skips() {
+        let items = vec![
+            ("API_KEY".to_string(), shown("s3cret"), true),
+            ("app/db/url".to_string(), shown("postgres://db"), false),
+            ("HIDDEN".to_string(), PrintValue::Hidden, true),
+            ("tls.crt".to_string(), shown("cert"), false),
+        ];
+        let target = K8sSecretTarget {
+            name: "web".to_string(),
+            namespace: Some("prod".to_string()),
+        };
+
+        let manifest = format_k8s_secret(&items, &target).unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&manifest).unwrap();
+
+        assert_eq!(parsed["apiVersion"].as_str(), Some("v1"));
+        assert_eq!(parsed["kind"].as_str(), Some("Secret"));
+        assert_eq!(parsed["metadata"]["namespace"].as_str(), Some("prod"));
+        assert_eq!(parsed["data"]["API_KEY"].as_str(), Some("czNjcmV0"));
+        assert_eq!(parsed["data"]["tls.crt"].as_str(), Some("Y2VydA=="));
+        let data = parsed["data"].as_mapping().unwrap();
+        assert_eq!(data.len(), 2);
+    }
+}
@@ -0,0 +1,181 @@
+//! Storing values pulled from a remote store (SSM, Secrets Manager, Secret Manager, Vault)
+
+use crate::crypto;
+use crate::error::SkitError;
+use crate::types::Safe;
+
+/// How many keys a dry run lists before summarizing the rest
+const PREVIEW_LIMIT: usize = 10;
+
+/// A value pulled from a remote store, keyed by its safe key name
+#[derive(Debug, Clone, PartialEq)]
+pub struct PulledEntry {
+    pub key: String,
+    pub value: String,
+    /// Store encrypted (SecureStrings and every secret manager value) or as plain text
+    pub encrypt: bool,
+}
+
+/// What a pull did to the safe
+#[derive(Debug, Default, PartialEq)]
+pub struct PullCounts {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub encrypted: usize,
+    pub plain: usize,
+}
+
+/// Result of [`apply_pulled`]: the listing for a dry run, or the counts once stored
+#[derive(Debug, PartialEq)]
+pub enum PullOutcome {
+    DryRun(String),
+    Applied(PullCounts),
+}
+
+/// Store pulled entries in the safe, or with `dry_run` list what would happen to each key
+///
+/// `replace` clears the safe first; `no_overwrite` keeps existing keys as they are. Encrypted
+/// entries need `password`.
+pub fn apply_pulled(
+    safe: &mut Safe,
+    entries: Vec<PulledEntry>,
+    password: Option<&str>,
+    replace: bool,
+    no_overwrite: bool,
+    dry_run: bool,
+) -> Result<PullOutcome, SkitError> {
+    if dry_run {
+        let mut listing = String::new();
+        for entry in entries.iter().take(PREVIEW_LIMIT) {
+            let action = match safe.find_item(&entry.key) {
+                Some(_) if no_overwrite && !replace => "skip - exists",
+                Some(_) if !replace => "update",
+                _ => "add",
+            };
+            let storage = if entry.encrypt { "" } else { ", plain text" };
+            listing.push_str(&format!("  {} [{}{}]\n", entry.key, action, storage));
+        }
+        if entries.len() > PREVIEW_LIMIT {
+            listing.push_str(&format!(
+                "  ... and {} more\n",
+                entries.len() - PREVIEW_LIMIT
+            ));
+        }
+        return Ok(PullOutcome::DryRun(listing));
+    }
+
+    if replace {
+        safe.items.clear();
+    }
+
+    let mut counts = PullCounts::default();
+    for entry in entries {
+        let is_new = safe.find_item(&entry.key).is_none();
+        if no_overwrite && !is_new {
+            counts.skipped += 1;
+            continue;
+        }
+
+        if entry.encrypt {
+            let password = password.ok_or_else(|| {
+                SkitError::InvalidPassword(
+                    "Password required to encrypt pulled secrets".to_string(),
+                )
+            })?;
+            let encrypted_value = crypto::EncryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .cipher(safe.cipher)
+                .plaintext(&entry.value)
+                .context(&entry.key)
+                .password(password)
+                .encrypt()
+                .map_err(SkitError::Crypto)?;
+            safe.add_or_update_item(entry.key, encrypted_value, true);
+            counts.encrypted += 1;
+        } else {
+            safe.add_or_update_item(entry.key, entry.value, false);
+            counts.plain += 1;
+        }
+
+        if is_new {
+            counts.added += 1;
+        } else {
+            counts.updated += 1;
+        }
+    }
+
+    Ok(PullOutcome::Applied(counts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, value: &str, encrypt: bool) -> PulledEntry {
+        PulledEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+            encrypt,
+        }
+    }
+
+    fn test_safe() -> Safe {
+        let mut safe = Safe::new_with_password("pass", "test").unwrap();
+        safe.add_or_update_item("EXISTING".to_string(), "old".to_string(), false);
+        safe
+    }
+
+    #[test]
+    fn test_dry_run_lists_actions_without_storing() {
+        let mut safe = test_safe();
+        let entries = vec![entry("EXISTING", "new", false), entry("TOKEN", "t", true)];
+
+        let outcome = apply_pulled(&mut safe, entries.clone(), None, false, false, true).unwrap();
+        assert_eq!(
+            outcome,
+            PullOutcome::DryRun("  EXISTING [update, plain text]\n  TOKEN [add]\n".to_string())
+        );
+        assert!(safe.find_item("TOKEN").is_none());
+
+        let outcome = apply_pulled(&mut safe, entries, None, false, true, true).unwrap();
+        assert!(
+            matches!(outcome, PullOutcome::DryRun(listing) if listing.contains("EXISTING [skip - exists"))
+        );
+    }
+
+    #[test]
+    fn test_apply_counts_and_respects_no_overwrite() {
+        let mut safe = test_safe();
+        let entries = vec![entry("EXISTING", "new", false), entry("HOST", "db", false)];
+
+        let outcome = apply_pulled(&mut safe, entries, None, false, true, false).unwrap();
+        assert_eq!(
+            outcome,
+            PullOutcome::Applied(PullCounts {
+                added: 1,
+                skipped: 1,
+                plain: 1,
+                ..Default::default()
+            })
+        );
+        assert_eq!(safe.find_item("EXISTING").unwrap().value, "old");
+        assert_eq!(safe.find_item("HOST").unwrap().value, "db");
+    }
+
+    #[test]
+    fn test_encrypted_entries_need_a_password() {
+        let mut safe = test_safe();
+        let entries = vec![entry("TOKEN", "t", true)];
+
+        assert!(matches!(
+            apply_pulled(&mut safe, entries.clone(), None, false, false, false),
+            Err(SkitError::InvalidPassword(_))
+        ));
+
+        apply_pulled(&mut safe, entries, Some("pass"), true, false, false).unwrap();
+        assert!(safe.find_item("EXISTING").is_none());
+        assert!(safe.find_item("TOKEN").unwrap().is_encrypted);
+    }
+}
@@ -5,7 +5,7 @@ use crate::types::Safe;
 use std::fs;
 use std::path::PathBuf;
 
-pub fn remember_safekey(safe_path: &str) -> Result<(), SkitError> {
+pub fn remember_safekey(safe_path: &str, use_keyring: bool) -> Result<(), SkitError> {
     // Load the safe to get the UUID
     let safe = Safe::load(safe_path)?;
 
@@ -16,8 +16,9 @@ pub fn remember_safekey(safe_path: &str) -> Result<(), SkitError> {
             pass
         }
         None => {
+            crate::input::require_interactive("set SKIT_SAFEKEY to the safe password")?;
             println!("Enter the password for this safe to verify and save it:");
-            crate::input::prompt_password_with_fallback("Password: ").map_err(SkitError::Io)?
+            crate::password::prompt_current_password(&safe, "Password: ")?
         }
     };
 
@@ -28,19 +29,18 @@ pub fn remember_safekey(safe_path: &str) -> Result<(), SkitError> {
         ));
     }
 
-    remember_safekey_with_password(&safe, &password).map(|_| ())
+    remember_safekey_with_password_quiet(&safe, safe_path, &password, false, use_keyring)
+        .map(|_| ())
 }
 
-/// Save a safe key with a known password (used internally when we already have the password)
-pub fn remember_safekey_with_password(safe: &Safe, password: &str) -> Result<(), SkitError> {
-    remember_safekey_with_password_quiet(safe, password, false).map(|_| ())
-}
-
-/// Save a safe key with a known password, with optional quiet mode
+/// Save a safe key with a known password, with optional quiet mode, and record it in the
+/// key index. Returns a description of where the key was stored.
 pub fn remember_safekey_with_password_quiet(
     safe: &Safe,
+    safe_path: &str,
     password: &str,
     quiet: bool,
+    use_keyring: bool,
 ) -> Result<String, SkitError> {
     // Verify the password is correct
     if safe.verify_password(password).is_err() {
@@ -49,6 +49,12 @@ pub fn remember_safekey_with_password_quiet(
         ));
     }
 
+    if use_keyring {
+        let location = remember_in_keychain(safe, password, quiet)?;
+        crate::key_index::record_use(safe, Some(safe_path));
+        return Ok(location);
+    }
+
     // Create the ~/.config/skit/keys directory
     let home_dir = dirs::home_dir().ok_or_else(|| {
         SkitError::Io(std::io::Error::new(
@@ -58,11 +64,12 @@ pub fn remember_safekey_with_password_quiet(
     })?;
 
     let skit_keys_dir: PathBuf = home_dir.join(".config").join("skit").join("keys");
-    fs::create_dir_all(&skit_keys_dir).map_err(SkitError::Io)?;
+    crate::fs_utils::create_private_dir(&skit_keys_dir)?;
 
     // Save the password to ~/.config/skit/keys/<uuid>.key securely
     let key_file = skit_keys_dir.join(format!("{}.key", safe.uuid));
     crate::fs_utils::write_secret_file_secure(&key_file, password)?;
+    crate::key_index::record_use(safe, Some(safe_path));
 
     if !quiet {
         print_success(&format!("Password saved to {}", key_file.display()));
@@ -71,3 +78,32 @@ pub fn remember_safekey_with_password_quiet(
 
     Ok(key_file.display().to_string())
 }
+
+fn remember_in_keychain(safe: &Safe, password: &str, quiet: bool) -> Result<String, SkitError> {
+    crate::keychain::store_password(&safe.uuid, password)?;
+
+    // The keychain replaces the plaintext key file, so don't leave a copy behind
+    if let Some(home_dir) = dirs::home_dir() {
+        let key_file = home_dir
+            .join(".config")
+            .join("skit")
+            .join("keys")
+            .join(format!("{}.key", safe.uuid));
+        if key_file.exists() {
+            fs::remove_file(&key_file).map_err(SkitError::Io)?;
+            if !quiet {
+                print_info(&format!(
+                    "Removed plaintext key file {}",
+                    key_file.display()
+                ));
+            }
+        }
+    }
+
+    if !quiet {
+        print_success("Password saved to the OS keychain");
+        print_info(&format!("Safe UUID: {}", safe.uuid));
+    }
+
+    Ok("the OS keychain".to_string())
+}
@@ -0,0 +1,234 @@
+use crate::OutputFormat;
+use crate::commands::template::{CommandTemplate, MessageOutput};
+use crate::crypto;
+use crate::display::print_success;
+use crate::error::SkitError;
+use crate::types::Safe;
+use crate::validation::is_valid_env_key;
+
+/// Arguments for the rename command
+#[derive(Debug)]
+pub struct RenameArgs {
+    pub old: String,
+    pub new: String,
+    pub force: bool,
+}
+
+/// Template-based implementation of the rename command
+pub struct RenameCommand;
+
+impl CommandTemplate for RenameCommand {
+    type Args = RenameArgs;
+    type Output = MessageOutput;
+
+    fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
+        if args.old.is_empty() || args.new.is_empty() {
+            return Err(SkitError::ParseError("Key cannot be empty".to_string()));
+        }
+        if !is_valid_env_key(&args.new) {
+            return Err(SkitError::ParseError(format!(
+                "Invalid key '{}' (must match [A-Za-z_][A-Za-z0-9_]*)",
+                args.new
+            )));
+        }
+        if args.old == args.new {
+            return Err(SkitError::ParseError(
+                "Old and new key names are the same".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
+        // Older values are moved as-is, but key-bound ones must be re-encrypted under the
+        // new name. Discarding an existing encrypted secret also requires the password.
+        let bound = safe
+            .find_item(&args.old)
+            .is_some_and(|item| item.is_encrypted && crypto::is_key_bound(&item.value));
+        let overwrites_encrypted = args.force
+            && safe
+                .find_item(&args.new)
+                .is_some_and(|item| item.is_encrypted);
+        bound || overwrites_encrypted
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        if safe.find_item(&args.old).is_none() {
+            return Err(safe.key_not_found(&args.old));
+        }
+
+        let overwritten = safe.find_item(&args.new).is_some();
+        if overwritten && !args.force {
+            return Err(SkitError::ParseError(format!(
+                "Key '{}' already exists in safe (use --force to overwrite)",
+                args.new
+            )));
+        }
+
+        if overwritten {
+            safe.items.shift_remove(&args.new);
+        }
+        // The renamed key keeps its place in the file
+        let (index, _, mut item) =
+            safe.items
+                .shift_remove_full(&args.old)
+                .ok_or_else(|| SkitError::KeyNotFound {
+                    key: args.old.clone(),
+                    suggestion: None,
+                })?;
+        if item.is_encrypted && crypto::is_key_bound(&item.value) {
+            let password = password.ok_or_else(|| {
+                SkitError::InvalidPassword("Password required to re-encrypt value".to_string())
+            })?;
+            let value = zeroize::Zeroizing::new(
+                crypto::DecryptBuilder::new()
+                    .kdf_params(safe.kdf_params)
+                    .master_key(safe.master_key.as_deref())
+                    .ciphertext(&item.value)
+                    .context(&args.old)
+                    .password(&password)
+                    .decrypt()?,
+            );
+            item.value = crypto::EncryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .cipher(safe.cipher)
+                .plaintext(&value)
+                .context(&args.new)
+                .password(&password)
+                .encrypt()?;
+        }
+        item.key = args.new.clone();
+        safe.items.shift_insert(index, args.new.clone(), item);
+
+        let message = if overwritten {
+            format!(
+                "Renamed '{}' to '{}' (overwrote existing value)",
+                args.old, args.new
+            )
+        } else {
+            format!("Renamed '{}' to '{}'", args.old, args.new)
+        };
+        Ok(MessageOutput::new(message))
+    }
+
+    fn modifies_safe(&self) -> bool {
+        true
+    }
+
+    fn audit_action(&self) -> &'static str {
+        "rename"
+    }
+
+    fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
+        print_success(&output.message);
+        Ok(())
+    }
+}
+
+/// Rename a secret. Values bound to their key name are re-encrypted under the new name;
+/// older values are moved as-is.
+pub fn rename(safe_path: &str, old: &str, new: &str, force: bool) -> Result<(), SkitError> {
+    let command = RenameCommand;
+    let args = RenameArgs {
+        old: old.to_string(),
+        new: new.to_string(),
+        force,
+    };
+
+    command.execute(safe_path, &OutputFormat::Table, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safe_with_items() -> Safe {
+        let content = "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n\
+                       OLD=ENC~v1~abc\nPLAIN=value\nSECRET=ENC~v1~def\n";
+        Safe::parse(content).unwrap()
+    }
+
+    fn args(old: &str, new: &str, force: bool) -> RenameArgs {
+        RenameArgs {
+            old: old.to_string(),
+            new: new.to_string(),
+            force,
+        }
+    }
+
+    #[test]
+    fn test_rename_refuses_existing_key_without_force() {
+        let mut safe = safe_with_items();
+        let result = RenameCommand.execute_operation(&mut safe, None, args("OLD", "PLAIN", false));
+
+        assert!(result.is_err());
+        assert_eq!(safe.find_item("PLAIN").unwrap().value, "value");
+    }
+
+    #[test]
+    fn test_rename_force_overwrites_and_keeps_ciphertext() {
+        let mut safe = safe_with_items();
+        RenameCommand
+            .execute_operation(&mut safe, None, args("OLD", "PLAIN", true))
+            .unwrap();
+
+        assert!(safe.find_item("OLD").is_none());
+        let item = safe.find_item("PLAIN").unwrap();
+        assert!(item.is_encrypted);
+        assert_eq!(item.value, "ENC~v1~abc");
+    }
+
+    #[test]
+    fn test_rename_reencrypts_key_bound_value() {
+        let mut safe = safe_with_items();
+        safe.kdf_params = crypto::KdfParams {
+            memory_kib: 1024,
+            time_cost: 1,
+            lanes: 1,
+        };
+        let bound = crypto::EncryptBuilder::new()
+            .kdf_params(safe.kdf_params)
+            .plaintext("secret")
+            .context("BOUND")
+            .password("pw")
+            .encrypt()
+            .unwrap();
+        safe.add_or_update_item("BOUND".to_string(), bound, true);
+        assert!(RenameCommand.requires_authentication(&safe, &args("BOUND", "MOVED", false)));
+
+        RenameCommand
+            .execute_operation(
+                &mut safe,
+                Some("pw".to_string()),
+                args("BOUND", "MOVED", false),
+            )
+            .unwrap();
+
+        let moved = &safe.find_item("MOVED").unwrap().value;
+        let decrypt = |key| {
+            crypto::DecryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .ciphertext(moved)
+                .context(key)
+                .password("pw")
+                .decrypt()
+        };
+        assert_eq!(decrypt("MOVED").unwrap(), "secret");
+        assert!(decrypt("BOUND").is_err());
+    }
+
+    #[test]
+    fn test_rename_requires_password_only_when_overwriting_encrypted() {
+        let safe = safe_with_items();
+
+        assert!(!RenameCommand.requires_authentication(&safe, &args("OLD", "NEW", false)));
+        assert!(!RenameCommand.requires_authentication(&safe, &args("OLD", "PLAIN", true)));
+        assert!(RenameCommand.requires_authentication(&safe, &args("OLD", "SECRET", true)));
+    }
+}
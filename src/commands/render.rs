@@ -0,0 +1,312 @@
+use crate::OutputFormat;
+use crate::commands::import::{STDIN_PATH, read_input};
+use crate::commands::template::CommandTemplate;
+use crate::display::print_success;
+use crate::error::SkitError;
+use crate::fs_utils;
+use crate::types::Safe;
+use crate::validation::is_valid_env_key;
+use std::collections::BTreeSet;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// What to put in place of a placeholder whose key is not in the safe
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingPolicy {
+    /// Leave the placeholder as written
+    #[default]
+    Keep,
+    /// Replace the placeholder with nothing
+    Empty,
+}
+
+/// A piece of a parsed template
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    /// A `{{KEY}}` or `${KEY}` reference, with the text it was written as
+    Placeholder {
+        key: String,
+        raw: String,
+    },
+}
+
+/// Split a template into text and placeholders.
+///
+/// `{{KEY}}` (spaces inside the braces are allowed) is always a placeholder, `${KEY}` only with
+/// `dollar`. Braces around anything that isn't a valid key name are left alone, so other
+/// template syntax passes through. A backslash escapes a placeholder: `\{{KEY}}` renders as
+/// `{{KEY}}` and, with `dollar`, `\${KEY}` as `${KEY}`.
+fn parse_template(text: &str, dollar: bool) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(escaped) = rest.strip_prefix('\\')
+            && (escaped.starts_with("{{") || (dollar && escaped.starts_with("${")))
+        {
+            literal.push_str(&escaped[..2]);
+            rest = &escaped[2..];
+            continue;
+        }
+
+        let delimiters = if rest.starts_with("{{") {
+            Some(("{{", "}}"))
+        } else if dollar && rest.starts_with("${") {
+            Some(("${", "}"))
+        } else {
+            None
+        };
+        if let Some((open, close)) = delimiters
+            && let Some(end) = rest[open.len()..].find(close)
+        {
+            let key = rest[open.len()..open.len() + end].trim();
+            if is_valid_env_key(key) {
+                let raw_len = open.len() + end + close.len();
+                if !literal.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Placeholder {
+                    key: key.to_string(),
+                    raw: rest[..raw_len].to_string(),
+                });
+                rest = &rest[raw_len..];
+                continue;
+            }
+        }
+
+        literal.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Text(literal));
+    }
+    segments
+}
+
+/// Keys referenced by the template's placeholders
+fn referenced_keys(segments: &[Segment]) -> BTreeSet<&str> {
+    segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Placeholder { key, .. } => Some(key.as_str()),
+            Segment::Text(_) => None,
+        })
+        .collect()
+}
+
+/// Arguments for the template command
+#[derive(Debug)]
+pub struct RenderArgs {
+    /// Template contents, already read from the file or stdin
+    pub template: String,
+    /// Write here instead of stdout
+    pub output: Option<String>,
+    /// Also substitute `${KEY}` placeholders
+    pub dollar: bool,
+    /// With `Some`, unknown keys are allowed and handled per the policy
+    pub missing: Option<MissingPolicy>,
+}
+
+/// Output for the template command
+#[derive(Debug)]
+pub struct RenderOutput {
+    pub rendered: Zeroizing<String>,
+    pub output: Option<String>,
+    pub substituted: usize,
+    pub missing: Vec<String>,
+}
+
+/// Template-based implementation of the template command
+pub struct RenderCommand;
+
+impl CommandTemplate for RenderCommand {
+    type Args = RenderArgs;
+    type Output = RenderOutput;
+
+    fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
+        referenced_keys(&parse_template(&args.template, args.dollar))
+            .iter()
+            .any(|key| safe.find_item(key).is_some_and(|item| item.is_encrypted))
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let segments = parse_template(&args.template, args.dollar);
+        let keys = referenced_keys(&segments);
+
+        let missing: Vec<String> = keys
+            .iter()
+            .filter(|key| safe.find_item(key).is_none())
+            .map(|key| key.to_string())
+            .collect();
+        if !missing.is_empty() && args.missing.is_none() {
+            return Err(safe.key_not_found(&missing[0]));
+        }
+
+        let items: Vec<_> = keys.iter().filter_map(|key| safe.find_item(key)).collect();
+        let decrypted = safe.decrypt_selected(&items, password.as_deref());
+        let mut values = std::collections::HashMap::new();
+        let mut failed = Vec::new();
+        for (item, result) in items.into_iter().zip(decrypted) {
+            match result {
+                Some(Ok(value)) => {
+                    values.insert(item.key.clone(), Zeroizing::new(value));
+                }
+                _ => failed.push(item.key.clone()),
+            }
+        }
+        if !failed.is_empty() {
+            return Err(SkitError::DecryptionFailed(failed));
+        }
+
+        let mut rendered = Zeroizing::new(String::with_capacity(args.template.len()));
+        let mut substituted = 0;
+        for segment in &segments {
+            match segment {
+                Segment::Text(text) => rendered.push_str(text),
+                Segment::Placeholder { key, raw } => match values.get(key) {
+                    Some(value) => {
+                        rendered.push_str(value);
+                        substituted += 1;
+                    }
+                    None if args.missing == Some(MissingPolicy::Empty) => {}
+                    None => rendered.push_str(raw),
+                },
+            }
+        }
+
+        Ok(RenderOutput {
+            rendered,
+            output: args.output,
+            substituted,
+            missing,
+        })
+    }
+
+    fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
+        if !output.missing.is_empty() {
+            eprintln!(
+                "Warning: Keys not in the safe: {}",
+                output.missing.join(", ")
+            );
+        }
+        let Some(path) = output.output.as_deref().filter(|path| *path != STDIN_PATH) else {
+            print!("{}", output.rendered.as_str());
+            return Ok(());
+        };
+
+        fs_utils::write_secret_file_overwrite(Path::new(path), &output.rendered, false)?;
+        print_success(&format!(
+            "Rendered {} placeholders into {}",
+            output.substituted, path
+        ));
+        Ok(())
+    }
+}
+
+/// Render a template file (or stdin with `-`), replacing `{{KEY}}` placeholders with values
+/// from the safe. Files are written with owner-only permissions; without `output` the result
+/// goes to stdout.
+pub fn render(
+    safe_path: &str,
+    format: &OutputFormat,
+    input: &str,
+    output: Option<&str>,
+    dollar: bool,
+    missing: Option<MissingPolicy>,
+) -> Result<(), SkitError> {
+    let command = RenderCommand;
+    let to_stdout = output.is_none_or(|path| path == STDIN_PATH);
+    let args = RenderArgs {
+        template: read_input(input)?,
+        output: output.map(str::to_string),
+        dollar,
+        missing,
+    };
+
+    // Rendering to stdout keeps auth-chain info logs out of the rendered output
+    match command.execute_with(safe_path, format, args, to_stdout) {
+        Err(e @ SkitError::KeyNotFound { .. }) => {
+            // Reported once, on stderr, so nothing lands in a redirected output
+            eprintln!("Error: {} (use --allow-missing to render anyway)", e);
+            std::process::exit(e.exit_code());
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholder(key: &str, raw: &str) -> Segment {
+        Segment::Placeholder {
+            key: key.to_string(),
+            raw: raw.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_template_placeholders() {
+        assert_eq!(
+            parse_template("host={{ DB_HOST }}:{{PORT}}\n", false),
+            vec![
+                Segment::Text("host=".to_string()),
+                placeholder("DB_HOST", "{{ DB_HOST }}"),
+                Segment::Text(":".to_string()),
+                placeholder("PORT", "{{PORT}}"),
+                Segment::Text("\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_template_dollar_needs_flag() {
+        assert_eq!(
+            parse_template("${KEY}", false),
+            vec![Segment::Text("${KEY}".to_string())]
+        );
+        assert_eq!(
+            parse_template("${KEY}", true),
+            vec![placeholder("KEY", "${KEY}")]
+        );
+    }
+
+    #[test]
+    fn test_parse_template_escaped_and_literal_braces() {
+        let cases = [
+            ("\\{{KEY}}", false, "{{KEY}}"),
+            ("\\${KEY}", true, "${KEY}"),
+            ("\\${KEY}", false, "\\${KEY}"),
+            ("{{ .Values.name }}", false, "{{ .Values.name }}"),
+            ("{\"a\": {\"b\": 1}}", false, "{\"a\": {\"b\": 1}}"),
+            ("{{unclosed", false, "{{unclosed"),
+            ("{{}}", false, "{{}}"),
+            ("path\\to\\{file}", true, "path\\to\\{file}"),
+        ];
+        for (template, dollar, expected) in cases {
+            assert_eq!(
+                parse_template(template, dollar),
+                vec![Segment::Text(expected.to_string())],
+                "{}",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn test_referenced_keys_are_unique() {
+        let segments = parse_template("{{A}} {{B}} {{A}}", false);
+        assert_eq!(
+            referenced_keys(&segments).into_iter().collect::<Vec<_>>(),
+            vec!["A", "B"]
+        );
+    }
+}
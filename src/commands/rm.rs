@@ -1,13 +1,47 @@
 use crate::OutputFormat;
 use crate::commands::template::{CommandTemplate, MessageOutput};
-use crate::display::print_success;
+use crate::display::{print_info, print_success, print_warning};
 use crate::error::SkitError;
+use crate::key_filter::glob_match;
 use crate::types::Safe;
 
+/// Which keys the rm command removes
+#[derive(Debug, Clone)]
+pub enum RmSelector {
+    Key(String),
+    /// Shell-style pattern with `*` and `?` wildcards
+    Glob(String),
+    Prefix(String),
+    All,
+}
+
+impl RmSelector {
+    fn matches(&self, key: &str) -> bool {
+        match self {
+            RmSelector::Key(name) => key == name,
+            RmSelector::Glob(pattern) => glob_match(pattern, key),
+            RmSelector::Prefix(prefix) => key.starts_with(prefix.as_str()),
+            RmSelector::All => true,
+        }
+    }
+
+    /// Keys in the safe this selector removes, sorted
+    pub fn matching_keys(&self, safe: &Safe) -> Vec<String> {
+        let mut keys: Vec<String> = safe
+            .items
+            .keys()
+            .filter(|key| self.matches(key))
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+}
+
 /// Arguments for the rm command
 #[derive(Debug)]
 pub struct RmArgs {
-    pub key: String,
+    pub keys: Vec<String>,
 }
 
 /// Template-based implementation of the rm command
@@ -18,19 +52,19 @@ impl CommandTemplate for RmCommand {
     type Output = MessageOutput;
 
     fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
-        if args.key.is_empty() {
+        if args.keys.is_empty() || args.keys.iter().any(|key| key.is_empty()) {
             return Err(SkitError::ParseError("Key cannot be empty".to_string()));
         }
         Ok(())
     }
 
     fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
-        // Only require authentication if the key exists and is encrypted
-        if let Some(item) = safe.find_item(&args.key) {
-            item.is_encrypted
-        } else {
-            false // If key doesn't exist, we'll handle that in execute_operation
-        }
+        // Only require authentication if a key being removed exists and is encrypted;
+        // missing keys are handled in execute_operation
+        args.keys
+            .iter()
+            .filter_map(|key| safe.find_item(key))
+            .any(|item| item.is_encrypted)
     }
 
     fn execute_operation(
@@ -39,18 +73,20 @@ impl CommandTemplate for RmCommand {
         _password: Option<String>,
         args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
-        // Check if key exists
-        if safe.find_item(&args.key).is_none() {
-            return Err(SkitError::KeyNotFound);
+        // Check every key exists before removing any
+        if let Some(missing) = args.keys.iter().find(|key| safe.find_item(key).is_none()) {
+            return Err(safe.key_not_found(missing));
         }
 
-        // Remove the item
-        safe.items.remove(&args.key);
+        for key in &args.keys {
+            safe.items.shift_remove(key);
+        }
 
-        Ok(MessageOutput::new(format!(
-            "Removed '{}' from safe",
-            args.key
-        )))
+        let message = match args.keys.as_slice() {
+            [key] => format!("Removed '{}' from safe", key),
+            keys => format!("Removed {} keys from safe", keys.len()),
+        };
+        Ok(MessageOutput::new(message))
     }
 
     fn modifies_safe(&self) -> bool {
@@ -63,12 +99,60 @@ impl CommandTemplate for RmCommand {
     }
 }
 
-/// Remove a secret from the safe
-pub fn rm(safe_path: &str, key: &str) -> Result<(), SkitError> {
-    let command = RmCommand;
-    let args = RmArgs {
-        key: key.to_string(),
+/// Remove a secret, or every secret matching a pattern or prefix, from the safe
+pub fn rm(safe_path: &str, selector: &RmSelector) -> Result<(), SkitError> {
+    let keys = match selector {
+        RmSelector::Key(key) => vec![key.clone()],
+        _ => {
+            let keys = selector.matching_keys(&Safe::load(safe_path)?);
+            if keys.is_empty() {
+                print_info("No keys matched, nothing removed");
+                return Ok(());
+            }
+            if keys.len() > 1 {
+                print_warning(&format!(
+                    "The following {} keys will be removed:",
+                    keys.len()
+                ));
+                for key in &keys {
+                    print_warning(&format!("  ├─ {}", key));
+                }
+                if !crate::input::confirm(&format!("Remove {} keys? [y/N]: ", keys.len()))? {
+                    print_info("Removal cancelled");
+                    return Ok(());
+                }
+            }
+            keys
+        }
     };
 
+    let command = RmCommand;
+    let args = RmArgs { keys };
+
     command.execute(safe_path, &OutputFormat::Table, args)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector_matching_keys() {
+        let content = "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n\
+                       OLD_API_KEY=a\nOLD_TOKEN=b\nAPI_KEY=c\n";
+        let safe = Safe::parse(content).unwrap();
+
+        let keys = |selector: RmSelector| selector.matching_keys(&safe);
+        assert_eq!(
+            keys(RmSelector::Prefix("OLD_".into())),
+            ["OLD_API_KEY", "OLD_TOKEN"]
+        );
+        assert_eq!(
+            keys(RmSelector::Glob("*API_KEY".into())),
+            ["API_KEY", "OLD_API_KEY"]
+        );
+        assert_eq!(keys(RmSelector::Key("API_KEY".into())), ["API_KEY"]);
+        assert_eq!(keys(RmSelector::All).len(), 3);
+        assert!(keys(RmSelector::Glob("NEW_*".into())).is_empty());
+    }
+}
@@ -1,47 +1,151 @@
+use crate::audit;
+use crate::commands::backup::{backup_safe_to, backup_timestamp};
 use crate::crypto;
 use crate::display::{print_info, print_success};
 use crate::error::SkitError;
-use crate::password::{get_password_with_auth_chain, validate_password_strength};
+use crate::password::{
+    PasswordSource, SAFE_PASSPHRASE_WORDS, generate_safe_passphrase, get_password_with_auth_chain,
+    validate_password_strength_min,
+};
 use crate::types::Safe;
-use std::io::{self, Write};
 
-pub fn rotate(safe_path: &str) -> Result<(), SkitError> {
+pub fn rotate(
+    safe_path: &str,
+    current_password_source: &PasswordSource,
+    new_password_source: &PasswordSource,
+    backup: bool,
+    min_strength: u8,
+    passphrase: bool,
+    cipher: Option<crypto::Cipher>,
+) -> Result<(), SkitError> {
     let mut safe = Safe::load(safe_path)?;
 
     println!("Starting credential rotation for safe: {}", safe_path);
     println!();
     println!("⚠️  WARNING: This will rotate your salt and password.");
-    println!("    All encrypted secrets will be re-encrypted with new credentials.");
-    println!("    Make sure you have a backup before proceeding.");
+    println!("    The safe master key is re-wrapped under the new password; secrets that");
+    println!("    predate the master key are re-encrypted with it.");
+    if backup {
+        println!("    A copy of the safe is saved before anything is changed.");
+    } else {
+        println!("    Make sure you have a backup (or pass --backup) before proceeding.");
+    }
     println!();
 
     // Confirmation prompt
-    print!("Do you want to continue? (yes/no): ");
-    let _ = io::stdout().flush();
-
-    let mut confirmation = String::new();
-    io::stdin()
-        .read_line(&mut confirmation)
-        .map_err(SkitError::Io)?;
-    let confirmation = confirmation.trim().to_lowercase();
-
-    if confirmation != "yes" && confirmation != "y" {
+    if !crate::input::confirm("Do you want to continue? (yes/no): ")? {
         print_info("Rotation cancelled");
         return Ok(());
     }
 
     println!();
 
-    // Step 1: Verify current password and collect encrypted secrets
+    // Step 1: Verify current password
+    let has_encrypted = safe.items.values().any(|item| item.is_encrypted);
+    let old_password = if has_encrypted || safe.master_key.is_some() {
+        match current_password_source.read()? {
+            Some(password) => {
+                safe.verify_password(&password).map_err(|_| {
+                    SkitError::InvalidPassword(
+                        "Invalid current password from --current-password-env".to_string(),
+                    )
+                })?;
+                Some(password)
+            }
+            None => Some(get_password_with_auth_chain(
+                &safe,
+                safe_path,
+                "Enter CURRENT password to decrypt existing secrets: ",
+            )?),
+        }
+    } else {
+        None
+    };
+
+    // Step 2: Get new password
+    let new_password = if passphrase {
+        let generated = generate_safe_passphrase(SAFE_PASSPHRASE_WORDS);
+        println!(
+            "Generated passphrase for the new password (keep this safe!): {}",
+            generated
+        );
+        generated
+    } else {
+        match new_password_source.read()? {
+            Some(password) => {
+                validate_password_strength_min(&password, min_strength)?;
+                password
+            }
+            None => {
+                crate::input::require_interactive(
+                    "pass --new-password-env or --passphrase to set the new password",
+                )?;
+                prompt_new_password(min_strength)?
+            }
+        }
+    };
+
+    // Snapshot the safe before anything is rewritten; no backup, no rotation
+    if backup {
+        let backup_path = format!("{}.pre-rotate.{}.bak", safe_path, backup_timestamp());
+        backup_safe_to(safe_path, std::path::Path::new(&backup_path)).map_err(|e| {
+            SkitError::ParseError(format!(
+                "Rotation aborted, could not back up the safe to {}: {}",
+                backup_path, e
+            ))
+        })?;
+        print_success(&format!("Backed up safe to {}", backup_path));
+    }
+
+    // Step 3: Re-key the safe under the new password, switching cipher if asked
+    if let Some(cipher) = cipher {
+        safe.cipher = cipher;
+    }
+    let re_encrypted = rekey(&mut safe, old_password.as_deref(), &new_password)?;
+
+    // Step 4: Save the rotated safe
+    safe.save(safe_path)?;
+    audit::record(&safe, "rotate");
+
+    println!();
+    print_success("Credential rotation completed successfully!");
+    print_info("New password is now active");
+    if re_encrypted > 0 {
+        print_info(&format!(
+            "Re-encrypted {} secrets under the safe master key",
+            re_encrypted
+        ));
+    }
+    print_info(&format!("Safe UUID: {}", safe.uuid));
+    print_info(
+        "💡 Tip: Use 'skit remember-safekey' to save your new safe key securely for easy access",
+    );
+
+    Ok(())
+}
+
+/// Put the safe under `new_password`: re-wrap (or create) the master key and re-encrypt
+/// any secrets that predate it, are not yet bound to their key name, or use a cipher other
+/// than the safe's. Returns how many secrets were re-encrypted.
+pub fn rekey(
+    safe: &mut Safe,
+    old_password: Option<&str>,
+    new_password: &str,
+) -> Result<usize, SkitError> {
     let encrypted_secrets = safe
         .items
         .values()
-        .filter(|item| item.is_encrypted)
+        .filter(|item| {
+            item.is_encrypted
+                && !(crypto::is_master_key_value(&item.value)
+                    && crypto::is_key_bound(&item.value)
+                    && crypto::value_cipher(&item.value) == Some(safe.cipher))
+        })
         .cloned()
         .collect::<Vec<_>>();
 
     if encrypted_secrets.is_empty() {
-        print_info("No encrypted secrets found. Only rotating salt and password hash.");
+        print_info("No secrets need re-encrypting. Only rotating password hash and master key.");
     } else {
         print_info(&format!(
             "Found {} encrypted secrets to re-encrypt",
@@ -49,17 +153,71 @@ pub fn rotate(safe_path: &str) -> Result<(), SkitError> {
         ));
     }
 
-    let old_password = if !encrypted_secrets.is_empty() {
-        Some(get_password_with_auth_chain(
-            &safe,
-            safe_path,
-            "Enter CURRENT password to decrypt existing secrets: ",
-        )?)
-    } else {
-        None
-    };
+    // Decrypt those secrets with the old credentials, before the master key is re-wrapped
+    let mut decrypted_secrets: Vec<(String, String)> = Vec::new();
+    if let Some(old_pwd) = old_password
+        && !encrypted_secrets.is_empty()
+    {
+        print_info("Decrypting secrets with current credentials...");
 
-    // Step 2: Get new password
+        for item in &encrypted_secrets {
+            let decrypted = crypto::DecryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .ciphertext(&item.value)
+                .context(&item.key)
+                .password(old_pwd)
+                .decrypt()
+                .map_err(SkitError::Crypto)?;
+            decrypted_secrets.push((item.key.clone(), decrypted));
+            print_info(&format!("Decrypted: {}", item.key));
+        }
+    }
+
+    // Generate new password hash and re-wrap (or create) the master key
+    print_info("Generating new password hash...");
+    safe.password_hash = crypto::hash_password(new_password)?;
+
+    match (old_password, safe.master_key.is_some()) {
+        (Some(old_pwd), true) => {
+            print_info("Re-wrapping safe master key...");
+            safe.rewrap_master_key(old_pwd, new_password)?;
+        }
+        _ => {
+            print_info("Upgrading safe to master key format...");
+            safe.create_master_key(new_password)?;
+        }
+    }
+
+    // Re-encrypt those secrets under the master key
+    if !decrypted_secrets.is_empty() {
+        print_info("Re-encrypting secrets with new credentials...");
+
+        for (key, decrypted_value) in decrypted_secrets {
+            // Re-encrypt under the master key with a fresh nonce
+            let re_encrypted = crypto::EncryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .cipher(safe.cipher)
+                .plaintext(&decrypted_value)
+                .context(&key)
+                .password(new_password)
+                .encrypt()
+                .map_err(SkitError::Crypto)?;
+
+            // Update the item in the safe
+            if let Some(item) = safe.items.get_mut(&key) {
+                item.value = re_encrypted;
+                print_info(&format!("Re-encrypted: {}", key));
+            }
+        }
+    }
+
+    Ok(encrypted_secrets.len())
+}
+
+/// Prompt for the new safe password until one scoring at least `min_strength` is confirmed
+pub fn prompt_new_password(min_strength: u8) -> Result<String, SkitError> {
     println!();
     println!("Creating new credentials:");
     println!("Password requirements:");
@@ -67,8 +225,9 @@ pub fn rotate(safe_path: &str) -> Result<(), SkitError> {
     println!("  - Uppercase and lowercase letters");
     println!("  - At least one digit");
     println!("  - At least one special character");
+    println!("  - Hard to guess: strength {}/4 or better", min_strength);
 
-    let new_password = loop {
+    let password = loop {
         let password = crate::input::prompt_password_with_fallback("Enter NEW password: ")
             .map_err(SkitError::Io)?;
 
@@ -77,7 +236,7 @@ pub fn rotate(safe_path: &str) -> Result<(), SkitError> {
             continue;
         }
 
-        match validate_password_strength(&password) {
+        match validate_password_strength_min(&password, min_strength) {
             Ok(()) => {
                 let confirm = crate::input::prompt_password_with_fallback("Confirm NEW password: ")
                     .map_err(SkitError::Io)?;
@@ -97,64 +256,5 @@ pub fn rotate(safe_path: &str) -> Result<(), SkitError> {
         }
     };
 
-    // Step 3: Decrypt all secrets with old credentials (if any)
-    let mut decrypted_secrets: Vec<(String, String)> = Vec::new();
-    if let Some(old_pwd) = &old_password {
-        print_info("Decrypting secrets with current credentials...");
-
-        for item in safe.items.values() {
-            if item.is_encrypted {
-                let decrypted = crypto::DecryptBuilder::new()
-                    .ciphertext(&item.value)
-                    .password(old_pwd)
-                    .decrypt()
-                    .map_err(SkitError::Crypto)?;
-                decrypted_secrets.push((item.key.clone(), decrypted));
-                print_info(&format!("Decrypted: {}", item.key));
-            }
-        }
-    }
-
-    // Step 4: Generate new password hash
-    print_info("Generating new password hash...");
-    safe.password_hash = crypto::hash_password(&new_password)?;
-
-    // Step 5: Re-encrypt all secrets with new credentials
-    if !decrypted_secrets.is_empty() {
-        print_info("Re-encrypting secrets with new credentials...");
-
-        for (key, decrypted_value) in decrypted_secrets {
-            // Re-encrypt with new password and new per-secret salt
-            let re_encrypted = crypto::EncryptBuilder::new()
-                .plaintext(&decrypted_value)
-                .password(&new_password)
-                .encrypt()
-                .map_err(SkitError::Crypto)?;
-
-            // Update the item in the safe
-            if let Some(item) = safe.items.get_mut(&key) {
-                item.value = re_encrypted;
-                print_info(&format!("Re-encrypted: {}", key));
-            }
-        }
-    }
-
-    // Step 6: Save the rotated safe
-    safe.save(safe_path)?;
-
-    println!();
-    print_success("Credential rotation completed successfully!");
-    print_info("New password is now active");
-    if !encrypted_secrets.is_empty() {
-        print_info(&format!(
-            "Re-encrypted {} secrets with new per-secret salts",
-            encrypted_secrets.len()
-        ));
-    }
-    print_info(&format!("Safe UUID: {}", safe.uuid));
-    print_info(
-        "💡 Tip: Use 'skit remember-safekey' to save your new safe key securely for easy access",
-    );
-
-    Ok(())
+    Ok(password)
 }
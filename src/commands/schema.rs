@@ -0,0 +1,252 @@
+use crate::OutputFormat;
+use crate::commands::template::CommandTemplate;
+use crate::display::{format_json_output, format_yaml_output, print_success, print_warning};
+use crate::error::SkitError;
+use crate::types::{Safe, SchemaCheckOutput, SchemaViolation};
+use crate::validation::is_valid_env_key;
+use regex::Regex;
+use std::fs;
+
+/// One required key from a schema file, with the pattern its value must match
+#[derive(Debug)]
+pub struct SchemaRule {
+    pub key: String,
+    /// The pattern as written in the schema, for messages
+    pub pattern_text: Option<String>,
+    pub pattern: Option<Regex>,
+}
+
+/// Parse a schema file: one required key per line, optionally followed by whitespace and a
+/// regular expression the whole value must match. Blank lines and `#` comments are skipped.
+pub fn parse_schema(content: &str) -> Result<Vec<SchemaRule>, SkitError> {
+    let mut rules = Vec::new();
+    for (line_num, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, pattern) = match line.split_once(char::is_whitespace) {
+            Some((key, pattern)) => (key, Some(pattern.trim())),
+            None => (line, None),
+        };
+        if !is_valid_env_key(key) {
+            return Err(SkitError::ParseError(format!(
+                "Invalid key '{}' on line {} of the schema (must match [A-Za-z_][A-Za-z0-9_]*)",
+                key,
+                line_num + 1
+            )));
+        }
+        let pattern = pattern
+            .map(|pattern| {
+                Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| {
+                    SkitError::ParseError(format!(
+                        "Invalid pattern for {} on line {} of the schema: {}",
+                        key,
+                        line_num + 1,
+                        e
+                    ))
+                })
+            })
+            .transpose()?;
+
+        rules.push(SchemaRule {
+            key: key.to_string(),
+            pattern_text: pattern
+                .is_some()
+                .then(|| line[key.len()..].trim().to_string()),
+            pattern,
+        });
+    }
+    Ok(rules)
+}
+
+fn violation(key: &str, rule: &str, message: &str) -> SchemaViolation {
+    SchemaViolation {
+        key: key.to_string(),
+        rule: rule.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Arguments for the schema check command
+#[derive(Debug)]
+pub struct SchemaCheckArgs {
+    pub rules: Vec<SchemaRule>,
+}
+
+/// Template-based implementation of `schema --check`
+pub struct SchemaCheckCommand;
+
+impl CommandTemplate for SchemaCheckCommand {
+    type Args = SchemaCheckArgs;
+    type Output = SchemaCheckOutput;
+
+    fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
+        // Only values with a pattern are decrypted
+        args.rules.iter().any(|rule| {
+            rule.pattern.is_some() && safe.find_item(&rule.key).is_some_and(|i| i.is_encrypted)
+        })
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let items: Vec<_> = args
+            .rules
+            .iter()
+            .filter(|rule| rule.pattern.is_some())
+            .filter_map(|rule| safe.find_item(&rule.key))
+            .collect();
+        let mut decrypted = safe
+            .decrypt_selected(&items, password.as_deref())
+            .into_iter();
+
+        let mut violations = Vec::new();
+        for rule in &args.rules {
+            if safe.find_item(&rule.key).is_none() {
+                violations.push(violation(&rule.key, "missing", "Key is not in the safe"));
+                continue;
+            }
+            let (Some(pattern), Some(pattern_text)) = (&rule.pattern, &rule.pattern_text) else {
+                continue;
+            };
+            let value = match decrypted.next().flatten() {
+                Some(Ok(value)) => zeroize::Zeroizing::new(value),
+                _ => {
+                    violations.push(violation(
+                        &rule.key,
+                        "decrypt-failed",
+                        "Value could not be decrypted",
+                    ));
+                    continue;
+                }
+            };
+            if !pattern.is_match(&value) {
+                // Name the pattern, never the value
+                violations.push(violation(
+                    &rule.key,
+                    "pattern",
+                    &format!("Value does not match {}", pattern_text),
+                ));
+            }
+        }
+
+        Ok(SchemaCheckOutput {
+            ok: violations.is_empty(),
+            checked: args.rules.len(),
+            violations,
+        })
+    }
+
+    fn format_output(&self, output: Self::Output, format: &OutputFormat) -> Result<(), SkitError> {
+        match format {
+            OutputFormat::Json => println!("{}", format_json_output(&output)?),
+            OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+            _ if output.ok => {
+                print_success(&format!("All {} schema keys are valid", output.checked));
+            }
+            _ => {
+                for violation in &output.violations {
+                    println!(
+                        "{:15}  {}  ({})",
+                        violation.rule, violation.key, violation.message
+                    );
+                }
+                print_warning(&format!(
+                    "{} of {} schema keys failed",
+                    output.violations.len(),
+                    output.checked
+                ));
+            }
+        }
+
+        if !output.ok {
+            return Err(SkitError::VerificationFailed(format!(
+                "{} schema violation(s)",
+                output.violations.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Check the safe against a schema of required keys and value patterns. Fails if a key is
+/// missing or a value doesn't match its pattern.
+pub fn schema_check(
+    safe_path: &str,
+    format: &OutputFormat,
+    schema_path: &str,
+) -> Result<(), SkitError> {
+    let content = fs::read_to_string(schema_path).map_err(|e| {
+        SkitError::ParseError(format!("Failed to read schema {}: {}", schema_path, e))
+    })?;
+    let command = SchemaCheckCommand;
+    let args = SchemaCheckArgs {
+        rules: parse_schema(&content)?,
+    };
+
+    command.execute(safe_path, format, args)
+}
+
+/// Print the safe's keys as a schema, one per line, to start a schema file from.
+/// Nothing is decrypted, so no password is needed.
+pub fn schema_export(safe_path: &str) -> Result<(), SkitError> {
+    let safe = Safe::load(safe_path)?;
+    println!("# Required keys for {}", safe.description);
+    println!("# Add a regular expression after a key to check its value, e.g. PORT [0-9]+");
+    for key in safe.items.keys() {
+        println!("{}", key);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str, schema: &str) -> SchemaCheckOutput {
+        let safe_content = format!(
+            "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n{}",
+            content
+        );
+        let mut safe = Safe::parse(&safe_content).unwrap();
+        let args = SchemaCheckArgs {
+            rules: parse_schema(schema).unwrap(),
+        };
+        SchemaCheckCommand
+            .execute_operation(&mut safe, None, args)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_schema() {
+        let rules = parse_schema("# comment\n\nDATABASE_URL postgres://.+\nAPI_KEY\n").unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].key, "DATABASE_URL");
+        assert!(rules[0].pattern.is_some());
+        assert!(rules[1].pattern.is_none());
+
+        assert!(parse_schema("bad-key\n").is_err());
+        assert!(parse_schema("PORT [0-9\n").is_err());
+    }
+
+    #[test]
+    fn test_schema_check_reports_missing_and_mismatched() {
+        let output = check("PORT=80a\nHOST=localhost\n", "PORT [0-9]+\nHOST\nAPI_KEY\n");
+        assert!(!output.ok);
+        assert_eq!(output.checked, 3);
+        let found: Vec<(&str, &str)> = output
+            .violations
+            .iter()
+            .map(|v| (v.key.as_str(), v.rule.as_str()))
+            .collect();
+        assert_eq!(found, [("PORT", "pattern"), ("API_KEY", "missing")]);
+        assert!(!output.violations[0].message.contains("80a"));
+
+        assert!(check("PORT=8080\n", "PORT [0-9]+\n").ok);
+    }
+}
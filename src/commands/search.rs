@@ -0,0 +1,254 @@
+use crate::OutputFormat;
+use crate::commands::template::CommandTemplate;
+use crate::display::{format_json_output, format_yaml_output, single_line};
+use crate::error::SkitError;
+use crate::types::{Safe, SearchMatch, SearchOutput};
+use regex::{Regex, RegexBuilder};
+
+/// Characters of the value shown on each side of a match
+const MATCH_CONTEXT: usize = 2;
+
+/// Shown in place of a value that did not match; fixed so it does not reveal the length
+const FULL_MASK: &str = "********";
+
+/// Arguments for the search command
+#[derive(Debug)]
+pub struct SearchArgs {
+    pub pattern: Regex,
+    /// Match key names only, without decrypting anything
+    pub keys_only: bool,
+    /// Include the masked value of each match; otherwise only where it matched is shown
+    pub reveal: bool,
+}
+
+/// Template-based implementation of the search command
+pub struct SearchCommand;
+
+impl CommandTemplate for SearchCommand {
+    type Args = SearchArgs;
+    type Output = SearchOutput;
+
+    fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
+        !args.keys_only && safe.items.values().any(|item| item.is_encrypted)
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        // Sort keys for consistent output
+        let mut keys: Vec<_> = safe.items.keys().collect();
+        keys.sort();
+
+        let items: Vec<_> = keys.iter().map(|key| &safe.items[*key]).collect();
+        let values = if args.keys_only {
+            Vec::new()
+        } else {
+            safe.decrypt_selected(&items, password.as_deref())
+        };
+        let mut values = values.into_iter();
+
+        let mut matches = Vec::new();
+        for item in items {
+            let value = if args.keys_only {
+                None
+            } else {
+                match values.next().flatten() {
+                    Some(Ok(value)) => Some(value),
+                    _ => {
+                        eprintln!("# Warning: Failed to decrypt '{}'", item.key);
+                        None
+                    }
+                }
+            };
+
+            let mut matched = Vec::new();
+            if args.pattern.is_match(&item.key) {
+                matched.push("key".to_string());
+            }
+            let value_match = value
+                .as_deref()
+                .and_then(|v| args.pattern.find(v).map(|m| (m.start(), m.end())));
+            if value_match.is_some() {
+                matched.push("value".to_string());
+            }
+            if matched.is_empty() {
+                continue;
+            }
+
+            matches.push(SearchMatch {
+                key: item.key.clone(),
+                item_type: if item.is_encrypted { "ENC" } else { "PLAIN" }.to_string(),
+                matched,
+                value: value
+                    .filter(|_| args.reveal)
+                    .map(|v| mask_value(&v, value_match)),
+            });
+        }
+
+        Ok(SearchOutput { matches })
+    }
+
+    fn format_output(&self, output: Self::Output, format: &OutputFormat) -> Result<(), SkitError> {
+        match format {
+            OutputFormat::Json => println!("{}", format_json_output(&output)?),
+            OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+            _ => print_search_table(&output.matches),
+        }
+        Ok(())
+    }
+}
+
+/// Mask a value, keeping the matched text and a little context on each side visible.
+/// Values that did not match are masked entirely.
+pub fn mask_value(value: &str, matched: Option<(usize, usize)>) -> String {
+    let Some((start, end)) = matched else {
+        return FULL_MASK.to_string();
+    };
+
+    let before: Vec<char> = value[..start].chars().collect();
+    let after: Vec<char> = value[end..].chars().collect();
+    let shown_before = before.len().min(MATCH_CONTEXT);
+    let shown_after = after.len().min(MATCH_CONTEXT);
+
+    let mut masked = String::new();
+    if before.len() > shown_before {
+        masked.push_str("***");
+    }
+    masked.extend(&before[before.len() - shown_before..]);
+    masked.push_str(&value[start..end]);
+    masked.extend(&after[..shown_after]);
+    if after.len() > shown_after {
+        masked.push_str("***");
+    }
+    masked
+}
+
+fn print_search_table(matches: &[SearchMatch]) {
+    if matches.is_empty() {
+        println!("No matches");
+        return;
+    }
+
+    let key_width = matches.iter().map(|m| m.key.len()).max().unwrap_or(3);
+    for m in matches {
+        match &m.value {
+            // Revealed values can span lines, which would break the row
+            Some(value) => println!(
+                "{:width$}  {:5}  {}",
+                m.key,
+                m.item_type,
+                single_line(value),
+                width = key_width
+            ),
+            None => println!(
+                "{:width$}  {:5}  {}",
+                m.key,
+                m.item_type,
+                m.matched.join(","),
+                width = key_width
+            ),
+        }
+    }
+}
+
+/// Find secrets whose key or decrypted value matches a regular expression
+pub fn search(
+    safe_path: &str,
+    pattern: &str,
+    keys_only: bool,
+    ignore_case: bool,
+    reveal: bool,
+    format: &OutputFormat,
+) -> Result<(), SkitError> {
+    let pattern = RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| SkitError::ParseError(format!("Invalid search pattern: {}", e)))?;
+
+    let command = SearchCommand;
+    let args = SearchArgs {
+        pattern,
+        keys_only,
+        reveal,
+    };
+
+    command.execute(safe_path, format, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safe_with_items() -> Safe {
+        let content = "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n\
+                       DB_HOST=db.internal.example.com\nDB_PORT=5432\nAPI_URL=https://api.example.com\n";
+        Safe::parse(content).unwrap()
+    }
+
+    fn run(pattern: &str, keys_only: bool) -> Vec<SearchMatch> {
+        let args = SearchArgs {
+            pattern: Regex::new(pattern).unwrap(),
+            keys_only,
+            reveal: !keys_only,
+        };
+        SearchCommand
+            .execute_operation(&mut safe_with_items(), None, args)
+            .unwrap()
+            .matches
+    }
+
+    #[test]
+    fn test_mask_value() {
+        let value = "postgres://user:hunter2@db:5432";
+        let start = value.find("hunter2").unwrap();
+        assert_eq!(
+            mask_value(value, Some((start, start + 7))),
+            "***r:hunter2@d***"
+        );
+        assert_eq!(mask_value("abc", Some((0, 3))), "abc");
+        assert_eq!(mask_value("secret-value", None), "********");
+    }
+
+    #[test]
+    fn test_search_matches_keys_and_values() {
+        let matches = run("example", false);
+        assert_eq!(
+            matches.iter().map(|m| m.key.as_str()).collect::<Vec<_>>(),
+            vec!["API_URL", "DB_HOST"]
+        );
+        assert_eq!(matches[0].matched, vec!["value".to_string()]);
+
+        let matches = run("^DB_", false);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[1].value.as_deref(), Some("********"));
+
+        let matches = run("example", true);
+        assert!(matches.is_empty());
+        let matches = run("PORT", true);
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].value.is_none());
+    }
+
+    #[test]
+    fn test_values_stay_hidden_without_reveal() {
+        let args = SearchArgs {
+            pattern: Regex::new(".*").unwrap(),
+            keys_only: false,
+            reveal: false,
+        };
+        let matches = SearchCommand
+            .execute_operation(&mut safe_with_items(), None, args)
+            .unwrap()
+            .matches;
+        assert_eq!(matches.len(), 3);
+        assert!(matches.iter().all(|m| m.value.is_none()));
+        assert!(
+            matches
+                .iter()
+                .all(|m| m.matched.contains(&"value".to_string()))
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use crate::OutputFormat;
-use crate::commands::template::{CommandTemplate, MessageOutput};
+use crate::commands::template::CommandTemplate;
 use crate::crypto;
 use crate::error::SkitError;
 use crate::types::Safe;
-use crate::validation::is_valid_env_key;
+use crate::validation::{is_valid_env_key, is_valid_tag};
+use std::io::Read;
+use zeroize::Zeroizing;
 
 /// Arguments for the set command
 #[derive(Debug)]
@@ -11,6 +13,21 @@ pub struct SetArgs {
     pub key: String,
     pub value: String,
     pub is_plain: bool,
+    pub description: Option<String>,
+    /// Tags added to the item; existing tags are kept
+    pub tags: Vec<String>,
+    /// Leave an existing key untouched instead of overwriting it
+    pub if_not_exists: bool,
+    /// Keep the existing ciphertext when the key already holds this value
+    pub stable: bool,
+}
+
+/// Output for the set command
+#[derive(Debug)]
+pub struct SetOutput {
+    pub message: String,
+    /// The safe was left alone, by `--if-not-exists` or `--stable`
+    pub skipped: bool,
 }
 
 /// Template-based implementation of the set command
@@ -18,7 +35,7 @@ pub struct SetCommand;
 
 impl CommandTemplate for SetCommand {
     type Args = SetArgs;
-    type Output = MessageOutput;
+    type Output = SetOutput;
 
     fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
         if args.key.is_empty() {
@@ -30,12 +47,18 @@ impl CommandTemplate for SetCommand {
                 args.key
             )));
         }
+        if let Some(tag) = args.tags.iter().find(|tag| !is_valid_tag(tag)) {
+            return Err(SkitError::ParseError(format!(
+                "Invalid tag '{}' (must match [A-Za-z0-9_.-]+)",
+                tag
+            )));
+        }
         Ok(())
     }
 
-    fn requires_authentication(&self, _safe: &Safe, args: &Self::Args) -> bool {
+    fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
         // Only require authentication if we're storing an encrypted value
-        !args.is_plain
+        !args.is_plain && !skips_existing(safe, args)
     }
 
     fn execute_operation(
@@ -44,50 +67,160 @@ impl CommandTemplate for SetCommand {
         password: Option<String>,
         args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
-        let stored_value = if args.is_plain {
-            args.value.clone()
+        if skips_existing(safe, &args) {
+            return Ok(SetOutput {
+                message: format!("Key '{}' already exists, skipping", args.key),
+                skipped: true,
+            });
+        }
+
+        let unchanged = args.stable
+            && safe.stores_value(&args.key, &args.value, !args.is_plain, password.as_deref());
+        if unchanged && args.description.is_none() && args.tags.is_empty() {
+            return Ok(SetOutput {
+                message: format!("Key '{}' is unchanged, skipping", args.key),
+                skipped: true,
+            });
+        }
+
+        let stored_value = if unchanged {
+            // Re-encrypting would pick a fresh salt and nonce and churn the diff
+            None
+        } else if args.is_plain {
+            Some(args.value.clone())
         } else {
             // For encrypted values, we must have a password at this point
             let password = password.ok_or_else(|| {
                 SkitError::InvalidPassword("Password required for encrypted values".to_string())
             })?;
-            crypto::EncryptBuilder::new()
-                .plaintext(&args.value)
-                .password(&password)
-                .encrypt()
-                .map_err(SkitError::Crypto)?
+            Some(
+                crypto::EncryptBuilder::new()
+                    .kdf_params(safe.kdf_params)
+                    .master_key(safe.master_key.as_deref())
+                    .cipher(safe.cipher)
+                    .plaintext(&args.value)
+                    .context(&args.key)
+                    .password(&password)
+                    .encrypt()
+                    .map_err(SkitError::Crypto)?,
+            )
         };
 
-        safe.add_or_update_item(args.key.clone(), stored_value, !args.is_plain);
+        if let Some(stored_value) = stored_value {
+            safe.add_or_update_item(args.key.clone(), stored_value, !args.is_plain);
+        }
+        if args.description.is_some() {
+            safe.set_description(&args.key, args.description);
+        }
+        safe.add_tags(&args.key, &args.tags);
 
         let type_str = if args.is_plain {
             "plain text"
         } else {
             "encrypted"
         };
-        Ok(MessageOutput::new(format!(
-            "Set {} ({}) in safe",
-            args.key, type_str
-        )))
+        Ok(SetOutput {
+            message: format!("Set {} ({}) in safe", args.key, type_str),
+            skipped: false,
+        })
     }
 
     fn modifies_safe(&self) -> bool {
         true
     }
 
+    fn changed_safe(&self, output: &Self::Output) -> bool {
+        !output.skipped
+    }
+
     fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
-        tracing::info!("✓ {}", output.message);
+        if output.skipped {
+            tracing::info!("{}", output.message);
+        } else {
+            tracing::info!("✓ {}", output.message);
+        }
         Ok(())
     }
 }
 
+/// Whether `--if-not-exists` applies because the key is already in the safe
+fn skips_existing(safe: &Safe, args: &SetArgs) -> bool {
+    args.if_not_exists && safe.find_item(&args.key).is_some()
+}
+
+/// Read a secret value from stdin up to EOF, dropping a single trailing newline
+fn read_value_from_stdin() -> Result<Zeroizing<String>, SkitError> {
+    let mut value = Zeroizing::new(String::new());
+    std::io::stdin()
+        .read_to_string(&mut value)
+        .map_err(SkitError::Io)?;
+    crate::input::mark_stdin_consumed();
+
+    if value.ends_with('\n') {
+        value.pop();
+        if value.ends_with('\r') {
+            value.pop();
+        }
+    }
+    Ok(value)
+}
+
+/// Read a secret value from a file, keeping its contents exactly as stored
+fn read_value_from_file(path: &str) -> Result<Zeroizing<String>, SkitError> {
+    if !std::path::Path::new(path).exists() {
+        return Err(SkitError::ParseError(format!("File not found: {}", path)));
+    }
+    let mut value = Zeroizing::new(String::new());
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut value))
+        .map_err(SkitError::Io)?;
+    Ok(value)
+}
+
 /// Add or update a secret in the safe
-pub fn set(safe_path: &str, key: &str, value: &str, is_plain: bool) -> Result<(), SkitError> {
+#[allow(clippy::too_many_arguments)]
+pub fn set(
+    safe_path: &str,
+    key: &str,
+    value: Option<&str>,
+    is_plain: bool,
+    from_stdin: bool,
+    from_file: Option<&str>,
+    description: Option<&str>,
+    tags: &[String],
+    if_not_exists: bool,
+    stable: bool,
+) -> Result<(), SkitError> {
+    if value.is_some() && from_file.is_some() {
+        return Err(SkitError::ParseError(
+            "Provide either a value or --from-file, not both".to_string(),
+        ));
+    }
+
+    let value = if from_stdin {
+        read_value_from_stdin()?
+    } else if let Some(path) = from_file {
+        read_value_from_file(path)?
+    } else if let Some(value) = value {
+        Zeroizing::new(value.to_string())
+    } else {
+        // Prompting keeps the value out of shell history and `ps` output
+        crate::input::require_interactive("pass the value as an argument, or use --stdin")?;
+        Zeroizing::new(
+            crate::input::prompt_password_with_fallback(&format!("Enter value for {}: ", key))
+                .map_err(SkitError::Io)?,
+        )
+    };
+
     let command = SetCommand;
     let args = SetArgs {
         key: key.to_string(),
         value: value.to_string(),
         is_plain,
+        description: description.map(|d| d.to_string()),
+        tags: tags.to_vec(),
+        if_not_exists,
+        stable,
     };
 
     // Use Table format as default (format doesn't matter for set command output)
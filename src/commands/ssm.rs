@@ -1,18 +1,26 @@
-use crate::aws::{client, parameters};
+use crate::aws::client::{self, run_async_blocking};
+use crate::aws::parameters::{self, KeyTransform};
+use crate::commands::pull::{PullOutcome, PulledEntry, apply_pulled};
 use crate::commands::template::{CommandTemplate, MessageOutput};
 use crate::crypto;
 use crate::error::SkitError;
 use crate::types::Safe;
-use std::sync::mpsc;
+use std::collections::HashSet;
 
 /// Arguments for the SSM pull command
 #[derive(Debug)]
 pub struct SsmPullArgs {
     pub prefix: Option<String>,
     pub region: Option<String>,
+    pub profile: Option<String>,
     pub replace: bool,
     pub no_overwrite: bool,
     pub dry_run: bool,
+    pub key_transform: KeyTransform,
+    /// Uppercase keys after transforming them
+    pub upper: bool,
+    /// Abort instead of skipping parameters whose name isn't a valid key
+    pub fail_on_invalid: bool,
 }
 
 /// Template-based implementation of the SSM pull command
@@ -50,123 +58,85 @@ impl CommandTemplate for SsmPullCommand {
         let SsmPullArgs {
             prefix,
             region,
+            profile,
             replace,
             no_overwrite,
             dry_run,
+            key_transform,
+            upper,
+            fail_on_invalid,
         } = args;
 
-        let resolved_prefix = match prefix.as_ref() {
-            Some(prefix) => {
-                let trimmed = prefix.trim();
-                if trimmed.is_empty() {
-                    return Err(SkitError::ParseError(
-                        "SSM prefix cannot be empty when provided".to_string(),
-                    ));
-                }
-                trimmed.to_string()
-            }
-            None => safe
-                .ssm_prefix
-                .as_ref()
-                .map(|p| p.trim().to_string())
-                .filter(|p| !p.is_empty())
-                .ok_or_else(|| {
-                    SkitError::ParseError(
-                        "No SSM prefix available. Provide --prefix or set one via `skit init --ssm-prefix ...` or a prior `skit ssm pull --prefix ...`."
-                            .to_string(),
-                    )
-                })?,
-        };
+        let resolved_prefix = resolve_prefix(prefix.as_deref(), safe)?;
+        let resolved_profile = profile.or_else(|| safe.ssm_profile.clone());
 
         let region_for_fetch = region.clone();
+        let profile_for_fetch = resolved_profile.clone();
         let prefix_for_fetch = resolved_prefix.clone();
         let ssm_parameters = run_async_blocking(async move {
-            let ssm_client = client::create_ssm_client(region_for_fetch.clone()).await?;
-            parameters::fetch_parameters(&ssm_client, &prefix_for_fetch, true).await
+            let ssm_client =
+                client::create_ssm_client(region_for_fetch.clone(), profile_for_fetch).await?;
+            parameters::fetch_parameters(&ssm_client, &prefix_for_fetch, true, true).await
         })?;
-
-        if dry_run {
-            let mut message = format!(
-                "Dry run: Would pull {} parameters from SSM prefix '{}'\n\n",
-                ssm_parameters.len(),
+        if ssm_parameters.is_empty() {
+            return Err(SkitError::AwsError(format!(
+                "No parameters found under prefix: {}",
                 resolved_prefix
-            );
-
-            for param in ssm_parameters.iter().take(10) {
-                let param_type = if param.is_encrypted {
-                    "SecureString (will be encrypted)"
-                } else {
-                    "String (will be plain text)"
-                };
-                message.push_str(&format!("  {} [{}]\n", param.key, param_type));
-            }
-
-            if ssm_parameters.len() > 10 {
-                message.push_str(&format!("  ... and {} more\n", ssm_parameters.len() - 10));
-            }
-
-            return Ok(MessageOutput { message });
+            )));
         }
-
-        let mut added_count = 0;
-        let mut updated_count = 0;
-        let mut skipped_count = 0;
-        let mut encrypted_count = 0;
-        let mut plain_count = 0;
-
-        if replace {
-            safe.items.clear();
+        // Rename and check every key before anything is written to the safe
+        let (ssm_parameters, invalid) =
+            parameters::apply_key_transform(ssm_parameters, key_transform, upper, fail_on_invalid)?;
+        if !invalid.is_empty() {
+            crate::display::print_warning(&format!(
+                "Skipping parameters that don't map to valid keys: {} (use --fail-on-invalid to abort instead)",
+                invalid.join(", ")
+            ));
         }
 
-        for param in ssm_parameters {
-            if no_overwrite && safe.find_item(&param.key).is_some() {
-                skipped_count += 1;
-                continue;
-            }
-
-            let is_new = safe.find_item(&param.key).is_none();
-
-            if param.is_encrypted {
-                let password = password.as_ref().ok_or_else(|| {
-                    SkitError::InvalidPassword(
-                        "Password required to encrypt SecureString parameters".to_string(),
-                    )
-                })?;
-
-                let encrypted_value = crypto::EncryptBuilder::new()
-                    .plaintext(&param.value)
-                    .password(password)
-                    .encrypt()
-                    .map_err(SkitError::Crypto)?;
-
-                safe.add_or_update_item(param.key.clone(), encrypted_value, true);
-                encrypted_count += 1;
-            } else {
-                safe.add_or_update_item(param.key.clone(), param.value, false);
-                plain_count += 1;
-            }
-
-            if is_new {
-                added_count += 1;
-            } else {
-                updated_count += 1;
+        let total = ssm_parameters.len();
+        let entries = ssm_parameters
+            .into_iter()
+            .map(|param| PulledEntry {
+                key: param.key,
+                value: param.value,
+                encrypt: param.is_encrypted,
+            })
+            .collect();
+        let counts = match apply_pulled(
+            safe,
+            entries,
+            password.as_deref(),
+            replace,
+            no_overwrite,
+            dry_run,
+        )? {
+            PullOutcome::DryRun(listing) => {
+                return Ok(MessageOutput {
+                    message: format!(
+                        "Dry run: Would pull {} parameters from SSM prefix '{}'\n\n{}",
+                        total, resolved_prefix, listing
+                    ),
+                });
             }
-        }
+            PullOutcome::Applied(counts) => counts,
+        };
 
         safe.ssm_prefix = Some(resolved_prefix.clone());
         safe.ssm_region = region.clone();
+        safe.ssm_profile = resolved_profile;
 
         let message = format!(
             "Successfully pulled {} parameters from SSM prefix '{}'\n\
              Added: {}, Updated: {}, Skipped: {}\n\
              Encrypted: {}, Plain text: {}",
-            added_count + updated_count,
+            counts.added + counts.updated,
             resolved_prefix,
-            added_count,
-            updated_count,
-            skipped_count,
-            encrypted_count,
-            plain_count
+            counts.added,
+            counts.updated,
+            counts.skipped,
+            counts.encrypted,
+            counts.plain
         );
 
         Ok(MessageOutput { message })
@@ -176,6 +146,10 @@ impl CommandTemplate for SsmPullCommand {
         true
     }
 
+    fn audit_action(&self) -> &'static str {
+        "ssm-pull"
+    }
+
     fn format_output(
         &self,
         output: Self::Output,
@@ -186,13 +160,18 @@ impl CommandTemplate for SsmPullCommand {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn ssm_pull(
     safe_path: &str,
     prefix: Option<&str>,
     region: Option<String>,
+    profile: Option<String>,
     replace: bool,
     no_overwrite: bool,
     dry_run: bool,
+    key_transform: KeyTransform,
+    upper: bool,
+    fail_on_invalid: bool,
 ) -> Result<(), SkitError> {
     use crate::display::print_info;
 
@@ -202,9 +181,13 @@ pub fn ssm_pull(
     let args = SsmPullArgs {
         prefix: prefix.map(|p| p.to_string()),
         region,
+        profile,
         replace,
         no_overwrite,
         dry_run,
+        key_transform,
+        upper,
+        fail_on_invalid,
     };
 
     command.validate_args(&args)?;
@@ -213,27 +196,322 @@ pub fn ssm_pull(
     Ok(())
 }
 
-fn run_async_blocking<T, F>(future: F) -> Result<T, SkitError>
-where
-    T: Send + 'static,
-    F: std::future::Future<Output = Result<T, SkitError>> + Send + 'static,
-{
-    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-        let (tx, rx) = mpsc::channel();
-        handle.spawn(async move {
-            let _ = tx.send(future.await);
-        });
-
-        match rx.recv() {
-            Ok(result) => result,
-            Err(e) => Err(SkitError::AwsError(format!(
-                "Failed to receive result from async task: {}",
-                e
-            ))),
+/// Arguments for the SSM push command
+#[derive(Debug)]
+pub struct SsmPushArgs {
+    pub prefix: Option<String>,
+    pub region: Option<String>,
+    pub profile: Option<String>,
+    pub overwrite: bool,
+    pub delete_missing: bool,
+    pub dry_run: bool,
+}
+
+/// Template-based implementation of the SSM push command
+pub struct SsmPushCommand;
+
+impl CommandTemplate for SsmPushCommand {
+    type Args = SsmPushArgs;
+    type Output = MessageOutput;
+
+    fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
+        if let Some(prefix) = &args.prefix
+            && prefix.trim().is_empty()
+        {
+            return Err(SkitError::ParseError(
+                "SSM prefix cannot be empty when provided".to_string(),
+            ));
         }
-    } else {
-        let runtime = tokio::runtime::Runtime::new()
-            .map_err(|e| SkitError::AwsError(format!("Failed to create async runtime: {}", e)))?;
-        runtime.block_on(future)
+        Ok(())
+    }
+
+    fn requires_authentication(&self, safe: &Safe, _args: &Self::Args) -> bool {
+        // Encrypted items must be decrypted before they can be sent as SecureString
+        safe.items.values().any(|item| item.is_encrypted)
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let SsmPushArgs {
+            prefix,
+            region,
+            profile,
+            overwrite,
+            delete_missing,
+            dry_run,
+        } = args;
+
+        let resolved_prefix = resolve_prefix(prefix.as_deref(), safe)?;
+        let resolved_profile = profile.or_else(|| safe.ssm_profile.clone());
+
+        let mut keys: Vec<_> = safe.items.keys().collect();
+        keys.sort();
+
+        let mut local_parameters = Vec::new();
+        for key in keys {
+            let item = &safe.items[key];
+            let value = if item.is_encrypted {
+                let password = password.as_ref().ok_or_else(|| {
+                    SkitError::InvalidPassword(
+                        "Password required to decrypt encrypted values".to_string(),
+                    )
+                })?;
+                crypto::DecryptBuilder::new()
+                    .kdf_params(safe.kdf_params)
+                    .master_key(safe.master_key.as_deref())
+                    .ciphertext(&item.value)
+                    .context(&item.key)
+                    .password(password)
+                    .decrypt()
+                    .map_err(SkitError::Crypto)?
+            } else {
+                item.value.clone()
+            };
+
+            local_parameters.push(parameters::SsmParameter {
+                key: item.key.clone(),
+                value,
+                is_encrypted: item.is_encrypted,
+                parameter_type: if item.is_encrypted {
+                    "SecureString"
+                } else {
+                    "String"
+                }
+                .to_string(),
+            });
+        }
+
+        let local_names: HashSet<String> = local_parameters
+            .iter()
+            .map(|p| parameters::parameter_name(&resolved_prefix, &p.key))
+            .collect();
+
+        let prefix_for_push = resolved_prefix.clone();
+        let (summary, deleted) = run_async_blocking(async move {
+            let ssm_client = client::create_ssm_client(region, resolved_profile).await?;
+            let remote_names =
+                parameters::fetch_parameter_names(&ssm_client, &prefix_for_push).await?;
+
+            let summary = parameters::put_parameters(
+                &ssm_client,
+                &prefix_for_push,
+                local_parameters,
+                &remote_names,
+                overwrite,
+                dry_run,
+            )
+            .await?;
+
+            let mut deleted = Vec::new();
+            if delete_missing {
+                deleted = remote_names
+                    .into_iter()
+                    .filter(|name| !local_names.contains(name))
+                    .collect::<Vec<_>>();
+                deleted.sort();
+
+                if !dry_run && !deleted.is_empty() {
+                    parameters::delete_parameters(&ssm_client, &deleted).await?;
+                }
+            }
+
+            Ok((summary, deleted))
+        })?;
+
+        if dry_run {
+            let mut message = format!(
+                "Dry run: Would push {} parameters to SSM prefix '{}'\n\n",
+                summary.created.len() + summary.updated.len(),
+                resolved_prefix
+            );
+
+            for name in &summary.created {
+                message.push_str(&format!("  {} [create]\n", name));
+            }
+            for name in &summary.updated {
+                message.push_str(&format!("  {} [overwrite]\n", name));
+            }
+            for name in &summary.skipped {
+                message.push_str(&format!("  {} [skip - exists]\n", name));
+            }
+            for name in &deleted {
+                message.push_str(&format!("  {} [delete]\n", name));
+            }
+
+            return Ok(MessageOutput { message });
+        }
+
+        let message = format!(
+            "Successfully pushed {} parameters to SSM prefix '{}'\n\
+             Created: {}, Updated: {}, Skipped: {}, Deleted: {}",
+            summary.created.len() + summary.updated.len(),
+            resolved_prefix,
+            summary.created.len(),
+            summary.updated.len(),
+            summary.skipped.len(),
+            deleted.len()
+        );
+
+        Ok(MessageOutput { message })
+    }
+
+    fn format_output(
+        &self,
+        output: Self::Output,
+        _format: &crate::OutputFormat,
+    ) -> Result<(), SkitError> {
+        crate::display::print_success(&output.message);
+        Ok(())
+    }
+}
+
+pub fn ssm_push(
+    safe_path: &str,
+    prefix: Option<&str>,
+    region: Option<String>,
+    profile: Option<String>,
+    overwrite: bool,
+    delete_missing: bool,
+    dry_run: bool,
+) -> Result<(), SkitError> {
+    use crate::display::print_info;
+
+    print_info("Pushing secrets to AWS SSM Parameter Store...\n");
+
+    let command = SsmPushCommand;
+    let args = SsmPushArgs {
+        prefix: prefix.map(|p| p.to_string()),
+        region,
+        profile,
+        overwrite,
+        delete_missing,
+        dry_run,
+    };
+
+    command.execute(safe_path, &crate::OutputFormat::Table, args)
+}
+
+/// Resolve the SSM prefix from the CLI flag, falling back to the safe's stored prefix
+fn resolve_prefix(prefix: Option<&str>, safe: &Safe) -> Result<String, SkitError> {
+    match prefix {
+        Some(prefix) => {
+            let trimmed = prefix.trim();
+            if trimmed.is_empty() {
+                return Err(SkitError::ParseError(
+                    "SSM prefix cannot be empty when provided".to_string(),
+                ));
+            }
+            Ok(trimmed.to_string())
+        }
+        None => safe
+            .ssm_prefix
+            .as_ref()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| {
+                SkitError::ParseError(
+                    "No SSM prefix available. Provide --prefix or set one via `skit init --ssm-prefix ...` or a prior `skit ssm pull --prefix ...`."
+                        .to_string(),
+                )
+            }),
+    }
+}
+
+/// Output of `ssm list`
+#[derive(Debug, serde::Serialize)]
+pub struct SsmListOutput {
+    pub prefix: String,
+    pub parameters: Vec<parameters::ParameterInfo>,
+}
+
+/// Show the name and type of every parameter under a prefix, to preview a pull. Nothing is
+/// decrypted and no safe password is needed; the safe, if there is one, only supplies the
+/// stored prefix and profile.
+pub fn ssm_list(
+    safe_path: &str,
+    format: &crate::OutputFormat,
+    prefix: Option<&str>,
+    region: Option<String>,
+    profile: Option<String>,
+) -> Result<(), SkitError> {
+    use crate::OutputFormat;
+    use crate::display::{format_json_output, format_yaml_output, print_info};
+
+    let safe = Safe::load(safe_path).ok();
+    let prefix = match &safe {
+        Some(safe) => resolve_prefix(prefix, safe)?,
+        None => resolve_prefix_without_safe(prefix)?,
+    };
+    let profile = profile.or_else(|| safe.and_then(|safe| safe.ssm_profile));
+
+    let prefix_for_fetch = prefix.clone();
+    let parameters = run_async_blocking(async move {
+        let ssm_client = client::create_ssm_client(region, profile).await?;
+        parameters::list_parameters(&ssm_client, &prefix_for_fetch).await
+    })?;
+    let output = SsmListOutput { prefix, parameters };
+
+    match format {
+        OutputFormat::Json => println!("{}", format_json_output(&output)?),
+        OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+        _ => {
+            let width = output
+                .parameters
+                .iter()
+                .map(|p| p.name.len())
+                .max()
+                .unwrap_or(0)
+                .max(4);
+            if !output.parameters.is_empty() {
+                println!("{:width$}  TYPE", "NAME", width = width);
+            }
+            for parameter in &output.parameters {
+                println!(
+                    "{:width$}  {}",
+                    parameter.name,
+                    parameter.parameter_type,
+                    width = width
+                );
+            }
+            print_info(&format!(
+                "{} parameters under '{}'",
+                output.parameters.len(),
+                output.prefix
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The `--prefix` given to a command that may run without a safe
+fn resolve_prefix_without_safe(prefix: Option<&str>) -> Result<String, SkitError> {
+    match prefix.map(str::trim) {
+        Some(prefix) if !prefix.is_empty() => Ok(prefix.to_string()),
+        Some(_) => Err(SkitError::ParseError(
+            "SSM prefix cannot be empty when provided".to_string(),
+        )),
+        None => Err(SkitError::ParseError(
+            "No SSM prefix available. Provide --prefix or run this next to a safe with a stored prefix."
+                .to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_prefix_without_safe() {
+        assert_eq!(
+            resolve_prefix_without_safe(Some(" /app/dev/ ")).unwrap(),
+            "/app/dev/"
+        );
+        assert!(resolve_prefix_without_safe(Some("  ")).is_err());
+        assert!(resolve_prefix_without_safe(None).is_err());
     }
 }
@@ -1,16 +1,25 @@
 use crate::OutputFormat;
 use crate::commands::template::CommandTemplate;
-use crate::crypto;
-use crate::display::{format_json_output, print_info, print_success, print_warning};
+use crate::commands::verify::verify_encrypted_items;
+use crate::display::{
+    format_json_output, format_yaml_output, print_info, print_success, print_warning,
+};
 use crate::error::SkitError;
+use crate::key_index::{self, KeyIndex};
+use crate::password::AuthSource;
 use crate::types::{
-    Safe, StatusIntegrity, StatusMetadata, StatusOutput, StatusStatistics,
+    Safe, StatusAuth, StatusIntegrity, StatusMetadata, StatusOutput, StatusStatistics,
     StatusVerificationDetails,
 };
+use chrono::{DateTime, Utc};
+use std::path::Path;
 
-/// Arguments for the status command (no arguments needed)
+/// Arguments for the status command
 #[derive(Debug)]
-pub struct StatusArgs;
+pub struct StatusArgs {
+    /// Skip decrypting each secret; only the password is checked
+    pub fast: bool,
+}
 
 /// Output for the status command
 #[derive(Debug)]
@@ -18,6 +27,7 @@ pub struct StatusCommandOutput {
     pub status_output: StatusOutput,
     pub encrypted_count: usize,
     pub verification_details: Option<StatusVerificationDetails>,
+    pub auth_source: Option<AuthSource>,
 }
 
 /// Template-based implementation of the status command
@@ -36,7 +46,7 @@ impl CommandTemplate for StatusCommand {
         &self,
         safe: &mut Safe,
         password: Option<String>,
-        _args: Self::Args,
+        args: Self::Args,
     ) -> Result<Self::Output, SkitError> {
         // Count statistics
         let total_items = safe.items.len();
@@ -53,46 +63,12 @@ impl CommandTemplate for StatusCommand {
                 password_hash_ok = true;
 
                 // If we have encrypted secrets, also test decryption
-                if encrypted_count > 0 {
-                    let mut verification_results = Vec::new();
-                    let mut failed_count = 0;
-
-                    for item in safe.items.values() {
-                        if item.is_encrypted {
-                            match crypto::DecryptBuilder::new()
-                                .ciphertext(&item.value)
-                                .password(&password)
-                                .decrypt()
-                            {
-                                Ok(_) => {
-                                    verification_results.push((item.key.clone(), true));
-                                }
-                                Err(_) => {
-                                    verification_results.push((item.key.clone(), false));
-                                    failed_count += 1;
-                                }
-                            }
-                        }
-                    }
-
-                    let verified_count = encrypted_count - failed_count;
-                    encrypted_secrets_verified = Some(failed_count == 0);
-
-                    let failed_keys: Vec<String> = verification_results
-                        .iter()
-                        .filter_map(
-                            |(key, success)| {
-                                if !success { Some(key.clone()) } else { None }
-                            },
-                        )
-                        .collect();
-
-                    verification_details = Some(StatusVerificationDetails {
-                        total_encrypted: encrypted_count,
-                        verified: verified_count,
-                        failed: failed_count,
-                        failed_keys,
-                    });
+                if args.fast {
+                    encrypted_secrets_verified = None;
+                } else if encrypted_count > 0 {
+                    let details = verify_encrypted_items(safe, &password);
+                    encrypted_secrets_verified = Some(details.failed == 0);
+                    verification_details = Some(details);
                 } else {
                     encrypted_secrets_verified = Some(true); // No encrypted secrets, so verification is trivially successful
                 }
@@ -105,6 +81,7 @@ impl CommandTemplate for StatusCommand {
         }
 
         let output = StatusOutput {
+            ok: password_hash_ok && encrypted_secrets_verified != Some(false),
             safe_path: "".to_string(), // Will be overridden in format_output
             metadata: StatusMetadata {
                 version: safe.version.clone(),
@@ -122,12 +99,14 @@ impl CommandTemplate for StatusCommand {
                 encrypted_secrets_verified,
                 verification_details: verification_details.clone(),
             },
+            auth: StatusAuth::default(), // Will be filled in by execute_with_path
         };
 
         Ok(StatusCommandOutput {
             status_output: output,
             encrypted_count,
             verification_details,
+            auth_source: None,
         })
     }
 
@@ -136,12 +115,21 @@ impl CommandTemplate for StatusCommand {
             OutputFormat::Json => {
                 println!("{}", format_json_output(&output.status_output)?);
             }
+            OutputFormat::Yaml => {
+                print!("{}", format_yaml_output(&output.status_output)?);
+            }
             _ => {
                 // Original text output with verification messages
                 println!();
                 print_info("Verifying password hash integrity...");
 
-                if output.encrypted_count > 0 {
+                if output.encrypted_count > 0
+                    && output
+                        .status_output
+                        .integrity
+                        .encrypted_secrets_verified
+                        .is_some()
+                {
                     print_info("Verifying encrypted secrets...");
                 }
 
@@ -159,6 +147,8 @@ impl CommandTemplate for StatusCommand {
                 println!("  Last updated: {}", output.status_output.metadata.updated);
                 println!();
 
+                print_auth(&output.status_output.auth, output.auth_source);
+
                 // Display statistics
                 println!("Statistics:");
                 println!(
@@ -186,6 +176,17 @@ impl CommandTemplate for StatusCommand {
                 if output.encrypted_count == 0 {
                     println!();
                     print_success("No encrypted secrets to verify");
+                } else if output
+                    .status_output
+                    .integrity
+                    .encrypted_secrets_verified
+                    .is_none()
+                {
+                    println!();
+                    print_info(&format!(
+                        "Skipped verifying {} encrypted secrets (--fast)",
+                        output.encrypted_count
+                    ));
                 } else if let Some(details) = &output.verification_details {
                     println!();
                     if details.failed == 0 {
@@ -212,6 +213,74 @@ impl CommandTemplate for StatusCommand {
     }
 }
 
+/// Saved key file for the safe and when it was last used, read before authenticating records
+/// a new use. Keys without an index entry fall back to the file's modification time.
+fn saved_key_file(safe: &Safe) -> Option<(std::path::PathBuf, Option<DateTime<Utc>>)> {
+    let dir = key_index::keys_dir()?;
+    let key_file = dir.join(format!("{}.key", safe.uuid));
+    if !key_file.exists() {
+        return None;
+    }
+    let last_used = KeyIndex::load(&dir)
+        .entries
+        .get(&safe.uuid)
+        .map(|entry| entry.last_used)
+        .or_else(|| {
+            std::fs::metadata(&key_file)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(DateTime::<Utc>::from)
+        });
+    Some((key_file, last_used))
+}
+
+fn status_auth(
+    safe: &Safe,
+    source: Option<AuthSource>,
+    key_file: Option<(std::path::PathBuf, Option<DateTime<Utc>>)>,
+) -> StatusAuth {
+    let Some((path, last_used)) = key_file else {
+        return StatusAuth {
+            source: source.map(|source| source.as_str().to_string()),
+            ..StatusAuth::default()
+        };
+    };
+    // The key file was already checked if it supplied the password
+    let valid = if source == Some(AuthSource::KeyFile) {
+        true
+    } else {
+        std::fs::read_to_string(&path).is_ok_and(|content| {
+            safe.verify_password(&zeroize::Zeroizing::new(content.trim().to_string()))
+                .is_ok()
+        })
+    };
+    StatusAuth {
+        source: source.map(|source| source.as_str().to_string()),
+        key_file: Some(crate::fs_utils::display_path(&path)),
+        key_file_last_used: last_used.map(|time| time.to_rfc3339()),
+        key_file_valid: Some(valid),
+    }
+}
+
+fn print_auth(auth: &StatusAuth, source: Option<AuthSource>) {
+    println!("Authentication:");
+    let source = source.map_or("none (no password set)", |source| source.description());
+    println!("  Unlocked with: {}", source);
+    match &auth.key_file {
+        Some(path) => {
+            println!("  Key file:      {}", path);
+            if let Some(last_used) = &auth.key_file_last_used {
+                println!("  Last used:     {}", last_used);
+            }
+            if auth.key_file_valid == Some(false) {
+                println!("  Key file is stale: it does not unlock this safe");
+            }
+        }
+        None => println!("  Key file:      none"),
+    }
+    println!();
+}
+
 impl StatusCommand {
     /// Custom execute method that properly sets the safe_path
     pub fn execute_with_path(
@@ -227,36 +296,52 @@ impl StatusCommand {
         let mut safe = Safe::load(safe_path)?;
 
         // Step 3: Authenticate (if required)
-        let password = if self.requires_authentication(&safe, &args) {
-            Some(crate::password::get_password_with_auth_chain_formatted(
+        let key_file = saved_key_file(&safe);
+        let (password, source) = if self.requires_authentication(&safe, &args) {
+            let (password, source) = crate::password::get_password_with_auth_chain_formatted(
                 &safe,
                 safe_path,
                 "Enter safe password: ",
                 Some(format),
-            )?)
+            )?;
+            (Some(password), Some(source))
         } else {
-            None
+            (None, None)
         };
+        let auth = status_auth(&safe, source, key_file);
 
         // Step 4: Execute core operation
         let mut output = self.execute_operation(&mut safe, password, args)?;
 
-        // Step 5: Set the safe_path in the output
-        output.status_output.safe_path = safe_path.to_string();
+        // Step 5: Set the safe_path and auth details in the output
+        output.status_output.safe_path = crate::fs_utils::display_path(Path::new(safe_path));
+        output.status_output.auth = auth;
+        output.auth_source = source;
 
         // Step 6: Save safe (if modified) - not needed for status
 
-        // Step 7: Format and display output
+        // Step 7: Format and display output, then fail if anything did not verify
+        let failed = output
+            .verification_details
+            .as_ref()
+            .map_or(0, |details| details.failed);
         self.format_output(output, format)?;
 
+        if failed > 0 {
+            return Err(SkitError::VerificationFailed(format!(
+                "{} encrypted secrets failed verification",
+                failed
+            )));
+        }
         Ok(())
     }
 }
 
-/// Show safe metadata and integrity status
-pub fn status(safe_path: &str, format: &OutputFormat) -> Result<(), SkitError> {
+/// Show safe metadata and integrity status. Fails if any encrypted secret does not decrypt,
+/// unless `fast` skips that check.
+pub fn status(safe_path: &str, format: &OutputFormat, fast: bool) -> Result<(), SkitError> {
     let command = StatusCommand;
-    let args = StatusArgs;
+    let args = StatusArgs { fast };
 
     command.execute_with_path(safe_path, format, args)
 }
@@ -1,6 +1,7 @@
 use crate::OutputFormat;
+use crate::audit;
 use crate::error::SkitError;
-use crate::password::get_password_with_auth_chain_formatted;
+use crate::password::{get_password_with_auth_chain_formatted, get_password_with_auth_chain_quiet};
 use crate::types::Safe;
 
 /// Template method trait for SKIT commands
@@ -31,6 +32,17 @@ pub trait CommandTemplate {
         false
     }
 
+    /// Whether a command that modifies the safe actually changed it this run.
+    /// Returning false skips saving, so an idempotent no-op leaves the file untouched.
+    fn changed_safe(&self, _output: &Self::Output) -> bool {
+        true
+    }
+
+    /// Action recorded in the audit log for keys this command adds or changes
+    fn audit_action(&self) -> &'static str {
+        "set"
+    }
+
     fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError>
     where
         Self::Output: std::fmt::Debug,
@@ -45,6 +57,21 @@ pub trait CommandTemplate {
         format: &OutputFormat,
         args: Self::Args,
     ) -> Result<(), SkitError>
+    where
+        Self::Output: std::fmt::Debug,
+    {
+        self.execute_with(safe_path, format, args, false)
+    }
+
+    /// `execute`, with `quiet` keeping the auth chain's info logs off stdout whatever the
+    /// format, for commands whose stdout is the secret itself
+    fn execute_with(
+        &self,
+        safe_path: &str,
+        format: &OutputFormat,
+        args: Self::Args,
+        quiet: bool,
+    ) -> Result<(), SkitError>
     where
         Self::Output: std::fmt::Debug,
     {
@@ -59,20 +86,30 @@ pub trait CommandTemplate {
         };
 
         let password = if self.requires_authentication(&safe, &args) {
-            Some(get_password_with_auth_chain_formatted(
-                &safe,
-                safe_path,
-                "Enter safe password: ",
-                Some(format),
-            )?)
+            let (password, _) = if quiet {
+                get_password_with_auth_chain_quiet(&safe, safe_path, "Enter safe password: ", true)?
+            } else {
+                get_password_with_auth_chain_formatted(
+                    &safe,
+                    safe_path,
+                    "Enter safe password: ",
+                    Some(format),
+                )?
+            };
+            Some(password)
         } else {
             None
         };
 
+        let before = self.modifies_safe().then(|| safe.items.clone());
+
         let output = self.execute_operation(&mut safe, password, args)?;
 
-        if self.modifies_safe() {
+        if let Some(before) = before
+            && self.changed_safe(&output)
+        {
             safe.save(safe_path)?;
+            audit::record_changes(&safe, &before, self.audit_action());
         }
 
         self.format_output(output, format)?;
@@ -0,0 +1,164 @@
+use crate::commands::pull::{PullOutcome, PulledEntry, apply_pulled};
+use crate::commands::template::{CommandTemplate, MessageOutput};
+use crate::error::SkitError;
+use crate::types::Safe;
+use crate::vault;
+
+/// Arguments for the Vault pull command
+#[derive(Debug)]
+pub struct VaultPullArgs {
+    pub path: Option<String>,
+    pub mount: String,
+    pub replace: bool,
+    pub no_overwrite: bool,
+    pub dry_run: bool,
+}
+
+/// Template-based implementation of the Vault KV pull command
+pub struct VaultPullCommand;
+
+impl CommandTemplate for VaultPullCommand {
+    type Args = VaultPullArgs;
+    type Output = MessageOutput;
+
+    fn validate_args(&self, args: &Self::Args) -> Result<(), SkitError> {
+        if let Some(path) = &args.path
+            && path.trim_matches('/').trim().is_empty()
+        {
+            return Err(SkitError::ParseError(
+                "Vault path cannot be empty when provided".to_string(),
+            ));
+        }
+        if args.mount.trim_matches('/').trim().is_empty() {
+            return Err(SkitError::ParseError(
+                "Vault mount cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn requires_authentication(&self, _safe: &Safe, args: &Self::Args) -> bool {
+        !args.dry_run // Every pulled secret is stored encrypted
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let VaultPullArgs {
+            path,
+            mount,
+            replace,
+            no_overwrite,
+            dry_run,
+        } = args;
+
+        let path = resolve_path(path.as_deref(), safe)?;
+        let mount = mount.trim_matches('/');
+        let secrets = vault::fetch_secrets(mount, &path)?;
+
+        let total = secrets.len();
+        let entries = secrets
+            .into_iter()
+            .map(|secret| PulledEntry {
+                key: secret.key,
+                value: secret.value,
+                encrypt: true,
+            })
+            .collect();
+        let counts = match apply_pulled(
+            safe,
+            entries,
+            password.as_deref(),
+            replace,
+            no_overwrite,
+            dry_run,
+        )? {
+            PullOutcome::DryRun(listing) => {
+                return Ok(MessageOutput {
+                    message: format!(
+                        "Dry run: Would pull {} secrets from Vault path '{}/{}'\n\n{}",
+                        total, mount, path, listing
+                    ),
+                });
+            }
+            PullOutcome::Applied(counts) => counts,
+        };
+
+        safe.vault_path = Some(path.clone());
+
+        let message = format!(
+            "Successfully pulled {} secrets from Vault path '{}/{}'\n\
+             Added: {}, Updated: {}, Skipped: {}",
+            counts.added + counts.updated,
+            mount,
+            path,
+            counts.added,
+            counts.updated,
+            counts.skipped
+        );
+
+        Ok(MessageOutput { message })
+    }
+
+    fn modifies_safe(&self) -> bool {
+        true
+    }
+
+    fn audit_action(&self) -> &'static str {
+        "vault-pull"
+    }
+
+    fn format_output(
+        &self,
+        output: Self::Output,
+        _format: &crate::OutputFormat,
+    ) -> Result<(), SkitError> {
+        crate::display::print_success(&output.message);
+        Ok(())
+    }
+}
+
+pub fn vault_pull(
+    safe_path: &str,
+    path: Option<&str>,
+    mount: &str,
+    replace: bool,
+    no_overwrite: bool,
+    dry_run: bool,
+) -> Result<(), SkitError> {
+    use crate::display::print_info;
+
+    print_info("Pulling secrets from HashiCorp Vault...\n");
+
+    let command = VaultPullCommand;
+    let args = VaultPullArgs {
+        path: path.map(|p| p.to_string()),
+        mount: mount.to_string(),
+        replace,
+        no_overwrite,
+        dry_run,
+    };
+
+    command.execute(safe_path, &crate::OutputFormat::Table, args)
+}
+
+/// Resolve the secret path from the CLI flag, falling back to the safe's stored path
+fn resolve_path(path: Option<&str>, safe: &Safe) -> Result<String, SkitError> {
+    match path {
+        Some(path) => Ok(path.trim().trim_matches('/').to_string()),
+        None => safe
+            .vault_path
+            .as_ref()
+            .map(|p| p.trim().trim_matches('/').to_string())
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| {
+                SkitError::ParseError(
+                    "No Vault path available. Provide a path or run a prior `skit vault pull <PATH>`."
+                        .to_string(),
+                )
+            }),
+    }
+}
@@ -0,0 +1,170 @@
+use crate::OutputFormat;
+use crate::commands::template::CommandTemplate;
+use crate::crypto;
+use crate::display::{format_json_output, format_yaml_output, print_success, print_warning};
+use crate::error::SkitError;
+use crate::types::{Safe, StatusVerificationDetails};
+
+/// Exit codes so a CI gate can tell a missing safe from a wrong password or damaged secrets
+const EXIT_SAFE_MISSING: i32 = 2;
+const EXIT_BAD_PASSWORD: i32 = 3;
+const EXIT_CORRUPTED: i32 = 4;
+
+/// Attempt to decrypt every encrypted item, recording which keys fail.
+/// Plaintext is discarded as soon as each value is checked.
+pub fn verify_encrypted_items(safe: &Safe, password: &str) -> StatusVerificationDetails {
+    let mut encrypted_items: Vec<_> = safe
+        .items
+        .values()
+        .filter(|item| item.is_encrypted)
+        .collect();
+    encrypted_items.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let ciphertexts: Vec<(&str, &str)> = encrypted_items
+        .iter()
+        .map(|item| (item.key.as_str(), item.value.as_str()))
+        .collect();
+    let results = crypto::decrypt_many(
+        &ciphertexts,
+        password,
+        &safe.kdf_params,
+        safe.master_key.as_deref(),
+    );
+
+    let failed_keys: Vec<String> = encrypted_items
+        .iter()
+        .zip(results)
+        .filter(|(_, result)| result.is_err())
+        .map(|(item, _)| item.key.clone())
+        .collect();
+
+    StatusVerificationDetails {
+        total_encrypted: encrypted_items.len(),
+        verified: encrypted_items.len() - failed_keys.len(),
+        failed: failed_keys.len(),
+        failed_keys,
+    }
+}
+
+/// Arguments for the verify command (no arguments needed)
+#[derive(Debug)]
+pub struct VerifyArgs;
+
+/// Template-based implementation of the verify command
+pub struct VerifyCommand;
+
+impl CommandTemplate for VerifyCommand {
+    type Args = VerifyArgs;
+    type Output = StatusVerificationDetails;
+
+    fn requires_authentication(&self, safe: &Safe, _args: &Self::Args) -> bool {
+        safe.items.values().any(|item| item.is_encrypted)
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        _args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        match password {
+            Some(password) => Ok(verify_encrypted_items(safe, &password)),
+            None => Ok(StatusVerificationDetails {
+                total_encrypted: 0,
+                verified: 0,
+                failed: 0,
+                failed_keys: vec![],
+            }),
+        }
+    }
+
+    fn format_output(&self, output: Self::Output, format: &OutputFormat) -> Result<(), SkitError> {
+        match format {
+            OutputFormat::Json => println!("{}", format_json_output(&output)?),
+            OutputFormat::Yaml => print!("{}", format_yaml_output(&output)?),
+            _ => {
+                if output.failed == 0 {
+                    print_success(&format!(
+                        "All {} encrypted secrets verified",
+                        output.total_encrypted
+                    ));
+                } else {
+                    print_warning(&format!(
+                        "{} of {} encrypted secrets failed verification:",
+                        output.failed, output.total_encrypted
+                    ));
+                    for key in &output.failed_keys {
+                        eprintln!("  - {}", key);
+                    }
+                }
+            }
+        }
+
+        if output.failed > 0 {
+            // Exit directly so machine-readable output isn't followed by an error line
+            std::process::exit(EXIT_CORRUPTED);
+        }
+
+        Ok(())
+    }
+}
+
+/// Map a verification error to its exit code
+fn exit_code(error: &SkitError) -> i32 {
+    match error {
+        SkitError::SafeNotFound(_) => EXIT_SAFE_MISSING,
+        SkitError::InvalidPassword(_) | SkitError::InputRequired(_) => EXIT_BAD_PASSWORD,
+        SkitError::ParseError(_) | SkitError::Crypto(_) => EXIT_CORRUPTED,
+        _ => 1,
+    }
+}
+
+/// Check that every encrypted secret decrypts. Exits 2 if the safe is missing, 3 if the
+/// password is wrong or unavailable, and 4 if the safe or any secret is corrupted.
+pub fn verify(safe_path: &str, format: &OutputFormat) -> Result<(), SkitError> {
+    let result = Safe::load(safe_path).and_then(|safe| {
+        // A damaged hash would otherwise surface as a wrong password
+        if !crypto::is_valid_password_hash(&safe.password_hash) {
+            return Err(SkitError::ParseError(
+                "Password hash in safe is corrupted".to_string(),
+            ));
+        }
+        VerifyCommand.execute(safe_path, format, VerifyArgs)
+    });
+
+    if let Err(e) = result {
+        tracing::error!("{}", e);
+        std::process::exit(exit_code(&e));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_encrypted_items_reports_failing_keys() {
+        let header = "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n\
+                      #@KDF_MEM=1024\n#@KDF_TIME=1\n#@KDF_LANES=1\nPLAIN=value\n";
+        let mut safe = Safe::parse(header).unwrap();
+
+        for (key, password) in [("GOOD", "pw"), ("ALSO_GOOD", "pw"), ("BAD", "other")] {
+            let value = crypto::EncryptBuilder::new()
+                .kdf_params(safe.kdf_params)
+                .master_key(safe.master_key.as_deref())
+                .plaintext("secret")
+                .context(key)
+                .password(password)
+                .encrypt()
+                .unwrap();
+            safe.add_or_update_item(key.to_string(), value, true);
+        }
+
+        let details = verify_encrypted_items(&safe, "pw");
+        assert_eq!(details.total_encrypted, 3);
+        assert_eq!(details.verified, 2);
+        assert_eq!(details.failed, 1);
+        assert_eq!(details.failed_keys, vec!["BAD".to_string()]);
+    }
+}
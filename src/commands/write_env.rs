@@ -0,0 +1,274 @@
+use crate::OutputFormat;
+use crate::commands::template::CommandTemplate;
+use crate::display::{print_info, print_success, print_warning};
+use crate::dotenv::quote_value;
+use crate::error::SkitError;
+use crate::fs_utils;
+use crate::input;
+use crate::key_filter::KeyFilter;
+use crate::types::Safe;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// File written when no `--output` is given
+pub const DEFAULT_ENV_FILE: &str = ".env";
+
+/// Arguments for the write-env command
+#[derive(Debug)]
+pub struct WriteEnvArgs {
+    pub output: String,
+    pub filter: KeyFilter,
+    /// Add to the end of an existing file instead of replacing it
+    pub append: bool,
+    /// Add the file to the `.gitignore` next to it
+    pub gitignore: bool,
+    /// Delete the file again after this many seconds
+    pub ttl: Option<u64>,
+}
+
+/// Output for the write-env command
+#[derive(Debug)]
+pub struct WriteEnvOutput {
+    pub entries: Vec<(String, String)>, // (key, value) pairs
+    pub output: String,
+    pub append: bool,
+    pub gitignore: bool,
+    pub ttl: Option<u64>,
+}
+
+/// Template-based implementation of the write-env command
+pub struct WriteEnvCommand;
+
+impl CommandTemplate for WriteEnvCommand {
+    type Args = WriteEnvArgs;
+    type Output = WriteEnvOutput;
+
+    fn requires_authentication(&self, safe: &Safe, args: &Self::Args) -> bool {
+        safe.items
+            .values()
+            .any(|item| item.is_encrypted && args.filter.allows(&item.key))
+    }
+
+    fn execute_operation(
+        &self,
+        safe: &mut Safe,
+        password: Option<String>,
+        args: Self::Args,
+    ) -> Result<Self::Output, SkitError> {
+        let unmatched = args.filter.unmatched_only(safe.items.keys());
+        if !unmatched.is_empty() {
+            eprintln!(
+                "Warning: --only matched no keys in the safe: {}",
+                unmatched.join(", ")
+            );
+        }
+
+        // Sort keys for consistent output
+        let mut keys: Vec<_> = safe
+            .items
+            .keys()
+            .filter(|key| args.filter.allows(key))
+            .collect();
+        keys.sort();
+
+        let items: Vec<_> = keys.iter().map(|key| &safe.items[*key]).collect();
+        let values = safe.decrypt_selected(&items, password.as_deref());
+
+        let mut entries = Vec::new();
+        for (item, value) in items.into_iter().zip(values) {
+            let Some(Ok(value)) = value else {
+                eprintln!("Warning: Failed to decrypt '{}', skipping", item.key);
+                continue;
+            };
+            entries.push((item.key.clone(), value));
+        }
+
+        Ok(WriteEnvOutput {
+            entries,
+            output: args.output,
+            append: args.append,
+            gitignore: args.gitignore,
+            ttl: args.ttl,
+        })
+    }
+
+    fn format_output(&self, output: Self::Output, _format: &OutputFormat) -> Result<(), SkitError> {
+        let content = Zeroizing::new(
+            output
+                .entries
+                .iter()
+                .map(|(key, value)| format!("{}={}\n", key, quote_value(value)))
+                .collect::<String>(),
+        );
+
+        let path = Path::new(&output.output);
+        fs_utils::write_secret_file_overwrite(path, &content, output.append)?;
+        print_success(&format!(
+            "Wrote {} secrets to {}",
+            output.entries.len(),
+            output.output
+        ));
+        print_warning(&format!(
+            "⚠ {} holds decrypted secrets in plain text. Never commit it, and delete it when done",
+            output.output
+        ));
+
+        if !is_gitignored(path) {
+            if output.gitignore || offer_gitignore(&output.output)? {
+                add_to_gitignore(path)?;
+                print_success(&format!("Added {} to .gitignore", file_name(path)));
+            } else {
+                print_warning(&format!(
+                    "{} is not in .gitignore (pass --gitignore to add it)",
+                    output.output
+                ));
+            }
+        }
+
+        if let Some(seconds) = output.ttl {
+            schedule_removal(path, seconds)?;
+            print_info(&format!(
+                "{} will be deleted in {} seconds",
+                output.output, seconds
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Ask whether to add the file to `.gitignore`, but only when someone is at a terminal
+fn offer_gitignore(output: &str) -> Result<bool, SkitError> {
+    if input::is_non_interactive() || !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    input::confirm(&format!("Add {} to .gitignore? [y/N]: ", output))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// The `.gitignore` in the same directory as the file
+fn gitignore_path(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.join(".gitignore"),
+        _ => PathBuf::from(".gitignore"),
+    }
+}
+
+/// Whether the neighbouring `.gitignore` lists the file by name
+pub fn is_gitignored(path: &Path) -> bool {
+    let name = file_name(path);
+    fs::read_to_string(gitignore_path(path))
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .any(|line| line == name || line.strip_prefix('/') == Some(name.as_str()))
+        })
+        .unwrap_or(false)
+}
+
+/// Append the file's name to the neighbouring `.gitignore`, creating it if needed
+pub fn add_to_gitignore(path: &Path) -> Result<(), SkitError> {
+    let gitignore = gitignore_path(path);
+    let mut content = fs::read_to_string(&gitignore).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&file_name(path));
+    content.push('\n');
+    fs_utils::write_file_atomic(&gitignore, &content)
+}
+
+/// Start a detached process that deletes the file after `seconds`
+fn schedule_removal(path: &Path, seconds: u64) -> Result<(), SkitError> {
+    use std::process::{Command, Stdio};
+
+    let path = fs::canonicalize(path).map_err(SkitError::Io)?;
+
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg("sleep \"$1\" && rm -f -- \"$2\"")
+            .arg("skit-write-env")
+            .arg(seconds.to_string())
+            .arg(&path);
+        command
+    };
+
+    #[cfg(not(unix))]
+    let mut command = {
+        let mut command = Command::new("powershell");
+        command.arg("-NoProfile").arg("-Command").arg(format!(
+            "Start-Sleep -Seconds {}; Remove-Item -Force -LiteralPath '{}'",
+            seconds,
+            path.display().to_string().replace('\'', "''")
+        ));
+        command
+    };
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            SkitError::Io(io::Error::other(format!(
+                "Failed to schedule removal of {}: {}",
+                path.display(),
+                e
+            )))
+        })?;
+    Ok(())
+}
+
+/// Decrypt the safe into a dotenv file with 0600 permissions for tools that need a real file
+pub fn write_env(
+    safe_path: &str,
+    output: &str,
+    filter: KeyFilter,
+    append: bool,
+    gitignore: bool,
+    ttl: Option<u64>,
+) -> Result<(), SkitError> {
+    let command = WriteEnvCommand;
+    let args = WriteEnvArgs {
+        output: output.to_string(),
+        filter,
+        append,
+        gitignore,
+        ttl,
+    };
+
+    command.execute(safe_path, &OutputFormat::Env, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_add_to_gitignore() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let env_file = temp_dir.path().join(".env");
+        let gitignore = temp_dir.path().join(".gitignore");
+
+        assert!(!is_gitignored(&env_file));
+        fs::write(&gitignore, "target").unwrap();
+        add_to_gitignore(&env_file).unwrap();
+
+        assert_eq!(fs::read_to_string(&gitignore).unwrap(), "target\n.env\n");
+        assert!(is_gitignored(&env_file));
+
+        fs::write(&gitignore, "/.env\n").unwrap();
+        assert!(is_gitignored(&env_file));
+    }
+}
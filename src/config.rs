@@ -0,0 +1,409 @@
+use crate::OutputFormat;
+use crate::error::SkitError;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Repo-local config file, read from the current directory
+pub const LOCAL_CONFIG_FILE: &str = ".skit.toml";
+
+/// Environment variable overriding the configured output format
+pub const FORMAT_ENV: &str = "SKIT_FORMAT";
+
+/// Environment variable naming the safe when `--safe` isn't passed
+pub const SAFE_ENV: &str = "SKIT_SAFE";
+
+/// `--safe` value that searches parent directories for the configured safe
+pub const AUTO_SAFE: &str = "auto";
+
+/// Environment variable setting how many numbered backups to keep on save
+pub const BACKUPS_ENV: &str = "SKIT_BACKUPS";
+
+/// Backups kept when `--backup` is passed without a configured count
+pub const DEFAULT_BACKUPS: u32 = 3;
+
+/// Safe used when neither `--safe` nor the config names one
+pub const DEFAULT_SAFE: &str = ".env.safe";
+
+/// Settings read from one config file; every key is optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub default_safe: Option<String>,
+    pub default_format: Option<String>,
+    pub no_color: Option<bool>,
+    pub auto_remember: Option<bool>,
+    /// Tries allowed at an interactive password prompt
+    pub password_attempts: Option<u32>,
+    /// Search parent directories for the safe, like `--safe auto`
+    pub discover: Option<bool>,
+    /// Numbered backups (`.env.safe.bak.N`) kept when the safe is saved; 0 disables them
+    pub backups: Option<u32>,
+    /// Safe to use inside a directory tree, e.g. `"~/work/api" = "api"`
+    pub directories: BTreeMap<String, String>,
+}
+
+/// Where an effective setting came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Cli,
+    Env(&'static str),
+    File(PathBuf),
+    Directory { file: PathBuf, dir: String },
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Cli => write!(f, "command line"),
+            Source::Env(var) => write!(f, "{} environment variable", var),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Directory { file, dir } => {
+                write!(f, "[directories] \"{}\" in {}", dir, file.display())
+            }
+            Source::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// An effective setting together with its source
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Resolved<T> {
+    fn new(value: T, source: Source) -> Self {
+        Self { value, source }
+    }
+}
+
+/// Path of the user config file: `~/.config/skit/config.toml`
+pub fn global_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config").join("skit").join("config.toml"))
+}
+
+/// Merged configuration; files later in the list take precedence
+#[derive(Debug, Default)]
+pub struct Config {
+    layers: Vec<(PathBuf, ConfigFile)>,
+}
+
+impl Config {
+    /// Load `~/.config/skit/config.toml` and then `./.skit.toml`, skipping missing files
+    pub fn load() -> Result<Self, SkitError> {
+        let mut paths: Vec<PathBuf> = global_config_path().into_iter().collect();
+        paths.push(PathBuf::from(LOCAL_CONFIG_FILE));
+        Self::load_from(&paths)
+    }
+
+    fn load_from(paths: &[PathBuf]) -> Result<Self, SkitError> {
+        let mut layers = Vec::new();
+        for path in paths {
+            let content = match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(SkitError::Io(e)),
+            };
+            let file = toml::from_str(&content).map_err(|e| {
+                SkitError::ParseError(format!("Invalid config file {}: {}", path.display(), e))
+            })?;
+            layers.push((path.clone(), file));
+        }
+        Ok(Self { layers })
+    }
+
+    /// Config files that were found, lowest precedence first
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.layers.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Highest-precedence value of a setting and the file it came from
+    fn lookup<T>(&self, get: impl Fn(&ConfigFile) -> Option<T>) -> Option<Resolved<T>> {
+        self.layers.iter().rev().find_map(|(path, file)| {
+            get(file).map(|v| Resolved::new(v, Source::File(path.clone())))
+        })
+    }
+
+    /// Safe name or path: `--safe`, then `SKIT_SAFE`, then the most specific `[directories]`
+    /// entry containing `cwd`, then `default_safe`, then `.env.safe`
+    pub fn safe(&self, cli: Option<&str>, cwd: &Path) -> Resolved<String> {
+        if let Some(safe) = cli {
+            return Resolved::new(safe.to_string(), Source::Cli);
+        }
+        if let Ok(safe) = env::var(SAFE_ENV)
+            && !safe.is_empty()
+        {
+            return Resolved::new(safe, Source::Env(SAFE_ENV));
+        }
+
+        let mut best: Option<(usize, Resolved<String>)> = None;
+        for (path, file) in &self.layers {
+            for (dir, safe) in &file.directories {
+                let dir_path = PathBuf::from(crate::safe::normalize_safe_path(dir));
+                if !dir_path.is_absolute() || !cwd.starts_with(&dir_path) {
+                    continue;
+                }
+                // Deeper directories win; on a tie the later file does
+                let depth = dir_path.components().count();
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_depth, _)| depth >= *best_depth)
+                {
+                    // A relative safe lives in the mapped directory, not wherever we were run from
+                    let safe_path = dir_path.join(crate::safe::normalize_safe_path(safe));
+                    let source = Source::Directory {
+                        file: path.clone(),
+                        dir: dir.clone(),
+                    };
+                    best = Some((
+                        depth,
+                        Resolved::new(safe_path.display().to_string(), source),
+                    ));
+                }
+            }
+        }
+
+        best.map(|(_, resolved)| resolved)
+            .or_else(|| self.lookup(|f| f.default_safe.clone()))
+            .unwrap_or_else(|| Resolved::new(DEFAULT_SAFE.to_string(), Source::Default))
+    }
+
+    pub fn no_color(&self) -> Resolved<bool> {
+        self.lookup(|f| f.no_color)
+            .unwrap_or_else(|| Resolved::new(false, Source::Default))
+    }
+
+    pub fn auto_remember(&self) -> Resolved<bool> {
+        self.lookup(|f| f.auto_remember)
+            .unwrap_or_else(|| Resolved::new(false, Source::Default))
+    }
+
+    pub fn discover(&self) -> Resolved<bool> {
+        self.lookup(|f| f.discover)
+            .unwrap_or_else(|| Resolved::new(false, Source::Default))
+    }
+
+    pub fn password_attempts(&self) -> Resolved<u32> {
+        self.lookup(|f| f.password_attempts).unwrap_or_else(|| {
+            Resolved::new(crate::input::DEFAULT_PASSWORD_ATTEMPTS, Source::Default)
+        })
+    }
+}
+
+/// Output format: `--format`, then `SKIT_FORMAT`, then `default_format`, then table
+pub fn resolve_format(
+    cli_format: Option<&OutputFormat>,
+    config: &Config,
+) -> Result<Resolved<OutputFormat>, SkitError> {
+    if let Some(format) = cli_format {
+        return Ok(Resolved::new(format.clone(), Source::Cli));
+    }
+
+    if let Ok(value) = env::var(FORMAT_ENV)
+        && !value.is_empty()
+    {
+        let format = parse_format(&value, FORMAT_ENV)?;
+        return Ok(Resolved::new(format, Source::Env(FORMAT_ENV)));
+    }
+
+    if let Some(resolved) = config.lookup(|f| f.default_format.clone()) {
+        let format = parse_format(&resolved.value, &resolved.source.to_string())?;
+        return Ok(Resolved::new(format, resolved.source));
+    }
+
+    Ok(Resolved::new(OutputFormat::Table, Source::Default))
+}
+
+/// Directories searched for a relative safe path, from `cwd` up to the filesystem root,
+/// with the candidate path in each
+pub fn discovery_candidates(safe_path: &str, cwd: &Path) -> Vec<PathBuf> {
+    if Path::new(safe_path).is_absolute() {
+        return vec![PathBuf::from(safe_path)];
+    }
+    cwd.ancestors().map(|dir| dir.join(safe_path)).collect()
+}
+
+/// Find a relative safe the way git finds `.git`: the first of `discovery_candidates` that
+/// exists. `Err` holds every path that was tried.
+pub fn discover_safe(safe_path: &str, cwd: &Path) -> Result<PathBuf, Vec<PathBuf>> {
+    let candidates = discovery_candidates(safe_path, cwd);
+    match candidates.iter().find(|path| path.is_file()) {
+        Some(found) => Ok(found.clone()),
+        None => Err(candidates),
+    }
+}
+
+/// Backups kept on save: `SKIT_BACKUPS`, then `backups`, then none. `--backup` turns them on
+/// with the default count when neither sets one.
+pub fn resolve_backups(cli_backup: bool, config: &Config) -> Result<Resolved<u32>, SkitError> {
+    let configured = match env::var(BACKUPS_ENV) {
+        Ok(value) if !value.is_empty() => {
+            let count = value.trim().parse().map_err(|_| {
+                SkitError::ParseError(format!(
+                    "Invalid {} value '{}'. Use the number of backups to keep",
+                    BACKUPS_ENV, value
+                ))
+            })?;
+            Resolved::new(count, Source::Env(BACKUPS_ENV))
+        }
+        _ => config
+            .lookup(|f| f.backups)
+            .unwrap_or_else(|| Resolved::new(0, Source::Default)),
+    };
+
+    if cli_backup && configured.value == 0 {
+        return Ok(Resolved::new(DEFAULT_BACKUPS, Source::Cli));
+    }
+    Ok(configured)
+}
+
+fn parse_format(value: &str, origin: &str) -> Result<OutputFormat, SkitError> {
+    OutputFormat::from_str(value, true).map_err(|_| {
+        SkitError::ParseError(format!(
+            "Invalid output format '{}' in {}. Use table, json, yaml, toml, env, terraform, postman or k8s-secret",
+            value, origin
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_config(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_later_files_take_precedence() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let global = write_config(
+            temp_dir.path(),
+            "config.toml",
+            "default_safe = \"proj\"\ndefault_format = \"json\"\nauto_remember = true\n",
+        );
+        let local = write_config(temp_dir.path(), ".skit.toml", "default_format = \"yaml\"\n");
+        let missing = temp_dir.path().join("missing.toml");
+        let config = Config::load_from(&[global.clone(), local.clone(), missing]).unwrap();
+
+        assert_eq!(config.files().count(), 2);
+        assert_eq!(
+            config.safe(None, temp_dir.path()),
+            Resolved::new("proj".to_string(), Source::File(global.clone()))
+        );
+        assert_eq!(
+            config.safe(Some("other"), temp_dir.path()),
+            Resolved::new("other".to_string(), Source::Cli)
+        );
+        assert_eq!(
+            config.auto_remember(),
+            Resolved::new(true, Source::File(global))
+        );
+        assert_eq!(config.no_color(), Resolved::new(false, Source::Default));
+
+        let format = config
+            .lookup(|f| f.default_format.clone())
+            .expect("format should be configured");
+        assert_eq!(format.value, "yaml");
+        assert_eq!(format.source, Source::File(local));
+
+        let cli = resolve_format(Some(&OutputFormat::Env), &config).unwrap();
+        assert!(matches!(cli.value, OutputFormat::Env));
+        assert_eq!(cli.source, Source::Cli);
+    }
+
+    #[test]
+    fn test_directory_mappings() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+        let global = write_config(
+            root,
+            "config.toml",
+            &format!(
+                "default_safe = \"fallback\"\n[directories]\n\"{0}\" = \"work\"\n\"{0}/api\" = \"api\"\n",
+                root.display()
+            ),
+        );
+        let config = Config::load_from(&[global]).unwrap();
+
+        let api = config.safe(None, &root.join("api").join("src"));
+        assert_eq!(
+            api.value,
+            root.join("api").join(".api.safe").display().to_string()
+        );
+        assert!(matches!(api.source, Source::Directory { .. }));
+        assert_eq!(
+            config.safe(None, &root.join("web")).value,
+            root.join(".work.safe").display().to_string()
+        );
+        assert_eq!(config.safe(None, Path::new("/elsewhere")).value, "fallback");
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let typo = write_config(temp_dir.path(), "typo.toml", "default_fromat = \"json\"\n");
+        assert!(Config::load_from(&[typo]).is_err());
+
+        let bad_format = write_config(temp_dir.path(), "bad.toml", "default_format = \"xml\"\n");
+        let config = Config::load_from(&[bad_format]).unwrap();
+        assert!(resolve_format(None, &config).is_err());
+    }
+
+    #[test]
+    fn test_discover_safe_walks_up_from_cwd() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+        let nested = root.join("services").join("api");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join(".env.safe"), "").unwrap();
+
+        assert_eq!(
+            discover_safe(".env.safe", &nested).unwrap(),
+            root.join(".env.safe")
+        );
+
+        // The nearest match wins
+        fs::write(nested.join(".env.safe"), "").unwrap();
+        assert_eq!(
+            discover_safe(".env.safe", &nested).unwrap(),
+            nested.join(".env.safe")
+        );
+
+        let tried = discover_safe(".missing.safe", &nested).unwrap_err();
+        assert_eq!(tried[0], nested.join(".missing.safe"));
+        assert!(tried.contains(&root.join(".missing.safe")));
+    }
+
+    #[test]
+    fn test_backups_setting() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file = write_config(temp_dir.path(), "config.toml", "backups = 5\n");
+        let configured = Config::load_from(std::slice::from_ref(&file)).unwrap();
+        let empty = Config::default();
+
+        assert_eq!(
+            resolve_backups(false, &empty).unwrap(),
+            Resolved::new(0, Source::Default)
+        );
+        assert_eq!(
+            resolve_backups(true, &empty).unwrap(),
+            Resolved::new(DEFAULT_BACKUPS, Source::Cli)
+        );
+        assert_eq!(
+            resolve_backups(true, &configured).unwrap(),
+            Resolved::new(5, Source::File(file))
+        );
+    }
+}
@@ -1,11 +1,15 @@
-use aes_gcm::aead::{Aead, OsRng, rand_core::RngCore};
+use aes_gcm::aead::{Aead, OsRng, Payload, rand_core::RngCore};
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use argon2::{Algorithm, Params, Version};
 use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier, password_hash::SaltString};
 use base64::{Engine as _, engine::general_purpose};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use sha2::{Digest, Sha256};
 use std::error::Error;
 use std::fmt;
-use zeroize::Zeroize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use zeroize::{Zeroize, Zeroizing};
 
 #[derive(Debug)]
 pub enum CryptoError {
@@ -30,14 +34,69 @@ impl fmt::Display for CryptoError {
 
 impl Error for CryptoError {}
 
+/// Argon2id cost parameters used to derive per-value encryption keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub time_cost: u32,
+    pub lanes: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: ARGON2_MEMORY_KIB,
+            time_cost: ARGON2_TIME_COST,
+            lanes: ARGON2_LANES,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Check the parameters against the limits Argon2 accepts and skit's own caps, so a tampered
+    /// safe header can't make every decryption allocate gigabytes or spin for minutes
+    pub fn validate(&self) -> Result<(), String> {
+        for (field, value, max) in [
+            ("memory cost", self.memory_kib, MAX_KDF_MEMORY_KIB),
+            ("time cost", self.time_cost, MAX_KDF_TIME_COST),
+            ("lanes", self.lanes, MAX_KDF_LANES),
+        ] {
+            if value > max {
+                return Err(format!(
+                    "Invalid KDF parameters: {} {} exceeds the maximum of {}",
+                    field, value, max
+                ));
+            }
+        }
+        self.to_argon2_params()
+            .map(|_| ())
+            .map_err(|e| format!("Invalid KDF parameters: {}", e))
+    }
+
+    fn to_argon2_params(self) -> Result<Params, argon2::Error> {
+        Params::new(self.memory_kib, self.time_cost, self.lanes, None)
+    }
+}
+
+/// Encrypts one value, e.g. `EncryptBuilder::new().plaintext(v).context(key).password(p).encrypt()`
+#[derive(Default)]
 pub struct EncryptBuilder<'a> {
     password: Option<&'a str>,
     plaintext: Option<&'a str>,
+    kdf_params: KdfParams,
+    cipher: Cipher,
+    master_key: Option<&'a str>,
+    context: Option<&'a str>,
 }
 
+/// Decrypts one value stored by [`EncryptBuilder`] with the same context and password
+#[derive(Default)]
 pub struct DecryptBuilder<'a> {
     password: Option<&'a str>,
     ciphertext: Option<&'a str>,
+    kdf_params: KdfParams,
+    master_key: Option<&'a str>,
+    context: Option<&'a str>,
 }
 
 impl<'a> EncryptBuilder<'a> {
@@ -45,6 +104,10 @@ impl<'a> EncryptBuilder<'a> {
         Self {
             password: None,
             plaintext: None,
+            kdf_params: KdfParams::default(),
+            cipher: Cipher::default(),
+            master_key: None,
+            context: None,
         }
     }
 
@@ -58,11 +121,48 @@ impl<'a> EncryptBuilder<'a> {
         self
     }
 
+    pub fn kdf_params(mut self, kdf_params: KdfParams) -> Self {
+        self.kdf_params = kdf_params;
+        self
+    }
+
+    pub fn cipher(mut self, cipher: Cipher) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Wrapped safe master key (`#@MASTER_KEY`). When set, values are encrypted
+    /// under the master key instead of deriving a key from the password per value.
+    pub fn master_key(mut self, wrapped: Option<&'a str>) -> Self {
+        self.master_key = wrapped;
+        self
+    }
+
+    /// Name of the key the value is stored under. The ciphertext is bound to it as
+    /// associated data, so it no longer decrypts if moved to another key.
+    pub fn context(mut self, key: &'a str) -> Self {
+        self.context = Some(key);
+        self
+    }
+
     pub fn encrypt(self) -> Result<String, CryptoError> {
         let password = self.password.ok_or(CryptoError::EncryptionFailed)?;
         let plaintext = self.plaintext.ok_or(CryptoError::EncryptionFailed)?;
 
-        encrypt_value_with_salt(password, plaintext)
+        match self.master_key {
+            Some(wrapped) => {
+                let key = unwrap_master_key(wrapped, password, &self.kdf_params)
+                    .map_err(|_| CryptoError::EncryptionFailed)?;
+                encrypt_value_v2(&key, plaintext, self.cipher, self.context)
+            }
+            None => encrypt_value_with_salt(
+                password,
+                plaintext,
+                &self.kdf_params,
+                self.cipher,
+                self.context,
+            ),
+        }
     }
 }
 
@@ -71,6 +171,9 @@ impl<'a> DecryptBuilder<'a> {
         Self {
             password: None,
             ciphertext: None,
+            kdf_params: KdfParams::default(),
+            master_key: None,
+            context: None,
         }
     }
 
@@ -84,20 +187,52 @@ impl<'a> DecryptBuilder<'a> {
         self
     }
 
+    pub fn kdf_params(mut self, kdf_params: KdfParams) -> Self {
+        self.kdf_params = kdf_params;
+        self
+    }
+
+    /// Wrapped safe master key (`#@MASTER_KEY`), needed for `ENC~v3~` values
+    pub fn master_key(mut self, wrapped: Option<&'a str>) -> Self {
+        self.master_key = wrapped;
+        self
+    }
+
+    /// Name of the key the value is stored under, needed for key-bound values.
+    /// Older values that are not bound to a key ignore it.
+    pub fn context(mut self, key: &'a str) -> Self {
+        self.context = Some(key);
+        self
+    }
+
     pub fn decrypt(self) -> Result<String, CryptoError> {
         let password = self.password.ok_or(CryptoError::DecryptionFailed)?;
         let ciphertext = self.ciphertext.ok_or(CryptoError::DecryptionFailed)?;
 
-        decrypt_value_with_salt(ciphertext, password)
+        decrypt_value(
+            ciphertext,
+            password,
+            &self.kdf_params,
+            self.master_key,
+            self.context,
+        )
     }
 }
 
 const ARGON2_MEMORY_KIB: u32 = 64 * 1024; // 64 MiB
 const ARGON2_TIME_COST: u32 = 3;
 const ARGON2_LANES: u32 = 1;
-
-fn argon2id_derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], CryptoError> {
-    let params = Params::new(ARGON2_MEMORY_KIB, ARGON2_TIME_COST, ARGON2_LANES, None)
+const MAX_KDF_MEMORY_KIB: u32 = 4 * 1024 * 1024; // 4 GiB
+const MAX_KDF_TIME_COST: u32 = 64;
+const MAX_KDF_LANES: u32 = 16;
+
+fn argon2id_derive_key(
+    password: &str,
+    salt: &[u8],
+    kdf_params: &KdfParams,
+) -> Result<[u8; 32], CryptoError> {
+    let params = kdf_params
+        .to_argon2_params()
         .map_err(|_| CryptoError::EncryptionFailed)?;
     let a2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
 
@@ -107,59 +242,440 @@ fn argon2id_derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], CryptoEr
     Ok(key)
 }
 
-pub fn encrypt_value_with_salt(password: &str, plaintext: &str) -> Result<String, CryptoError> {
-    // Random 16-byte salt and 12-byte nonce
+/// Value cipher. The choice is recorded in the ciphertext prefix so decryption
+/// never needs to be told which one was used. A `k` after the version (`ENC~v1k~`)
+/// marks values bound to their key name as associated data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Cipher {
+    /// AES-256-GCM, emitted as `ENC~v1~`
+    #[default]
+    Aes256Gcm,
+    /// XChaCha20-Poly1305, emitted as `ENC~v2~`, for hosts without AES acceleration
+    XChaCha20,
+}
+
+/// Associated data for a value: the key name for key-bound values, nothing for older ones.
+/// Decrypting a key-bound value without knowing its key fails.
+fn associated_data(bound: bool, context: Option<&str>) -> Result<&[u8], CryptoError> {
+    match (bound, context) {
+        (false, _) => Ok(&[]),
+        (true, Some(key)) => Ok(key.as_bytes()),
+        (true, None) => Err(CryptoError::DecryptionFailed),
+    }
+}
+
+impl Cipher {
+    /// Name used in the `#@CIPHER` safe header and on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Cipher::Aes256Gcm => "aes256gcm",
+            Cipher::XChaCha20 => "xchacha20",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Cipher::Aes256Gcm, Cipher::XChaCha20]
+            .into_iter()
+            .find(|cipher| cipher.name() == name)
+    }
+
+    fn prefix(self, bound: bool) -> &'static str {
+        match (self, bound) {
+            (Cipher::Aes256Gcm, false) => "ENC~v1~",
+            (Cipher::Aes256Gcm, true) => "ENC~v1k~",
+            (Cipher::XChaCha20, false) => "ENC~v2~",
+            (Cipher::XChaCha20, true) => "ENC~v2k~",
+        }
+    }
+
+    /// Identifier byte at the start of `ENC~v3~` blobs
+    fn id(self) -> u8 {
+        match self {
+            Cipher::Aes256Gcm => 0,
+            Cipher::XChaCha20 => 1,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        [Cipher::Aes256Gcm, Cipher::XChaCha20]
+            .into_iter()
+            .find(|cipher| cipher.id() == id)
+    }
+
+    fn nonce_len(self) -> usize {
+        match self {
+            Cipher::Aes256Gcm => 12,
+            Cipher::XChaCha20 => 24,
+        }
+    }
+
+    /// Split a stored value into its cipher, whether it is key-bound, and the base64 blob
+    fn from_value(enc: &str) -> Option<(Self, bool, &str)> {
+        [Cipher::Aes256Gcm, Cipher::XChaCha20]
+            .into_iter()
+            .flat_map(|cipher| [(cipher, false), (cipher, true)])
+            .find_map(|(cipher, bound)| {
+                enc.strip_prefix(cipher.prefix(bound))
+                    .map(|b64| (cipher, bound, b64))
+            })
+    }
+
+    fn seal(
+        self,
+        key: &[u8],
+        nonce: &[u8],
+        plaintext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+        let result = match self {
+            Cipher::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .map_err(|_| CryptoError::EncryptionFailed)?
+                .encrypt(Nonce::from_slice(nonce), payload),
+            Cipher::XChaCha20 => XChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| CryptoError::EncryptionFailed)?
+                .encrypt(XNonce::from_slice(nonce), payload),
+        };
+        result.map_err(|_| CryptoError::EncryptionFailed)
+    }
+
+    fn open(
+        self,
+        key: &[u8],
+        nonce: &[u8],
+        ciphertext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        let result = match self {
+            Cipher::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .map_err(|_| CryptoError::DecryptionFailed)?
+                .decrypt(Nonce::from_slice(nonce), payload),
+            Cipher::XChaCha20 => XChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| CryptoError::DecryptionFailed)?
+                .decrypt(XNonce::from_slice(nonce), payload),
+        };
+        result.map_err(|_| CryptoError::DecryptionFailed)
+    }
+}
+
+/// Encrypt a value under a key derived from the password. With a `context` (the key name)
+/// the value is key-bound; without one it uses the older unbound format.
+pub fn encrypt_value_with_salt(
+    password: &str,
+    plaintext: &str,
+    kdf_params: &KdfParams,
+    cipher: Cipher,
+    context: Option<&str>,
+) -> Result<String, CryptoError> {
+    // Random 16-byte salt and a cipher-sized nonce
     let mut salt = [0u8; 16];
-    let mut nonce_bytes = [0u8; 12];
+    let mut nonce_bytes = vec![0u8; cipher.nonce_len()];
     OsRng.fill_bytes(&mut salt);
     OsRng.fill_bytes(&mut nonce_bytes);
 
-    let mut key = argon2id_derive_key(password, &salt)?;
-    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| CryptoError::EncryptionFailed)?;
-    let nonce = Nonce::from_slice(&nonce_bytes);
-
-    let ct = cipher
-        .encrypt(nonce, plaintext.as_bytes())
-        .map_err(|_| CryptoError::EncryptionFailed)?;
-
+    let mut key = argon2id_derive_key(password, &salt, kdf_params)?;
+    let aad = context.map(str::as_bytes).unwrap_or_default();
+    let ct = cipher.seal(&key, &nonce_bytes, plaintext.as_bytes(), aad);
     key.zeroize();
+    let ct = ct?;
 
     // package: salt || nonce || ciphertext+tag
-    let mut blob = Vec::with_capacity(16 + 12 + ct.len());
+    let mut blob = Vec::with_capacity(16 + nonce_bytes.len() + ct.len());
     blob.extend_from_slice(&salt);
     blob.extend_from_slice(&nonce_bytes);
     blob.extend_from_slice(&ct);
 
-    Ok(format!("ENC~v1~{}", general_purpose::STANDARD.encode(blob)))
+    Ok(format!(
+        "{}{}",
+        cipher.prefix(context.is_some()),
+        general_purpose::STANDARD.encode(blob)
+    ))
 }
 
-pub fn decrypt_value_with_salt(enc: &str, password: &str) -> Result<String, CryptoError> {
-    if !enc.starts_with("ENC~v1~") {
-        return Err(CryptoError::InvalidFormat);
-    }
-    let b64 = &enc[7..];
+/// Decrypt a password-derived value. `context` is the key name, needed for key-bound values.
+pub fn decrypt_value_with_salt(
+    enc: &str,
+    password: &str,
+    kdf_params: &KdfParams,
+    context: Option<&str>,
+) -> Result<String, CryptoError> {
+    let (cipher, bound, b64) = Cipher::from_value(enc).ok_or(CryptoError::InvalidFormat)?;
+    let aad = associated_data(bound, context)?;
     let data = general_purpose::STANDARD
         .decode(b64)
         .map_err(|_| CryptoError::InvalidFormat)?;
-    if data.len() < 16 + 12 + 16 {
+    let nonce_len = cipher.nonce_len();
+    if data.len() < 16 + nonce_len + 16 {
         return Err(CryptoError::InvalidFormat);
     }
 
     let (salt, rest) = data.split_at(16);
-    let (nonce_bytes, ciphertext) = rest.split_at(12);
-
-    let mut key = argon2id_derive_key(password, salt).map_err(|_| CryptoError::DecryptionFailed)?;
-    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| CryptoError::DecryptionFailed)?;
-    let nonce = Nonce::from_slice(nonce_bytes);
+    let (nonce_bytes, ciphertext) = rest.split_at(nonce_len);
 
-    let pt = cipher
-        .decrypt(nonce, ciphertext)
+    let mut key = argon2id_derive_key(password, salt, kdf_params)
         .map_err(|_| CryptoError::DecryptionFailed)?;
-
+    let pt = cipher.open(&key, nonce_bytes, ciphertext, aad);
     key.zeroize();
+
+    String::from_utf8(pt?).map_err(|_| CryptoError::DecryptionFailed)
+}
+
+/// Prefix for values encrypted under a safe master key
+const MASTER_KEY_PREFIX: &str = "ENC~v3~";
+/// Prefix for master-key values bound to their key name
+const MASTER_KEY_BOUND_PREFIX: &str = "ENC~v3k~";
+
+/// Whether a stored value is encrypted under the safe master key rather than the password
+pub fn is_master_key_value(enc: &str) -> bool {
+    enc.starts_with(MASTER_KEY_PREFIX) || enc.starts_with(MASTER_KEY_BOUND_PREFIX)
+}
+
+/// Whether a stored value is bound to its key name, so moving it to another key needs
+/// re-encryption
+pub fn is_key_bound(enc: &str) -> bool {
+    enc.starts_with(MASTER_KEY_BOUND_PREFIX)
+        || Cipher::from_value(enc).is_some_and(|(_, bound, _)| bound)
+}
+
+/// The cipher a stored value was encrypted with, or `None` if it isn't an encrypted value
+pub fn value_cipher(enc: &str) -> Option<Cipher> {
+    let master_key_blob = enc
+        .strip_prefix(MASTER_KEY_BOUND_PREFIX)
+        .or_else(|| enc.strip_prefix(MASTER_KEY_PREFIX));
+    match master_key_blob {
+        Some(b64) => {
+            let data = general_purpose::STANDARD.decode(b64).ok()?;
+            Cipher::from_id(*data.first()?)
+        }
+        None => Cipher::from_value(enc).map(|(cipher, _, _)| cipher),
+    }
+}
+
+/// Generate a random 256-bit safe master key
+pub fn generate_master_key() -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(key.as_mut());
+    key
+}
+
+/// Encrypt a master key under the safe password for storage in `#@MASTER_KEY`
+pub fn wrap_master_key(
+    master_key: &[u8; 32],
+    password: &str,
+    kdf_params: &KdfParams,
+    cipher: Cipher,
+) -> Result<String, CryptoError> {
+    let encoded = Zeroizing::new(general_purpose::STANDARD.encode(master_key));
+    encrypt_value_with_salt(password, &encoded, kdf_params, cipher, None)
+}
+
+/// Unwrapped master keys, keyed by a digest of (wrapped key, password). Unwrapping
+/// costs a full Argon2 derivation, so each safe pays it once per process.
+type CachedMasterKey = ([u8; 32], Zeroizing<[u8; 32]>);
+static MASTER_KEY_CACHE: Mutex<Vec<CachedMasterKey>> = Mutex::new(Vec::new());
+
+fn master_key_cache_id(wrapped: &str, password: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(wrapped.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(password.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Decrypt a wrapped master key with the safe password
+pub fn unwrap_master_key(
+    wrapped: &str,
+    password: &str,
+    kdf_params: &KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, CryptoError> {
+    let cache_id = master_key_cache_id(wrapped, password);
+    if let Ok(cache) = MASTER_KEY_CACHE.lock()
+        && let Some((_, key)) = cache.iter().find(|(id, _)| *id == cache_id)
+    {
+        return Ok(key.clone());
+    }
+
+    let encoded = Zeroizing::new(decrypt_value_with_salt(
+        wrapped, password, kdf_params, None,
+    )?);
+    let decoded = Zeroizing::new(
+        general_purpose::STANDARD
+            .decode(encoded.as_bytes())
+            .map_err(|_| CryptoError::InvalidFormat)?,
+    );
+    let key: [u8; 32] = decoded
+        .as_slice()
+        .try_into()
+        .map_err(|_| CryptoError::InvalidFormat)?;
+    let key = Zeroizing::new(key);
+
+    if let Ok(mut cache) = MASTER_KEY_CACHE.lock() {
+        cache.push((cache_id, key.clone()));
+    }
+    Ok(key)
+}
+
+/// Encrypt a value under the safe master key with a fresh nonce, bound to `context` if given
+pub fn encrypt_value_v2(
+    master_key: &[u8; 32],
+    plaintext: &str,
+    cipher: Cipher,
+    context: Option<&str>,
+) -> Result<String, CryptoError> {
+    let mut nonce_bytes = vec![0u8; cipher.nonce_len()];
+    OsRng.fill_bytes(&mut nonce_bytes);
+
+    let aad = context.map(str::as_bytes).unwrap_or_default();
+    let ct = cipher.seal(master_key, &nonce_bytes, plaintext.as_bytes(), aad)?;
+
+    // package: cipher id || nonce || ciphertext+tag
+    let mut blob = Vec::with_capacity(1 + nonce_bytes.len() + ct.len());
+    blob.push(cipher.id());
+    blob.extend_from_slice(&nonce_bytes);
+    blob.extend_from_slice(&ct);
+
+    let prefix = if context.is_some() {
+        MASTER_KEY_BOUND_PREFIX
+    } else {
+        MASTER_KEY_PREFIX
+    };
+    Ok(format!(
+        "{}{}",
+        prefix,
+        general_purpose::STANDARD.encode(blob)
+    ))
+}
+
+/// Decrypt an `ENC~v3~` value with the safe master key. `context` is the key name, needed
+/// for key-bound values.
+pub fn decrypt_value_v2(
+    enc: &str,
+    master_key: &[u8; 32],
+    context: Option<&str>,
+) -> Result<String, CryptoError> {
+    let (bound, b64) = match enc.strip_prefix(MASTER_KEY_BOUND_PREFIX) {
+        Some(b64) => (true, b64),
+        None => (
+            false,
+            enc.strip_prefix(MASTER_KEY_PREFIX)
+                .ok_or(CryptoError::InvalidFormat)?,
+        ),
+    };
+    let aad = associated_data(bound, context)?;
+    let data = general_purpose::STANDARD
+        .decode(b64)
+        .map_err(|_| CryptoError::InvalidFormat)?;
+
+    let (&id, rest) = data.split_first().ok_or(CryptoError::InvalidFormat)?;
+    let cipher = Cipher::from_id(id).ok_or(CryptoError::InvalidFormat)?;
+    let nonce_len = cipher.nonce_len();
+    if rest.len() < nonce_len + 16 {
+        return Err(CryptoError::InvalidFormat);
+    }
+
+    let (nonce_bytes, ciphertext) = rest.split_at(nonce_len);
+    let pt = cipher.open(master_key, nonce_bytes, ciphertext, aad)?;
     String::from_utf8(pt).map_err(|_| CryptoError::DecryptionFailed)
 }
 
+/// Decrypt any stored value format: master-key values need the wrapped master key,
+/// older values derive their key from the password.
+fn decrypt_value(
+    enc: &str,
+    password: &str,
+    kdf_params: &KdfParams,
+    master_key: Option<&str>,
+    context: Option<&str>,
+) -> Result<String, CryptoError> {
+    if is_master_key_value(enc) {
+        let wrapped = master_key.ok_or(CryptoError::DecryptionFailed)?;
+        let key = unwrap_master_key(wrapped, password, kdf_params)
+            .map_err(|_| CryptoError::DecryptionFailed)?;
+        decrypt_value_v2(enc, &key, context)
+    } else {
+        decrypt_value_with_salt(enc, password, kdf_params, context)
+    }
+}
+
+/// Upper bound on concurrent Argon2 derivations. Each one holds `memory_kib` of
+/// RAM (64 MiB by default), so this also bounds peak memory use.
+const MAX_PARALLEL_DECRYPTS: usize = 8;
+
+/// Decrypt many `(key, ciphertext)` pairs concurrently, returning one result per pair in
+/// input order
+pub fn decrypt_many(
+    ciphertexts: &[(&str, &str)],
+    password: &str,
+    kdf_params: &KdfParams,
+    master_key: Option<&str>,
+) -> Vec<Result<String, CryptoError>> {
+    // Unwrap the master key once up front so workers don't race to derive it
+    if let Some(wrapped) = master_key
+        && ciphertexts.iter().any(|(_, ct)| is_master_key_value(ct))
+    {
+        let _ = unwrap_master_key(wrapped, password, kdf_params);
+    }
+
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_PARALLEL_DECRYPTS)
+        .min(ciphertexts.len());
+
+    if workers <= 1 {
+        return ciphertexts
+            .iter()
+            .map(|(key, ct)| decrypt_value(ct, password, kdf_params, master_key, Some(key)))
+            .collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<Result<String, CryptoError>>> =
+        (0..ciphertexts.len()).map(|_| None).collect();
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((key, ct)) = ciphertexts.get(index) else {
+                            break;
+                        };
+                        done.push((
+                            index,
+                            decrypt_value(ct, password, kdf_params, master_key, Some(key)),
+                        ));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let done = handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for (index, result) in done {
+                results[index] = Some(result);
+            }
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.unwrap_or(Err(CryptoError::DecryptionFailed)))
+        .collect()
+}
+
 pub fn hash_password(password: &str) -> Result<String, CryptoError> {
     let salt = SaltString::generate(&mut OsRng);
     let argon2 = Argon2::default();
@@ -170,6 +686,11 @@ pub fn hash_password(password: &str) -> Result<String, CryptoError> {
         .map_err(|_| CryptoError::PasswordHashFailed)
 }
 
+/// Whether a stored password hash is well-formed, without checking any password against it
+pub fn is_valid_password_hash(hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok()
+}
+
 pub fn verify_password(password: &str, hash: &str) -> Result<(), CryptoError> {
     let parsed_hash = PasswordHash::new(hash).map_err(|_| CryptoError::InvalidFormat)?;
 
@@ -177,3 +698,231 @@ pub fn verify_password(password: &str, hash: &str) -> Result<(), CryptoError> {
         .verify_password(password.as_bytes(), &parsed_hash)
         .map_err(|_| CryptoError::PasswordVerificationFailed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    // Cheap parameters keep the tests fast; the parallel path is the same
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 1024,
+        time_cost: 1,
+        lanes: 1,
+    };
+
+    #[test]
+    fn test_decrypt_many_preserves_order_and_reports_failures() {
+        let mut ciphertexts: Vec<String> = (0..32)
+            .map(|i| {
+                encrypt_value_with_salt(
+                    "pw",
+                    &format!("value-{}", i),
+                    &TEST_KDF,
+                    Cipher::default(),
+                    None,
+                )
+                .unwrap()
+            })
+            .collect();
+        ciphertexts[5] =
+            encrypt_value_with_salt("other", "nope", &TEST_KDF, Cipher::default(), None).unwrap();
+        ciphertexts[17] = "ENC~v1~garbage".to_string();
+
+        let refs: Vec<(&str, &str)> = ciphertexts.iter().map(|ct| ("KEY", ct.as_str())).collect();
+        let results = decrypt_many(&refs, "pw", &TEST_KDF, None);
+
+        assert_eq!(results.len(), 32);
+        for (i, result) in results.iter().enumerate() {
+            match i {
+                5 | 17 => assert!(result.is_err(), "item {} should fail", i),
+                _ => assert_eq!(result.as_ref().unwrap(), &format!("value-{}", i)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_xchacha20_round_trip_and_prefix() {
+        let enc = EncryptBuilder::new()
+            .password("pw")
+            .plaintext("secret")
+            .kdf_params(TEST_KDF)
+            .cipher(Cipher::XChaCha20)
+            .encrypt()
+            .unwrap();
+        assert!(enc.starts_with("ENC~v2~"));
+
+        let dec = DecryptBuilder::new()
+            .password("pw")
+            .ciphertext(&enc)
+            .kdf_params(TEST_KDF)
+            .decrypt()
+            .unwrap();
+        assert_eq!(dec, "secret");
+        assert!(decrypt_value_with_salt(&enc, "wrong", &TEST_KDF, None).is_err());
+    }
+
+    #[test]
+    fn test_mixed_cipher_values_decrypt_and_are_detected() {
+        let master_key = generate_master_key();
+        let wrapped = wrap_master_key(&master_key, "pw", &TEST_KDF, Cipher::default()).unwrap();
+        let mut values = Vec::new();
+        for cipher in [Cipher::Aes256Gcm, Cipher::XChaCha20] {
+            let per_value =
+                encrypt_value_with_salt("pw", "secret", &TEST_KDF, cipher, None).unwrap();
+            let under_master_key =
+                encrypt_value_v2(&master_key, "secret", cipher, Some("KEY")).unwrap();
+            assert_eq!(value_cipher(&per_value), Some(cipher));
+            assert_eq!(value_cipher(&under_master_key), Some(cipher));
+            values.push(per_value);
+            values.push(under_master_key);
+        }
+        assert_eq!(value_cipher("plain"), None);
+
+        let refs: Vec<(&str, &str)> = values.iter().map(|v| ("KEY", v.as_str())).collect();
+        for result in decrypt_many(&refs, "pw", &TEST_KDF, Some(&wrapped)) {
+            assert_eq!(result.unwrap(), "secret");
+        }
+    }
+
+    #[test]
+    fn test_aes_gcm_remains_default() {
+        let enc =
+            encrypt_value_with_salt("pw", "secret", &TEST_KDF, Cipher::default(), None).unwrap();
+        assert!(enc.starts_with("ENC~v1~"));
+        assert_eq!(
+            decrypt_value_with_salt(&enc, "pw", &TEST_KDF, None).unwrap(),
+            "secret"
+        );
+    }
+
+    #[test]
+    fn test_master_key_values_round_trip() {
+        let master_key = generate_master_key();
+        let wrapped = wrap_master_key(&master_key, "pw", &TEST_KDF, Cipher::default()).unwrap();
+
+        for cipher in [Cipher::Aes256Gcm, Cipher::XChaCha20] {
+            let enc = EncryptBuilder::new()
+                .password("pw")
+                .plaintext("secret")
+                .kdf_params(TEST_KDF)
+                .master_key(Some(&wrapped))
+                .cipher(cipher)
+                .encrypt()
+                .unwrap();
+            assert!(is_master_key_value(&enc));
+
+            let dec = DecryptBuilder::new()
+                .password("pw")
+                .ciphertext(&enc)
+                .kdf_params(TEST_KDF)
+                .master_key(Some(&wrapped))
+                .decrypt()
+                .unwrap();
+            assert_eq!(dec, "secret");
+
+            // Wrong password can't unwrap the master key; missing master key can't decrypt
+            let wrong = DecryptBuilder::new()
+                .password("other")
+                .ciphertext(&enc)
+                .kdf_params(TEST_KDF)
+                .master_key(Some(&wrapped))
+                .decrypt();
+            assert!(wrong.is_err());
+            assert!(decrypt_value(&enc, "pw", &TEST_KDF, None, None).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rewrapped_master_key_still_decrypts_values() {
+        let master_key = generate_master_key();
+        let wrapped = wrap_master_key(&master_key, "old", &TEST_KDF, Cipher::default()).unwrap();
+        let enc = encrypt_value_v2(&master_key, "secret", Cipher::default(), None).unwrap();
+
+        let unwrapped = unwrap_master_key(&wrapped, "old", &TEST_KDF).unwrap();
+        let rewrapped = wrap_master_key(&unwrapped, "new", &TEST_KDF, Cipher::default()).unwrap();
+
+        let results = decrypt_many(&[("KEY", &enc)], "new", &TEST_KDF, Some(&rewrapped));
+        assert_eq!(results[0].as_ref().unwrap(), "secret");
+    }
+
+    #[test]
+    fn test_key_bound_values_only_decrypt_under_their_key() {
+        let master_key = generate_master_key();
+        let wrapped = wrap_master_key(&master_key, "pw", &TEST_KDF, Cipher::default()).unwrap();
+
+        for (cipher, wrapped) in [
+            (Cipher::Aes256Gcm, None),
+            (Cipher::XChaCha20, None),
+            (Cipher::Aes256Gcm, Some(wrapped.as_str())),
+        ] {
+            let enc = EncryptBuilder::new()
+                .password("pw")
+                .plaintext("secret")
+                .kdf_params(TEST_KDF)
+                .master_key(wrapped)
+                .cipher(cipher)
+                .context("PROD_DB_URL")
+                .encrypt()
+                .unwrap();
+            assert!(is_key_bound(&enc), "{}", enc);
+
+            let decrypt =
+                |context: Option<&str>| decrypt_value(&enc, "pw", &TEST_KDF, wrapped, context);
+            assert_eq!(decrypt(Some("PROD_DB_URL")).unwrap(), "secret");
+            assert!(decrypt(Some("STAGING_DB_URL")).is_err());
+            assert!(decrypt(None).is_err());
+        }
+
+        // Unbound values decrypt under any key
+        let legacy =
+            encrypt_value_with_salt("pw", "secret", &TEST_KDF, Cipher::default(), None).unwrap();
+        assert!(!is_key_bound(&legacy));
+        let results = decrypt_many(&[("ANY_KEY", &legacy)], "pw", &TEST_KDF, None);
+        assert_eq!(results[0].as_ref().unwrap(), "secret");
+    }
+
+    #[test]
+    fn test_decrypt_many_empty() {
+        assert!(decrypt_many(&[], "pw", &TEST_KDF, None).is_empty());
+    }
+
+    /// Timing comparison on a large safe: `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_decrypt_many_speedup() {
+        let kdf = KdfParams::default();
+        let ciphertexts: Vec<String> = (0..120)
+            .map(|i| {
+                encrypt_value_with_salt(
+                    "pw",
+                    &format!("value-{}", i),
+                    &kdf,
+                    Cipher::default(),
+                    None,
+                )
+                .unwrap()
+            })
+            .collect();
+        let refs: Vec<(&str, &str)> = ciphertexts.iter().map(|ct| ("KEY", ct.as_str())).collect();
+
+        let start = Instant::now();
+        for (_, ct) in &refs {
+            decrypt_value_with_salt(ct, "pw", &kdf, None).unwrap();
+        }
+        let sequential = start.elapsed();
+
+        let start = Instant::now();
+        let results = decrypt_many(&refs, "pw", &kdf, None);
+        let parallel = start.elapsed();
+
+        assert!(results.iter().all(Result::is_ok));
+        println!(
+            "{} items: sequential {:?}, parallel {:?} ({:.1}x)",
+            refs.len(),
+            sequential,
+            parallel,
+            sequential.as_secs_f64() / parallel.as_secs_f64()
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use crate::error::SkitError;
+use crate::types::ItemMetadata;
+use std::collections::HashMap;
 
 // Legacy print functions that now use tracing
 // These are kept for backward compatibility but redirect to tracing macros
@@ -59,7 +61,8 @@ pub fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
     lines
 }
 
-pub fn print_grouped(items: &[(String, String, bool)]) {
+/// Print items grouped by type. Metadata found for a key is shown under its value.
+pub fn print_grouped(items: &[(String, String, bool)], metadata: &HashMap<String, ItemMetadata>) {
     if items.is_empty() {
         println!("No items in safe");
         return;
@@ -91,6 +94,9 @@ pub fn print_grouped(items: &[(String, String, bool)]) {
                     println!("{}{}{}", line_prefix, bullet, line);
                 }
             }
+            if let Some(meta) = metadata.get(key) {
+                print_item_metadata(meta, is_last);
+            }
         }
         if !plain_items.is_empty() {
             println!();
@@ -114,61 +120,109 @@ pub fn print_grouped(items: &[(String, String, bool)]) {
                     println!("{}{}{}", line_prefix, bullet, line);
                 }
             }
+            if let Some(meta) = metadata.get(key) {
+                print_item_metadata(meta, is_last);
+            }
         }
     }
 }
 
-pub fn print_keys_table(items: &[(String, bool)]) {
+fn print_item_metadata(metadata: &ItemMetadata, is_last: bool) {
+    let line_prefix = if is_last { "    " } else { "│   " };
+    if let Some(ref description) = metadata.description {
+        println!("{}  description: {}", line_prefix, description);
+    }
+    if !metadata.tags.is_empty() {
+        println!("{}  tags: {}", line_prefix, metadata.tags.join(", "));
+    }
+    if let Some(ref created) = metadata.created {
+        println!("{}  created: {}", line_prefix, created);
+    }
+    if let Some(ref updated) = metadata.updated {
+        println!("{}  updated: {}", line_prefix, updated);
+    }
+}
+
+/// Table of keys and their types, with a Size column when sizes are given
+pub fn print_keys_table(items: &[(String, bool, Option<usize>)]) {
     if items.is_empty() {
         println!("No keys in safe");
         return;
     }
 
-    let key_width = items.iter().map(|(k, _)| k.len()).max().unwrap_or(3).max(3);
-    let type_width = 4; // "Type" header width
-
-    println!(
-        "{:-<width$}-+-{:-<twidth$}-",
-        "",
-        "",
-        width = key_width,
-        twidth = type_width
-    );
+    let key_width = items
+        .iter()
+        .map(|(k, _, _)| k.len())
+        .max()
+        .unwrap_or(3)
+        .max(3);
+    let type_width = 5; // "PLAIN" width
+    let show_size = items.iter().any(|(_, _, size)| size.is_some());
+    let size_width = items
+        .iter()
+        .filter_map(|(_, _, size)| size.map(|s| s.to_string().len()))
+        .max()
+        .unwrap_or(0)
+        .max(4); // "Size" header width
+
+    let separator = || {
+        let mut line = format!(
+            "{:-<width$}-+-{:-<twidth$}-",
+            "",
+            "",
+            width = key_width,
+            twidth = type_width
+        );
+        if show_size {
+            line.push_str(&format!("+-{:-<swidth$}-", "", swidth = size_width));
+        }
+        println!("{}", line);
+    };
 
-    println!(
+    separator();
+    let mut header = format!(
         " {:^width$} | {:^twidth$} ",
         "Key",
         "Type",
         width = key_width,
         twidth = type_width
     );
+    if show_size {
+        header.push_str(&format!("| {:^swidth$} ", "Size", swidth = size_width));
+    }
+    println!("{}", header);
+    separator();
 
-    println!(
-        "{:-<width$}-+-{:-<twidth$}-",
-        "",
-        "",
-        width = key_width,
-        twidth = type_width
-    );
-
-    for (key, is_encrypted) in items {
+    for (key, is_encrypted, size) in items {
         let type_str = if *is_encrypted { "ENC" } else { "PLAIN" };
-        println!(
+        let mut row = format!(
             " {:width$} | {:^twidth$} ",
             key,
             type_str,
             width = key_width,
             twidth = type_width
         );
+        if let Some(size) = size {
+            row.push_str(&format!("| {:>swidth$} ", size, swidth = size_width));
+        }
+        println!("{}", row);
     }
 
-    println!(
-        "{:-<width$}-+-{:-<twidth$}-",
-        "",
-        "",
-        width = key_width,
-        twidth = type_width
-    );
+    separator();
+}
+
+/// Escape line breaks and tabs so a value stays on a single table row
+pub fn single_line(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 pub fn wrap_with_quotes(value: &str) -> String {
@@ -229,6 +283,71 @@ pub fn format_json_output<T: serde::Serialize>(data: &T) -> Result<String, SkitE
         .map_err(|e| SkitError::ParseError(format!("JSON serialization error: {}", e)))
 }
 
+pub fn format_yaml_output<T: serde::Serialize>(data: &T) -> Result<String, SkitError> {
+    serde_yaml::to_string(data)
+        .map_err(|e| SkitError::ParseError(format!("YAML serialization error: {}", e)))
+}
+
+/// Render secrets as a TOML document of string values. Keys containing `/` (as pulled from
+/// SSM) become nested tables, `app/db/url` giving `url` under `[app.db]`. A nested key that
+/// would clash with a plain key or another table stays a flat quoted key instead, whatever
+/// order the keys come in.
+pub fn format_toml_output(items: &[(String, String)]) -> Result<String, SkitError> {
+    let mut root = toml::Table::new();
+    let (flat, nested): (Vec<_>, Vec<_>) = items
+        .iter()
+        .map(|(key, value)| {
+            let segments: Vec<&str> = key.split('/').filter(|s| !s.is_empty()).collect();
+            (key, value, segments)
+        })
+        .partition(|(_, _, segments)| segments.len() <= 1);
+
+    // Plain keys first, so a table never takes a name a plain key needs
+    for (key, value, _) in flat {
+        root.insert(key.clone(), toml::Value::String(value.clone()));
+    }
+    for (key, value, segments) in nested {
+        if !insert_nested(&mut root, &segments, value) {
+            // Keys are unique and this one contains `/`, so it can't clash
+            root.insert(key.clone(), toml::Value::String(value.clone()));
+        }
+    }
+
+    toml::to_string(&root)
+        .map_err(|e| SkitError::ParseError(format!("TOML serialization error: {}", e)))
+}
+
+/// Insert `value` under nested tables named by `segments`, returning false on a clash
+fn insert_nested(root: &mut toml::Table, segments: &[&str], value: &str) -> bool {
+    let (last, tables) = segments.split_last().expect("at least two segments");
+
+    // Check the whole path first so a clash leaves no empty tables behind
+    let mut existing = Some(&*root);
+    for segment in tables {
+        existing = match existing.and_then(|table| table.get(*segment)) {
+            Some(toml::Value::Table(inner)) => Some(inner),
+            Some(_) => return false,
+            None => None,
+        };
+    }
+    if existing.is_some_and(|table| table.contains_key(*last)) {
+        return false;
+    }
+
+    let mut table = root;
+    for segment in tables {
+        table = match table
+            .entry(segment.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        {
+            toml::Value::Table(inner) => inner,
+            _ => unreachable!("checked above"),
+        };
+    }
+    table.insert(last.to_string(), toml::Value::String(value.to_string()));
+    true
+}
+
 pub fn print_terraform_output(items: &[(String, String, bool)]) {
     if items.is_empty() {
         println!("No items in safe");
@@ -244,6 +363,46 @@ pub fn print_terraform_output(items: &[(String, String, bool)]) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_toml_output_nests_and_escapes() {
+        let items = vec![
+            ("API_KEY".to_string(), "say \"hi\"\nbye".to_string()),
+            ("app/db/url".to_string(), "postgres://db".to_string()),
+            ("app/db/user".to_string(), "admin".to_string()),
+            ("PORT".to_string(), "3000".to_string()),
+            ("PORT/extra".to_string(), "x".to_string()),
+        ];
+        let output = format_toml_output(&items).unwrap();
+
+        let parsed: toml::Table = toml::from_str(&output).unwrap();
+        assert_eq!(parsed["API_KEY"].as_str(), Some("say \"hi\"\nbye"));
+        assert_eq!(parsed["app"]["db"]["url"].as_str(), Some("postgres://db"));
+        assert_eq!(parsed["app"]["db"]["user"].as_str(), Some("admin"));
+        assert_eq!(parsed["PORT"].as_str(), Some("3000"));
+        assert_eq!(parsed["PORT/extra"].as_str(), Some("x"));
+        assert!(output.contains("[app.db]"));
+    }
+
+    #[test]
+    fn test_format_toml_output_plain_key_after_table_of_same_name() {
+        // Insertion order can put the nested key first
+        let items = vec![
+            ("app/db".to_string(), "nested".to_string()),
+            ("app".to_string(), "plain".to_string()),
+        ];
+        let output = format_toml_output(&items).unwrap();
+
+        let parsed: toml::Table = toml::from_str(&output).unwrap();
+        assert_eq!(parsed["app"].as_str(), Some("plain"));
+        assert_eq!(parsed["app/db"].as_str(), Some("nested"));
+    }
+
+    #[test]
+    fn test_single_line_escapes_line_breaks() {
+        assert_eq!(single_line("a\r\nb\tc"), "a\\r\\nb\\tc");
+        assert_eq!(single_line("C:\\path"), "C:\\path");
+    }
+
     #[test]
     fn test_wrap_text_short() {
         let result = wrap_text("short text", 100);
@@ -276,6 +435,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_format_yaml_output_empty() {
+        assert_eq!(
+            format_yaml_output(&serde_yaml::Mapping::new()).unwrap(),
+            "{}\n"
+        );
+        assert_eq!(format_yaml_output(&Vec::<String>::new()).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn test_format_yaml_output_round_trips_special_values() {
+        let mut mapping = serde_yaml::Mapping::new();
+        mapping.insert("PEM".into(), "-----BEGIN KEY-----\nabc: def\n".into());
+        mapping.insert("KEY: #1".into(), "- 'quoted' \"value\"".into());
+        mapping.insert("EMPTY".into(), "".into());
+
+        let yaml = format_yaml_output(&mapping).unwrap();
+        let parsed: serde_yaml::Mapping = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, mapping);
+    }
+
     #[test]
     fn test_wrap_text_exact_length() {
         let text = format!("exactly100chars{}", "0".repeat(85)); // 15 + 85 = 100 chars
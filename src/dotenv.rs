@@ -0,0 +1,122 @@
+/// Quote a value for a `KEY=value` line if it would not survive a raw round-trip.
+///
+/// Values containing newlines, surrounding whitespace, or a leading quote are written as
+/// double-quoted strings with `\n`, `\r`, `\t`, `\"` and `\\` escapes. So are plain values
+/// starting with `ENC~`, which would otherwise be read back as encrypted, and values with a
+/// `#` that dotenv parsers would read as the start of a comment. Everything else is
+/// written verbatim so existing single-line safes keep their exact format.
+pub fn quote_value(value: &str) -> String {
+    let needs_quoting = value.contains('\n')
+        || value.contains('\r')
+        || value.starts_with('"')
+        || value.starts_with('\'')
+        || value.starts_with("ENC~")
+        || value.starts_with('#')
+        || value.contains(" #")
+        || value.contains("\t#")
+        || value.trim() != value;
+
+    if !needs_quoting {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Find the byte index of the first unescaped `"` after the opening quote
+pub fn find_closing_quote(value: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in value.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Decode a double-quoted value produced by `quote_value`.
+///
+/// Returns `None` if the value is not a single well-formed double-quoted string, in which
+/// case callers should treat it as a raw value.
+pub fn unquote_value(value: &str) -> Option<String> {
+    if !value.starts_with('"') || find_closing_quote(value)? != value.len() - 1 {
+        return None;
+    }
+
+    let inner = &value[1..value.len() - 1];
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unquoted.push('\n'),
+            Some('r') => unquoted.push('\r'),
+            Some('t') => unquoted.push('\t'),
+            Some('"') => unquoted.push('"'),
+            Some('\\') => unquoted.push('\\'),
+            Some(other) => {
+                unquoted.push('\\');
+                unquoted.push(other);
+            }
+            None => unquoted.push('\\'),
+        }
+    }
+
+    Some(unquoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_values_are_not_quoted() {
+        assert_eq!(quote_value("simple"), "simple");
+        assert_eq!(quote_value("a=b#c"), "a=b#c");
+        assert_eq!(quote_value("say \"hi\""), "say \"hi\"");
+    }
+
+    #[test]
+    fn test_quote_round_trip() {
+        for value in [
+            "line1\nline2",
+            "  padded  ",
+            "\"quoted\"",
+            "'single'",
+            "back\\slash\nnext",
+            "tab\there\r\n",
+            "ENC~v1~abc",
+            "value # not a comment",
+            "#hash",
+        ] {
+            let quoted = quote_value(value);
+            assert_eq!(unquote_value(&quoted).as_deref(), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_unquote_rejects_malformed() {
+        assert_eq!(unquote_value("plain"), None);
+        assert_eq!(unquote_value("\"open"), None);
+        assert_eq!(unquote_value("\"a\" \"b\""), None);
+    }
+}
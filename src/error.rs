@@ -8,12 +8,25 @@ pub enum SkitError {
     Io(io::Error),
     Crypto(crypto::CryptoError),
     SerdeJson(serde_json::Error),
-    KeyNotFound,
+    /// The requested key, and the closest existing key if one looks like a typo
+    KeyNotFound {
+        key: String,
+        suggestion: Option<String>,
+    },
     SafeNotFound(String),
     InvalidPassword(String),
     EmptyCommand,
     ParseError(String),
     AwsError(String),
+    /// Google Cloud Secret Manager, or running `gcloud`, failed
+    GcpError(String),
+    /// HashiCorp Vault, or running `vault`, failed
+    VaultError(String),
+    InputRequired(String),
+    /// `--strict` was given and these keys could not be decrypted
+    DecryptionFailed(Vec<String>),
+    /// An integrity check failed; the command's own output already lists what failed
+    VerificationFailed(String),
 }
 
 impl fmt::Display for SkitError {
@@ -22,16 +35,82 @@ impl fmt::Display for SkitError {
             SkitError::Io(e) => write!(f, "IO error: {}", e),
             SkitError::Crypto(e) => write!(f, "Crypto error: {}", e),
             SkitError::SerdeJson(e) => write!(f, "JSON serialization error: {}", e),
-            SkitError::KeyNotFound => write!(f, "Key not found in safe"),
+            SkitError::KeyNotFound { key, suggestion } => {
+                write!(f, "Key '{}' not found in safe", key)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean '{}'?)", suggestion)?;
+                }
+                Ok(())
+            }
             SkitError::SafeNotFound(path) => write!(f, "Safe not found: {}", path),
             SkitError::InvalidPassword(msg) => write!(f, "{}", msg),
             SkitError::EmptyCommand => write!(f, "No command provided to execute"),
             SkitError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             SkitError::AwsError(msg) => write!(f, "AWS error: {}", msg),
+            SkitError::GcpError(msg) => write!(f, "GCP error: {}", msg),
+            SkitError::VaultError(msg) => write!(f, "Vault error: {}", msg),
+            SkitError::InputRequired(hint) => {
+                write!(f, "Input required but prompts are disabled: {}", hint)
+            }
+            SkitError::DecryptionFailed(keys) => {
+                write!(
+                    f,
+                    "Failed to decrypt {} key(s): {}",
+                    keys.len(),
+                    keys.join(", ")
+                )
+            }
+            SkitError::VerificationFailed(msg) => write!(f, "{}", msg),
         }
     }
 }
 
+impl SkitError {
+    /// Process exit code, so scripts can tell failure reasons apart without parsing messages.
+    /// These values are part of the CLI's interface; don't renumber them.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SkitError::SafeNotFound(_) => 2,
+            SkitError::KeyNotFound { .. } => 3,
+            SkitError::InvalidPassword(_) => 4,
+            SkitError::AwsError(_) | SkitError::GcpError(_) | SkitError::VaultError(_) => 5,
+            SkitError::ParseError(_) => 6,
+            SkitError::Crypto(_) | SkitError::DecryptionFailed(_) => 7,
+            _ => 1,
+        }
+    }
+
+    /// Stable machine-readable name of the error, used in JSON error output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SkitError::Io(_) => "io",
+            SkitError::Crypto(_) => "crypto",
+            SkitError::SerdeJson(_) => "serialization",
+            SkitError::KeyNotFound { .. } => "key_not_found",
+            SkitError::SafeNotFound(_) => "safe_not_found",
+            SkitError::InvalidPassword(_) => "invalid_password",
+            SkitError::EmptyCommand => "empty_command",
+            SkitError::ParseError(_) => "parse_error",
+            SkitError::AwsError(_) => "aws_error",
+            SkitError::GcpError(_) => "gcp_error",
+            SkitError::VaultError(_) => "vault_error",
+            SkitError::InputRequired(_) => "input_required",
+            SkitError::DecryptionFailed(_) => "decryption_failed",
+            SkitError::VerificationFailed(_) => "verification_failed",
+        }
+    }
+
+    /// `{"error": {"kind": ..., "message": ...}}`, printed instead of a log line for `--format json`
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": {
+                "kind": self.kind(),
+                "message": self.to_string(),
+            }
+        })
+    }
+}
+
 impl Error for SkitError {}
 
 impl From<io::Error> for SkitError {
@@ -51,3 +130,45 @@ impl From<serde_json::Error> for SkitError {
         SkitError::SerdeJson(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let errors = [
+            SkitError::EmptyCommand,
+            SkitError::SafeNotFound("x".to_string()),
+            SkitError::KeyNotFound {
+                key: "x".to_string(),
+                suggestion: None,
+            },
+            SkitError::InvalidPassword("x".to_string()),
+            SkitError::AwsError("x".to_string()),
+            SkitError::ParseError("x".to_string()),
+            SkitError::DecryptionFailed(vec!["x".to_string()]),
+        ];
+        let codes: Vec<i32> = errors.iter().map(SkitError::exit_code).collect();
+        assert_eq!(codes, [1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_key_not_found_message() {
+        let error = SkitError::KeyNotFound {
+            key: "API_KYE".to_string(),
+            suggestion: Some("API_KEY".to_string()),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Key 'API_KYE' not found in safe (did you mean 'API_KEY'?)"
+        );
+    }
+
+    #[test]
+    fn test_json_error_shape() {
+        let json = SkitError::SafeNotFound(".env.safe".to_string()).to_json();
+        assert_eq!(json["error"]["kind"], "safe_not_found");
+        assert_eq!(json["error"]["message"], "Safe not found: .env.safe");
+    }
+}
@@ -1,15 +1,15 @@
 use crate::error::SkitError;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
-use std::path::Path;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 /// Securely create and write a secret file.
 /// - Fails if the file already exists.
-/// - Creates the file with 0o600 permissions on Unix.
+/// - Creates the file with 0o600 permissions on Unix, or an owner-only ACL on Windows.
 /// - Refuses to operate on symlinks.
 pub fn write_secret_file_secure(path: &Path, contents: &str) -> Result<(), SkitError> {
-    // Ensure parent directory exists and is not a symlink
-    if let Some(parent) = path.parent() {
+    // Ensure parent directory exists and is not a symlink (a bare file name has an empty parent)
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
         fs::create_dir_all(parent).map_err(SkitError::Io)?;
         let meta = fs::symlink_metadata(parent).map_err(SkitError::Io)?;
         if !meta.is_dir() {
@@ -50,15 +50,704 @@ pub fn write_secret_file_secure(path: &Path, contents: &str) -> Result<(), SkitE
 
     #[cfg(not(unix))]
     {
-        // Note: On Windows, secure file permissions (mode 0o600) are not set
-        // The file will be created with default Windows ACLs
         let mut file = OpenOptions::new()
             .create_new(true)
             .write(true)
             .open(path)
             .map_err(SkitError::Io)?;
+        // Lock the file down before any secret is written to it
+        #[cfg(windows)]
+        restrict_to_owner(path)?;
         file.write_all(contents.as_bytes()).map_err(SkitError::Io)?;
         file.flush().map_err(SkitError::Io)?;
         Ok(())
     }
 }
+
+/// Write a secret file that may already exist, replacing or appending to it.
+/// - Creates the file with 0o600 permissions on Unix and tightens an existing file to 0o600.
+/// - On Windows the file's ACL is replaced with an owner-only one.
+/// - Refuses to operate on symlinks, both the target and its parent directory.
+pub fn write_secret_file_overwrite(
+    path: &Path,
+    contents: &str,
+    append: bool,
+) -> Result<(), SkitError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        let meta = fs::symlink_metadata(parent).map_err(SkitError::Io)?;
+        if meta.file_type().is_symlink() {
+            return Err(SkitError::Io(std::io::Error::other(format!(
+                "Refusing to write through symlinked directory: {}",
+                parent.display()
+            ))));
+        }
+    }
+
+    let mut options = OpenOptions::new();
+    options.create(true).write(true);
+    if append {
+        options.append(true);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        // O_NOFOLLOW refuses a symlink at open time, so it can't be swapped in after a check;
+        // O_NONBLOCK keeps a FIFO planted at the path from blocking before the fstat below
+        options
+            .mode(0o600)
+            .custom_flags(libc::O_NOFOLLOW | libc::O_NONBLOCK);
+        let mut file = options.open(path).map_err(|e| {
+            if e.raw_os_error() == Some(libc::ELOOP) {
+                SkitError::Io(std::io::Error::other(format!(
+                    "Refusing to write through symlink: {}",
+                    path.display()
+                )))
+            } else {
+                SkitError::Io(e)
+            }
+        })?;
+        // Checked on the open handle, so it describes the file actually being written
+        if !file.metadata().map_err(SkitError::Io)?.is_file() {
+            return Err(SkitError::Io(std::io::Error::other(format!(
+                "Not a regular file: {}",
+                path.display()
+            ))));
+        }
+        // The mode only applies to newly created files
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .map_err(SkitError::Io)?;
+        if !append {
+            file.set_len(0).map_err(SkitError::Io)?;
+        }
+        file.write_all(contents.as_bytes()).map_err(SkitError::Io)?;
+        file.flush().map_err(SkitError::Io)?;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    {
+        if let Ok(meta) = fs::symlink_metadata(path) {
+            if meta.file_type().is_symlink() {
+                return Err(SkitError::Io(std::io::Error::other(format!(
+                    "Refusing to write through symlink: {}",
+                    path.display()
+                ))));
+            }
+            if !meta.is_file() {
+                return Err(SkitError::Io(std::io::Error::other(format!(
+                    "Not a regular file: {}",
+                    path.display()
+                ))));
+            }
+        }
+        if !append {
+            options.truncate(true);
+        }
+        let mut file = options.open(path).map_err(SkitError::Io)?;
+        #[cfg(windows)]
+        restrict_to_owner(path)?;
+        file.write_all(contents.as_bytes()).map_err(SkitError::Io)?;
+        file.flush().map_err(SkitError::Io)?;
+        Ok(())
+    }
+}
+
+/// Create a directory and any missing parents. On Unix the directories created here are
+/// accessible to the owner only (0o700); existing ones are left alone.
+pub fn create_private_dir(path: &Path) -> Result<(), SkitError> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(path).map_err(SkitError::Io)
+}
+
+/// Atomically replace a file with new contents.
+/// - Writes to a temporary file in the same directory, fsyncs it, then renames it over the target.
+/// - Preserves the permissions of an existing target file.
+/// - On failure the original file is left untouched and the temporary file is removed.
+pub fn write_file_atomic(path: &Path, contents: &str) -> Result<(), SkitError> {
+    write_file_atomic_with(path, None, |file| file.write_all(contents.as_bytes()))
+}
+
+/// Atomically write a file holding secrets, replacing any existing file.
+/// - Same temp-file-plus-rename write as `write_file_atomic`.
+/// - The result always has 0o600 permissions on Unix (an owner-only ACL on Windows),
+///   whatever the previous file had.
+pub fn write_secret_file_atomic(path: &Path, contents: &str) -> Result<(), SkitError> {
+    write_file_atomic_with(path, Some(0o600), |file| {
+        file.write_all(contents.as_bytes())
+    })
+}
+
+fn write_file_atomic_with<F>(path: &Path, mode: Option<u32>, write: F) -> Result<(), SkitError>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    // Write through symlinks to the real file rather than replacing the link
+    let target = match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            fs::canonicalize(path).map_err(SkitError::Io)?
+        }
+        _ => path.to_path_buf(),
+    };
+
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let file_name = target.file_name().and_then(|n| n.to_str()).ok_or_else(|| {
+        SkitError::Io(io::Error::other(format!(
+            "Invalid file path: {}",
+            path.display()
+        )))
+    })?;
+    let temp_path = dir.join(format!(".{}.tmp-{}", file_name, uuid::Uuid::new_v4()));
+
+    let existing_permissions = match mode {
+        Some(_) => None,
+        None => fs::metadata(&target).ok().map(|meta| meta.permissions()),
+    };
+
+    let result = (|| -> io::Result<()> {
+        let mut options = OpenOptions::new();
+        options.create_new(true).write(true);
+        #[cfg(unix)]
+        if let Some(mode) = mode {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(mode);
+        }
+        let mut file = options.open(&temp_path)?;
+        if let Some(permissions) = existing_permissions {
+            file.set_permissions(permissions)?;
+        }
+        #[cfg(windows)]
+        if mode.is_some() {
+            windows_acl::restrict_to_owner(&temp_path)?;
+        }
+        write(&mut file)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temp_path, &target)
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(SkitError::Io(e));
+    }
+
+    // Persist the rename itself; not all platforms support syncing directories
+    #[cfg(unix)]
+    if let Ok(dir_handle) = File::open(&dir) {
+        let _ = dir_handle.sync_all();
+    }
+
+    Ok(())
+}
+
+/// Absolute form of `path` for display, with symlinks and `..` resolved when it exists
+pub fn display_path(path: &Path) -> String {
+    fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+/// Path of the `n`th numbered backup of `path`, e.g. `.env.safe.bak.1`
+pub fn numbered_backup_path(path: &Path, n: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".bak.{}", n));
+    PathBuf::from(name)
+}
+
+/// Copy `path` to `<path>.bak.1` before it gets overwritten, keeping at most `keep` backups.
+/// - Older backups move up one number and any numbered `keep` or higher are removed first.
+/// - Does nothing when `keep` is 0 or the file doesn't exist yet.
+/// - Backups keep the permissions of the original file.
+pub fn rotate_backups(path: &Path, keep: u32) -> Result<(), SkitError> {
+    if keep == 0 || !path.exists() {
+        return Ok(());
+    }
+
+    // Also clears backups left over from a larger `keep`
+    let mut n = keep;
+    while numbered_backup_path(path, n).exists() {
+        fs::remove_file(numbered_backup_path(path, n)).map_err(SkitError::Io)?;
+        n += 1;
+    }
+    for n in (1..keep).rev() {
+        let from = numbered_backup_path(path, n);
+        if from.exists() {
+            fs::rename(&from, numbered_backup_path(path, n + 1)).map_err(SkitError::Io)?;
+        }
+    }
+
+    fs::copy(path, numbered_backup_path(path, 1)).map_err(SkitError::Io)?;
+    Ok(())
+}
+
+/// Replace the DACL of a file with a single entry granting the current user full access.
+/// Inherited entries are dropped, so other users (including members of Everyone or Users) lose access.
+#[cfg(windows)]
+pub fn restrict_to_owner(path: &Path) -> Result<(), SkitError> {
+    windows_acl::restrict_to_owner(path).map_err(|e| {
+        SkitError::Io(io::Error::new(
+            e.kind(),
+            format!(
+                "Failed to restrict permissions on {}: {}",
+                path.display(),
+                e
+            ),
+        ))
+    })
+}
+
+/// Whether only the current user, SYSTEM and Administrators are granted access to a file.
+#[cfg(windows)]
+pub fn is_owner_only(path: &Path) -> Result<bool, SkitError> {
+    windows_acl::is_owner_only(path).map_err(|e| {
+        SkitError::Io(io::Error::new(
+            e.kind(),
+            format!("Failed to read permissions of {}: {}", path.display(), e),
+        ))
+    })
+}
+
+#[cfg(windows)]
+pub(crate) mod windows_acl {
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::{CloseHandle, ERROR_SUCCESS, HANDLE, LocalFree};
+    use windows_sys::Win32::Security::Authorization::{
+        EXPLICIT_ACCESS_W, GetNamedSecurityInfoW, NO_MULTIPLE_TRUSTEE, SE_FILE_OBJECT, SET_ACCESS,
+        SetEntriesInAclW, SetNamedSecurityInfoW, TRUSTEE_IS_SID, TRUSTEE_IS_USER, TRUSTEE_W,
+    };
+    use windows_sys::Win32::Security::{
+        ACCESS_ALLOWED_ACE, ACE_HEADER, ACL, ACL_SIZE_INFORMATION, AclSizeInformation,
+        DACL_SECURITY_INFORMATION, EqualSid, GetAce, GetAclInformation, GetTokenInformation,
+        IsWellKnownSid, NO_INHERITANCE, PROTECTED_DACL_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR,
+        PSID, TOKEN_QUERY, TOKEN_USER, TokenUser, WinBuiltinAdministratorsSid, WinLocalSystemSid,
+    };
+    use windows_sys::Win32::Storage::FileSystem::FILE_ALL_ACCESS;
+    use windows_sys::Win32::System::SystemServices::ACCESS_ALLOWED_ACE_TYPE;
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    fn wide(path: &Path) -> Vec<u16> {
+        path.as_os_str().encode_wide().chain(Some(0)).collect()
+    }
+
+    fn win32_error(code: u32) -> io::Error {
+        io::Error::from_raw_os_error(code as i32)
+    }
+
+    /// The TOKEN_USER of a process; the user SID points into this buffer
+    struct ProcessUser {
+        buffer: Vec<u64>,
+    }
+
+    impl ProcessUser {
+        fn query() -> io::Result<Self> {
+            Self::of_process(unsafe { GetCurrentProcess() })
+        }
+
+        fn of_process(process: HANDLE) -> io::Result<Self> {
+            unsafe {
+                let mut token: HANDLE = ptr::null_mut();
+                if OpenProcessToken(process, TOKEN_QUERY, &mut token) == 0 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                let mut len = 0u32;
+                GetTokenInformation(token, TokenUser, ptr::null_mut(), 0, &mut len);
+                // u64 elements keep the buffer aligned for TOKEN_USER
+                let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+                let ok = GetTokenInformation(
+                    token,
+                    TokenUser,
+                    buffer.as_mut_ptr().cast(),
+                    len,
+                    &mut len,
+                );
+                let err = io::Error::last_os_error();
+                CloseHandle(token);
+                if ok == 0 {
+                    return Err(err);
+                }
+                Ok(Self { buffer })
+            }
+        }
+
+        fn sid(&self) -> PSID {
+            unsafe { (*self.buffer.as_ptr().cast::<TOKEN_USER>()).User.Sid }
+        }
+    }
+
+    /// A DACL granting the current user full access and nobody else anything; freed on drop
+    pub(crate) struct OwnerOnlyAcl(*mut ACL);
+
+    impl OwnerOnlyAcl {
+        pub(crate) fn new() -> io::Result<Self> {
+            let user = ProcessUser::query()?;
+            let access = EXPLICIT_ACCESS_W {
+                grfAccessPermissions: FILE_ALL_ACCESS,
+                grfAccessMode: SET_ACCESS,
+                grfInheritance: NO_INHERITANCE,
+                Trustee: TRUSTEE_W {
+                    pMultipleTrustee: ptr::null_mut(),
+                    MultipleTrusteeOperation: NO_MULTIPLE_TRUSTEE,
+                    TrusteeForm: TRUSTEE_IS_SID,
+                    TrusteeType: TRUSTEE_IS_USER,
+                    ptstrName: user.sid().cast(),
+                },
+            };
+
+            // The entry's SID is copied into the new ACL, so `user` can go
+            let mut acl: *mut ACL = ptr::null_mut();
+            let status = unsafe { SetEntriesInAclW(1, &access, ptr::null(), &mut acl) };
+            if status != ERROR_SUCCESS {
+                return Err(win32_error(status));
+            }
+            Ok(Self(acl))
+        }
+
+        pub(crate) fn as_ptr(&self) -> *mut ACL {
+            self.0
+        }
+    }
+
+    impl Drop for OwnerOnlyAcl {
+        fn drop(&mut self) {
+            unsafe { LocalFree(self.0.cast()) };
+        }
+    }
+
+    pub fn restrict_to_owner(path: &Path) -> io::Result<()> {
+        let acl = OwnerOnlyAcl::new()?;
+        let path = wide(path);
+
+        // A protected DACL stops entries being inherited from the parent directory
+        let status = unsafe {
+            SetNamedSecurityInfoW(
+                path.as_ptr(),
+                SE_FILE_OBJECT,
+                DACL_SECURITY_INFORMATION | PROTECTED_DACL_SECURITY_INFORMATION,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                acl.as_ptr(),
+                ptr::null(),
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return Err(win32_error(status));
+        }
+        Ok(())
+    }
+
+    /// Whether the process with this ID runs as the current user
+    pub(crate) fn is_current_user_process(process_id: u32) -> io::Result<bool> {
+        let user = ProcessUser::query()?;
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+            if process.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let other = ProcessUser::of_process(process);
+            CloseHandle(process);
+            Ok(EqualSid(user.sid(), other?.sid()) != 0)
+        }
+    }
+
+    pub fn is_owner_only(path: &Path) -> io::Result<bool> {
+        let user = ProcessUser::query()?;
+        let path = wide(path);
+
+        unsafe {
+            let mut dacl: *mut ACL = ptr::null_mut();
+            let mut descriptor: PSECURITY_DESCRIPTOR = ptr::null_mut();
+            let status = GetNamedSecurityInfoW(
+                path.as_ptr(),
+                SE_FILE_OBJECT,
+                DACL_SECURITY_INFORMATION,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut dacl,
+                ptr::null_mut(),
+                &mut descriptor,
+            );
+            if status != ERROR_SUCCESS {
+                return Err(win32_error(status));
+            }
+
+            let result = dacl_is_owner_only(dacl, user.sid());
+            LocalFree(descriptor);
+            result
+        }
+    }
+
+    unsafe fn dacl_is_owner_only(dacl: *const ACL, user: PSID) -> io::Result<bool> {
+        // A null DACL grants everyone full access
+        if dacl.is_null() {
+            return Ok(false);
+        }
+
+        unsafe {
+            let mut info = ACL_SIZE_INFORMATION {
+                AceCount: 0,
+                AclBytesInUse: 0,
+                AclBytesFree: 0,
+            };
+            if GetAclInformation(
+                dacl,
+                (&mut info as *mut ACL_SIZE_INFORMATION).cast(),
+                size_of::<ACL_SIZE_INFORMATION>() as u32,
+                AclSizeInformation,
+            ) == 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+
+            for index in 0..info.AceCount {
+                let mut ace = ptr::null_mut();
+                if GetAce(dacl, index, &mut ace) == 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let header = &*ace.cast::<ACE_HEADER>();
+                if u32::from(header.AceType) != ACCESS_ALLOWED_ACE_TYPE {
+                    continue;
+                }
+
+                let allowed = ace.cast::<ACCESS_ALLOWED_ACE>();
+                let sid: PSID = ptr::addr_of_mut!((*allowed).SidStart).cast();
+                let trusted = EqualSid(sid, user) != 0
+                    || IsWellKnownSid(sid, WinLocalSystemSid) != 0
+                    || IsWellKnownSid(sid, WinBuiltinAdministratorsSid) != 0;
+                if !trusted {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_file_atomic_replaces_content() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        fs::write(&path, "old").unwrap();
+
+        write_file_atomic(&path, "new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_rotate_backups_shifts_and_prunes() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let backup = |n| fs::read_to_string(numbered_backup_path(&path, n)).ok();
+
+        // Nothing to back up yet
+        rotate_backups(&path, 3).unwrap();
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        for version in ["v1", "v2", "v3", "v4", "v5"] {
+            rotate_backups(&path, 3).unwrap();
+            fs::write(&path, version).unwrap();
+        }
+        assert_eq!(backup(1).as_deref(), Some("v4"));
+        assert_eq!(backup(2).as_deref(), Some("v3"));
+        assert_eq!(backup(3).as_deref(), Some("v2"));
+        assert_eq!(backup(4), None);
+
+        // Lowering the limit prunes the extra backups on the next save
+        rotate_backups(&path, 1).unwrap();
+        assert_eq!(backup(1).as_deref(), Some("v5"));
+        assert_eq!(backup(2), None);
+        assert_eq!(backup(3), None);
+
+        rotate_backups(&path, 0).unwrap();
+        assert_eq!(backup(1).as_deref(), Some("v5"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rotate_backups_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        fs::write(&path, "secret").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        rotate_backups(&path, 2).unwrap();
+
+        let mode = fs::metadata(numbered_backup_path(&path, 1))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_write_failure_keeps_original() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        fs::write(&path, "original").unwrap();
+
+        let result = write_file_atomic_with(&path, None, |file| {
+            file.write_all(b"partial")?;
+            Err(io::Error::other("simulated disk full"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_only_directory_keeps_original() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        fs::write(&path, "original").unwrap();
+        fs::set_permissions(temp_dir.path(), fs::Permissions::from_mode(0o555)).unwrap();
+
+        // Privileged users can still write to read-only directories
+        let probe = temp_dir.path().join("probe");
+        let writable = File::create(&probe).is_ok();
+        let _ = fs::remove_file(&probe);
+
+        let result = write_file_atomic(&path, "new");
+        fs::set_permissions(temp_dir.path(), fs::Permissions::from_mode(0o755)).unwrap();
+
+        if !writable {
+            assert!(result.is_err());
+        }
+        let expected = if writable { "new" } else { "original" };
+        assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_file_atomic_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_file_atomic(&path, "new").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_secret_file_atomic_restricts_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_secret_file_atomic(&path, "KEY=value\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "KEY=value\n");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_secret_file_overwrite_appends_and_restricts_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env");
+        fs::write(&path, "A=1\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_secret_file_overwrite(&path, "B=2\n", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "A=1\nB=2\n");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        write_secret_file_overwrite(&path, "C=3\n", false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "C=3\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_secret_file_overwrite_refuses_symlink() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let target = temp_dir.path().join("target");
+        fs::write(&target, "original").unwrap();
+        let link = temp_dir.path().join(".env");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert!(write_secret_file_overwrite(&link, "KEY=value\n", false).is_err());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "original");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_secret_file_overwrite_refuses_fifo() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        // Returns instead of blocking on a reader
+        assert!(write_secret_file_overwrite(&path, "KEY=value\n", false).is_err());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_secret_file_gets_owner_only_acl() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("safe.key");
+
+        write_secret_file_secure(&path, "secret").unwrap();
+
+        assert!(is_owner_only(&path).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_restrict_to_owner_keeps_file_readable() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env");
+        fs::write(&path, "KEY=value").unwrap();
+
+        restrict_to_owner(&path).unwrap();
+        write_secret_file_overwrite(&path, "KEY=other", false).unwrap();
+
+        assert!(is_owner_only(&path).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "KEY=other");
+    }
+}
@@ -0,0 +1,169 @@
+//! Google Cloud Secret Manager, read through the `gcloud` CLI so that its login and
+//! application-default credentials are used as-is.
+
+use crate::display::print_warning;
+use crate::error::SkitError;
+use crate::validation::{check_key_collisions, is_valid_env_key};
+use std::process::Command;
+
+/// Overrides the `gcloud` executable, e.g. with a full path
+pub const GCLOUD_ENV: &str = "SKIT_GCLOUD";
+
+/// A value pulled from Google Secret Manager, keyed by its safe key name
+#[derive(Debug, Clone, PartialEq)]
+pub struct GcpSecret {
+    pub key: String,
+    pub value: String,
+}
+
+fn gcloud(args: &[&str]) -> Result<Vec<u8>, SkitError> {
+    let program = std::env::var(GCLOUD_ENV).unwrap_or_else(|_| "gcloud".to_string());
+    let output = Command::new(&program)
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => SkitError::GcpError(format!(
+                "'{}' not found. Install the Google Cloud CLI or set {} to its path",
+                program, GCLOUD_ENV
+            )),
+            _ => SkitError::GcpError(format!("Failed to run {}: {}", program, e)),
+        })?;
+
+    if !output.status.success() {
+        return Err(SkitError::GcpError(format!(
+            "gcloud {} failed: {}",
+            args[..2].join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Short secret names from `gcloud secrets list --format=json`, whose `name` fields are full
+/// resource paths (`projects/<number>/secrets/<name>`)
+fn parse_secret_names(json: &[u8]) -> Result<Vec<String>, SkitError> {
+    let entries: Vec<serde_json::Value> = serde_json::from_slice(json).map_err(|e| {
+        SkitError::GcpError(format!("Unexpected output from gcloud secrets list: {}", e))
+    })?;
+    Ok(entries
+        .iter()
+        .filter_map(|entry| entry["name"].as_str())
+        .map(|name| name.rsplit('/').next().unwrap_or(name).to_string())
+        .collect())
+}
+
+/// Safe key for a secret: the name without `prefix`, with `-` (allowed in secret names but not
+/// in env keys) turned into `_`
+fn secret_key(name: &str, prefix: &str) -> String {
+    name.strip_prefix(prefix).unwrap_or(name).replace('-', "_")
+}
+
+/// Pair each secret name with its safe key, skipping (with a warning) names that don't map to a
+/// valid key. Fails if two names map to the same key, e.g. `app-db` and `app_db`.
+fn keyed_names(names: Vec<String>, prefix: &str) -> Result<Vec<(String, String)>, SkitError> {
+    let mut keyed = Vec::new();
+    for name in names {
+        let key = secret_key(&name, prefix);
+        if !is_valid_env_key(&key) {
+            print_warning(&format!(
+                "Skipping secret '{}': '{}' is not a valid key name",
+                name, key
+            ));
+            continue;
+        }
+        keyed.push((name, key));
+    }
+
+    check_key_collisions(
+        "Secrets",
+        keyed
+            .iter()
+            .map(|(name, key)| (name.as_str(), key.as_str())),
+    )?;
+    Ok(keyed)
+}
+
+/// Fetch the latest version of every secret in `project` whose name starts with `prefix`
+///
+/// # Arguments
+/// * `project` - Google Cloud project ID
+/// * `prefix` - Secret name prefix (e.g., "myapp_dev_"); empty pulls every secret
+///
+/// # Returns
+/// Vector of GcpSecret structs, keyed by the secret name without the prefix
+///
+/// Secrets that aren't UTF-8 or don't map to a valid key are skipped with a warning; names that
+/// map to the same key are an error.
+pub fn fetch_secrets(project: &str, prefix: &str) -> Result<Vec<GcpSecret>, SkitError> {
+    let listing = gcloud(&["secrets", "list", "--project", project, "--format=json"])?;
+    let mut names: Vec<String> = parse_secret_names(&listing)?
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+
+    if names.is_empty() {
+        return Err(SkitError::GcpError(format!(
+            "No secrets found in project {} under prefix: {}",
+            project, prefix
+        )));
+    }
+    names.sort();
+
+    let mut secrets = Vec::new();
+    for (name, key) in keyed_names(names, prefix)? {
+        let payload = gcloud(&[
+            "secrets",
+            "versions",
+            "access",
+            "latest",
+            "--secret",
+            &name,
+            "--project",
+            project,
+        ])?;
+        match String::from_utf8(payload) {
+            Ok(value) => secrets.push(GcpSecret { key, value }),
+            Err(_) => print_warning(&format!("Skipping binary secret '{}'", name)),
+        }
+    }
+
+    Ok(secrets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secret_names() {
+        let json = br#"[
+            {"name": "projects/123/secrets/myapp_API_KEY", "replication": {}},
+            {"name": "projects/123/secrets/other"}
+        ]"#;
+        assert_eq!(
+            parse_secret_names(json).unwrap(),
+            vec!["myapp_API_KEY", "other"]
+        );
+        assert!(parse_secret_names(b"not json").is_err());
+    }
+
+    #[test]
+    fn test_secret_key_strips_prefix_and_dashes() {
+        assert_eq!(secret_key("myapp_API_KEY", "myapp_"), "API_KEY");
+        assert_eq!(secret_key("myapp-db-url", "myapp-"), "db_url");
+        assert_eq!(secret_key("TOKEN", ""), "TOKEN");
+    }
+
+    #[test]
+    fn test_keyed_names_rejects_collisions() {
+        let names = vec!["app-db".to_string(), "app-9x".to_string()];
+        assert_eq!(
+            keyed_names(names, "app-").unwrap(),
+            vec![("app-db".to_string(), "db".to_string())]
+        );
+
+        let names = vec!["app-db".to_string(), "app_db".to_string()];
+        let err = keyed_names(names, "").unwrap_err().to_string();
+        assert!(err.contains("app-db, app_db -> app_db"), "{}", err);
+    }
+}
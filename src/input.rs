@@ -1,13 +1,111 @@
+use crate::error::SkitError;
 use crossterm::{
     cursor,
     event::{Event, KeyCode, KeyEvent, KeyModifiers, read},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode},
 };
-use std::io::{self, Write, stdout};
+use std::io::{self, IsTerminal, Write, stdout};
 use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use zeroize::Zeroizing;
 
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+static STDIN_CONSUMED: AtomicBool = AtomicBool::new(false);
+
+/// Tries allowed at an interactive password prompt unless configured otherwise
+pub const DEFAULT_PASSWORD_ATTEMPTS: u32 = 3;
+static PASSWORD_ATTEMPTS: AtomicU32 = AtomicU32::new(DEFAULT_PASSWORD_ATTEMPTS);
+
+/// Apply the global `--no-input` and `--yes` flags
+pub fn set_prompt_mode(no_input: bool, assume_yes: bool) {
+    NO_INPUT.store(no_input, Ordering::Relaxed);
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// Whether prompting is disabled, either by `--no-input` or by running under CI (`CI=true`)
+pub fn is_non_interactive() -> bool {
+    NO_INPUT.load(Ordering::Relaxed)
+        || std::env::var("CI")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false)
+}
+
+/// Apply the configured number of password prompt attempts
+pub fn set_password_attempts(attempts: u32) {
+    PASSWORD_ATTEMPTS.store(attempts, Ordering::Relaxed);
+}
+
+/// How often a wrong password may be re-entered; a single try when nobody is at a terminal
+pub fn password_attempts() -> u32 {
+    if is_non_interactive() || !io::stdin().is_terminal() {
+        return 1;
+    }
+    PASSWORD_ATTEMPTS.load(Ordering::Relaxed).max(1)
+}
+
+/// Fail fast instead of prompting when running non-interactively.
+/// `hint` tells the user which flag or variable supplies the input instead.
+pub fn require_interactive(hint: &str) -> Result<(), SkitError> {
+    if is_non_interactive() {
+        return Err(SkitError::InputRequired(hint.to_string()));
+    }
+    Ok(())
+}
+
+/// Record that stdin was read as data, so prompts must read from the terminal instead
+pub fn mark_stdin_consumed() {
+    STDIN_CONSUMED.store(true, Ordering::Relaxed);
+}
+
+/// Read one line of prompt input from stdin, or from the terminal once stdin has been consumed
+pub fn read_prompt_line(buf: &mut String) -> io::Result<usize> {
+    if STDIN_CONSUMED.load(Ordering::Relaxed) {
+        #[cfg(unix)]
+        let tty = std::fs::File::open("/dev/tty");
+        #[cfg(windows)]
+        let tty = std::fs::File::open("CONIN$");
+        #[cfg(not(any(unix, windows)))]
+        let tty: io::Result<std::fs::File> = Err(io::Error::from(io::ErrorKind::Unsupported));
+
+        return io::BufRead::read_line(&mut io::BufReader::new(tty?), buf);
+    }
+    io::stdin().read_line(buf)
+}
+
+/// Ask a yes/no confirmation, answered automatically by `--yes`
+pub fn confirm(prompt: &str) -> Result<bool, SkitError> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        return Ok(true);
+    }
+    require_interactive("pass --yes to confirm")?;
+
+    print!("{}", prompt);
+    stdout().flush().map_err(SkitError::Io)?;
+
+    let mut input = String::new();
+    read_prompt_line(&mut input).map_err(SkitError::Io)?;
+
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
+
+/// Ask the user to type `expected` back before something irreversible, answered by `--yes`
+pub fn confirm_typed(prompt: &str, expected: &str) -> Result<bool, SkitError> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        return Ok(true);
+    }
+    require_interactive("pass --yes to confirm")?;
+
+    print!("{}", prompt);
+    stdout().flush().map_err(SkitError::Io)?;
+
+    let mut input = String::new();
+    read_prompt_line(&mut input).map_err(SkitError::Io)?;
+    Ok(input.trim() == expected)
+}
+
 /// Read a password with visual masking (shows asterisks) using crossterm
 pub fn prompt_password_masked(prompt: &str) -> Result<String, io::Error> {
     print!("{}", prompt);
@@ -39,16 +137,14 @@ fn read_password_chars(password: &mut String) -> Result<(), io::Error> {
             match code {
                 KeyCode::Enter => break,
 
-                KeyCode::Backspace => {
-                    if !password.is_empty() {
-                        password.pop();
-                        execute!(
-                            stdout(),
-                            cursor::MoveLeft(1),
-                            crossterm::style::Print(" "),
-                            cursor::MoveLeft(1)
-                        )?;
-                    }
+                KeyCode::Backspace if !password.is_empty() => {
+                    password.pop();
+                    execute!(
+                        stdout(),
+                        cursor::MoveLeft(1),
+                        crossterm::style::Print(" "),
+                        cursor::MoveLeft(1)
+                    )?;
                 }
 
                 KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
@@ -85,7 +181,7 @@ pub fn prompt_password_with_fallback(prompt: &str) -> Result<String, io::Error>
             stdout().flush()?;
 
             let mut input = Zeroizing::new(String::new());
-            io::stdin().read_line(&mut input)?;
+            read_prompt_line(&mut input)?;
 
             // Remove trailing newline
             if input.ends_with('\n') {
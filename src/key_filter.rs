@@ -0,0 +1,190 @@
+use crate::types::SafeItem;
+
+/// Which safe keys a command passes on, from `--only`, `--exclude`, `--prefix` and `--tag`.
+/// `--only` and `--exclude` take comma-separated key names, which may use `*` and `?` wildcards.
+#[derive(Debug, Default)]
+pub struct KeyFilter {
+    only: Option<Vec<String>>,
+    exclude: Vec<String>,
+    prefix: Option<String>,
+    strip_prefix: bool,
+    tags: Vec<String>,
+}
+
+impl KeyFilter {
+    pub fn new(only: Option<&str>, exclude: Option<&str>) -> Self {
+        KeyFilter {
+            only: only.map(split_patterns),
+            exclude: exclude.map(split_patterns).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Keep only keys starting with `prefix`, optionally removing it from the passed-on names
+    pub fn with_prefix(mut self, prefix: Option<&str>, strip_prefix: bool) -> Self {
+        self.prefix = prefix.map(|p| p.to_string());
+        self.strip_prefix = strip_prefix;
+        self
+    }
+
+    /// Keep only items carrying every one of these tags
+    pub fn with_tags(mut self, tags: &[String]) -> Self {
+        self.tags = tags.to_vec();
+        self
+    }
+
+    /// Whether an item passes every filter, including its tags
+    pub fn allows_item(&self, item: &SafeItem) -> bool {
+        self.allows(&item.key) && has_tags(item, &self.tags)
+    }
+
+    /// Whether a key passes every filter except tags, which need the item
+    pub fn allows(&self, key: &str) -> bool {
+        let in_prefix = match &self.prefix {
+            Some(prefix) => key.starts_with(prefix.as_str()),
+            None => true,
+        };
+        let included = match &self.only {
+            Some(patterns) => patterns.iter().any(|p| glob_match(p, key)),
+            None => true,
+        };
+        in_prefix && included && !self.exclude.iter().any(|p| glob_match(p, key))
+    }
+
+    /// The variable name a key is passed on as, with the prefix removed under `--strip-prefix`
+    pub fn output_name<'a>(&self, key: &'a str) -> &'a str {
+        match &self.prefix {
+            Some(prefix) if self.strip_prefix => key.strip_prefix(prefix.as_str()).unwrap_or(key),
+            _ => key,
+        }
+    }
+
+    /// `--only` entries that match none of the given keys
+    pub fn unmatched_only<'a, I>(&self, keys: I) -> Vec<&str>
+    where
+        I: IntoIterator<Item = &'a String> + Clone,
+    {
+        self.only
+            .iter()
+            .flatten()
+            .filter(|p| !keys.clone().into_iter().any(|key| glob_match(p, key)))
+            .map(|p| p.as_str())
+            .collect()
+    }
+}
+
+/// Whether an item carries every one of `tags`
+pub fn has_tags(item: &SafeItem, tags: &[String]) -> bool {
+    tags.iter().all(|tag| item.metadata.tags.contains(tag))
+}
+
+fn split_patterns(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Match a key against a pattern where `*` matches any run of characters and `?` exactly one
+pub fn glob_match(pattern: &str, key: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+
+    let (mut p, mut k) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while k < key.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == key[k]) {
+            p += 1;
+            k += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, k));
+            p += 1;
+        } else if let Some((star_p, star_k)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            k = star_k + 1;
+            backtrack = Some((star_p, star_k + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("AWS_*", "AWS_SECRET_ACCESS_KEY"));
+        assert!(glob_match("AWS_*", "AWS_"));
+        assert!(glob_match("*_KEY", "API_KEY"));
+        assert!(glob_match("DB_?", "DB_1"));
+        assert!(glob_match("*", "ANY"));
+        assert!(glob_match("A*B*C", "AxxBxxBxC"));
+        assert!(!glob_match("AWS_*", "XAWS_KEY"));
+        assert!(!glob_match("DB_?", "DB_10"));
+        assert!(!glob_match("API_KEY", "API_KEYS"));
+    }
+
+    #[test]
+    fn test_key_filter_only_and_exclude() {
+        let filter = KeyFilter::new(Some("AWS_*, API_KEY"), Some("AWS_SESSION_TOKEN"));
+
+        assert!(filter.allows("AWS_ACCESS_KEY_ID"));
+        assert!(filter.allows("API_KEY"));
+        assert!(!filter.allows("AWS_SESSION_TOKEN"));
+        assert!(!filter.allows("DATABASE_URL"));
+
+        let keys = ["API_KEY".to_string(), "DATABASE_URL".to_string()];
+        assert_eq!(filter.unmatched_only(keys.iter()), vec!["AWS_*"]);
+    }
+
+    #[test]
+    fn test_key_filter_default_allows_everything() {
+        let filter = KeyFilter::default();
+        assert!(filter.allows("ANYTHING"));
+        assert!(
+            filter
+                .unmatched_only(["ANYTHING".to_string()].iter())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_key_filter_tags() {
+        use crate::types::ItemMetadata;
+
+        let item = |key: &str, tags: &[&str]| SafeItem {
+            key: key.to_string(),
+            value: String::new(),
+            is_encrypted: false,
+            metadata: ItemMetadata {
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                ..ItemMetadata::default()
+            },
+        };
+        let filter = KeyFilter::new(None, Some("DEBUG")).with_tags(&["prod".to_string()]);
+
+        assert!(filter.allows_item(&item("API_KEY", &["prod", "service-a"])));
+        assert!(!filter.allows_item(&item("API_KEY", &["staging"])));
+        assert!(!filter.allows_item(&item("DEBUG", &["prod"])));
+        assert!(KeyFilter::default().allows_item(&item("ANY", &[])));
+    }
+
+    #[test]
+    fn test_key_filter_prefix() {
+        let filter = KeyFilter::default().with_prefix(Some("BILLING_"), false);
+        assert!(filter.allows("BILLING_API_KEY"));
+        assert!(!filter.allows("AUTH_API_KEY"));
+        assert_eq!(filter.output_name("BILLING_API_KEY"), "BILLING_API_KEY");
+
+        let filter = KeyFilter::new(None, Some("*_TOKEN")).with_prefix(Some("BILLING_"), true);
+        assert!(filter.allows("BILLING_API_KEY"));
+        assert!(!filter.allows("BILLING_TOKEN"));
+        assert_eq!(filter.output_name("BILLING_API_KEY"), "API_KEY");
+    }
+}
@@ -0,0 +1,169 @@
+use crate::error::SkitError;
+use crate::types::Safe;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Index file kept next to the saved key files
+pub const INDEX_FILE: &str = "index.json";
+
+/// What is known about one saved safe key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyIndexEntry {
+    /// Description of the safe the key unlocks
+    pub description: String,
+    pub created: DateTime<Utc>,
+    pub last_used: DateTime<Utc>,
+    /// Absolute path of the safe the key was last used with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+}
+
+impl KeyIndexEntry {
+    /// Whether the safe this key was used with is gone: its file was removed, or now holds
+    /// a different safe. Entries without a recorded path are never orphans.
+    pub fn is_orphan(&self, uuid: &str) -> bool {
+        let Some(path) = &self.source_path else {
+            return false;
+        };
+        if !Path::new(path).exists() {
+            return true;
+        }
+        Safe::load(path).is_ok_and(|safe| safe.uuid != uuid)
+    }
+}
+
+/// Saved keys by safe UUID, stored as `~/.config/skit/keys/index.json`.
+///
+/// Keys saved before the index existed have no entry until they are next used, so callers
+/// fall back to the key file's modification time for them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeyIndex {
+    pub entries: BTreeMap<String, KeyIndexEntry>,
+}
+
+impl KeyIndex {
+    /// Load the index from a keys directory. A missing index is empty; an unreadable one is
+    /// reported and treated as empty so it gets rebuilt as keys are used.
+    pub fn load(keys_dir: &Path) -> Self {
+        let path = keys_dir.join(INDEX_FILE);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable key index {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, keys_dir: &Path) -> Result<(), SkitError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| SkitError::ParseError(format!("Failed to encode key index: {}", e)))?;
+        crate::fs_utils::write_file_atomic(&keys_dir.join(INDEX_FILE), &content)
+    }
+
+    /// Note that the key for `safe` was just saved or used
+    pub fn record(&mut self, safe: &Safe, safe_path: Option<&str>) {
+        let now = Utc::now();
+        let source_path = safe_path
+            .and_then(|path| fs::canonicalize(path).ok())
+            .map(|path| path.display().to_string());
+
+        let entry = self
+            .entries
+            .entry(safe.uuid.clone())
+            .or_insert_with(|| KeyIndexEntry {
+                description: String::new(),
+                created: now,
+                last_used: now,
+                source_path: None,
+            });
+        entry.description = safe.description.clone();
+        entry.last_used = now;
+        if source_path.is_some() {
+            entry.source_path = source_path;
+        }
+    }
+}
+
+/// Directory holding saved key files, `~/.config/skit/keys`
+pub fn keys_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config").join("skit").join("keys"))
+}
+
+/// Record a use of the saved key for `safe`. Failing to update the index never fails the
+/// command that needed the key.
+pub fn record_use(safe: &Safe, safe_path: Option<&str>) {
+    let Some(dir) = keys_dir().filter(|dir| dir.is_dir()) else {
+        return;
+    };
+    let mut index = KeyIndex::load(&dir);
+    index.record(safe, safe_path);
+    if let Err(e) = index.save(&dir) {
+        tracing::debug!("Could not update key index: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn safe_with_uuid(uuid: &str) -> String {
+        format!(
+            "#@VERSION=1.0\n#@UUID={}\n#@DESCRIPTION=Billing API\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n",
+            uuid
+        )
+    }
+
+    #[test]
+    fn test_record_round_trips_and_keeps_created() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let safe_path = temp_dir.path().join(".env.safe");
+        fs::write(&safe_path, safe_with_uuid("u-1")).unwrap();
+        let safe = Safe::load(safe_path.to_str().unwrap()).unwrap();
+
+        let mut index = KeyIndex::default();
+        index.record(&safe, safe_path.to_str());
+        let created = index.entries["u-1"].created;
+        index.record(&safe, None);
+        index.save(temp_dir.path()).unwrap();
+
+        let loaded = KeyIndex::load(temp_dir.path());
+        let entry = &loaded.entries["u-1"];
+        assert_eq!(entry.description, "Billing API");
+        assert_eq!(entry.created, created);
+        assert!(entry.last_used >= created);
+        assert!(entry.source_path.as_deref().unwrap().ends_with(".env.safe"));
+    }
+
+    #[test]
+    fn test_is_orphan() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let safe_path = temp_dir.path().join(".env.safe");
+        fs::write(&safe_path, safe_with_uuid("u-1")).unwrap();
+
+        let entry = |path: Option<&Path>| KeyIndexEntry {
+            description: String::new(),
+            created: Utc::now(),
+            last_used: Utc::now(),
+            source_path: path.map(|p| p.display().to_string()),
+        };
+
+        assert!(!entry(Some(&safe_path)).is_orphan("u-1"));
+        assert!(entry(Some(&safe_path)).is_orphan("u-2"));
+        assert!(entry(Some(&temp_dir.path().join("gone.safe"))).is_orphan("u-1"));
+        assert!(!entry(None).is_orphan("u-1"));
+    }
+
+    #[test]
+    fn test_unreadable_index_is_empty() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        fs::write(temp_dir.path().join(INDEX_FILE), "not json").unwrap();
+        assert!(KeyIndex::load(temp_dir.path()).entries.is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::error::SkitError;
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+/// Service name under which safe keys are stored in the OS keychain
+#[cfg(feature = "keyring")]
+const KEYRING_SERVICE: &str = "skit";
+
+/// Path of the marker file that records a keychain entry for a safe.
+///
+/// The OS keychains cannot be enumerated portably, so each keychain entry gets an
+/// empty `<uuid>.keyring` file next to the plaintext key files, which lets
+/// `cleanup-keys` age keychain entries the same way it ages `.key` files. Its
+/// modification time stands in for the last use of entries missing from the key index.
+pub fn marker_path(uuid: &str) -> Option<PathBuf> {
+    dirs::home_dir().map(|home| {
+        home.join(".config")
+            .join("skit")
+            .join("keys")
+            .join(format!("{}.keyring", uuid))
+    })
+}
+
+#[cfg(feature = "keyring")]
+fn entry(uuid: &str) -> Result<::keyring::Entry, SkitError> {
+    ::keyring::Entry::new(KEYRING_SERVICE, uuid).map_err(keyring_error)
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_error(e: ::keyring::Error) -> SkitError {
+    SkitError::ParseError(format!("OS keychain error: {}", e))
+}
+
+#[cfg(not(feature = "keyring"))]
+fn unsupported() -> SkitError {
+    SkitError::ParseError(
+        "This build of skit was compiled without keychain support (enable the 'keyring' feature)"
+            .to_string(),
+    )
+}
+
+/// Store a safe key in the OS keychain and record its marker file
+pub fn store_password(uuid: &str, password: &str) -> Result<(), SkitError> {
+    #[cfg(feature = "keyring")]
+    {
+        entry(uuid)?.set_password(password).map_err(keyring_error)?;
+
+        if let Some(marker) = marker_path(uuid) {
+            if let Some(parent) = marker.parent() {
+                std::fs::create_dir_all(parent).map_err(SkitError::Io)?;
+            }
+            std::fs::write(&marker, b"").map_err(SkitError::Io)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    {
+        let _ = (uuid, password);
+        Err(unsupported())
+    }
+}
+
+/// Look up a safe key in the OS keychain. Returns `Ok(None)` when no entry was recorded.
+pub fn get_password(uuid: &str) -> Result<Option<Zeroizing<String>>, SkitError> {
+    let marker = match marker_path(uuid) {
+        Some(marker) if marker.exists() => marker,
+        _ => return Ok(None),
+    };
+
+    #[cfg(feature = "keyring")]
+    {
+        match entry(uuid)?.get_password() {
+            Ok(password) => {
+                let _ = filetime::set_file_mtime(&marker, filetime::FileTime::now());
+                Ok(Some(Zeroizing::new(password)))
+            }
+            Err(::keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(keyring_error(e)),
+        }
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    {
+        let _ = marker;
+        Err(unsupported())
+    }
+}
+
+/// Remove a safe key from the OS keychain along with its marker file
+pub fn delete_password(uuid: &str) -> Result<(), SkitError> {
+    #[cfg(feature = "keyring")]
+    {
+        match entry(uuid)?.delete_credential() {
+            Ok(()) | Err(::keyring::Error::NoEntry) => {}
+            Err(e) => return Err(keyring_error(e)),
+        }
+    }
+
+    if let Some(marker) = marker_path(uuid)
+        && marker.exists()
+    {
+        std::fs::remove_file(&marker).map_err(SkitError::Io)?;
+    }
+
+    Ok(())
+}
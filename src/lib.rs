@@ -0,0 +1,118 @@
+//! Read and write skit safes from Rust.
+//!
+//! A safe is a dotenv-style file whose values are stored either as plain text or
+//! encrypted under the safe's password. Load it with [`Safe::load`], unlock it with
+//! [`Safe::unlock`], and read decrypted values from the returned [`UnlockedSafe`]:
+//!
+//! ```
+//! # fn main() -> Result<(), skit::SkitError> {
+//! # let dir = tempfile::TempDir::new().unwrap();
+//! # let path = dir.path().join(".env.safe");
+//! # let path = path.to_str().unwrap();
+//! # let mut safe = skit::Safe::new_with_password("Example.Pass-123", "example")?;
+//! # safe.create_master_key("Example.Pass-123")?;
+//! # let mut unlocked = safe.unlock("Example.Pass-123")?;
+//! # unlocked.set("DATABASE_URL", "postgres://localhost/app")?;
+//! # unlocked.save(path)?;
+//! let safe = skit::Safe::load(path)?;
+//! let secrets = safe.unlock("Example.Pass-123")?;
+//!
+//! assert_eq!(
+//!     secrets.get("DATABASE_URL").as_deref(),
+//!     Some("postgres://localhost/app")
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `skit` command-line tool is built from the same crate with the default `cli`
+//! feature. Library users who only need the [`Safe`] API can turn it off:
+//!
+//! ```toml
+//! skit = { version = "0.1", default-features = false }
+//! ```
+//!
+//! Everything outside the re-exports below (and the `crypto`, `error`, `safe` and
+//! `types` modules) exists for the CLI and may change without notice.
+
+pub mod crypto;
+#[doc(hidden)]
+pub mod dotenv;
+pub mod error;
+#[doc(hidden)]
+pub mod fs_utils;
+pub mod safe;
+pub mod types;
+
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod agent;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod audit;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod aws;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod clipboard;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod commands;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod config;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod display;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod gcp;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod input;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod key_filter;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod key_index;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod keychain;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod logging;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod password;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod shell;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod validation;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod vault;
+
+pub use crypto::{Cipher, CryptoError, DecryptBuilder, EncryptBuilder, KdfParams};
+pub use error::SkitError;
+pub use safe::UnlockedSafe;
+pub use types::{ItemMetadata, Safe, SafeItem, SortOrder};
+
+/// Output formats of the CLI's `--format` flag
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Env,
+    Postman,
+    Terraform,
+    Yaml,
+    Toml,
+    /// Kubernetes `v1/Secret` manifest
+    K8sSecret,
+}
@@ -7,9 +7,11 @@ use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberI
 /// - NO_COLOR environment variable (disables colors)
 /// - TTY detection (no colors when piped)
 /// - RUST_LOG environment variable for filtering
-pub fn init_logging() {
+///
+/// `no_color` comes from the config file and disables colors like NO_COLOR does.
+pub fn init_logging(no_color: bool) {
     // Check if we should force colors or respect NO_COLOR
-    let use_ansi = should_use_colors();
+    let use_ansi = !no_color && should_use_colors();
 
     // Set up the env filter - defaults to "info" if RUST_LOG is not set
     let env_filter = EnvFilter::try_from_default_env()
@@ -59,23 +61,23 @@ mod tests {
     #[test]
     fn test_no_color_detection() {
         // Test NO_COLOR environment variable
-        env::set_var("NO_COLOR", "1");
-        assert_eq!(should_use_colors(), false);
-        env::remove_var("NO_COLOR");
+        unsafe { env::set_var("NO_COLOR", "1") };
+        assert!(!should_use_colors());
+        unsafe { env::remove_var("NO_COLOR") };
 
         // Test SKIT_NO_COLOR environment variable
-        env::set_var("SKIT_NO_COLOR", "1");
-        assert_eq!(should_use_colors(), false);
-        env::remove_var("SKIT_NO_COLOR");
+        unsafe { env::set_var("SKIT_NO_COLOR", "1") };
+        assert!(!should_use_colors());
+        unsafe { env::remove_var("SKIT_NO_COLOR") };
 
         // Test FORCE_COLOR environment variable
-        env::set_var("FORCE_COLOR", "1");
-        assert_eq!(should_use_colors(), true);
-        env::remove_var("FORCE_COLOR");
+        unsafe { env::set_var("FORCE_COLOR", "1") };
+        assert!(should_use_colors());
+        unsafe { env::remove_var("FORCE_COLOR") };
 
         // Test SKIT_FORCE_COLOR environment variable
-        env::set_var("SKIT_FORCE_COLOR", "1");
-        assert_eq!(should_use_colors(), true);
-        env::remove_var("SKIT_FORCE_COLOR");
+        unsafe { env::set_var("SKIT_FORCE_COLOR", "1") };
+        assert!(should_use_colors());
+        unsafe { env::remove_var("SKIT_FORCE_COLOR") };
     }
 }
@@ -1,30 +1,11 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{CommandFactory, Parser, Subcommand};
 use std::process;
 
-mod aws;
-mod commands;
-mod crypto;
-mod display;
-mod error;
-mod fs_utils;
-mod input;
-mod logging;
-mod password;
-mod safe;
-mod shell;
-mod types;
-mod validation;
-
-use error::SkitError;
-
-#[derive(ValueEnum, Clone, Debug)]
-pub enum OutputFormat {
-    Table,
-    Json,
-    Env,
-    Postman,
-    Terraform,
-}
+use skit::safe::normalize_safe_path;
+use skit::{
+    OutputFormat, SkitError, aws, clipboard, commands, config, crypto, input, key_filter, logging,
+    password, safe, types,
+};
 
 #[derive(Parser)]
 #[command(name = "skit")]
@@ -35,23 +16,50 @@ pub enum OutputFormat {
     long_about = "skit stores secrets in .env format with encrypted values, making it safe to commit to git.\n\nUsage: skit [GLOBAL_OPTIONS] <COMMAND> [COMMAND_OPTIONS]\nExample: skit --safe myproject init --generate"
 )]
 #[command(version = env!("SKIT_VERSION"))]
+#[command(
+    after_help = "Exit codes:\n  0  Success\n  1  Other error\n  2  Safe file not found\n  3  Key not found in the safe\n  4  Wrong or missing password\n  5  AWS, GCP or Vault error\n  6  Invalid input\n  7  A value failed to decrypt (corrupted or tampered)"
+)]
 struct Cli {
     #[arg(
         short = 's',
         long,
-        default_value = ".env.safe",
-        help = "Path to the safe file (global option)"
+        help = "Path to the safe file (default: from config, else .env.safe) (global option)"
     )]
-    safe: String,
+    safe: Option<String>,
 
     #[arg(
         short = 'o',
         long = "format",
         value_enum,
-        default_value = "table",
-        help = "Output format: table, json, env, terraform, or postman (default: table) (global option)"
+        help = "Output format: table, json, yaml, toml, env, terraform, postman, or k8s-secret (default: SKIT_FORMAT, config, else table) (global option)"
+    )]
+    format: Option<OutputFormat>,
+
+    #[arg(
+        long,
+        help = "Never prompt; fail with an error naming the flag to pass instead (implied by CI=true) (global option)"
+    )]
+    no_input: bool,
+
+    #[arg(
+        short = 'y',
+        long,
+        help = "Answer yes to confirmation prompts (global option)"
+    )]
+    yes: bool,
+
+    #[arg(
+        long = "password-file",
+        value_name = "PATH",
+        help = "Read the safe password from this file before trying SKIT_SAFEKEY, saved keys or a prompt (global option)"
+    )]
+    password_file: Option<String>,
+
+    #[arg(
+        long,
+        help = "Keep numbered backups (<safe>.bak.N) of the safe when saving it (default count: SKIT_BACKUPS, config, else 3) (global option)"
     )]
-    format: OutputFormat,
+    backup: bool,
 
     #[command(subcommand)]
     command: Commands,
@@ -67,6 +75,11 @@ enum Commands {
             help = "Remember the safe key for automatic authentication"
         )]
         remember: bool,
+        #[arg(
+            long,
+            help = "Store the remembered safe key in the OS keychain instead of a key file"
+        )]
+        keyring: bool,
         #[arg(short = 'd', long, help = "Description for the safe")]
         description: Option<String>,
         #[arg(
@@ -75,22 +88,162 @@ enum Commands {
             help = "Default AWS SSM parameter prefix to associate with this safe (e.g., /app/dev/)"
         )]
         ssm_prefix: Option<String>,
+        #[arg(
+            long = "kdf-mem",
+            value_name = "KIB",
+            help = "Argon2 memory cost in KiB for encrypting secrets (default: 65536)"
+        )]
+        kdf_mem: Option<u32>,
+        #[arg(
+            long = "kdf-time",
+            value_name = "ITERATIONS",
+            help = "Argon2 time cost (iterations) for encrypting secrets (default: 3)"
+        )]
+        kdf_time: Option<u32>,
+        #[arg(
+            long,
+            default_value = "aes256gcm",
+            value_parser = ["aes256gcm", "xchacha20"],
+            help = "Cipher for encrypted values (xchacha20 suits hosts without AES acceleration)"
+        )]
+        cipher: String,
+        #[arg(
+            long,
+            value_name = "VAR",
+            conflicts_with = "password_file",
+            help = "Read the safe password from this environment variable instead of prompting"
+        )]
+        password_env: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Read the safe password from this file instead of prompting"
+        )]
+        password_file: Option<String>,
+        #[arg(
+            long,
+            value_name = "SCORE",
+            default_value_t = password::DEFAULT_MIN_STRENGTH,
+            value_parser = clap::value_parser!(u8).range(0..=4),
+            help = "Minimum zxcvbn strength score (0-4) the new password must reach"
+        )]
+        min_strength: u8,
+        #[arg(
+            long,
+            conflicts_with_all = ["password_env", "password_file"],
+            help = "Generate a passphrase of random words as the new safe password and print it"
+        )]
+        passphrase: bool,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Create the keys listed in this file (one per line) with empty encrypted values"
+        )]
+        from_template: Option<String>,
     },
 
     #[command(about = "Add or update a secret (encrypted by default)")]
     Set {
         #[arg(help = "Secret key name")]
         key: String,
-        #[arg(help = "Secret value")]
-        value: String,
+        #[arg(help = "Secret value (prompted for with masked input when omitted)")]
+        value: Option<String>,
         #[arg(short = 'p', long, help = "Store as plain text instead of encrypted")]
         plain: bool,
+        #[arg(
+            long,
+            help = "Read the value from stdin instead of the command line (keeps it out of shell history)"
+        )]
+        stdin: bool,
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with = "stdin",
+            help = "Read the value from a file (e.g. a TLS key or JSON credentials)"
+        )]
+        from_file: Option<String>,
+        #[arg(
+            long = "desc",
+            value_name = "TEXT",
+            help = "Describe what this secret is for (kept when omitted, cleared with \"\")"
+        )]
+        description: Option<String>,
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Tag the secret for grouping and filtering (repeatable; existing tags are kept)"
+        )]
+        tags: Vec<String>,
+        #[arg(
+            long,
+            help = "Leave the key untouched if it already exists (no password needed then)"
+        )]
+        if_not_exists: bool,
+        #[arg(
+            long,
+            help = "Keep the existing ciphertext if the key already holds this value (avoids noisy diffs)"
+        )]
+        stable: bool,
     },
 
     #[command(about = "Get and decrypt a secret value")]
     Get {
         #[arg(help = "Secret key name to retrieve")]
         key: String,
+        #[arg(
+            short = 'c',
+            long,
+            help = "Copy the value to the clipboard instead of printing it"
+        )]
+        copy: bool,
+        #[arg(
+            long,
+            alias = "clear-after",
+            value_name = "SECONDS",
+            default_value_t = 30,
+            requires = "copy",
+            help = "Seconds before the clipboard is cleared (0 to keep it)"
+        )]
+        timeout: u64,
+    },
+
+    #[command(
+        about = "Print a secret value with no trailing newline (exits 3 if the key is missing)"
+    )]
+    Cat {
+        #[arg(help = "Secret key name to print")]
+        key: String,
+    },
+
+    #[command(about = "Generate a random secret and store it encrypted")]
+    Generate {
+        #[arg(help = "Secret key name")]
+        key: String,
+        #[arg(
+            short = 'l',
+            long,
+            default_value_t = 32,
+            help = "Number of characters to generate"
+        )]
+        length: usize,
+        #[arg(
+            long,
+            default_value = "alnum",
+            value_parser = ["alnum", "hex", "base64", "ascii-printable"],
+            help = "Characters to draw from"
+        )]
+        charset: String,
+        #[arg(
+            long,
+            value_name = "N",
+            conflicts_with_all = ["length", "charset"],
+            help = "Generate a passphrase of N random words instead"
+        )]
+        words: Option<usize>,
+        #[arg(short = 'f', long, help = "Overwrite the key if it already exists")]
+        force: bool,
+        #[arg(short = 'q', long, help = "Don't print the generated value")]
+        quiet: bool,
     },
 
     #[command(about = "Display all secrets in organized format")]
@@ -107,95 +260,807 @@ enum Commands {
             help = "Show only encrypted values (requires password)"
         )]
         enc: bool,
+        #[arg(
+            short = 'v',
+            long,
+            help = "Show descriptions, tags and created/updated times (table format)"
+        )]
+        verbose: bool,
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Show only secrets with this tag (repeatable; all must match)"
+        )]
+        tags: Vec<String>,
+        #[arg(
+            long,
+            visible_alias = "no-decrypt",
+            help = "Show stored values verbatim, ciphertext included, without asking for a password"
+        )]
+        raw: bool,
+        #[arg(
+            long,
+            conflicts_with = "raw",
+            help = "Fail, listing the keys, if any value can't be decrypted instead of showing [DECRYPTION_FAILED]"
+        )]
+        strict: bool,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t,
+            help = "Key order: asc, desc, or insertion (as stored in the safe file)"
+        )]
+        sort: types::SortOrder,
+        #[arg(
+            long,
+            value_name = "NAME",
+            default_value = commands::print::DEFAULT_K8S_SECRET_NAME,
+            help = "Secret name for -o k8s-secret"
+        )]
+        k8s_name: String,
+        #[arg(
+            long,
+            value_name = "NAMESPACE",
+            help = "Secret namespace for -o k8s-secret (default: omitted)"
+        )]
+        k8s_namespace: Option<String>,
     },
 
     #[command(about = "List all secret keys with their types (encrypted/plain)")]
-    Keys,
+    Keys {
+        #[arg(long, hide = true)]
+        complete_keys: bool,
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "List only keys with this tag (repeatable; all must match)"
+        )]
+        tags: Vec<String>,
+        #[arg(
+            long,
+            visible_alias = "values-length",
+            help = "Show the stored size of each value in bytes (ciphertext for encrypted values; no password needed)"
+        )]
+        size: bool,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t,
+            help = "Key order: asc, desc, or insertion (as stored in the safe file)"
+        )]
+        sort: types::SortOrder,
+        #[arg(
+            long,
+            help = "With --format env, print KEY<tab>ENC|PLAIN instead of bare keys"
+        )]
+        types: bool,
+        #[arg(
+            long,
+            help = "Print only the number of keys: total, encrypted and plain"
+        )]
+        count: bool,
+    },
+
+    #[command(
+        about = "Print a .env.example listing the safe's keys without values (no password needed)"
+    )]
+    Example {
+        #[arg(
+            short = 'o',
+            long,
+            value_name = "PATH",
+            help = "Write to this file instead of stdout"
+        )]
+        output: Option<String>,
+        #[arg(
+            long,
+            help = "Add a comment with the type and description above each key"
+        )]
+        comments: bool,
+        #[arg(
+            long,
+            value_name = "PATH",
+            num_args = 0..=1,
+            default_missing_value = commands::example::DEFAULT_EXAMPLE_FILE,
+            conflicts_with_all = ["output", "comments"],
+            help = "Check that an example file (default: .env.example) lists exactly the safe's keys; exits non-zero otherwise"
+        )]
+        check: Option<String>,
+    },
+
+    #[command(
+        about = "Find secrets whose key or value matches a regular expression (values are masked)"
+    )]
+    Search {
+        #[arg(help = "Regular expression to search for")]
+        pattern: String,
+        #[arg(
+            short = 'k',
+            long,
+            help = "Match key names only (no password needed, nothing is decrypted)"
+        )]
+        keys_only: bool,
+        #[arg(short = 'i', long, help = "Match case-insensitively")]
+        ignore_case: bool,
+        #[arg(
+            long,
+            conflicts_with = "keys_only",
+            help = "Show matched values, masked except for the match and a little context"
+        )]
+        reveal: bool,
+    },
 
     #[command(about = "Remove a secret from the safe")]
     Rm {
-        #[arg(help = "Secret key name to remove")]
-        key: String,
+        #[arg(
+            help = "Secret key name to remove",
+            required_unless_present_any = ["glob", "prefix", "all"],
+            conflicts_with_all = ["glob", "prefix", "all"]
+        )]
+        key: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            conflicts_with_all = ["prefix", "all"],
+            help = "Remove every key matching a shell-style pattern like 'OLD_*' (asks to confirm)"
+        )]
+        glob: Option<String>,
+        #[arg(
+            long,
+            value_name = "P",
+            conflicts_with = "all",
+            help = "Remove every key starting with this prefix (asks to confirm)"
+        )]
+        prefix: Option<String>,
+        #[arg(long, help = "Remove every key in the safe (asks to confirm)")]
+        all: bool,
+    },
+
+    #[command(
+        about = "Rename a secret key without re-encrypting its value",
+        visible_alias = "mv"
+    )]
+    Rename {
+        #[arg(help = "Existing secret key name")]
+        old: String,
+        #[arg(help = "New secret key name")]
+        new: String,
+        #[arg(short = 'f', long, help = "Overwrite the new key if it already exists")]
+        force: bool,
     },
 
     #[command(about = "Execute command with secrets injected as environment variables")]
     Exec {
+        #[arg(
+            long,
+            value_name = "KEYS",
+            help = "Comma-separated keys to inject, others are left out (supports globs like AWS_*)"
+        )]
+        only: Option<String>,
+        #[arg(
+            long,
+            visible_alias = "except",
+            value_name = "KEYS",
+            conflicts_with = "only",
+            help = "Comma-separated keys to leave out (supports globs like AWS_*)"
+        )]
+        exclude: Option<String>,
+        #[arg(
+            long,
+            value_name = "P",
+            help = "Only inject keys starting with this prefix"
+        )]
+        prefix: Option<String>,
+        #[arg(
+            long,
+            requires = "prefix",
+            help = "Remove the prefix from injected variable names"
+        )]
+        strip_prefix: bool,
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Only inject secrets with this tag (repeatable; all must match)"
+        )]
+        tags: Vec<String>,
+        #[arg(
+            long,
+            help = "Start from an empty environment: only the safe's variables plus --keep"
+        )]
+        isolated: bool,
+        #[arg(
+            long,
+            value_name = "VARS",
+            requires = "isolated",
+            help = "Comma-separated host variables to keep with --isolated (default: PATH,HOME,USER,LANG,TERM)"
+        )]
+        keep: Option<String>,
+        #[arg(
+            long = "env",
+            value_name = "KEY=VALUE",
+            help = "Set an extra variable for the command (repeatable; overrides safe values)"
+        )]
+        env: Vec<String>,
+        #[arg(
+            long,
+            help = "Start the command without secrets that fail to decrypt instead of aborting"
+        )]
+        no_strict: bool,
         #[arg(last = true, help = "Command and arguments to execute")]
         command: Vec<String>,
     },
 
     #[command(about = "Show safe metadata and integrity status")]
-    Status,
+    Status {
+        #[arg(
+            long,
+            help = "Only check the password, skipping per-secret decryption (faster on big safes)"
+        )]
+        fast: bool,
+    },
 
-    #[command(about = "Rotate encryption keys (re-encrypt all secrets)")]
-    Rotate,
+    #[command(about = "Check that every encrypted secret decrypts (exits non-zero on failure)")]
+    Verify,
 
-    #[command(about = "List all safe files in current directory")]
-    Ls,
+    #[command(
+        about = "Check secret values for empty, placeholder or duplicated values and plain-text private keys (exits non-zero on errors)"
+    )]
+    Lint,
 
-    #[command(about = "Output secrets for shell sourcing")]
-    Env,
+    #[command(about = "Print the safe's keys as a schema, or check the safe against one")]
+    Schema {
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Check the safe against this schema (required keys, optional value patterns)"
+        )]
+        check: Option<String>,
+    },
 
-    #[command(about = "Output secrets in KEY=value format for piping to external commands")]
-    Export,
+    #[command(
+        about = "Diagnose common setup problems (safe, safe key, permissions, AWS, terminal)"
+    )]
+    Doctor,
 
-    #[command(about = "Remember safe key for easy access")]
-    RememberSafekey,
+    #[command(about = "Upgrade a safe to the master key format")]
+    Migrate,
 
-    #[command(about = "Clean up old saved keys")]
-    CleanupKeys {
+    #[command(about = "Rotate encryption keys (re-encrypt all secrets)")]
+    Rotate {
         #[arg(
-            long = "older-than-days",
-            help = "Remove keys not accessed for N days (required)"
+            long,
+            value_name = "VAR",
+            help = "Read the current password from this environment variable instead of the usual lookup"
         )]
-        older_than_days: u64,
-        #[arg(long, help = "Show what would be removed without actually removing")]
-        dry_run: bool,
+        current_password_env: Option<String>,
+        #[arg(
+            long,
+            value_name = "VAR",
+            help = "Read the new password from this environment variable instead of prompting"
+        )]
+        new_password_env: Option<String>,
+        #[arg(
+            long,
+            help = "Copy the safe to <safe>.pre-rotate.<timestamp>.bak first (rotation is aborted if the copy fails)"
+        )]
+        backup: bool,
+        #[arg(
+            long,
+            value_name = "SCORE",
+            default_value_t = password::DEFAULT_MIN_STRENGTH,
+            value_parser = clap::value_parser!(u8).range(0..=4),
+            help = "Minimum zxcvbn strength score (0-4) the new password must reach"
+        )]
+        min_strength: u8,
+        #[arg(
+            long,
+            conflicts_with = "new_password_env",
+            help = "Generate a passphrase of random words as the new safe password and print it"
+        )]
+        passphrase: bool,
+        #[arg(
+            long,
+            value_parser = ["aes256gcm", "xchacha20"],
+            help = "Re-encrypt every secret with this cipher and use it for new values"
+        )]
+        cipher: Option<String>,
     },
 
-    #[command(about = "Import secrets from existing cleartext file into safe")]
-    Import {
-        #[arg(short = 'f', long = "file", help = "Path to the input file to import")]
-        file: String,
+    #[command(about = "Change the safe password without prompts for confirmation (scriptable)")]
+    Passwd {
         #[arg(
-            long = "plain-keys",
-            help = "Comma-separated list of keys to store as plain text (default: all keys are encrypted)"
+            long,
+            value_name = "VAR",
+            conflicts_with = "new_password_file",
+            help = "Read the new password from this environment variable instead of prompting"
         )]
-        plain_keys: Option<String>,
+        new_password_env: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Read the new password from this file instead of prompting"
+        )]
+        new_password_file: Option<String>,
     },
 
-    #[command(about = "Copy an existing safe to a new safe with new encryption")]
-    Copy {
-        #[arg(help = "Destination safe path")]
-        dest: String,
+    #[command(about = "List all safe files in current directory")]
+    Ls {
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Look for safes in this directory instead of the current one"
+        )]
+        path: Option<String>,
         #[arg(
             short = 'r',
             long,
-            help = "Remember the safe key for automatic authentication"
+            help = "Search subdirectories too (skips .git, node_modules and target)"
         )]
-        remember: bool,
-        #[arg(short = 'd', long, help = "Description for the new safe")]
-        description: Option<String>,
-    },
-
-    #[command(about = "AWS SSM Parameter Store integration")]
-    Ssm {
-        #[command(subcommand)]
-        action: SsmAction,
+        recursive: bool,
+        #[arg(
+            long,
+            value_name = "N",
+            requires = "recursive",
+            help = "With --recursive, search at most N directory levels deep"
+        )]
+        max_depth: Option<usize>,
+        #[arg(long, help = "Only list safes that fail to load")]
+        errors_only: bool,
     },
-}
 
-#[derive(Subcommand)]
-enum SsmAction {
-    #[command(about = "Pull parameters from AWS SSM Parameter Store into safe")]
-    Pull {
+    #[command(about = "Output secrets for shell sourcing")]
+    Env {
         #[arg(
             long,
-            help = "SSM parameter path prefix (e.g., /myapp/dev/). If omitted, uses the safe's stored prefix"
+            value_name = "P",
+            help = "Only output keys starting with this prefix"
         )]
         prefix: Option<String>,
-        #[arg(long, help = "AWS region (default: from AWS config)")]
-        region: Option<String>,
+        #[arg(
+            long,
+            requires = "prefix",
+            help = "Remove the prefix from output variable names"
+        )]
+        strip_prefix: bool,
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Only output secrets with this tag (repeatable; all must match)"
+        )]
+        tags: Vec<String>,
+        #[arg(
+            long,
+            value_parser = ["bash", "zsh", "fish", "nu", "powershell", "cmd", "csh"],
+            help = "Shell syntax to emit instead of detecting the current shell"
+        )]
+        shell: Option<String>,
+        #[arg(
+            long,
+            help = "Fail, listing the keys, if any value can't be decrypted instead of skipping it"
+        )]
+        strict: bool,
+    },
+
+    #[command(about = "Output secrets in KEY=value format for piping to external commands")]
+    Export {
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Write to this file (mode 0600) instead of stdout"
+        )]
+        file: Option<String>,
+        #[arg(
+            short = 'f',
+            long,
+            requires = "file",
+            help = "Overwrite the file if it already exists"
+        )]
+        force: bool,
+        #[arg(
+            long,
+            help = "Fail, listing the keys, if any value can't be decrypted instead of skipping it"
+        )]
+        strict: bool,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t,
+            help = "Key order: asc, desc, or insertion (as stored in the safe file)"
+        )]
+        sort: types::SortOrder,
+    },
+
+    #[command(
+        about = "Write decrypted secrets to a dotenv file (mode 0600) for tools that need a real .env"
+    )]
+    WriteEnv {
+        #[arg(
+            short = 'o',
+            long,
+            value_name = "PATH",
+            default_value = commands::write_env::DEFAULT_ENV_FILE,
+            help = "File to write"
+        )]
+        output: String,
+        #[arg(
+            long,
+            value_name = "KEYS",
+            help = "Comma-separated keys to write, others are left out (supports globs like AWS_*)"
+        )]
+        only: Option<String>,
+        #[arg(
+            long,
+            value_name = "KEYS",
+            help = "Comma-separated keys to leave out (supports globs like AWS_*)"
+        )]
+        exclude: Option<String>,
+        #[arg(long, help = "Append to the file instead of replacing it")]
+        append: bool,
+        #[arg(
+            long,
+            help = "Add the file to the .gitignore in its directory, creating it if needed"
+        )]
+        gitignore: bool,
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Delete the file again after this many seconds"
+        )]
+        ttl: Option<u64>,
+    },
+
+    #[command(about = "Render a file, replacing {{KEY}} placeholders with secrets from the safe")]
+    Template {
+        #[arg(value_name = "FILE", help = "Template to render, or - for stdin")]
+        input: String,
+        #[arg(
+            short = 'o',
+            long,
+            value_name = "PATH",
+            help = "File to write (mode 0600), or - for stdout (default)"
+        )]
+        output: Option<String>,
+        #[arg(long, help = "Also replace ${KEY} placeholders")]
+        dollar: bool,
+        #[arg(long, help = "Render placeholders for keys that are not in the safe")]
+        allow_missing: bool,
+        #[arg(
+            long,
+            value_enum,
+            value_name = "POLICY",
+            default_value_t,
+            requires = "allow_missing",
+            help = "What unknown placeholders become with --allow-missing"
+        )]
+        missing: commands::render::MissingPolicy,
+    },
+
+    #[command(about = "Remember safe key for easy access")]
+    RememberSafekey {
+        #[arg(
+            long,
+            help = "Store the safe key in the OS keychain instead of a key file"
+        )]
+        keyring: bool,
+    },
+
+    #[command(about = "Delete the safe and forget its remembered key")]
+    Destroy {
+        #[arg(
+            long,
+            help = "Only forget the remembered key (key file and OS keychain entry); keep the safe"
+        )]
+        keep_safe: bool,
+        #[arg(long, help = "Delete the file even if it doesn't parse as a safe")]
+        force: bool,
+    },
+
+    #[command(about = "Cache unlocked safe passwords in memory for this session")]
+    Agent {
+        #[command(subcommand)]
+        action: AgentAction,
+    },
+
+    #[command(about = "Clean up old saved keys")]
+    CleanupKeys {
+        #[arg(
+            long = "older-than-days",
+            required_unless_present = "orphans",
+            help = "Remove keys not accessed for N days"
+        )]
+        older_than_days: Option<u64>,
+        #[arg(
+            long,
+            help = "Remove keys whose safe no longer exists at its last known path"
+        )]
+        orphans: bool,
+        #[arg(long, help = "Show what would be removed without actually removing")]
+        dry_run: bool,
+    },
+
+    #[command(about = "Restrict saved key files to their owner (mode 600)")]
+    FixPerms {
+        #[arg(
+            long,
+            help = "Only report key files other users can read, exiting non-zero if there are any"
+        )]
+        check: bool,
+    },
+
+    #[command(about = "Import secrets from existing cleartext file into safe")]
+    Import {
+        #[arg(
+            short = 'f',
+            long = "file",
+            help = "Path to the input file to import (dotenv, .json or .yaml/.yml)"
+        )]
+        file: String,
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            help = "Input file syntax: env, json or yaml (default: from the file extension)"
+        )]
+        input_format: Option<commands::import::InputFormat>,
+        #[arg(
+            long,
+            value_name = "SEP",
+            default_value = commands::import::DEFAULT_FLATTEN_SEPARATOR,
+            help = "Join nested JSON/YAML keys with this separator (db.host becomes DB_HOST)"
+        )]
+        flatten_separator: String,
+        #[arg(
+            long = "plain-keys",
+            help = "Comma-separated list of keys to store as plain text (default: all keys are encrypted)"
+        )]
+        plain_keys: Option<String>,
+        #[arg(
+            long,
+            value_name = "VAR",
+            conflicts_with = "password_file",
+            help = "Read the new safe password from this environment variable instead of prompting"
+        )]
+        password_env: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Read the new safe password from this file instead of prompting"
+        )]
+        password_file: Option<String>,
+        #[arg(
+            long,
+            visible_alias = "merge",
+            conflicts_with_all = ["password_env", "password_file"],
+            help = "Merge into the existing safe instead of creating a new one (authenticates as usual)"
+        )]
+        into: bool,
+        #[arg(
+            long,
+            requires = "into",
+            conflicts_with = "no_overwrite",
+            help = "Replace existing keys without asking"
+        )]
+        overwrite: bool,
+        #[arg(
+            long = "no-overwrite",
+            requires = "into",
+            help = "Keep existing keys without asking (default: ask per key, or keep with --no-input)"
+        )]
+        no_overwrite: bool,
+        #[arg(
+            long,
+            requires = "into",
+            help = "Keep the existing ciphertext of keys whose value is unchanged (avoids noisy diffs)"
+        )]
+        stable: bool,
+    },
+
+    #[command(about = "Edit all secrets in $EDITOR and re-encrypt on save")]
+    Edit {
+        #[arg(help = "Edit only this secret's value (default: edit all secrets)")]
+        key: Option<String>,
+        #[arg(
+            long = "plain-keys",
+            conflicts_with = "key",
+            help = "Comma-separated list of keys to store as plain text"
+        )]
+        plain_keys: Option<String>,
+    },
+
+    #[command(about = "Copy an existing safe to a new safe with new encryption")]
+    Copy {
+        #[arg(help = "Destination safe path")]
+        dest: String,
+        #[arg(
+            short = 'r',
+            long,
+            help = "Remember the safe key for automatic authentication"
+        )]
+        remember: bool,
+        #[arg(
+            long,
+            help = "Store the remembered safe key in the OS keychain instead of a key file"
+        )]
+        keyring: bool,
+        #[arg(short = 'd', long, help = "Description for the new safe")]
+        description: Option<String>,
+        #[arg(
+            long,
+            value_name = "KEYS",
+            help = "Comma-separated keys to copy, others are left out (supports globs like AWS_*)"
+        )]
+        keys: Option<String>,
+        #[arg(
+            long,
+            value_name = "KEYS",
+            help = "Comma-separated keys to leave out (supports globs like AWS_*)"
+        )]
+        exclude: Option<String>,
+        #[arg(
+            long,
+            value_name = "VAR",
+            conflicts_with = "password_file",
+            help = "Read the new safe password from this environment variable instead of prompting"
+        )]
+        password_env: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Read the new safe password from this file instead of prompting"
+        )]
+        password_file: Option<String>,
+        #[arg(
+            long,
+            conflicts_with_all = ["password_env", "password_file"],
+            help = "Generate a passphrase of random words as the new safe password and print it"
+        )]
+        passphrase: bool,
+    },
+
+    #[command(about = "Merge secrets from another safe into this safe")]
+    Merge {
+        #[arg(help = "Source safe path to merge from")]
+        source: String,
+        #[arg(long, help = "Don't overwrite existing keys")]
+        no_overwrite: bool,
+    },
+
+    #[command(about = "Compare secrets with another safe (key names only, values are never shown)")]
+    Diff {
+        #[arg(help = "Other safe path to compare against")]
+        other: String,
+    },
+
+    #[command(about = "AWS SSM Parameter Store integration")]
+    Ssm {
+        #[command(subcommand)]
+        action: SsmAction,
+    },
+
+    #[command(
+        name = "asm",
+        alias = "secrets-manager",
+        about = "AWS Secrets Manager integration"
+    )]
+    SecretsManager {
+        #[command(subcommand)]
+        action: AsmAction,
+    },
+
+    #[command(about = "Google Cloud Secret Manager integration")]
+    Gcp {
+        #[command(subcommand)]
+        action: GcpAction,
+    },
+
+    #[command(about = "HashiCorp Vault KV integration")]
+    Vault {
+        #[command(subcommand)]
+        action: VaultAction,
+    },
+
+    #[command(about = "Create a timestamped copy of the safe file (no password required)")]
+    Backup {
+        #[arg(help = "Directory to write the backup to (default: current directory)")]
+        dir: Option<String>,
+    },
+
+    #[command(about = "Swap a numbered backup kept by --backup/SKIT_BACKUPS back in as the safe")]
+    Restore {
+        #[arg(
+            long = "from-backup",
+            value_name = "N",
+            value_parser = clap::value_parser!(u32).range(1..),
+            help = "Backup to restore: 1 is the most recent (<safe>.bak.1)"
+        )]
+        from_backup: u32,
+    },
+
+    #[command(about = "Show when secrets were set, removed or rotated (values are never logged)")]
+    History {
+        #[arg(long, help = "Only show entries for this key")]
+        key: Option<String>,
+        #[arg(
+            long,
+            value_name = "DATE",
+            help = "Only show entries from this date on (YYYY-MM-DD or RFC 3339)"
+        )]
+        since: Option<String>,
+    },
+
+    #[command(about = "Inspect skit configuration")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    #[command(about = "Print a shell completion script (bash, zsh, fish or powershell)")]
+    Completions {
+        #[arg(value_enum, help = "Shell to generate completions for")]
+        shell: commands::completions::CompletionShell,
+    },
+
+    #[command(hide = true, name = "clear-clipboard")]
+    ClearClipboard {
+        #[arg(long)]
+        after: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum AgentAction {
+    #[command(about = "Start the agent in the background")]
+    Start {
+        #[arg(
+            long,
+            default_value = "8h",
+            help = "Forget each password this long after it was added (e.g. 90s, 15m, 8h, 1d)"
+        )]
+        ttl: String,
+        #[arg(
+            long,
+            default_value = "1h",
+            help = "Forget a password that hasn't been used for this long"
+        )]
+        idle: String,
+        #[arg(long, help = "Run in this process instead of in the background")]
+        foreground: bool,
+    },
+    #[command(about = "Unlock the safe and hand its password to the agent")]
+    Add,
+    #[command(about = "Forget every cached password")]
+    Lock,
+    #[command(about = "Show the agent and the safes it holds unlocked")]
+    Status,
+    #[command(about = "Stop the agent")]
+    Stop,
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    #[command(about = "Show the effective configuration and where each setting comes from")]
+    Show,
+}
+
+#[derive(Subcommand)]
+enum AsmAction {
+    #[command(about = "Pull secrets from AWS Secrets Manager into safe (stored encrypted)")]
+    Pull {
+        #[arg(
+            long,
+            help = "Secret name prefix (e.g., myapp/dev/). If omitted, uses the safe's stored prefix"
+        )]
+        prefix: Option<String>,
+        #[arg(long, help = "AWS region (default: from AWS config)")]
+        region: Option<String>,
+        #[arg(
+            long,
+            overrides_with = "no_flatten",
+            help = "Store each field of a JSON secret as its own key, e.g. database_host (default)"
+        )]
+        flatten: bool,
+        #[arg(long, help = "Store JSON secrets as a single value")]
+        no_flatten: bool,
         #[arg(long, help = "Replace all existing secrets (default: merge)")]
         replace: bool,
         #[arg(long, help = "Don't overwrite existing keys")]
@@ -205,89 +1070,732 @@ enum SsmAction {
     },
 }
 
-fn normalize_safe_path(safe_name: &str) -> String {
-    // If it's already in the correct format (.*.safe), use as-is
-    if safe_name.starts_with('.') && safe_name.ends_with(".safe") {
-        return safe_name.to_string();
-    }
+#[derive(Subcommand)]
+enum GcpAction {
+    #[command(about = "Pull secrets from Google Secret Manager into safe (stored encrypted)")]
+    Pull {
+        #[arg(
+            long,
+            help = "Google Cloud project ID. If omitted, uses the safe's stored project"
+        )]
+        project: Option<String>,
+        #[arg(long, help = "Only pull secrets whose name starts with this prefix")]
+        prefix: Option<String>,
+        #[arg(long, help = "Replace all existing secrets (default: merge)")]
+        replace: bool,
+        #[arg(long, help = "Don't overwrite existing keys")]
+        no_overwrite: bool,
+        #[arg(long, help = "Show what would be pulled without actually pulling")]
+        dry_run: bool,
+    },
+}
 
-    // If it already ends with .safe but doesn't start with dot, add dot
-    if safe_name.ends_with(".safe") && !safe_name.starts_with('.') {
-        return format!(".{}", safe_name);
-    }
-    format!(".{}.safe", safe_name)
+#[derive(Subcommand)]
+enum VaultAction {
+    #[command(
+        about = "Pull the keys of a Vault KV v2 secret into safe (stored encrypted; uses VAULT_ADDR/VAULT_TOKEN)"
+    )]
+    Pull {
+        #[arg(
+            help = "Secret path within the mount (e.g., myapp/dev). If omitted, uses the safe's stored path"
+        )]
+        path: Option<String>,
+        #[arg(
+            long,
+            default_value = "secret",
+            help = "Mount path of the KV v2 secrets engine"
+        )]
+        mount: String,
+        #[arg(long, help = "Replace all existing secrets (default: merge)")]
+        replace: bool,
+        #[arg(long, help = "Don't overwrite existing keys")]
+        no_overwrite: bool,
+        #[arg(long, help = "Show what would be pulled without actually pulling")]
+        dry_run: bool,
+    },
 }
 
-fn resolve_format(cli_format: &OutputFormat) -> OutputFormat {
-    cli_format.clone()
+#[derive(Subcommand)]
+enum SsmAction {
+    #[command(
+        about = "List parameter names and types under a prefix without importing or decrypting them"
+    )]
+    List {
+        #[arg(
+            long,
+            help = "SSM parameter path prefix (e.g., /myapp/dev/). If omitted, uses the safe's stored prefix"
+        )]
+        prefix: Option<String>,
+        #[arg(long, help = "AWS region (default: from AWS config)")]
+        region: Option<String>,
+        #[arg(
+            long,
+            help = "AWS profile from ~/.aws/config (default: the safe's stored profile, else AWS_PROFILE)"
+        )]
+        profile: Option<String>,
+    },
+    #[command(about = "Pull parameters from AWS SSM Parameter Store into safe")]
+    Pull {
+        #[arg(
+            long,
+            help = "SSM parameter path prefix (e.g., /myapp/dev/). If omitted, uses the safe's stored prefix"
+        )]
+        prefix: Option<String>,
+        #[arg(long, help = "AWS region (default: from AWS config)")]
+        region: Option<String>,
+        #[arg(
+            long,
+            help = "AWS profile from ~/.aws/config (default: the safe's stored profile, else AWS_PROFILE)"
+        )]
+        profile: Option<String>,
+        #[arg(long, help = "Replace all existing secrets (default: merge)")]
+        replace: bool,
+        #[arg(long, help = "Don't overwrite existing keys")]
+        no_overwrite: bool,
+        #[arg(long, help = "Show what would be pulled without actually pulling")]
+        dry_run: bool,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t,
+            help = "How parameter names become keys: sanitize maps /, - and . to _, none keeps them"
+        )]
+        key_transform: aws::parameters::KeyTransform,
+        #[arg(long, help = "Uppercase keys after transforming them")]
+        upper: bool,
+        #[arg(
+            long,
+            help = "Abort instead of skipping parameters that don't map to valid keys"
+        )]
+        fail_on_invalid: bool,
+    },
+
+    #[command(about = "Push safe secrets to AWS SSM Parameter Store")]
+    Push {
+        #[arg(
+            long,
+            help = "SSM parameter path prefix (e.g., /myapp/dev/). If omitted, uses the safe's stored prefix"
+        )]
+        prefix: Option<String>,
+        #[arg(long, help = "AWS region (default: from AWS config)")]
+        region: Option<String>,
+        #[arg(
+            long,
+            help = "AWS profile from ~/.aws/config (default: the safe's stored profile, else AWS_PROFILE)"
+        )]
+        profile: Option<String>,
+        #[arg(
+            long,
+            help = "Overwrite parameters that already exist in SSM (default: skip them)"
+        )]
+        overwrite: bool,
+        #[arg(
+            long,
+            help = "Delete parameters under the prefix that are not present in the safe"
+        )]
+        delete_missing: bool,
+        #[arg(long, help = "Show what would be pushed without actually pushing")]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() {
-    logging::init_logging();
-
     let cli = Cli::parse();
-    let safe_path = normalize_safe_path(&cli.safe);
-    let format = resolve_format(&cli.format);
+    let config = config::Config::load();
+    logging::init_logging(config.as_ref().is_ok_and(|c| c.no_color().value));
+    let config = config.unwrap_or_else(|e| {
+        tracing::error!("{}", e);
+        process::exit(e.exit_code());
+    });
+
+    input::set_prompt_mode(cli.no_input, cli.yes);
+    input::set_password_attempts(config.password_attempts().value);
+    password::set_password_file(cli.password_file.clone());
+    let cwd = std::env::current_dir().unwrap_or_default();
+    // `--safe auto` searches upward for whatever safe would be used otherwise
+    let (cli_safe, auto_discover) = match cli.safe.as_deref() {
+        Some(config::AUTO_SAFE) => (None, true),
+        other => (other, false),
+    };
+    let resolved_safe = config.safe(cli_safe, &cwd);
+    let mut safe_path = normalize_safe_path(&resolved_safe.value);
+    if auto_discover || (config.discover().value && resolved_safe.source != config::Source::Cli) {
+        match config::discover_safe(&safe_path, &cwd) {
+            Ok(found) => safe_path = found.display().to_string(),
+            Err(tried) => safe::set_searched_paths(&safe_path, tried),
+        }
+    }
+    let resolved_format =
+        config::resolve_format(cli.format.as_ref(), &config).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            process::exit(e.exit_code());
+        });
+    let format = resolved_format.value.clone();
+    let auto_remember = config.auto_remember().value;
+    let resolved_backups = config::resolve_backups(cli.backup, &config).unwrap_or_else(|e| {
+        tracing::error!("{}", e);
+        process::exit(e.exit_code());
+    });
+    safe::set_backup_count(resolved_backups.value);
+
+    // `cat` output is meant for `$(skit cat KEY)` captures, so its errors stay off stdout
+    let errors_to_stderr = matches!(cli.command, Commands::Cat { .. });
 
     let result: Result<(), SkitError> = match cli.command {
         Commands::Init {
             remember,
+            keyring,
             description,
             ssm_prefix,
-        } => commands::init(
+            kdf_mem,
+            kdf_time,
+            cipher,
+            password_env,
+            password_file,
+            min_strength,
+            passphrase,
+            from_template,
+        } => {
+            let defaults = crypto::KdfParams::default();
+            let kdf_params = crypto::KdfParams {
+                memory_kib: kdf_mem.unwrap_or(defaults.memory_kib),
+                time_cost: kdf_time.unwrap_or(defaults.time_cost),
+                ..defaults
+            };
+            commands::init(
+                &safe_path,
+                remember || auto_remember,
+                keyring,
+                description.as_deref(),
+                ssm_prefix.as_deref(),
+                kdf_params,
+                crypto::Cipher::from_name(&cipher).unwrap_or_default(),
+                &password::PasswordSource {
+                    env_var: password_env,
+                    file: password_file,
+                },
+                min_strength,
+                passphrase,
+                from_template.as_deref(),
+            )
+        }
+        Commands::Set {
+            key,
+            value,
+            plain,
+            stdin,
+            from_file,
+            description,
+            tags,
+            if_not_exists,
+            stable,
+        } => commands::set(
             &safe_path,
-            remember,
+            &key,
+            value.as_deref(),
+            plain,
+            stdin,
+            from_file.as_deref(),
             description.as_deref(),
-            ssm_prefix.as_deref(),
+            &tags,
+            if_not_exists,
+            stable,
+        ),
+        Commands::Get { key, copy, timeout } => {
+            commands::get(&safe_path, &key, copy.then_some(timeout))
+        }
+        Commands::Cat { key } => commands::cat(&safe_path, &key),
+        Commands::Generate {
+            key,
+            length,
+            charset,
+            words,
+            force,
+            quiet,
+        } => {
+            let kind = match words {
+                Some(words) => commands::generate::GenerateKind::Passphrase { words },
+                None => commands::generate::GenerateKind::Characters {
+                    length,
+                    charset: password::Charset::from_name(&charset)
+                        .unwrap_or(password::Charset::Alnum),
+                },
+            };
+            commands::generate(&safe_path, &key, kind, force, quiet)
+        }
+        Commands::Print {
+            plain,
+            enc,
+            verbose,
+            tags,
+            raw,
+            strict,
+            sort,
+            k8s_name,
+            k8s_namespace,
+        } => commands::print(
+            &safe_path,
+            &format,
+            plain,
+            enc,
+            verbose,
+            &tags,
+            raw,
+            strict,
+            sort,
+            commands::print::K8sSecretTarget {
+                name: k8s_name,
+                namespace: k8s_namespace,
+            },
+        ),
+        Commands::Keys {
+            complete_keys,
+            tags,
+            size,
+            sort,
+            types,
+            count,
+        } => {
+            if complete_keys {
+                commands::keys::complete_keys(&safe_path)
+            } else {
+                commands::keys(&safe_path, &format, &tags, size, sort, types, count)
+            }
+        }
+        Commands::Example {
+            output,
+            comments,
+            check,
+        } => commands::example(&safe_path, output.as_deref(), comments, check.as_deref()),
+        Commands::Search {
+            pattern,
+            keys_only,
+            ignore_case,
+            reveal,
+        } => commands::search(
+            &safe_path,
+            &pattern,
+            keys_only,
+            ignore_case,
+            reveal,
+            &format,
+        ),
+        Commands::Rm {
+            key,
+            glob,
+            prefix,
+            all,
+        } => {
+            use commands::rm::RmSelector;
+            let selector = match (key, glob, prefix) {
+                (Some(key), _, _) => RmSelector::Key(key),
+                (_, Some(pattern), _) => RmSelector::Glob(pattern),
+                (_, _, Some(prefix)) => RmSelector::Prefix(prefix),
+                _ if all => RmSelector::All,
+                _ => unreachable!("clap requires a key, --glob, --prefix or --all"),
+            };
+            commands::rm(&safe_path, &selector)
+        }
+        Commands::Rename { old, new, force } => commands::rename(&safe_path, &old, &new, force),
+        Commands::Exec {
+            only,
+            exclude,
+            prefix,
+            strip_prefix,
+            tags,
+            isolated,
+            keep,
+            env,
+            no_strict,
+            command,
+        } => commands::exec::ExecEnvironment::new(isolated, keep.as_deref(), &env).and_then(
+            |environment| {
+                commands::exec(
+                    &safe_path,
+                    &command,
+                    &key_filter::KeyFilter::new(only.as_deref(), exclude.as_deref())
+                        .with_prefix(prefix.as_deref(), strip_prefix)
+                        .with_tags(&tags),
+                    &environment,
+                    !no_strict,
+                )
+            },
+        ),
+        Commands::Status { fast } => commands::status(&safe_path, &format, fast),
+        Commands::Verify => commands::verify(&safe_path, &format),
+        Commands::Lint => commands::lint(&safe_path, &format),
+        Commands::Schema { check } => match check {
+            Some(schema_path) => commands::schema_check(&safe_path, &format, &schema_path),
+            None => commands::schema_export(&safe_path),
+        },
+        Commands::Doctor => commands::doctor(&safe_path, &format),
+        Commands::Migrate => commands::migrate(&safe_path),
+        Commands::Rotate {
+            current_password_env,
+            new_password_env,
+            backup,
+            min_strength,
+            passphrase,
+            cipher,
+        } => commands::rotate(
+            &safe_path,
+            &password::PasswordSource::from_env(current_password_env),
+            &password::PasswordSource::from_env(new_password_env),
+            backup,
+            min_strength,
+            passphrase,
+            cipher.as_deref().and_then(crypto::Cipher::from_name),
+        ),
+        Commands::Passwd {
+            new_password_env,
+            new_password_file,
+        } => commands::passwd(
+            &safe_path,
+            &password::PasswordSource {
+                env_var: new_password_env,
+                file: new_password_file,
+            },
+        ),
+        Commands::Ls {
+            path,
+            recursive,
+            max_depth,
+            errors_only,
+        } => commands::ls(
+            &format,
+            &commands::ls::LsOptions {
+                path,
+                recursive,
+                max_depth,
+                errors_only,
+            },
         ),
-        Commands::Set { key, value, plain } => commands::set(&safe_path, &key, &value, plain),
-        Commands::Get { key } => commands::get(&safe_path, &key),
-        Commands::Print { plain, enc } => commands::print(&safe_path, &format, plain, enc),
-        Commands::Keys => commands::keys(&safe_path, &format),
-        Commands::Rm { key } => commands::rm(&safe_path, &key),
-        Commands::Exec { command } => commands::exec(&safe_path, &command),
-        Commands::Status => commands::status(&safe_path, &format),
-        Commands::Rotate => commands::rotate(&safe_path),
-        Commands::Ls => commands::ls(&format),
-        Commands::Env => commands::env(&safe_path),
-        Commands::Export => commands::export(&safe_path),
-        Commands::RememberSafekey => commands::remember_safekey(&safe_path),
+        Commands::Env {
+            prefix,
+            strip_prefix,
+            tags,
+            shell,
+            strict,
+        } => commands::env(
+            &safe_path,
+            key_filter::KeyFilter::default()
+                .with_prefix(prefix.as_deref(), strip_prefix)
+                .with_tags(&tags),
+            shell.as_deref(),
+            strict,
+        ),
+        Commands::Export {
+            file,
+            force,
+            strict,
+            sort,
+        } => commands::export(&safe_path, file.as_deref(), force, strict, sort),
+        Commands::WriteEnv {
+            output,
+            only,
+            exclude,
+            append,
+            gitignore,
+            ttl,
+        } => commands::write_env(
+            &safe_path,
+            &output,
+            key_filter::KeyFilter::new(only.as_deref(), exclude.as_deref()),
+            append,
+            gitignore,
+            ttl,
+        ),
+        Commands::Template {
+            input,
+            output,
+            dollar,
+            allow_missing,
+            missing,
+        } => commands::render(
+            &safe_path,
+            &format,
+            &input,
+            output.as_deref(),
+            dollar,
+            allow_missing.then_some(missing),
+        ),
+        Commands::RememberSafekey { keyring } => commands::remember_safekey(&safe_path, keyring),
+        Commands::Destroy { keep_safe, force } => commands::destroy(&safe_path, keep_safe, force),
         Commands::CleanupKeys {
             older_than_days,
+            orphans,
             dry_run,
-        } => commands::cleanup_keys(older_than_days, dry_run),
-        Commands::Import { file, plain_keys } => {
-            commands::import(&safe_path, &file, plain_keys.as_deref())
+        } => commands::cleanup_keys(older_than_days, orphans, dry_run),
+        Commands::FixPerms { check } => commands::fix_perms(check),
+        Commands::Agent { action } => match action {
+            AgentAction::Start {
+                ttl,
+                idle,
+                foreground,
+            } => commands::agent::agent_start(&ttl, &idle, foreground),
+            AgentAction::Add => commands::agent::agent_add(&safe_path),
+            AgentAction::Lock => commands::agent::agent_lock(),
+            AgentAction::Status => commands::agent::agent_status(&format),
+            AgentAction::Stop => commands::agent::agent_stop(),
+        },
+        Commands::Import {
+            file,
+            input_format,
+            flatten_separator,
+            plain_keys,
+            password_env,
+            password_file,
+            into,
+            overwrite,
+            no_overwrite,
+            stable,
+        } => {
+            let conflict = if overwrite {
+                commands::import::ConflictPolicy::Overwrite
+            } else if no_overwrite {
+                commands::import::ConflictPolicy::NoOverwrite
+            } else {
+                commands::import::ConflictPolicy::Prompt
+            };
+            commands::import(
+                &safe_path,
+                &file,
+                input_format,
+                &flatten_separator,
+                plain_keys.as_deref(),
+                &password::PasswordSource {
+                    env_var: password_env,
+                    file: password_file,
+                },
+                into,
+                conflict,
+                stable,
+            )
+        }
+        Commands::Edit { key, plain_keys } => {
+            commands::edit(&safe_path, key.as_deref(), plain_keys.as_deref())
         }
         Commands::Copy {
             dest,
             remember,
+            keyring,
             description,
+            keys,
+            exclude,
+            password_env,
+            password_file,
+            passphrase,
         } => {
             let dest_path = normalize_safe_path(&dest);
-            commands::copy(&safe_path, &dest_path, remember, description.as_deref())
+            commands::copy(
+                &safe_path,
+                &dest_path,
+                remember || auto_remember,
+                keyring,
+                description.as_deref(),
+                &key_filter::KeyFilter::new(keys.as_deref(), exclude.as_deref()),
+                &password::PasswordSource {
+                    env_var: password_env,
+                    file: password_file,
+                },
+                passphrase,
+            )
+        }
+        Commands::Merge {
+            source,
+            no_overwrite,
+        } => {
+            let source_path = normalize_safe_path(&source);
+            commands::merge(&safe_path, &source_path, no_overwrite)
+        }
+        Commands::Diff { other } => {
+            let other_path = normalize_safe_path(&other);
+            commands::diff(&safe_path, &other_path, &format)
         }
         Commands::Ssm { action } => match action {
+            SsmAction::List {
+                prefix,
+                region,
+                profile,
+            } => commands::ssm::ssm_list(&safe_path, &format, prefix.as_deref(), region, profile),
             SsmAction::Pull {
                 prefix,
                 region,
+                profile,
                 replace,
                 no_overwrite,
                 dry_run,
+                key_transform,
+                upper,
+                fail_on_invalid,
             } => commands::ssm_pull(
                 &safe_path,
                 prefix.as_deref(),
                 region,
+                profile,
+                replace,
+                no_overwrite,
+                dry_run,
+                key_transform,
+                upper,
+                fail_on_invalid,
+            ),
+            SsmAction::Push {
+                prefix,
+                region,
+                profile,
+                overwrite,
+                delete_missing,
+                dry_run,
+            } => commands::ssm_push(
+                &safe_path,
+                prefix.as_deref(),
+                region,
+                profile,
+                overwrite,
+                delete_missing,
+                dry_run,
+            ),
+        },
+        Commands::SecretsManager { action } => match action {
+            AsmAction::Pull {
+                prefix,
+                region,
+                flatten: _,
+                no_flatten,
+                replace,
+                no_overwrite,
+                dry_run,
+            } => commands::asm_pull(
+                &safe_path,
+                prefix.as_deref(),
+                region,
+                !no_flatten,
+                replace,
+                no_overwrite,
+                dry_run,
+            ),
+        },
+        Commands::Gcp { action } => match action {
+            GcpAction::Pull {
+                project,
+                prefix,
+                replace,
+                no_overwrite,
+                dry_run,
+            } => commands::gcp_pull(
+                &safe_path,
+                project.as_deref(),
+                prefix.as_deref(),
                 replace,
                 no_overwrite,
                 dry_run,
             ),
         },
+        Commands::Vault { action } => match action {
+            VaultAction::Pull {
+                path,
+                mount,
+                replace,
+                no_overwrite,
+                dry_run,
+            } => commands::vault_pull(
+                &safe_path,
+                path.as_deref(),
+                &mount,
+                replace,
+                no_overwrite,
+                dry_run,
+            ),
+        },
+        Commands::Backup { dir } => commands::backup(&safe_path, dir.as_deref()),
+        Commands::Restore { from_backup } => commands::restore(&safe_path, from_backup),
+        Commands::History { key, since } => {
+            commands::history(&safe_path, &format, key.as_deref(), since.as_deref())
+        }
+        Commands::Config { action } => match action {
+            ConfigAction::Show => commands::config_show(
+                &config,
+                &resolved_safe,
+                &resolved_format,
+                &resolved_backups,
+                &format,
+            ),
+        },
+        Commands::Completions { shell } => commands::completions(shell, &mut Cli::command()),
+        Commands::ClearClipboard { after } => match std::env::var(clipboard::CLEAR_DIGEST_ENV) {
+            Ok(digest) => clipboard::clear_after(after, &digest),
+            Err(_) => Err(SkitError::ParseError(format!(
+                "{} is not set",
+                clipboard::CLEAR_DIGEST_ENV
+            ))),
+        },
     };
 
     if let Err(e) = result {
-        tracing::error!("{}", e);
-        process::exit(1);
+        // Verification failures are already reported, and a trailing log line would break
+        // machine-readable output
+        if !matches!(e, SkitError::VerificationFailed(_)) {
+            match format {
+                OutputFormat::Json => println!("{}", e.to_json()),
+                _ if errors_to_stderr => eprintln!("{}", e),
+                _ => tracing::error!("{}", e),
+            }
+        }
+        process::exit(e.exit_code());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exec_except_alias() {
+        let cli = Cli::try_parse_from(["skit", "exec", "--except", "A,B", "--", "true"]).unwrap();
+        match cli.command {
+            Commands::Exec { exclude, .. } => assert_eq!(exclude.as_deref(), Some("A,B")),
+            _ => panic!("expected exec"),
+        }
+    }
+
+    #[test]
+    fn test_exec_except_conflicts_with_only() {
+        let result =
+            Cli::try_parse_from(["skit", "exec", "--only", "A", "--except", "B", "--", "true"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_asm_flatten_flags_last_wins() {
+        let no_flatten = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["skit", "asm", "pull"], args].concat()).unwrap();
+            match cli.command {
+                Commands::SecretsManager {
+                    action: AsmAction::Pull { no_flatten, .. },
+                } => no_flatten,
+                _ => panic!("expected asm pull"),
+            }
+        };
+        assert!(!no_flatten(&[]));
+        assert!(no_flatten(&["--no-flatten"]));
+        assert!(no_flatten(&["--flatten", "--no-flatten"]));
+        assert!(!no_flatten(&["--no-flatten", "--flatten"]));
+    }
+
+    #[test]
+    fn test_ssm_list_takes_no_pull_flags() {
+        let cli = Cli::try_parse_from(["skit", "ssm", "list", "--prefix", "/app/"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Ssm {
+                action: SsmAction::List {
+                    prefix: Some(_),
+                    ..
+                }
+            }
+        ));
+        assert!(Cli::try_parse_from(["skit", "ssm", "list", "--replace"]).is_err());
     }
 }
@@ -1,10 +1,49 @@
 use crate::error::SkitError;
 use crate::types::Safe;
 use std::fs;
-use std::path::Path;
+use std::sync::OnceLock;
 use zeroize::Zeroizing;
 
+static PASSWORD_FILE: OnceLock<String> = OnceLock::new();
+
+/// Apply the global `--password-file` option, which takes precedence over every other key source
+pub fn set_password_file(path: Option<String>) {
+    if let Some(path) = path {
+        let _ = PASSWORD_FILE.set(path);
+    }
+}
+
+/// Minimum zxcvbn score (0-4) for a new safe password unless `--min-strength` says otherwise
+pub const DEFAULT_MIN_STRENGTH: u8 = 3;
+
 pub fn validate_password_strength(password: &str) -> Result<(), SkitError> {
+    validate_password_strength_min(password, DEFAULT_MIN_STRENGTH)
+}
+
+/// Check the hard requirements, then require a zxcvbn score of at least `min_strength`
+pub fn validate_password_strength_min(password: &str, min_strength: u8) -> Result<(), SkitError> {
+    validate_password_rules(password)?;
+
+    let entropy = zxcvbn::zxcvbn(password, &[]);
+    let score = u8::from(entropy.score());
+    if score < min_strength {
+        let mut message = format!(
+            "Password is too easy to guess (strength {}/4, at least {} required); it could be cracked offline in {}",
+            score,
+            min_strength,
+            entropy.crack_times().offline_slow_hashing_1e4_per_second()
+        );
+        if let Some(warning) = entropy.feedback().and_then(|f| f.warning()) {
+            message.push_str(&format!(". {}", warning));
+        }
+        return Err(SkitError::ParseError(message));
+    }
+
+    Ok(())
+}
+
+/// Length and character-class requirements every password must meet
+fn validate_password_rules(password: &str) -> Result<(), SkitError> {
     if password.len() < 12 {
         return Err(SkitError::ParseError(
             "Password must be at least 12 characters long".to_string(),
@@ -52,9 +91,20 @@ pub fn validate_password_strength(password: &str) -> Result<(), SkitError> {
     Ok(())
 }
 
+use rand::rngs::OsRng;
 use rand::seq::SliceRandom;
 
+/// Generate a random 12 character password with the top zxcvbn score, so it passes any `--min-strength`
 pub fn generate_secure_password() -> String {
+    loop {
+        let password = generate_random_password();
+        if zxcvbn::zxcvbn(&password, &[]).score() == zxcvbn::Score::Four {
+            return password;
+        }
+    }
+}
+
+fn generate_random_password() -> String {
     let mut rng = rand::thread_rng();
 
     let lowercase = "abcdefghijklmnopqrstuvwxyz".chars().collect::<Vec<char>>();
@@ -96,6 +146,148 @@ pub fn generate_secure_password() -> String {
     password.into_iter().collect()
 }
 
+/// A password supplied up front with `--password-env` or `--password-file` instead of a prompt
+#[derive(Debug, Default)]
+pub struct PasswordSource {
+    pub env_var: Option<String>,
+    pub file: Option<String>,
+}
+
+impl PasswordSource {
+    pub fn from_env(env_var: Option<String>) -> Self {
+        PasswordSource {
+            env_var,
+            file: None,
+        }
+    }
+
+    /// Read the supplied password, or `None` if neither source was given
+    pub fn read(&self) -> Result<Option<String>, SkitError> {
+        if let Some(var) = &self.env_var {
+            return match std::env::var(var) {
+                Ok(password) if !password.is_empty() => Ok(Some(password)),
+                _ => Err(SkitError::InvalidPassword(format!(
+                    "Environment variable {} is not set or empty",
+                    var
+                ))),
+            };
+        }
+
+        if let Some(path) = &self.file {
+            let content = Zeroizing::new(fs::read_to_string(path).map_err(|e| {
+                SkitError::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("Failed to read password file {}: {}", path, e),
+                ))
+            })?);
+            let password = content.trim();
+            if password.is_empty() {
+                return Err(SkitError::InvalidPassword(format!(
+                    "Password file {} is empty",
+                    path
+                )));
+            }
+            return Ok(Some(password.to_string()));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Character sets for randomly generated secrets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    Alnum,
+    Hex,
+    Base64,
+    AsciiPrintable,
+}
+
+impl Charset {
+    pub fn name(self) -> &'static str {
+        match self {
+            Charset::Alnum => "alnum",
+            Charset::Hex => "hex",
+            Charset::Base64 => "base64",
+            Charset::AsciiPrintable => "ascii-printable",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Charset::Alnum,
+            Charset::Hex,
+            Charset::Base64,
+            Charset::AsciiPrintable,
+        ]
+        .into_iter()
+        .find(|charset| charset.name() == name)
+    }
+
+    fn chars(self) -> Vec<char> {
+        let alnum = ('a'..='z').chain('A'..='Z').chain('0'..='9');
+        match self {
+            Charset::Alnum => alnum.collect(),
+            Charset::Hex => ('0'..='9').chain('a'..='f').collect(),
+            Charset::Base64 => alnum.chain(['+', '/']).collect(),
+            // Everything visible from '!' to '~', so no spaces
+            Charset::AsciiPrintable => ('!'..='~').collect(),
+        }
+    }
+}
+
+/// Generate a random secret of `length` characters drawn from `charset` with the OS RNG
+pub fn generate_random_string(length: usize, charset: Charset) -> String {
+    let chars = charset.chars();
+    (0..length)
+        .map(|_| *chars.choose(&mut OsRng).expect("charset not empty"))
+        .collect()
+}
+
+/// Word list for passphrases: the EFF long list (7776 words, 3-9 letters, four of them
+/// hyphenated such as `yo-yo`), so each word adds log2(7776) ≈ 12.9 bits
+const WORDLIST: &str = include_str!("wordlist.txt");
+
+/// Pick `count` words from [`WORDLIST`] with the OS RNG
+fn random_words(count: usize) -> Vec<&'static str> {
+    let wordlist: Vec<&str> = WORDLIST.lines().collect();
+    (0..count)
+        .map(|_| *wordlist.choose(&mut OsRng).expect("wordlist not empty"))
+        .collect()
+}
+
+/// Generate a diceware-style passphrase of `words` random words joined with `-`
+pub fn generate_passphrase(words: usize) -> String {
+    random_words(words).join("-")
+}
+
+/// Words in a generated safe passphrase: about 64.6 bits from the words, plus
+/// log2(10) + log2(4) ≈ 5.3 from the digit and special character, so about 70 bits in total
+pub const SAFE_PASSPHRASE_WORDS: usize = 5;
+
+/// Generate a passphrase usable as a safe password: `word_count` words joined with `-`, the
+/// first one capitalised, then a digit and a special character, so every character class the
+/// validator requires is always present. Retries until zxcvbn gives it the top score.
+pub fn generate_safe_passphrase(word_count: usize) -> String {
+    let special = ['.', '_', '@', '#'];
+    loop {
+        let words = generate_passphrase(word_count.max(2));
+        let mut chars = words.chars();
+        let first = chars
+            .next()
+            .expect("passphrase not empty")
+            .to_ascii_uppercase();
+        let digit = char::from(b'0' + rand::Rng::gen_range(&mut OsRng, 0..10u8));
+        let symbol = *special
+            .choose(&mut OsRng)
+            .expect("special charset not empty");
+        let passphrase = format!("{}{}-{}{}", first, chars.as_str(), digit, symbol);
+        if zxcvbn::zxcvbn(&passphrase, &[]).score() == zxcvbn::Score::Four {
+            return passphrase;
+        }
+    }
+}
+
 pub fn get_env_var_name_for_safe(_safe_path: &str) -> String {
     // Always use SKIT_SAFEKEY environment variable
     // The safe file is determined by the -s/--safe parameter
@@ -107,23 +299,73 @@ pub fn try_get_password_from_env(safe_path: &str) -> Option<String> {
     std::env::var(&env_var_name).ok().filter(|p| !p.is_empty())
 }
 
-/// Touch a key file to update its modification time for cleanup tracking
-fn touch_key_file(key_file: &Path) -> Result<(), SkitError> {
-    use filetime::{FileTime, set_file_mtime};
-    use std::time::SystemTime;
+/// Warn about and tighten a key file other users can read, e.g. one copied in from elsewhere
+#[cfg(windows)]
+fn repair_key_file_acl(key_file: &std::path::Path) {
+    use crate::fs_utils::{is_owner_only, restrict_to_owner};
+
+    match is_owner_only(key_file) {
+        Ok(true) => {}
+        Ok(false) => {
+            tracing::warn!(
+                "Key file {} is accessible to other users; restricting it to the current user",
+                key_file.display()
+            );
+            if let Err(e) = restrict_to_owner(key_file) {
+                tracing::warn!("{}", e);
+            }
+        }
+        Err(e) => tracing::warn!("{}", e),
+    }
+}
+
+/// Environment variable that turns key file permission warnings into errors
+pub const STRICT_PERMS_ENV: &str = "SKIT_STRICT_PERMS";
+
+fn strict_perms() -> bool {
+    std::env::var(STRICT_PERMS_ENV).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+/// Warn about, or with `strict` refuse, a key file or keys directory that other users can
+/// access: anyone who can read a key file can unlock its safe
+#[cfg(unix)]
+fn check_key_file_permissions(key_file: &std::path::Path, strict: bool) -> Result<(), SkitError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut exposed = Vec::new();
+    for path in key_file.parent().into_iter().chain(Some(key_file)) {
+        let mode = fs::metadata(path)
+            .map_err(SkitError::Io)?
+            .permissions()
+            .mode()
+            & 0o777;
+        if mode & 0o077 != 0 {
+            exposed.push(format!("{} (mode {:o})", path.display(), mode));
+        }
+    }
+    if exposed.is_empty() {
+        return Ok(());
+    }
 
-    // Set the modification time to now without changing file content
-    let now = SystemTime::now();
-    let filetime = FileTime::from_system_time(now);
-    set_file_mtime(key_file, filetime).map_err(|e| {
-        SkitError::Io(std::io::Error::new(
-            e.kind(),
-            format!("Failed to touch key file {}: {}", key_file.display(), e),
-        ))
-    })
+    let message = format!(
+        "{} accessible to other users; run 'skit fix-perms' to restrict it",
+        exposed.join(" and ")
+    );
+    if strict {
+        return Err(SkitError::Io(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("Refusing to read key file: {}", message),
+        )));
+    }
+    // On stderr: this runs on every unlock, including `cat` and `env` whose stdout is piped
+    eprintln!("Warning: {}", message);
+    Ok(())
 }
 
-pub fn try_get_password_from_keyfile(safe: &Safe) -> Result<Option<String>, SkitError> {
+pub fn try_get_password_from_keyfile(
+    safe: &Safe,
+    safe_path: &str,
+) -> Result<Option<String>, SkitError> {
     let home_dir = match dirs::home_dir() {
         Some(dir) => dir,
         None => return Ok(None), // No home directory, skip key file lookup
@@ -139,6 +381,9 @@ pub fn try_get_password_from_keyfile(safe: &Safe) -> Result<Option<String>, Skit
         return Ok(None);
     }
 
+    #[cfg(unix)]
+    check_key_file_permissions(&key_file, strict_perms())?;
+
     let password = Zeroizing::new(
         fs::read_to_string(&key_file)
             .map_err(|e| {
@@ -151,10 +396,14 @@ pub fn try_get_password_from_keyfile(safe: &Safe) -> Result<Option<String>, Skit
             .to_string(),
     );
 
-    touch_key_file(&key_file)?;
+    #[cfg(windows)]
+    repair_key_file_acl(&key_file);
 
     match safe.verify_password(&password) {
-        Ok(()) => Ok(Some(password.to_string())),
+        Ok(()) => {
+            crate::key_index::record_use(safe, Some(safe_path));
+            Ok(Some(password.to_string()))
+        }
         Err(_) => Err(SkitError::InvalidPassword(format!(
             "Password in key file {} is invalid",
             key_file.display()
@@ -162,27 +411,119 @@ pub fn try_get_password_from_keyfile(safe: &Safe) -> Result<Option<String>, Skit
     }
 }
 
+pub fn try_get_password_from_keyring(safe: &Safe) -> Result<Option<String>, SkitError> {
+    let password = match crate::keychain::get_password(&safe.uuid) {
+        Ok(Some(password)) => password,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            // A broken keychain backend shouldn't lock users out; fall back to the key file
+            tracing::warn!("Could not read safe key from OS keychain: {}", e);
+            return Ok(None);
+        }
+    };
+
+    match safe.verify_password(&password) {
+        Ok(()) => Ok(Some(password.to_string())),
+        Err(_) => Err(SkitError::InvalidPassword(
+            "Password in OS keychain is invalid".to_string(),
+        )),
+    }
+}
+
+/// Where the auth chain found the safe password
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthSource {
+    PasswordFile,
+    Environment,
+    Agent,
+    Keychain,
+    KeyFile,
+    Prompt,
+}
+
+impl AuthSource {
+    /// Stable identifier used in machine-readable output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthSource::PasswordFile => "password_file",
+            AuthSource::Environment => "environment",
+            AuthSource::Agent => "agent",
+            AuthSource::Keychain => "keychain",
+            AuthSource::KeyFile => "key_file",
+            AuthSource::Prompt => "prompt",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            AuthSource::PasswordFile => "--password-file",
+            AuthSource::Environment => "SKIT_SAFEKEY environment variable",
+            AuthSource::Agent => "skit agent",
+            AuthSource::Keychain => "OS keychain",
+            AuthSource::KeyFile => "saved key file",
+            AuthSource::Prompt => "interactive prompt",
+        }
+    }
+}
+
 pub fn get_password_with_auth_chain(
     safe: &Safe,
     safe_path: &str,
     prompt_message: &str,
 ) -> Result<String, SkitError> {
     get_password_with_auth_chain_formatted(safe, safe_path, prompt_message, None)
+        .map(|(password, _)| password)
 }
 
+/// Find the safe password: `--password-file`, `SKIT_SAFEKEY`, the agent, the OS keychain, a
+/// saved key file, then a prompt. Returns the password with the source that supplied it.
+/// Machine-readable formats leave out the info log naming the source.
 pub fn get_password_with_auth_chain_formatted(
     safe: &Safe,
     safe_path: &str,
     prompt_message: &str,
     format: Option<&crate::OutputFormat>,
-) -> Result<String, SkitError> {
-    let suppress_info = matches!(
+) -> Result<(String, AuthSource), SkitError> {
+    let quiet = matches!(
         format,
         Some(crate::OutputFormat::Json)
+            | Some(crate::OutputFormat::Yaml)
             | Some(crate::OutputFormat::Env)
             | Some(crate::OutputFormat::Terraform)
+            | Some(crate::OutputFormat::Toml)
+            | Some(crate::OutputFormat::K8sSecret)
             | Some(crate::OutputFormat::Postman)
     );
+    get_password_with_auth_chain_quiet(safe, safe_path, prompt_message, quiet)
+}
+
+/// The auth chain of [`get_password_with_auth_chain_formatted`], with `quiet` leaving out the
+/// info log that names the source
+pub fn get_password_with_auth_chain_quiet(
+    safe: &Safe,
+    safe_path: &str,
+    prompt_message: &str,
+    quiet: bool,
+) -> Result<(String, AuthSource), SkitError> {
+    if let Some(path) = PASSWORD_FILE.get() {
+        let source = PasswordSource {
+            env_var: None,
+            file: Some(path.clone()),
+        };
+        let password = Zeroizing::new(source.read()?.unwrap_or_default());
+        return match safe.verify_password(&password) {
+            Ok(()) => {
+                if !quiet {
+                    tracing::info!("📄 Using safe key from password file");
+                }
+                Ok((password.to_string(), AuthSource::PasswordFile))
+            }
+            Err(_) => Err(SkitError::InvalidPassword(format!(
+                "Invalid password in password file {}",
+                path
+            ))),
+        };
+    }
 
     let env_var_name = get_env_var_name_for_safe(safe_path);
     if let Ok(password_raw) = std::env::var(&env_var_name)
@@ -191,10 +532,10 @@ pub fn get_password_with_auth_chain_formatted(
         let password = Zeroizing::new(password_raw);
         match safe.verify_password(&password) {
             Ok(()) => {
-                if !suppress_info {
+                if !quiet {
                     tracing::info!("🌍 Using safe key from environment");
                 }
-                return Ok(password.to_string());
+                return Ok((password.to_string(), AuthSource::Environment));
             }
             Err(_) => {
                 return Err(SkitError::InvalidPassword(format!(
@@ -205,22 +546,225 @@ pub fn get_password_with_auth_chain_formatted(
         }
     }
 
-    if let Some(password) = try_get_password_from_keyfile(safe)? {
-        if !suppress_info {
+    if let Some(password) = crate::agent::get_password(&safe.uuid)
+        && safe.verify_password(&password).is_ok()
+    {
+        if !quiet {
+            tracing::info!("🔓 Using safe key from agent");
+        }
+        return Ok((password.to_string(), AuthSource::Agent));
+    }
+
+    if let Some(password) = try_get_password_from_keyring(safe)? {
+        crate::key_index::record_use(safe, Some(safe_path));
+        if !quiet {
+            tracing::info!("🔐 Using safe key from OS keychain");
+        }
+        return Ok((password, AuthSource::Keychain));
+    }
+
+    if let Some(password) = try_get_password_from_keyfile(safe, safe_path)? {
+        if !quiet {
             tracing::info!("🔐 Using saved safe key");
         }
-        return Ok(password);
+        return Ok((password, AuthSource::KeyFile));
     }
 
     // Finally, fall back to prompting with visual feedback
-    let password =
-        crate::input::prompt_password_with_fallback(prompt_message).map_err(SkitError::Io)?;
-    println!(); // Add line break after password prompt
+    crate::input::require_interactive(&format!(
+        "set {} or save the key with 'skit remember-safekey'",
+        env_var_name
+    ))?;
+    let password = prompt_current_password(safe, prompt_message)?;
+    // A running agent remembers what was typed for the rest of the session
+    if crate::agent::is_running()
+        && let Err(e) = crate::agent::add_password(&safe.uuid, safe_path, &password)
+    {
+        tracing::warn!("Could not hand the safe key to the agent: {}", e);
+    }
+    Ok((password, AuthSource::Prompt))
+}
 
-    match safe.verify_password(&password) {
-        Ok(()) => Ok(password),
-        Err(_) => Err(SkitError::InvalidPassword(
-            "Invalid password from interactive prompt".to_string(),
-        )),
+/// Prompt for the safe's current password, letting the user retry a typo
+pub fn prompt_current_password(safe: &Safe, prompt_message: &str) -> Result<String, SkitError> {
+    retry_password_prompt(
+        crate::input::password_attempts(),
+        || {
+            let password = crate::input::prompt_password_with_fallback(prompt_message)?;
+            println!(); // Add line break after password prompt
+            Ok(password)
+        },
+        |password| safe.verify_password(password).is_ok(),
+    )
+}
+
+fn retry_password_prompt(
+    attempts: u32,
+    mut prompt: impl FnMut() -> std::io::Result<String>,
+    verify: impl Fn(&str) -> bool,
+) -> Result<String, SkitError> {
+    for attempt in 1..=attempts {
+        let password = Zeroizing::new(prompt().map_err(SkitError::Io)?);
+        if verify(&password) {
+            return Ok(password.to_string());
+        }
+
+        let remaining = attempts - attempt;
+        if remaining > 0 {
+            eprintln!(
+                "Password incorrect, {} attempt{} remaining",
+                remaining,
+                if remaining == 1 { "" } else { "s" }
+            );
+        }
+    }
+
+    Err(SkitError::InvalidPassword(
+        "Invalid password from interactive prompt".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_file_permissions_check() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let keys_dir = temp_dir.path().join("keys");
+        fs::create_dir(&keys_dir).unwrap();
+        fs::set_permissions(&keys_dir, fs::Permissions::from_mode(0o700)).unwrap();
+        let key_file = keys_dir.join("u.key");
+        fs::write(&key_file, "password").unwrap();
+
+        fs::set_permissions(&key_file, fs::Permissions::from_mode(0o600)).unwrap();
+        assert!(check_key_file_permissions(&key_file, true).is_ok());
+
+        // Readable by others: only a warning unless strict
+        fs::set_permissions(&key_file, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(check_key_file_permissions(&key_file, false).is_ok());
+        let err = check_key_file_permissions(&key_file, true).unwrap_err();
+        assert!(err.to_string().contains("mode 644"), "{}", err);
+
+        fs::set_permissions(&key_file, fs::Permissions::from_mode(0o600)).unwrap();
+        fs::set_permissions(&keys_dir, fs::Permissions::from_mode(0o755)).unwrap();
+        let err = check_key_file_permissions(&key_file, true).unwrap_err();
+        assert!(err.to_string().contains("mode 755"), "{}", err);
+    }
+
+    #[test]
+    fn test_generate_random_string_length_and_charset() {
+        for charset in [
+            Charset::Alnum,
+            Charset::Hex,
+            Charset::Base64,
+            Charset::AsciiPrintable,
+        ] {
+            let allowed = charset.chars();
+            let value = generate_random_string(64, charset);
+            assert_eq!(value.chars().count(), 64);
+            assert!(value.chars().all(|c| allowed.contains(&c)), "{}", value);
+        }
+        assert_eq!(generate_random_string(0, Charset::Hex), "");
+    }
+
+    #[test]
+    fn test_charset_sizes() {
+        assert_eq!(Charset::Alnum.chars().len(), 62);
+        assert_eq!(Charset::Hex.chars().len(), 16);
+        assert_eq!(Charset::Base64.chars().len(), 64);
+        assert_eq!(Charset::AsciiPrintable.chars().len(), 94);
+        assert!(!Charset::AsciiPrintable.chars().contains(&' '));
+    }
+
+    #[test]
+    fn test_charset_names_round_trip() {
+        for name in ["alnum", "hex", "base64", "ascii-printable"] {
+            assert_eq!(Charset::from_name(name).unwrap().name(), name);
+        }
+        assert!(Charset::from_name("unicode").is_none());
+    }
+
+    #[test]
+    fn test_password_prompt_retries() {
+        let answers = ["typo", "correct", "unused"];
+
+        let mut prompts = 0;
+        let password = retry_password_prompt(
+            3,
+            || {
+                prompts += 1;
+                Ok(answers[prompts - 1].to_string())
+            },
+            |p| p == "correct",
+        )
+        .unwrap();
+        assert_eq!(password, "correct");
+        assert_eq!(prompts, 2);
+
+        let mut prompts = 0;
+        let result = retry_password_prompt(
+            3,
+            || {
+                prompts += 1;
+                Ok("typo".to_string())
+            },
+            |p| p == "correct",
+        );
+        assert!(matches!(result, Err(SkitError::InvalidPassword(_))));
+        assert_eq!(prompts, 3);
+
+        // A single attempt never prompts twice
+        let mut prompts = 0;
+        let result = retry_password_prompt(
+            1,
+            || {
+                prompts += 1;
+                Ok(answers[prompts - 1].to_string())
+            },
+            |p| p == "correct",
+        );
+        assert!(result.is_err());
+        assert_eq!(prompts, 1);
+    }
+
+    #[test]
+    fn test_min_strength() {
+        // Meets every character rule but is a dictionary word with a suffix
+        assert!(validate_password_rules("Password1234.").is_ok());
+        assert!(validate_password_strength("Password1234.").is_err());
+        assert!(validate_password_strength_min("Password1234.", 0).is_ok());
+
+        assert!(validate_password_strength("Initial.Pass-123").is_ok());
+        for _ in 0..5 {
+            assert!(validate_password_strength_min(&generate_secure_password(), 4).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_generate_safe_passphrase() {
+        for _ in 0..5 {
+            let passphrase = generate_safe_passphrase(SAFE_PASSPHRASE_WORDS);
+            assert!(validate_password_strength_min(&passphrase, 4).is_ok());
+            // A hyphenated list word like `t-shirt` adds a `-`
+            assert!(passphrase.split('-').count() > SAFE_PASSPHRASE_WORDS);
+            assert!(passphrase.starts_with(|c: char| c.is_ascii_uppercase()));
+        }
+    }
+
+    #[test]
+    fn test_generate_passphrase() {
+        let words: HashSet<&str> = WORDLIST.lines().collect();
+        assert_eq!(words.len(), 7776);
+        assert_eq!(WORDLIST.lines().count(), 7776);
+
+        let picked = random_words(6);
+        assert_eq!(picked.len(), 6);
+        assert!(picked.iter().all(|w| words.contains(w)));
+        assert!(generate_passphrase(6).split('-').count() >= 6);
     }
 }
@@ -1,15 +1,149 @@
 use crate::crypto;
+use crate::dotenv::{quote_value, unquote_value};
 use crate::error::SkitError;
-use crate::types::{Safe, SafeItem};
+use crate::types::{ItemMetadata, Safe, SafeItem, SortOrder};
+use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU32, Ordering};
+use zeroize::Zeroizing;
+
+static BACKUP_COUNT: AtomicU32 = AtomicU32::new(0);
+static SEARCHED_PATHS: OnceLock<(String, Vec<PathBuf>)> = OnceLock::new();
+
+/// Apply the `--backup` flag / `SKIT_BACKUPS` setting: numbered backups kept on every save
+pub fn set_backup_count(keep: u32) {
+    BACKUP_COUNT.store(keep, Ordering::Relaxed);
+}
+
+/// Record the paths safe discovery tried without finding `path`, for its not-found error
+pub fn set_searched_paths(path: &str, tried: Vec<PathBuf>) {
+    let _ = SEARCHED_PATHS.set((path.to_string(), tried));
+}
+
+/// The missing safe plus every path that was tried for it
+fn describe_missing(path: &str) -> String {
+    // Only the discovered safe has a search behind it; copy/merge/diff targets are looked up as given
+    let tried: Vec<String> = match SEARCHED_PATHS.get() {
+        Some((searched_for, searched)) if searched_for == path && !searched.is_empty() => {
+            searched.iter().map(|p| p.display().to_string()).collect()
+        }
+        _ => match std::path::absolute(path) {
+            Ok(absolute) if absolute != Path::new(path) => vec![absolute.display().to_string()],
+            _ => return path.to_string(),
+        },
+    };
+    format!("{} (tried {})", path, tried.join(", "))
+}
+
+/// Turn a safe name into its file path: bare names like `myproject` become `.myproject.safe`,
+/// while paths (with a separator, a drive letter or a leading `~`) are used as given
+pub fn normalize_safe_path(safe_name: &str) -> String {
+    // Expand `~` and `~/...` to the home directory; the result is a path, used as given
+    if let Some(rest) = safe_name.strip_prefix('~')
+        && (rest.is_empty() || rest.starts_with(['/', '\\']))
+        && let Some(home) = dirs::home_dir()
+    {
+        return format!("{}{}", home.display(), rest);
+    }
+
+    // Absolute paths, paths with separators and Windows drive paths are used as given
+    if is_path(safe_name) {
+        return safe_name.to_string();
+    }
+
+    // If it's already in the correct format (.*.safe), use as-is
+    if safe_name.starts_with('.') && safe_name.ends_with(".safe") {
+        return safe_name.to_string();
+    }
+
+    // If it already ends with .safe but doesn't start with dot, add dot
+    if safe_name.ends_with(".safe") && !safe_name.starts_with('.') {
+        return format!(".{}", safe_name);
+    }
+    format!(".{}.safe", safe_name)
+}
+
+/// Whether a safe name is a path rather than a bare name that gets the `.NAME.safe` convention
+fn is_path(safe_name: &str) -> bool {
+    let bytes = safe_name.as_bytes();
+    let has_drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    has_drive || safe_name.contains(['/', '\\']) || std::path::Path::new(safe_name).is_absolute()
+}
+
+/// A safe together with its verified password, for reading and writing decrypted values.
+/// The password is zeroed when this is dropped.
+pub struct UnlockedSafe {
+    safe: Safe,
+    password: Zeroizing<String>,
+}
+
+impl UnlockedSafe {
+    /// The decrypted value of `key`, or `None` if there is no such key or its value can't
+    /// be decrypted
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.try_get(key).ok().flatten()
+    }
+
+    /// Like [`get`](Self::get), but a value that fails to decrypt is an error
+    pub fn try_get(&self, key: &str) -> Result<Option<String>, SkitError> {
+        let Some(item) = self.safe.find_item(key) else {
+            return Ok(None);
+        };
+        if !item.is_encrypted {
+            return Ok(Some(item.value.clone()));
+        }
+        let value = crypto::DecryptBuilder::new()
+            .kdf_params(self.safe.kdf_params)
+            .master_key(self.safe.master_key.as_deref())
+            .ciphertext(&item.value)
+            .context(key)
+            .password(&self.password)
+            .decrypt()?;
+        Ok(Some(value))
+    }
+
+    /// Store `value` under `key`, encrypted like `skit set`. Call [`save`](Self::save) to
+    /// write the change.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), SkitError> {
+        let encrypted = crypto::EncryptBuilder::new()
+            .kdf_params(self.safe.kdf_params)
+            .master_key(self.safe.master_key.as_deref())
+            .cipher(self.safe.cipher)
+            .plaintext(value)
+            .context(key)
+            .password(&self.password)
+            .encrypt()?;
+        self.safe
+            .add_or_update_item(key.to_string(), encrypted, true);
+        Ok(())
+    }
+
+    /// Every key in the safe, in file order
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.safe.items.keys().map(String::as_str)
+    }
+
+    /// Write the safe to `path`
+    pub fn save(&mut self, path: &str) -> Result<(), SkitError> {
+        self.safe.save(path)
+    }
+
+    /// The underlying safe, e.g. for its description or item metadata
+    pub fn safe(&self) -> &Safe {
+        &self.safe
+    }
+}
 
 impl Safe {
+    /// Read and parse the safe file at `path`
     pub fn load(path: &str) -> Result<Self, SkitError> {
         let content = fs::read_to_string(path).map_err(|e| {
             if e.kind() == io::ErrorKind::NotFound {
-                SkitError::SafeNotFound(path.to_string())
+                SkitError::SafeNotFound(describe_missing(path))
             } else {
                 SkitError::Io(e)
             }
@@ -34,7 +168,14 @@ impl Safe {
             password_hash: crypto::hash_password(password)?,
             ssm_prefix: None,
             ssm_region: None,
-            items: HashMap::new(),
+            ssm_profile: None,
+            asm_prefix: None,
+            gcp_project: None,
+            vault_path: None,
+            kdf_params: crypto::KdfParams::default(),
+            cipher: crypto::Cipher::default(),
+            master_key: None,
+            items: IndexMap::new(),
         })
     }
 
@@ -47,7 +188,18 @@ impl Safe {
         let mut password_hash = String::new();
         let mut ssm_prefix: Option<String> = None;
         let mut ssm_region: Option<String> = None;
-        let mut items = HashMap::new();
+        let mut ssm_profile: Option<String> = None;
+        let mut asm_prefix: Option<String> = None;
+        let mut gcp_project: Option<String> = None;
+        let mut vault_path: Option<String> = None;
+        // Safes written before the KDF cost was configurable use the defaults
+        let mut kdf_params = crypto::KdfParams::default();
+        let mut cipher = crypto::Cipher::default();
+        let mut master_key: Option<String> = None;
+        // Safes written before plain values could be quoted store them verbatim
+        let mut quoted_values = false;
+        let mut items = IndexMap::new();
+        let mut annotations: HashMap<String, ItemMetadata> = HashMap::new();
 
         for (line_num, line) in content.lines().enumerate() {
             let line = line.trim();
@@ -70,19 +222,39 @@ impl Safe {
                         "PASS_HASH" => password_hash = value.to_string(),
                         "SSM_PREFIX" => ssm_prefix = Some(value.to_string()),
                         "SSM_REGION" => ssm_region = Some(value.to_string()),
+                        "SSM_PROFILE" => ssm_profile = Some(value.to_string()),
+                        "ASM_PREFIX" => asm_prefix = Some(value.to_string()),
+                        "GCP_PROJECT" => gcp_project = Some(value.to_string()),
+                        "VAULT_PATH" => vault_path = Some(value.to_string()),
+                        "KDF_MEM" => kdf_params.memory_kib = parse_kdf_value(field, value)?,
+                        "KDF_TIME" => kdf_params.time_cost = parse_kdf_value(field, value)?,
+                        "KDF_LANES" => kdf_params.lanes = parse_kdf_value(field, value)?,
+                        "MASTER_KEY" => master_key = Some(value.to_string()),
+                        "QUOTED_VALUES" => quoted_values = value == "1",
+                        "CIPHER" => {
+                            cipher = crypto::Cipher::from_name(value).ok_or_else(|| {
+                                SkitError::ParseError(format!("Unknown #@CIPHER value '{}'", value))
+                            })?
+                        }
                         _ => {}
                     }
                 }
                 continue;
             }
 
+            if let Some(annotation) = line.strip_prefix("#%") {
+                let (key, attrs) = annotation.split_once(' ').unwrap_or((annotation, ""));
+                annotations.insert(key.to_string(), parse_item_metadata(attrs));
+                continue;
+            }
+
             if line.starts_with('#') {
                 continue;
             }
 
             if let Some(eq_pos) = line.find('=') {
                 let key = line[..eq_pos].trim().to_string();
-                let value = line[eq_pos + 1..].trim().to_string();
+                let raw_value = line[eq_pos + 1..].trim().to_string();
 
                 if key.is_empty() {
                     return Err(SkitError::ParseError(format!(
@@ -93,18 +265,22 @@ impl Safe {
 
                 // Handle encryption format versioning: v1 (current), legacy, and very old formats
                 let (is_encrypted, _salt, stored_value) =
-                    if let Some(content) = value.strip_prefix("ENC~") {
-                        if content.starts_with("v1~") {
-                            (true, None, value.to_string())
+                    if let Some(content) = raw_value.strip_prefix("ENC~") {
+                        if ["v1~", "v2~", "v3~", "v1k~", "v2k~", "v3k~"]
+                            .iter()
+                            .any(|v| content.starts_with(v))
+                        {
+                            (true, None, raw_value.to_string())
                         } else if let Some(salt_end) = content.find('~') {
                             let salt = content[..salt_end].to_string();
                             let encrypted_data = content[salt_end + 1..].to_string();
                             (true, Some(salt), format!("ENC~{}", encrypted_data))
                         } else {
-                            (true, None, value.to_string())
+                            (true, None, raw_value.to_string())
                         }
                     } else {
-                        (false, None, value)
+                        // Unquoted once the whole header has been read, see below
+                        (false, None, raw_value)
                     };
 
                 items.insert(
@@ -113,6 +289,7 @@ impl Safe {
                         key,
                         value: stored_value,
                         is_encrypted,
+                        metadata: ItemMetadata::default(),
                     },
                 );
             } else {
@@ -156,6 +333,25 @@ impl Safe {
             ));
         }
 
+        kdf_params.validate().map_err(SkitError::ParseError)?;
+
+        // Annotations for keys that no longer exist are dropped
+        for (key, metadata) in annotations {
+            if let Some(item) = items.get_mut(&key) {
+                item.metadata = metadata;
+            }
+        }
+
+        // Plain values may be double-quoted to carry newlines and padding, but only in safes
+        // that say so: older safes may hold values that merely look quoted
+        if quoted_values {
+            for item in items.values_mut().filter(|item| !item.is_encrypted) {
+                if let Some(value) = unquote_value(&item.value) {
+                    item.value = value;
+                }
+            }
+        }
+
         Ok(Safe {
             version,
             uuid,
@@ -165,6 +361,13 @@ impl Safe {
             password_hash,
             ssm_prefix,
             ssm_region,
+            ssm_profile,
+            asm_prefix,
+            gcp_project,
+            vault_path,
+            kdf_params,
+            cipher,
+            master_key,
             items,
         })
     }
@@ -183,6 +386,16 @@ impl Safe {
         content.push_str(&format!("#@CREATED={}\n", self.created));
         content.push_str(&format!("#@UPDATED={}\n", self.updated));
         content.push_str(&format!("#@PASS_HASH={}\n", self.password_hash));
+        content.push_str(&format!("#@KDF_MEM={}\n", self.kdf_params.memory_kib));
+        content.push_str(&format!("#@KDF_TIME={}\n", self.kdf_params.time_cost));
+        content.push_str(&format!("#@KDF_LANES={}\n", self.kdf_params.lanes));
+        if self.cipher != crypto::Cipher::default() {
+            content.push_str(&format!("#@CIPHER={}\n", self.cipher.name()));
+        }
+        content.push_str("#@QUOTED_VALUES=1\n");
+        if let Some(ref master_key) = self.master_key {
+            content.push_str(&format!("#@MASTER_KEY={}\n", master_key));
+        }
 
         if let Some(ref prefix) = self.ssm_prefix {
             content.push_str(&format!("#@SSM_PREFIX={}\n", prefix));
@@ -190,41 +403,819 @@ impl Safe {
         if let Some(ref region) = self.ssm_region {
             content.push_str(&format!("#@SSM_REGION={}\n", region));
         }
+        if let Some(ref profile) = self.ssm_profile {
+            content.push_str(&format!("#@SSM_PROFILE={}\n", profile));
+        }
+        if let Some(ref prefix) = self.asm_prefix {
+            content.push_str(&format!("#@ASM_PREFIX={}\n", prefix));
+        }
+        if let Some(ref project) = self.gcp_project {
+            content.push_str(&format!("#@GCP_PROJECT={}\n", project));
+        }
+        if let Some(ref path) = self.vault_path {
+            content.push_str(&format!("#@VAULT_PATH={}\n", path));
+        }
 
         content.push_str("# ========================================\n");
         content.push_str("# SECRETS (KEY=VALUE or KEY=ENC~<data>)\n");
         content.push_str("# ========================================\n");
 
+        // Items are written in the order they were read, with new keys at the end
+        for item in self.items.values() {
+            let output_value = if item.is_encrypted {
+                item.value.clone()
+            } else {
+                quote_value(&item.value)
+            };
+            if !item.metadata.is_empty() {
+                content.push_str(&format!(
+                    "#%{} {}\n",
+                    item.key,
+                    format_item_metadata(&item.metadata)
+                ));
+            }
+            content.push_str(&format!("{}={}\n", item.key, output_value));
+        }
+
+        crate::fs_utils::rotate_backups(Path::new(path), BACKUP_COUNT.load(Ordering::Relaxed))?;
+        crate::fs_utils::write_file_atomic(Path::new(path), &content)
+    }
+
+    /// Keys in the requested order
+    pub fn sorted_keys(&self, order: SortOrder) -> Vec<&String> {
         let mut keys: Vec<_> = self.items.keys().collect();
-        keys.sort();
+        match order {
+            SortOrder::Asc => keys.sort(),
+            SortOrder::Desc => keys.sort_by(|a, b| b.cmp(a)),
+            SortOrder::Insertion => {}
+        }
+        keys
+    }
 
-        for key in keys {
-            let item = &self.items[key];
-            let output_value = item.value.clone();
-            content.push_str(&format!("{}={}\n", item.key, output_value));
+    /// Values of `items`, in the same order: plain values as stored, encrypted ones decrypted
+    /// concurrently with `password`. Encrypted items get `None` when there is no password.
+    pub fn decrypt_selected(
+        &self,
+        items: &[&SafeItem],
+        password: Option<&str>,
+    ) -> Vec<Option<Result<String, crypto::CryptoError>>> {
+        let ciphertexts: Vec<(&str, &str)> = items
+            .iter()
+            .filter(|item| item.is_encrypted)
+            .map(|item| (item.key.as_str(), item.value.as_str()))
+            .collect();
+        let mut decrypted = match password {
+            Some(password) => crypto::decrypt_many(
+                &ciphertexts,
+                password,
+                &self.kdf_params,
+                self.master_key.as_deref(),
+            ),
+            None => Vec::new(),
         }
+        .into_iter();
 
-        fs::write(path, content)?;
-        Ok(())
+        items
+            .iter()
+            .map(|item| {
+                if item.is_encrypted {
+                    decrypted.next()
+                } else {
+                    Some(Ok(item.value.clone()))
+                }
+            })
+            .collect()
     }
 
     pub fn find_item(&self, key: &str) -> Option<&SafeItem> {
         self.items.get(key)
     }
 
+    /// The existing key closest to `key`, if it is near enough to be a likely typo
+    pub fn closest_key(&self, key: &str) -> Option<&str> {
+        let wanted = key.to_ascii_lowercase();
+        let max_distance = (key.len() / 3).max(1);
+        self.items
+            .keys()
+            .map(|candidate| {
+                (
+                    edit_distance(&wanted, &candidate.to_ascii_lowercase()),
+                    candidate,
+                )
+            })
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, candidate)| candidate.as_str())
+    }
+
+    /// `KeyNotFound` for `key`, suggesting the closest existing key
+    pub fn key_not_found(&self, key: &str) -> SkitError {
+        SkitError::KeyNotFound {
+            key: key.to_string(),
+            suggestion: self.closest_key(key).map(str::to_string),
+        }
+    }
+
+    /// Insert or replace an item, stamping its updated time (and created time when new)
     pub fn add_or_update_item(&mut self, key: String, value: String, is_encrypted: bool) {
+        let now = item_timestamp();
+        let metadata = match self.items.get(&key) {
+            Some(existing) => ItemMetadata {
+                updated: Some(now),
+                ..existing.metadata.clone()
+            },
+            None => ItemMetadata {
+                created: Some(now.clone()),
+                updated: Some(now),
+                ..ItemMetadata::default()
+            },
+        };
         self.items.insert(
             key.clone(),
             SafeItem {
                 key,
                 value,
                 is_encrypted,
+                metadata,
             },
         );
     }
 
+    /// Set or clear the description of an existing item
+    pub fn set_description(&mut self, key: &str, description: Option<String>) {
+        if let Some(item) = self.items.get_mut(key) {
+            item.metadata.description = description.filter(|d| !d.is_empty());
+        }
+    }
+
+    /// Add tags to an existing item, skipping ones it already has
+    pub fn add_tags(&mut self, key: &str, tags: &[String]) {
+        if let Some(item) = self.items.get_mut(key) {
+            for tag in tags {
+                if !item.metadata.tags.contains(tag) {
+                    item.metadata.tags.push(tag.clone());
+                }
+            }
+        }
+    }
+
+    /// Whether an existing item already holds this value with the same encryption. Encrypted
+    /// items are decrypted to compare, so the password is needed for them; an item that fails
+    /// to decrypt is treated as different.
+    pub fn stores_value(
+        &self,
+        key: &str,
+        value: &str,
+        is_encrypted: bool,
+        password: Option<&str>,
+    ) -> bool {
+        let Some(item) = self.find_item(key) else {
+            return false;
+        };
+        if item.is_encrypted != is_encrypted {
+            return false;
+        }
+        if !item.is_encrypted {
+            return item.value == value;
+        }
+        let Some(password) = password else {
+            return false;
+        };
+        crypto::DecryptBuilder::new()
+            .kdf_params(self.kdf_params)
+            .master_key(self.master_key.as_deref())
+            .ciphertext(&item.value)
+            .context(key)
+            .password(password)
+            .decrypt()
+            .is_ok_and(|current| zeroize::Zeroizing::new(current).as_str() == value)
+    }
+
+    /// Check `password` and keep it with the safe, so values can be read and written
+    pub fn unlock(self, password: &str) -> Result<UnlockedSafe, SkitError> {
+        self.verify_password(password)?;
+        Ok(UnlockedSafe {
+            safe: self,
+            password: Zeroizing::new(password.to_string()),
+        })
+    }
+
     pub fn verify_password(&self, password: &str) -> Result<(), SkitError> {
         crypto::verify_password(password, &self.password_hash)
             .map_err(|_| SkitError::InvalidPassword("Invalid password".to_string()))
     }
+
+    /// Give the safe a fresh master key wrapped under `password` (format 2.0).
+    /// Values already encrypted under an older master key become unreadable, so
+    /// callers must re-encrypt them afterwards.
+    pub fn create_master_key(&mut self, password: &str) -> Result<(), SkitError> {
+        let master_key = crypto::generate_master_key();
+        self.master_key = Some(crypto::wrap_master_key(
+            &master_key,
+            password,
+            &self.kdf_params,
+            self.cipher,
+        )?);
+        self.version = MASTER_KEY_VERSION.to_string();
+        Ok(())
+    }
+
+    /// Re-wrap the master key under a new password without touching any values
+    pub fn rewrap_master_key(
+        &mut self,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), SkitError> {
+        let Some(ref wrapped) = self.master_key else {
+            return Ok(());
+        };
+        let master_key = crypto::unwrap_master_key(wrapped, old_password, &self.kdf_params)?;
+        self.master_key = Some(crypto::wrap_master_key(
+            &master_key,
+            new_password,
+            &self.kdf_params,
+            self.cipher,
+        )?);
+        Ok(())
+    }
+}
+
+/// Safe format version for safes with a wrapped master key
+const MASTER_KEY_VERSION: &str = "2.0";
+
+fn parse_kdf_value(field: &str, value: &str) -> Result<u32, SkitError> {
+    value.parse().map_err(|_| {
+        SkitError::ParseError(format!(
+            "Invalid #@{} value '{}': expected a positive integer",
+            field, value
+        ))
+    })
+}
+
+/// Timestamp format shared with the safe's `#@CREATED`/`#@UPDATED` headers
+fn item_timestamp() -> String {
+    chrono::Utc::now()
+        .format("%Y-%m-%d %H:%M:%S UTC")
+        .to_string()
+}
+
+/// Render item metadata as `updated=...;created=...;desc=...;tags=a,b`, skipping unset fields
+fn format_item_metadata(metadata: &ItemMetadata) -> String {
+    let tags = (!metadata.tags.is_empty()).then(|| metadata.tags.join(","));
+    [
+        ("updated", &metadata.updated),
+        ("created", &metadata.created),
+        ("desc", &metadata.description),
+        ("tags", &tags),
+    ]
+    .iter()
+    .filter_map(|(name, value)| {
+        value
+            .as_ref()
+            .map(|v| format!("{}={}", name, escape_metadata_value(v)))
+    })
+    .collect::<Vec<_>>()
+    .join(";")
+}
+
+/// Parse the attributes of a `#%KEY` line. Unknown attributes are ignored.
+fn parse_item_metadata(attrs: &str) -> ItemMetadata {
+    let mut metadata = ItemMetadata::default();
+    for attr in attrs.split(';') {
+        if let Some((name, value)) = attr.split_once('=') {
+            let value = Some(unescape_metadata_value(value));
+            match name.trim() {
+                "updated" => metadata.updated = value,
+                "created" => metadata.created = value,
+                "desc" => metadata.description = value,
+                "tags" => {
+                    metadata.tags = value
+                        .iter()
+                        .flat_map(|v| v.split(','))
+                        .filter(|tag| !tag.is_empty())
+                        .map(|tag| tag.to_string())
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+    }
+    metadata
+}
+
+/// Percent-escape the characters that would break a `#%` line
+fn escape_metadata_value(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace(';', "%3B")
+        .replace('\n', "%0A")
+        .replace('\r', "%0D")
+}
+
+fn unescape_metadata_value(value: &str) -> String {
+    value
+        .replace("%0D", "\r")
+        .replace("%0A", "\n")
+        .replace("%3B", ";")
+        .replace("%25", "%")
+}
+
+/// Levenshtein distance between two strings, by character
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_safe() -> Safe {
+        Safe {
+            version: "1.0".to_string(),
+            uuid: "test-uuid".to_string(),
+            description: "Test safe".to_string(),
+            created: "2025-01-01 00:00:00 UTC".to_string(),
+            updated: "2025-01-01 00:00:00 UTC".to_string(),
+            password_hash: "$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA".to_string(),
+            ssm_prefix: None,
+            ssm_region: None,
+            ssm_profile: None,
+            asm_prefix: None,
+            gcp_project: None,
+            vault_path: None,
+            kdf_params: crypto::KdfParams::default(),
+            cipher: crypto::Cipher::default(),
+            master_key: None,
+            items: IndexMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_decrypt_selected_keeps_item_order() {
+        let mut safe = Safe::new_with_password("pass", "test").unwrap();
+        let encrypted = crypto::EncryptBuilder::new()
+            .kdf_params(safe.kdf_params)
+            .cipher(safe.cipher)
+            .plaintext("secret")
+            .context("TOKEN")
+            .password("pass")
+            .encrypt()
+            .unwrap();
+        safe.add_or_update_item("TOKEN".to_string(), encrypted, true);
+        safe.add_or_update_item("HOST".to_string(), "db".to_string(), false);
+        let items: Vec<&SafeItem> = ["HOST", "TOKEN"]
+            .iter()
+            .map(|key| safe.find_item(key).unwrap())
+            .collect();
+
+        let values: Vec<_> = safe
+            .decrypt_selected(&items, Some("pass"))
+            .into_iter()
+            .map(|value| value.unwrap().unwrap())
+            .collect();
+        assert_eq!(values, ["db", "secret"]);
+
+        let values = safe.decrypt_selected(&items, None);
+        assert!(matches!(values[0], Some(Ok(_))));
+        assert!(values[1].is_none());
+    }
+
+    #[test]
+    fn test_searched_paths_only_describe_the_discovered_safe() {
+        set_searched_paths(
+            ".primary.safe",
+            vec![PathBuf::from("/work/app/.primary.safe")],
+        );
+        assert_eq!(
+            describe_missing(".primary.safe"),
+            ".primary.safe (tried /work/app/.primary.safe)"
+        );
+        assert!(!describe_missing("/elsewhere/.target.safe").contains("/work/app"));
+    }
+
+    #[test]
+    fn test_normalize_bare_names() {
+        assert_eq!(normalize_safe_path("myproject"), ".myproject.safe");
+        assert_eq!(normalize_safe_path("env"), ".env.safe");
+    }
+
+    #[test]
+    fn test_normalize_names_ending_in_safe() {
+        assert_eq!(normalize_safe_path(".env.safe"), ".env.safe");
+        assert_eq!(normalize_safe_path("prod.safe"), ".prod.safe");
+    }
+
+    #[test]
+    fn test_normalize_absolute_paths() {
+        assert_eq!(normalize_safe_path("/tmp/project/dev"), "/tmp/project/dev");
+        assert_eq!(
+            normalize_safe_path("/tmp/project/.dev.safe"),
+            "/tmp/project/.dev.safe"
+        );
+        assert_eq!(
+            normalize_safe_path("C:\\secrets\\prod.safe"),
+            "C:\\secrets\\prod.safe"
+        );
+    }
+
+    #[test]
+    fn test_normalize_relative_paths() {
+        assert_eq!(normalize_safe_path("../infra/prod"), "../infra/prod");
+        assert_eq!(
+            normalize_safe_path("../shared/env.safe"),
+            "../shared/env.safe"
+        );
+        assert_eq!(normalize_safe_path("./dev.safe"), "./dev.safe");
+    }
+
+    #[test]
+    fn test_normalize_home_relative_paths() {
+        let home = dirs::home_dir().expect("home directory");
+        assert_eq!(
+            normalize_safe_path("~/secrets/prod.safe"),
+            format!("{}/secrets/prod.safe", home.display())
+        );
+        assert_eq!(normalize_safe_path("~"), home.display().to_string());
+        // `~name` is a bare name, not a home directory reference
+        assert_eq!(normalize_safe_path("~backup"), ".~backup.safe");
+    }
+
+    #[test]
+    fn test_unlocked_safe_reads_and_writes_values() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+        let password = "Unlock.Pass-123";
+
+        let mut safe = Safe::new_with_password(password, "test").unwrap();
+        safe.create_master_key(password).unwrap();
+        safe.add_or_update_item("PORT".to_string(), "3000".to_string(), false);
+        let mut unlocked = safe.unlock(password).unwrap();
+        unlocked.set("API_KEY", "secret").unwrap();
+        unlocked.save(path).unwrap();
+
+        assert!(Safe::load(path).unwrap().unlock("wrong").is_err());
+        let unlocked = Safe::load(path).unwrap().unlock(password).unwrap();
+        assert_eq!(unlocked.get("API_KEY").as_deref(), Some("secret"));
+        assert_eq!(unlocked.get("PORT").as_deref(), Some("3000"));
+        assert_eq!(unlocked.get("MISSING"), None);
+        assert_eq!(unlocked.keys().collect::<Vec<_>>(), ["PORT", "API_KEY"]);
+        assert!(unlocked.safe().find_item("API_KEY").unwrap().is_encrypted);
+    }
+
+    #[test]
+    fn test_special_values_round_trip() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+
+        let values = [
+            ("PEM", "-----BEGIN KEY-----\nabc=\n-----END KEY-----\n"),
+            ("EQUALS", "a=b=c"),
+            ("HASH", "#not-a-comment # still value"),
+            ("QUOTES", "\"double\" and 'single'"),
+            ("PADDED", "  spaced  "),
+            ("BACKSLASH", "C:\\path\\n"),
+        ];
+
+        let mut safe = test_safe();
+        for (key, value) in values {
+            safe.add_or_update_item(key.to_string(), value.to_string(), false);
+        }
+        safe.save(path).unwrap();
+
+        let loaded = Safe::load(path).unwrap();
+        for (key, value) in values {
+            assert_eq!(loaded.find_item(key).unwrap().value, value, "key {}", key);
+        }
+    }
+
+    #[test]
+    fn test_closest_key_suggests_typos_only() {
+        let mut safe = test_safe();
+        for key in ["API_KEY", "DATABASE_URL", "DB"] {
+            safe.add_or_update_item(key.to_string(), "v".to_string(), false);
+        }
+
+        assert_eq!(safe.closest_key("API_KYE"), Some("API_KEY"));
+        assert_eq!(safe.closest_key("database_url"), Some("DATABASE_URL"));
+        assert_eq!(safe.closest_key("DATABSE_URL"), Some("DATABASE_URL"));
+        assert_eq!(safe.closest_key("REDIS_URL"), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_file_order_round_trips_and_sorts() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+
+        let mut safe = test_safe();
+        for key in ["ZETA", "ALPHA", "MIKE"] {
+            safe.add_or_update_item(key.to_string(), "v".to_string(), false);
+        }
+        safe.save(path).unwrap();
+
+        let mut loaded = Safe::load(path).unwrap();
+        assert_eq!(
+            loaded.sorted_keys(SortOrder::Insertion),
+            ["ZETA", "ALPHA", "MIKE"]
+        );
+        assert_eq!(
+            loaded.sorted_keys(SortOrder::Asc),
+            ["ALPHA", "MIKE", "ZETA"]
+        );
+        assert_eq!(
+            loaded.sorted_keys(SortOrder::Desc),
+            ["ZETA", "MIKE", "ALPHA"]
+        );
+
+        // Updating a key keeps its place; new keys go at the end
+        loaded.add_or_update_item("ZETA".to_string(), "w".to_string(), false);
+        loaded.add_or_update_item("BRAVO".to_string(), "v".to_string(), false);
+        loaded.items.shift_remove("ALPHA");
+        loaded.save(path).unwrap();
+        let content = std::fs::read_to_string(path).unwrap();
+        let keys: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split('=').next())
+            .collect();
+        assert_eq!(keys, ["ZETA", "MIKE", "BRAVO"]);
+    }
+
+    #[test]
+    fn test_plain_value_that_looks_encrypted_round_trips() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+
+        let blob = crypto::EncryptBuilder::new()
+            .kdf_params(crypto::KdfParams {
+                memory_kib: 8,
+                time_cost: 1,
+                lanes: 1,
+            })
+            .plaintext("secret")
+            .password("password")
+            .encrypt()
+            .unwrap();
+        assert!(blob.starts_with("ENC~v1~"));
+
+        // What `import` reads from `IMPORTED="ENC~..."`: the quoted value, unquoted
+        let imported = crate::dotenv::unquote_value(&format!("\"{}\"", blob)).unwrap();
+
+        let mut safe = test_safe();
+        safe.add_or_update_item("TOKEN".to_string(), blob.clone(), false);
+        safe.add_or_update_item("IMPORTED".to_string(), imported, false);
+        safe.save(path).unwrap();
+
+        let loaded = Safe::load(path).unwrap();
+        for key in ["TOKEN", "IMPORTED"] {
+            let item = loaded.find_item(key).unwrap();
+            assert!(!item.is_encrypted, "key {}", key);
+            assert_eq!(item.value, blob, "key {}", key);
+        }
+    }
+
+    #[test]
+    fn test_failed_save_leaves_no_temp_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        // A non-empty directory in the way makes the final rename fail, even for root
+        let path = temp_dir.path().join(".env.safe");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("keep"), "original").unwrap();
+
+        let mut safe = test_safe();
+        assert!(safe.save(path.to_str().unwrap()).is_err());
+
+        assert_eq!(fs::read_to_string(path.join("keep")).unwrap(), "original");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_item_metadata_round_trip() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+
+        let mut safe = test_safe();
+        safe.add_or_update_item("API_KEY".to_string(), "v1".to_string(), false);
+        safe.add_or_update_item("BARE".to_string(), "v".to_string(), false);
+        safe.items.get_mut("BARE").unwrap().metadata = ItemMetadata::default();
+        let description = "Billing API; 100% prod\nsecond line";
+        safe.set_description("API_KEY", Some(description.to_string()));
+        safe.add_tags("API_KEY", &["prod".to_string(), "billing".to_string()]);
+        safe.add_tags("API_KEY", &["prod".to_string()]);
+        safe.save(path).unwrap();
+
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.contains("#%API_KEY updated="));
+        assert!(content.contains(";tags=prod,billing\n"));
+        assert!(!content.contains("#%BARE"));
+
+        let loaded = Safe::load(path).unwrap();
+        let metadata = &loaded.find_item("API_KEY").unwrap().metadata;
+        assert_eq!(metadata, &safe.items["API_KEY"].metadata);
+        assert_eq!(metadata.description.as_deref(), Some(description));
+        assert!(loaded.find_item("BARE").unwrap().metadata.is_empty());
+    }
+
+    #[test]
+    fn test_stores_value_compares_plaintext() {
+        let mut safe = test_safe();
+        safe.kdf_params = crypto::KdfParams {
+            memory_kib: 1024,
+            time_cost: 1,
+            lanes: 1,
+        };
+        let encrypted = crypto::EncryptBuilder::new()
+            .kdf_params(safe.kdf_params)
+            .plaintext("secret")
+            .password("pw")
+            .encrypt()
+            .unwrap();
+        safe.add_or_update_item("TOKEN".to_string(), encrypted, true);
+        safe.add_or_update_item("PORT".to_string(), "3000".to_string(), false);
+
+        assert!(safe.stores_value("TOKEN", "secret", true, Some("pw")));
+        assert!(!safe.stores_value("TOKEN", "other", true, Some("pw")));
+        assert!(!safe.stores_value("TOKEN", "secret", true, Some("wrong")));
+        assert!(!safe.stores_value("TOKEN", "secret", false, Some("pw")));
+        assert!(safe.stores_value("PORT", "3000", false, None));
+        assert!(!safe.stores_value("PORT", "3000", true, Some("pw")));
+        assert!(!safe.stores_value("MISSING", "x", false, None));
+    }
+
+    #[test]
+    fn test_add_or_update_item_stamps_metadata() {
+        let mut safe = test_safe();
+        safe.add_or_update_item("KEY".to_string(), "v1".to_string(), false);
+        let first = safe.items["KEY"].metadata.clone();
+        assert!(first.created.is_some());
+        assert_eq!(first.created, first.updated);
+
+        safe.items.get_mut("KEY").unwrap().metadata = ItemMetadata {
+            created: Some("2020-01-01 00:00:00 UTC".to_string()),
+            updated: Some("2020-01-01 00:00:00 UTC".to_string()),
+            description: Some("kept".to_string()),
+            tags: vec!["prod".to_string()],
+        };
+        safe.add_or_update_item("KEY".to_string(), "v2".to_string(), false);
+        let second = &safe.items["KEY"].metadata;
+        assert_eq!(second.created.as_deref(), Some("2020-01-01 00:00:00 UTC"));
+        assert_ne!(second.updated.as_deref(), Some("2020-01-01 00:00:00 UTC"));
+        assert_eq!(second.description.as_deref(), Some("kept"));
+        assert_eq!(second.tags, vec!["prod".to_string()]);
+    }
+
+    #[test]
+    fn test_legacy_single_line_values_parse_unchanged() {
+        let content = "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n\
+                       URL=postgres://u:p@host/db?a=b\nMSG=say \"hi\"\nTOKEN=ENC~v1~abc\n";
+        let safe = Safe::parse(content).unwrap();
+
+        assert_eq!(
+            safe.find_item("URL").unwrap().value,
+            "postgres://u:p@host/db?a=b"
+        );
+        assert_eq!(safe.find_item("MSG").unwrap().value, "say \"hi\"");
+        assert!(safe.find_item("TOKEN").unwrap().is_encrypted);
+        assert!(safe.items.values().all(|item| item.metadata.is_empty()));
+    }
+
+    #[test]
+    fn test_quoted_plain_values_need_the_marker() {
+        let header =
+            "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n";
+        let body = "QUOTED=\"quoted\"\nLINES=\"a\\nb\"\n";
+
+        let legacy = Safe::parse(&format!("{}{}", header, body)).unwrap();
+        assert_eq!(legacy.find_item("QUOTED").unwrap().value, "\"quoted\"");
+        assert_eq!(legacy.find_item("LINES").unwrap().value, "\"a\\nb\"");
+
+        let current = Safe::parse(&format!("{}#@QUOTED_VALUES=1\n{}", header, body)).unwrap();
+        assert_eq!(current.find_item("QUOTED").unwrap().value, "quoted");
+        assert_eq!(current.find_item("LINES").unwrap().value, "a\nb");
+
+        // A legacy value keeps its quotes through a save
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+        let mut legacy = legacy;
+        legacy.save(path).unwrap();
+        let loaded = Safe::load(path).unwrap();
+        assert_eq!(loaded.find_item("QUOTED").unwrap().value, "\"quoted\"");
+    }
+
+    #[test]
+    fn test_save_preserves_file_order() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+        let content = "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n\
+                       Z=1\nA=2\nM=3\n";
+
+        Safe::parse(content).unwrap().save(path).unwrap();
+
+        let saved = std::fs::read_to_string(path).unwrap();
+        let keys: Vec<&str> = saved
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split('=').next())
+            .collect();
+        assert_eq!(keys, ["Z", "A", "M"]);
+    }
+
+    #[test]
+    fn test_kdf_params_default_when_absent_and_round_trip() {
+        let content =
+            "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n";
+        let safe = Safe::parse(content).unwrap();
+        assert_eq!(safe.kdf_params, crypto::KdfParams::default());
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+
+        let mut safe = test_safe();
+        safe.kdf_params = crypto::KdfParams {
+            memory_kib: 19456,
+            time_cost: 2,
+            lanes: 1,
+        };
+        safe.save(path).unwrap();
+
+        let loaded = Safe::load(path).unwrap();
+        assert_eq!(loaded.kdf_params, safe.kdf_params);
+    }
+
+    #[test]
+    fn test_cipher_header_round_trip() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+
+        let mut safe = test_safe();
+        safe.save(path).unwrap();
+        assert!(!fs::read_to_string(path).unwrap().contains("#@CIPHER"));
+
+        safe.cipher = crypto::Cipher::XChaCha20;
+        safe.add_or_update_item("TOKEN".to_string(), "ENC~v2~abc".to_string(), true);
+        safe.save(path).unwrap();
+
+        let loaded = Safe::load(path).unwrap();
+        assert_eq!(loaded.cipher, crypto::Cipher::XChaCha20);
+        assert!(loaded.find_item("TOKEN").unwrap().is_encrypted);
+    }
+
+    #[test]
+    fn test_aws_prefix_headers_round_trip() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let path = temp_dir.path().join(".env.safe");
+        let path = path.to_str().unwrap();
+
+        let mut safe = test_safe();
+        safe.ssm_prefix = Some("/myapp/dev/".to_string());
+        safe.ssm_profile = Some("staging".to_string());
+        safe.asm_prefix = Some("myapp/dev/".to_string());
+        safe.gcp_project = Some("my-project".to_string());
+        safe.vault_path = Some("myapp/dev".to_string());
+        safe.save(path).unwrap();
+        assert!(
+            fs::read_to_string(path)
+                .unwrap()
+                .contains("#@ASM_PREFIX=myapp/dev/\n")
+        );
+
+        let loaded = Safe::load(path).unwrap();
+        assert_eq!(loaded.ssm_prefix.as_deref(), Some("/myapp/dev/"));
+        assert_eq!(loaded.ssm_profile.as_deref(), Some("staging"));
+        assert_eq!(loaded.asm_prefix.as_deref(), Some("myapp/dev/"));
+        assert_eq!(loaded.gcp_project.as_deref(), Some("my-project"));
+        assert_eq!(loaded.vault_path.as_deref(), Some("myapp/dev"));
+    }
+
+    #[test]
+    fn test_invalid_kdf_params_rejected() {
+        let header =
+            "#@VERSION=1.0\n#@UUID=u\n#@DESCRIPTION=d\n#@CREATED=c\n#@UPDATED=u\n#@PASS_HASH=h\n";
+
+        assert!(Safe::parse(&format!("{}#@KDF_MEM=lots\n", header)).is_err());
+        assert!(Safe::parse(&format!("{}#@KDF_TIME=0\n", header)).is_err());
+        assert!(Safe::parse(&format!("{}#@KDF_MEM=4294967295\n", header)).is_err());
+        assert!(Safe::parse(&format!("{}#@KDF_TIME=1000000\n", header)).is_err());
+        assert!(Safe::parse(&format!("{}#@KDF_LANES=1024\n", header)).is_err());
+    }
 }
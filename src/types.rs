@@ -1,5 +1,8 @@
+#[cfg(feature = "cli")]
+use crate::audit::AuditEntry;
+use crate::crypto::{Cipher, KdfParams};
+use indexmap::IndexMap;
 use serde::Serialize;
-use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct Safe {
@@ -11,7 +14,28 @@ pub struct Safe {
     pub password_hash: String,
     pub ssm_prefix: Option<String>,
     pub ssm_region: Option<String>,
-    pub items: HashMap<String, SafeItem>,
+    pub ssm_profile: Option<String>,
+    pub asm_prefix: Option<String>,
+    pub gcp_project: Option<String>,
+    pub vault_path: Option<String>,
+    pub kdf_params: KdfParams,
+    pub cipher: Cipher,
+    /// Safe master key wrapped under the password (`#@MASTER_KEY`), for format 2.0 safes
+    pub master_key: Option<String>,
+    pub items: IndexMap<String, SafeItem>,
+}
+
+/// Order in which keys are listed by `print`, `keys` and `export`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SortOrder {
+    /// Ascending by key
+    #[default]
+    Asc,
+    /// Descending by key
+    Desc,
+    /// As stored in the safe file, with keys added later at the end
+    Insertion,
 }
 
 #[derive(Debug, Clone)]
@@ -19,6 +43,24 @@ pub struct SafeItem {
     pub key: String,
     pub value: String,
     pub is_encrypted: bool,
+    pub metadata: ItemMetadata,
+}
+
+/// Optional per-item annotations, stored as a `#%KEY updated=...;created=...;desc=...;tags=...` line.
+/// Safes written before annotations existed have none.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemMetadata {
+    pub created: Option<String>,
+    pub updated: Option<String>,
+    pub description: Option<String>,
+    /// Labels for grouping secrets, e.g. `prod` or `service-a`
+    pub tags: Vec<String>,
+}
+
+impl ItemMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == ItemMetadata::default()
+    }
 }
 
 // JSON output structures
@@ -33,11 +75,107 @@ pub struct PrintItem {
     pub value: String,
     #[serde(rename = "type")]
     pub item_type: String,
+    /// The value is a placeholder because decryption failed
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub decrypt_failed: bool,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Serialize, Debug)]
+pub struct HistoryOutput {
+    pub entries: Vec<AuditEntry>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ConfigOutput {
+    pub files: Vec<String>,
+    pub settings: Vec<ConfigSetting>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ConfigSetting {
+    pub name: String,
+    pub value: String,
+    pub source: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SearchOutput {
+    pub matches: Vec<SearchMatch>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SearchMatch {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    /// Where the pattern matched: "key", "value" or both
+    pub matched: Vec<String>,
+    /// Value with everything but the match masked; only present with `--reveal`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LintOutput {
+    pub findings: Vec<LintFinding>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LintFinding {
+    /// "error" or "warning"; any error makes `lint` exit non-zero
+    pub severity: String,
+    pub rule: String,
+    pub keys: Vec<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SchemaCheckOutput {
+    /// False if any schema key is missing or invalid
+    pub ok: bool,
+    /// Number of keys in the schema
+    pub checked: usize,
+    pub violations: Vec<SchemaViolation>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SchemaViolation {
+    pub key: String,
+    /// "missing", "pattern" or "decrypt-failed"
+    pub rule: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DoctorOutput {
+    /// False if any critical check failed
+    pub ok: bool,
+    pub checks: Vec<DoctorCheck>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DoctorCheck {
+    pub name: String,
+    /// "pass", "warn", "fail" or "skip"
+    pub status: String,
+    /// A failed critical check makes `doctor` exit non-zero
+    pub critical: bool,
+    pub detail: String,
 }
 
 #[derive(Serialize)]
 pub struct KeysOutput {
     pub keys: Vec<KeyItem>,
+    pub summary: KeysSummary,
+}
+
+/// Key counts for `keys --count`, and alongside the list in JSON
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct KeysSummary {
+    pub total: usize,
+    pub encrypted: usize,
+    pub plain: usize,
 }
 
 #[derive(Serialize)]
@@ -45,14 +183,49 @@ pub struct KeyItem {
     pub key: String,
     #[serde(rename = "type")]
     pub item_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Stored bytes: ciphertext length for encrypted values, only with `--size`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<usize>,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Serialize)]
+pub struct AgentStatusOutput {
+    pub running: bool,
+    pub socket: String,
+    pub entries: Vec<crate::agent::AgentEntry>,
 }
 
 #[derive(Serialize, Debug)]
 pub struct StatusOutput {
+    /// False if the password hash or any checked secret failed verification
+    pub ok: bool,
     pub safe_path: String,
     pub metadata: StatusMetadata,
     pub statistics: StatusStatistics,
     pub integrity: StatusIntegrity,
+    pub auth: StatusAuth,
+}
+
+/// How the safe was unlocked, and whether a saved key file exists for it
+#[derive(Serialize, Debug, Default)]
+pub struct StatusAuth {
+    /// `password_file`, `environment`, `agent`, `keychain`, `key_file` or `prompt`; none when
+    /// the safe has no password to check
+    pub source: Option<String>,
+    /// Saved key file for this safe, reported even when another source won
+    pub key_file: Option<String>,
+    pub key_file_last_used: Option<String>,
+    /// Whether the saved key file still unlocks the safe
+    pub key_file_valid: Option<bool>,
 }
 
 #[derive(Serialize, Debug)]
@@ -105,3 +278,10 @@ pub struct SafeStatistics {
     pub encrypted: usize,
     pub plain: usize,
 }
+
+#[derive(Serialize)]
+pub struct DiffOutput {
+    pub only_a: Vec<String>,
+    pub only_b: Vec<String>,
+    pub changed: Vec<String>,
+}
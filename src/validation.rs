@@ -1,3 +1,6 @@
+use crate::error::SkitError;
+use std::collections::BTreeMap;
+
 /// Validate environment variable key naming for shells and process envs.
 /// Pattern: [A-Za-z_][A-Za-z0-9_]*
 pub fn is_valid_env_key(key: &str) -> bool {
@@ -13,3 +16,67 @@ pub fn is_valid_env_key(key: &str) -> bool {
     }
     true
 }
+
+/// Turn a remote secret or parameter name into an env key by replacing `/`, `-` and `.` with
+/// `_`, so `database/host` becomes `database_host`. The result may still be invalid, e.g. when
+/// it starts with a digit.
+pub fn sanitize_env_key(name: &str) -> String {
+    name.chars()
+        .map(|c| if matches!(c, '/' | '-' | '.') { '_' } else { c })
+        .collect()
+}
+
+/// Fail if two remote names map to the same safe key, listing every collision, so a pull
+/// never lets one value silently overwrite another
+pub fn check_key_collisions<'a>(
+    what: &str,
+    pairs: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<(), SkitError> {
+    let mut sources: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (name, key) in pairs {
+        sources.entry(key).or_default().push(name);
+    }
+
+    let collisions: Vec<String> = sources
+        .iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(key, names)| format!("{} -> {}", names.join(", "), key))
+        .collect();
+    if !collisions.is_empty() {
+        return Err(SkitError::ParseError(format!(
+            "{} map to the same key: {}",
+            what,
+            collisions.join("; ")
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a tag name: letters, digits, `_`, `.` and `-`, as they are stored comma-separated
+pub fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Validate a Kubernetes Secret data key: letters, digits, `-`, `_` and `.`, at most 253 chars
+pub fn is_valid_k8s_data_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 253
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Validate a Kubernetes object name (RFC 1123 subdomain): lowercase letters, digits, `-` and
+/// `.`, starting and ending with a letter or digit, at most 253 chars
+pub fn is_valid_k8s_name(name: &str) -> bool {
+    let edge_ok = |c: Option<char>| c.is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    name.len() <= 253
+        && edge_ok(name.chars().next())
+        && edge_ok(name.chars().last())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '.'))
+}
@@ -0,0 +1,130 @@
+//! HashiCorp Vault KV v2, read through the `vault` CLI, which picks up `VAULT_ADDR`,
+//! `VAULT_TOKEN` and the rest of Vault's own configuration.
+
+use crate::display::print_warning;
+use crate::error::SkitError;
+use crate::validation::is_valid_env_key;
+use std::process::Command;
+
+/// Overrides the `vault` executable, e.g. with a full path
+pub const VAULT_BIN_ENV: &str = "SKIT_VAULT";
+
+/// A value read from a Vault secret, keyed by its safe key name
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultSecret {
+    pub key: String,
+    pub value: String,
+}
+
+/// Key/value pairs from `vault kv get -format=json`, whose KV v2 payload sits under
+/// `data.data`. Numbers and booleans are stored as their JSON text and null as an empty value,
+/// as `import` does; lists, nested maps and keys that aren't valid env keys are skipped with a
+/// warning.
+fn parse_kv_response(json: &[u8]) -> Result<Vec<VaultSecret>, SkitError> {
+    let response: serde_json::Value = serde_json::from_slice(json).map_err(|e| {
+        SkitError::VaultError(format!("Unexpected output from vault kv get: {}", e))
+    })?;
+    let data = response["data"]["data"].as_object().ok_or_else(|| {
+        SkitError::VaultError(
+            "Response has no data.data map; is the mount a KV version 2 engine?".to_string(),
+        )
+    })?;
+
+    let mut secrets = Vec::new();
+    for (key, value) in data {
+        if !is_valid_env_key(key) {
+            print_warning(&format!("Skipping '{}': not a valid key name", key));
+            continue;
+        }
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::Bool(b) => b.to_string(),
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                print_warning(&format!(
+                    "Skipping '{}': lists and maps are not supported",
+                    key
+                ));
+                continue;
+            }
+        };
+        secrets.push(VaultSecret {
+            key: key.clone(),
+            value,
+        });
+    }
+    secrets.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(secrets)
+}
+
+/// Fetch every key of the secret at `path` in the KV v2 engine mounted at `mount`
+///
+/// # Arguments
+/// * `mount` - Mount path of the KV v2 engine (e.g., "secret")
+/// * `path` - Secret path within the mount (e.g., "myapp/dev")
+///
+/// # Returns
+/// Vector of VaultSecret structs, one per key of the secret's latest version
+pub fn fetch_secrets(mount: &str, path: &str) -> Result<Vec<VaultSecret>, SkitError> {
+    let program = std::env::var(VAULT_BIN_ENV).unwrap_or_else(|_| "vault".to_string());
+    let output = Command::new(&program)
+        .args(["kv", "get", "-format=json"])
+        .arg(format!("-mount={}", mount))
+        .arg(path)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => SkitError::VaultError(format!(
+                "'{}' not found. Install the Vault CLI or set {} to its path",
+                program, VAULT_BIN_ENV
+            )),
+            _ => SkitError::VaultError(format!("Failed to run {}: {}", program, e)),
+        })?;
+
+    if !output.status.success() {
+        return Err(SkitError::VaultError(format!(
+            "vault kv get {}/{} failed: {}",
+            mount,
+            path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let secrets = parse_kv_response(&output.stdout)?;
+    if secrets.is_empty() {
+        return Err(SkitError::VaultError(format!(
+            "No values found at {}/{}",
+            mount, path
+        )));
+    }
+    Ok(secrets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kv_response() {
+        let json = br#"{
+            "data": {
+                "data": {
+                    "TOKEN": "t", "API_KEY": "k", "bad-key": "x", "PORT": 5432,
+                    "DEBUG": false, "UNSET": null, "HOSTS": ["a", "b"]
+                },
+                "metadata": {"version": 3}
+            }
+        }"#;
+        let secrets = parse_kv_response(json).unwrap();
+        let keys: Vec<&str> = secrets.iter().map(|s| s.key.as_str()).collect();
+        assert_eq!(keys, ["API_KEY", "DEBUG", "PORT", "TOKEN", "UNSET"]);
+        let values: Vec<&str> = secrets.iter().map(|s| s.value.as_str()).collect();
+        assert_eq!(values, ["k", "false", "5432", "t", ""]);
+    }
+
+    #[test]
+    fn test_parse_kv_response_needs_kv_v2() {
+        assert!(parse_kv_response(br#"{"data": {"TOKEN": "t"}}"#).is_err());
+        assert!(parse_kv_response(b"not json").is_err());
+    }
+}
@@ -0,0 +1,79 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{assert_success, output_text, skit};
+use std::path::Path;
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Agent.Pass-123";
+
+/// Stops the background agent even when an assertion fails
+struct AgentGuard<'a>(&'a Path);
+
+impl Drop for AgentGuard<'_> {
+    fn drop(&mut self) {
+        skit(self.0, &["agent", "stop"], &[]);
+    }
+}
+
+#[test]
+fn test_agent_caches_password_until_locked() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_KEY", "secret"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    ));
+
+    assert_success(&skit(dir, &["agent", "start", "--ttl", "10m"], &[]));
+    let _guard = AgentGuard(dir);
+
+    // A second agent on the same socket is refused as an I/O failure, not invalid input
+    let output = skit(dir, &["agent", "start", "--foreground"], &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output_text(&output).contains("already running"));
+
+    // With no agent entry there is nothing to unlock the safe with
+    assert!(
+        !skit(dir, &["--no-input", "cat", "API_KEY"], &[])
+            .status
+            .success()
+    );
+
+    assert_success(&skit(dir, &["agent", "add"], &[("SKIT_SAFEKEY", PASSWORD)]));
+    let output = skit(dir, &["--no-input", "cat", "API_KEY"], &[]);
+    assert_success(&output);
+    assert_eq!(output.stdout, b"secret");
+
+    let output = skit(dir, &["-o", "json", "agent", "status"], &[]);
+    assert_success(&output);
+    let text = output_text(&output);
+    assert!(text.contains("\"running\": true"));
+    assert!(text.contains(".env.safe"));
+    assert!(!text.contains(PASSWORD));
+
+    // The socket is only reachable by its owner
+    use std::os::unix::fs::PermissionsExt;
+    let socket = dir.join(".config").join("skit").join("agent.sock");
+    let mode = std::fs::metadata(&socket).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    assert_success(&skit(dir, &["agent", "lock"], &[]));
+    assert!(
+        !skit(dir, &["--no-input", "cat", "API_KEY"], &[])
+            .status
+            .success()
+    );
+
+    assert_success(&skit(dir, &["agent", "stop"], &[]));
+    let output = skit(dir, &["agent", "status"], &[]);
+    assert!(output_text(&output).contains("No agent running"));
+}
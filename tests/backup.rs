@@ -0,0 +1,53 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Backup.Pass-123";
+
+#[test]
+fn test_backups_rotate_on_save_and_restore_swaps() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let safe_path = dir.join(".env.safe");
+    let backup = |n: u32| std::fs::read_to_string(dir.join(format!(".env.safe.bak.{}", n))).ok();
+    let env = [("SKIT_SAFEKEY", PASSWORD), ("SKIT_BACKUPS", "2")];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert!(backup(1).is_none());
+
+    let mut versions = vec![std::fs::read_to_string(&safe_path).unwrap()];
+    for value in ["one", "two", "three"] {
+        assert_success(&skit(dir, &["set", "PORT", value, "--plain"], &env));
+        versions.push(std::fs::read_to_string(&safe_path).unwrap());
+    }
+
+    // Most recent first, and only two are kept
+    assert_eq!(backup(1).as_ref(), Some(&versions[2]));
+    assert_eq!(backup(2).as_ref(), Some(&versions[1]));
+    assert!(backup(3).is_none());
+
+    // Without backups configured nothing is rotated
+    assert_success(&skit(
+        dir,
+        &["set", "OTHER", "x", "--plain"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    ));
+    assert_eq!(backup(1).as_ref(), Some(&versions[2]));
+
+    let current = std::fs::read_to_string(&safe_path).unwrap();
+    let output = skit(dir, &["--no-input", "restore", "--from-backup", "2"], &[]);
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("--yes"));
+
+    assert_success(&skit(dir, &["--yes", "restore", "--from-backup", "2"], &[]));
+    assert_eq!(std::fs::read_to_string(&safe_path).unwrap(), versions[1]);
+    assert_eq!(backup(2), Some(current));
+
+    let output = skit(dir, &["--yes", "restore", "--from-backup", "3"], &[]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,66 @@
+// Each integration test crate uses a different subset of these helpers
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+fn skit_command(dir: &Path, args: &[&str], envs: &[(&str, &str)]) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_skit"));
+    cmd.args(args)
+        .current_dir(dir)
+        .env("HOME", dir)
+        .env_remove("SKIT_SAFEKEY")
+        .env_remove("CI")
+        .env_remove("SKIT_FORMAT")
+        .env_remove("SKIT_BACKUPS")
+        .env_remove("SKIT_SAFE")
+        .env_remove("SKIT_AGENT_SOCK")
+        .env_remove("XDG_RUNTIME_DIR")
+        .env_remove("SKIT_GCLOUD")
+        .env_remove("SKIT_VAULT")
+        .envs(envs.iter().copied())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd
+}
+
+/// Run skit in `dir` with no TTY on stdin and an isolated home directory
+pub fn skit(dir: &Path, args: &[&str], envs: &[(&str, &str)]) -> Output {
+    skit_command(dir, args, envs)
+        .stdin(Stdio::null())
+        .output()
+        .expect("failed to run skit")
+}
+
+/// Like `skit`, but feed `input` to stdin
+pub fn skit_with_stdin(dir: &Path, args: &[&str], envs: &[(&str, &str)], input: &str) -> Output {
+    let mut child = skit_command(dir, args, envs)
+        .stdin(Stdio::piped())
+        .spawn()
+        .expect("failed to run skit");
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes())
+        .expect("failed to write stdin");
+    child.wait_with_output().expect("failed to run skit")
+}
+
+/// Log lines go to stdout alongside regular output, so inspect both streams
+pub fn output_text(output: &Output) -> String {
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+pub fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "skit failed: {}",
+        output_text(output)
+    );
+}
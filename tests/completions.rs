@@ -0,0 +1,103 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{assert_success, skit};
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Completions.Pass-123";
+
+/// Write the completion script for `shell` into `dir` and return its path
+fn write_script(dir: &Path, shell: &str) -> String {
+    let output = skit(dir, &["completions", shell], &[]);
+    assert_success(&output);
+    let path = dir.join(format!("skit.{}", shell));
+    std::fs::write(&path, &output.stdout).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn shell_available(shell: &str) -> bool {
+    Command::new(shell).arg("-c").arg("exit 0").output().is_ok()
+}
+
+#[test]
+fn test_bash_script_is_valid() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let script = write_script(dir.path(), "bash");
+
+    let output = Command::new("bash").args(["-n", &script]).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_fish_script_is_valid() {
+    if !shell_available("fish") {
+        eprintln!("fish not installed, skipping");
+        return;
+    }
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let script = write_script(dir.path(), "fish");
+
+    let output = Command::new("fish").args(["-n", &script]).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_bash_completes_key_names() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    for key in ["API_KEY", "API_URL", "DB_HOST"] {
+        assert_success(&skit(dir, &["set", key, "value", "--plain"], &[]));
+    }
+    let script = write_script(dir, "bash");
+
+    // Key names are read without a password
+    let output = skit(dir, &["keys", "--complete-keys"], &[]);
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "API_KEY\nAPI_URL\nDB_HOST\n"
+    );
+
+    let bin_dir = Path::new(env!("CARGO_BIN_EXE_skit")).parent().unwrap();
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let output = Command::new("bash")
+        .arg("-c")
+        .arg(format!(
+            "source {}; COMP_WORDS=(skit get API); COMP_CWORD=2; _skit_keys; printf '%s\\n' \"${{COMPREPLY[@]}}\"",
+            script
+        ))
+        .current_dir(dir)
+        .env("HOME", dir)
+        .env("PATH", path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "API_KEY\nAPI_URL\n"
+    );
+}
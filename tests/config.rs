@@ -0,0 +1,109 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+/// `(value, source)` of a setting from `skit config show` JSON output
+fn setting(output: &std::process::Output, name: &str) -> (String, String) {
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|_| panic!("expected JSON output: {}", output_text(output)));
+    let setting = json["settings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == name)
+        .unwrap();
+    (
+        setting["value"].as_str().unwrap().to_string(),
+        setting["source"].as_str().unwrap().to_string(),
+    )
+}
+
+#[test]
+fn test_config_precedence() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let config_dir = dir.join(".config").join("skit");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "default_safe = \"proj\"\ndefault_format = \"yaml\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join(".skit.toml"), "default_format = \"json\"\n").unwrap();
+
+    // The repo-local file overrides the global one
+    let output = skit(dir, &["config", "show"], &[]);
+    assert_success(&output);
+    let (safe, source) = setting(&output, "safe");
+    assert_eq!(safe, "proj");
+    assert!(source.ends_with("config.toml"), "{}", source);
+    assert_eq!(
+        setting(&output, "format"),
+        ("json".into(), ".skit.toml".into())
+    );
+
+    // SKIT_FORMAT beats the config, and --format beats both
+    let output = skit(dir, &["config", "show"], &[("SKIT_FORMAT", "yaml")]);
+    assert_success(&output);
+    assert!(output_text(&output).contains("SKIT_FORMAT environment variable"));
+    let output = skit(
+        dir,
+        &["-o", "json", "-s", "other", "config", "show"],
+        &[("SKIT_FORMAT", "yaml")],
+    );
+    assert_eq!(
+        setting(&output, "format"),
+        ("json".into(), "command line".into())
+    );
+    assert_eq!(
+        setting(&output, "safe"),
+        ("other".into(), "command line".into())
+    );
+
+    // A broken config file is reported instead of ignored
+    std::fs::write(dir.join(".skit.toml"), "default_format = \"xml\"\n").unwrap();
+    let output = skit(dir, &["config", "show"], &[]);
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("Invalid output format 'xml'"));
+}
+
+#[test]
+fn test_safe_env_and_upward_discovery() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let root = std::fs::canonicalize(dir.path()).unwrap();
+    let nested = root.join("services").join("api");
+    std::fs::create_dir_all(&nested).unwrap();
+    let password = "Discover.Pass-123";
+
+    assert_success(&skit(
+        &root,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", password)],
+    ));
+
+    let output = skit(&nested, &["config", "show"], &[("SKIT_SAFE", "shared")]);
+    assert!(output_text(&output).contains("SKIT_SAFE environment variable"));
+
+    // Not found in the subdirectory itself, and the error names where it looked
+    let output = skit(&nested, &["--no-input", "keys"], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output_text(&output).contains(&nested.join(".env.safe").display().to_string()));
+
+    // --safe auto walks up to the safe in the root and reports its absolute path
+    let output = skit(
+        &nested,
+        &["--safe", "auto", "-o", "json", "status", "--fast"],
+        &[("SKIT_SAFEKEY", password)],
+    );
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["safe_path"],
+        root.join(".env.safe").display().to_string()
+    );
+
+    // The same through the config file
+    std::fs::write(nested.join(".skit.toml"), "discover = true\n").unwrap();
+    assert_success(&skit(&nested, &["--no-input", "keys"], &[]));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Source.Pass-123";
+const COPY_PASSWORD: &str = "Copied.Pass-456";
+
+#[test]
+fn test_copy_selected_keys() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD), ("COPY_PW", COPY_PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    for (key, value) in [
+        ("API_KEY", "api"),
+        ("AWS_ACCESS_KEY_ID", "id"),
+        ("AWS_SECRET", "aws"),
+        ("DB_PASSWORD", "db"),
+    ] {
+        assert_success(&skit(dir, &["set", key, value], &auth));
+    }
+
+    // A key that doesn't exist fails before anything is written
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "copy",
+            "contractor",
+            "--keys",
+            "API_KEY,MISSING",
+            "--password-env",
+            "COPY_PW",
+        ],
+        &auth,
+    );
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("MISSING"));
+    assert!(!dir.join(".contractor.safe").exists());
+
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "copy",
+            "contractor",
+            "--keys",
+            "API_KEY,AWS_*",
+            "--exclude",
+            "AWS_SECRET",
+            "--password-env",
+            "COPY_PW",
+        ],
+        &auth,
+    );
+    assert_success(&output);
+    let text = output_text(&output);
+    assert!(
+        text.contains("Copied keys: API_KEY, AWS_ACCESS_KEY_ID"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("Skipped keys: AWS_SECRET, DB_PASSWORD"),
+        "{}",
+        text
+    );
+
+    let output = skit(
+        dir,
+        &["--no-input", "-s", "contractor", "keys"],
+        &[("SKIT_SAFEKEY", COPY_PASSWORD)],
+    );
+    assert_success(&output);
+    let keys = String::from_utf8_lossy(&output.stdout);
+    assert!(keys.contains("AWS_ACCESS_KEY_ID"));
+    assert!(!keys.contains("DB_PASSWORD") && !keys.contains("AWS_SECRET"));
+
+    let output = skit(
+        dir,
+        &["--no-input", "-s", "contractor", "cat", "API_KEY"],
+        &[("SKIT_SAFEKEY", COPY_PASSWORD)],
+    );
+    assert_success(&output);
+    assert_eq!(output.stdout, b"api");
+}
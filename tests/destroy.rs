@@ -0,0 +1,76 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Destroy.Pass-123";
+
+#[test]
+fn test_destroy_removes_safe_and_remembered_key() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let safe_path = dir.join(".env.safe");
+    let keys_dir = dir.join(".config").join("skit").join("keys");
+
+    assert_success(&skit(
+        dir,
+        &[
+            "--no-input",
+            "init",
+            "--remember",
+            "--password-env",
+            "INIT_PW",
+        ],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    let key_files = || {
+        std::fs::read_dir(&keys_dir)
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .path()
+                    .extension()
+                    .is_some_and(|ext| ext == "key")
+            })
+            .count()
+    };
+    assert_eq!(key_files(), 1);
+
+    // Typed confirmation can't happen without a terminal
+    let output = skit(dir, &["--no-input", "destroy"], &[]);
+    assert!(!output.status.success());
+    assert!(safe_path.exists());
+
+    let output = skit(dir, &["--yes", "destroy", "--keep-safe"], &[]);
+    assert_success(&output);
+    assert!(output_text(&output).contains("Removed key file"));
+    assert_eq!(key_files(), 0);
+    assert!(safe_path.exists());
+
+    let output = skit(dir, &["--yes", "destroy"], &[]);
+    assert_success(&output);
+    assert!(output_text(&output).contains("Removed safe .env.safe"));
+    assert!(!safe_path.exists());
+}
+
+#[test]
+fn test_destroy_refuses_files_that_are_not_safes() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let path = dir.join(".notes.safe");
+    std::fs::write(&path, "just some notes\n").unwrap();
+
+    let output = skit(dir, &["--yes", "-s", "notes", "destroy"], &[]);
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("--force"));
+    assert!(path.exists());
+
+    assert_success(&skit(
+        dir,
+        &["--yes", "-s", "notes", "destroy", "--force"],
+        &[],
+    ));
+    assert!(!path.exists());
+}
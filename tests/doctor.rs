@@ -0,0 +1,52 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Doctor.Pass-123";
+
+#[test]
+fn test_doctor_fails_without_a_safe() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let output = skit(dir.path(), &["--no-input", "doctor"], &[]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let text = output_text(&output);
+    assert!(text.contains("❌"), "{}", text);
+    assert!(text.contains("skit init"), "{}", text);
+}
+
+#[test]
+fn test_doctor_checks_safe_key() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+
+    let output = skit(
+        dir,
+        &["--no-input", "-o", "json", "doctor"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    );
+    assert_success(&output);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["ok"], true);
+    let safekey = report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|check| check["name"] == "SKIT_SAFEKEY")
+        .unwrap();
+    assert_eq!(safekey["status"], "pass");
+
+    let output = skit(
+        dir,
+        &["--no-input", "doctor"],
+        &[("SKIT_SAFEKEY", "Wrong.Pass-123")],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output_text(&output).contains("does not unlock the safe"));
+}
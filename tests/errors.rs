@@ -0,0 +1,91 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Errors.Pass-123";
+
+fn json_error(output: &std::process::Output) -> serde_json::Value {
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|e| panic!("stdout is not JSON ({}): {}", e, output_text(output)));
+    json["error"].clone()
+}
+
+#[test]
+fn test_json_format_reports_errors_as_json() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+
+    let output = skit(dir, &["-o", "json", "print"], &auth);
+    assert_eq!(output.status.code(), Some(2));
+    let error = json_error(&output);
+    assert_eq!(error["kind"], "safe_not_found");
+    assert!(error["message"].as_str().unwrap().contains(".env.safe"));
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    let output = skit(dir, &["-o", "json", "get", "MISSING"], &auth);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(json_error(&output)["kind"], "key_not_found");
+
+    // Other formats keep the human-readable message
+    let output = skit(dir, &["get", "MISSING"], &auth);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(serde_json::from_slice::<serde_json::Value>(&output.stdout).is_err());
+}
+
+#[test]
+fn test_get_exit_codes() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+
+    let output = skit(dir, &["--no-input", "get", "API_KEY"], &auth);
+    assert_eq!(output.status.code(), Some(2), "{}", output_text(&output));
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "API_KEY", "secret"], &auth));
+    assert_success(&skit(dir, &["set", "OTHER_KEY", "other"], &auth));
+
+    let output = skit(dir, &["--no-input", "get", "API_KYE"], &auth);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        output_text(&output).contains("did you mean 'API_KEY'?"),
+        "{}",
+        output_text(&output)
+    );
+
+    let output = skit(
+        dir,
+        &["--no-input", "get", "API_KEY"],
+        &[("SKIT_SAFEKEY", "Wrong.Pass-123")],
+    );
+    assert_eq!(output.status.code(), Some(4));
+
+    // A ciphertext moved under another key no longer decrypts
+    let path = dir.join(".env.safe");
+    let content = std::fs::read_to_string(&path).unwrap();
+    let other = content
+        .lines()
+        .find_map(|line| line.strip_prefix("OTHER_KEY="))
+        .unwrap()
+        .to_string();
+    let tampered: String = content
+        .lines()
+        .map(|line| match line.strip_prefix("API_KEY=") {
+            Some(_) => format!("API_KEY={}\n", other),
+            None => format!("{}\n", line),
+        })
+        .collect();
+    std::fs::write(&path, tampered).unwrap();
+    let output = skit(dir, &["--no-input", "get", "API_KEY"], &auth);
+    assert_eq!(output.status.code(), Some(7), "{}", output_text(&output));
+}
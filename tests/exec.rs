@@ -0,0 +1,160 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{assert_success, output_text, skit};
+use std::path::Path;
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Exec.Filter-123";
+
+/// Create a safe holding plain APP_* keys and one encrypted SECRET
+fn create_safe(dir: &Path) {
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    for (key, value) in [("APP_NAME", "demo"), ("APP_PORT", "8080")] {
+        assert_success(&skit(dir, &["set", key, value, "--plain"], &[]));
+    }
+    assert_success(&skit(
+        dir,
+        &["set", "SECRET", "hunter2"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    ));
+}
+
+const PRINT_ENV: &str = "echo \"[${APP_NAME-unset}|${APP_PORT-unset}|${SECRET-unset}]\"";
+
+#[test]
+fn test_exec_only_glob() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    create_safe(dir.path());
+
+    let output = skit(
+        dir.path(),
+        &[
+            "--no-input",
+            "exec",
+            "--only",
+            "APP_*",
+            "--",
+            "sh",
+            "-c",
+            PRINT_ENV,
+        ],
+        &[],
+    );
+    assert_success(&output);
+    assert!(output_text(&output).contains("[demo|8080|unset]"));
+}
+
+#[test]
+fn test_exec_exclude_skips_password() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    create_safe(dir.path());
+
+    // No SKIT_SAFEKEY and --no-input: only succeeds if the encrypted key needs no password
+    let output = skit(
+        dir.path(),
+        &[
+            "--no-input",
+            "exec",
+            "--exclude",
+            "SECRET,APP_PORT",
+            "--",
+            "sh",
+            "-c",
+            PRINT_ENV,
+        ],
+        &[],
+    );
+    assert_success(&output);
+    assert!(output_text(&output).contains("[demo|unset|unset]"));
+}
+
+#[test]
+fn test_exec_only_warns_about_unknown_keys() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    create_safe(dir.path());
+
+    let output = skit(
+        dir.path(),
+        &[
+            "--no-input",
+            "exec",
+            "--only",
+            "SECRET,MISSING",
+            "--",
+            "sh",
+            "-c",
+            PRINT_ENV,
+        ],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    );
+    assert_success(&output);
+    let text = output_text(&output);
+    assert!(text.contains("[unset|unset|hunter2]"));
+    assert!(text.contains("MISSING"));
+}
+
+#[test]
+fn test_exec_prefix_strip() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    create_safe(dir.path());
+
+    // APP_NAME becomes NAME; SECRET is filtered out, so no password is needed
+    let output = skit(
+        dir.path(),
+        &[
+            "--no-input",
+            "exec",
+            "--prefix",
+            "APP_",
+            "--strip-prefix",
+            "--",
+            "sh",
+            "-c",
+            "echo \"[${NAME-unset}|${APP_NAME-unset}|${SECRET-unset}]\"",
+        ],
+        &[],
+    );
+    assert_success(&output);
+    assert!(output_text(&output).contains("[demo|unset|unset]"));
+}
+
+#[test]
+fn test_exec_isolated_drops_host_variables() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    create_safe(dir.path());
+
+    // PATH is not kept, yet `env` is still found on skit's own PATH
+    let output = skit(
+        dir.path(),
+        &[
+            "--no-input",
+            "exec",
+            "--exclude",
+            "SECRET",
+            "--isolated",
+            "--keep",
+            "LANG",
+            "--env",
+            "EXTRA=1",
+            "--",
+            "env",
+        ],
+        &[("HOST_VAR", "leaked"), ("LANG", "C")],
+    );
+    assert_success(&output);
+    let mut vars: Vec<&str> = std::str::from_utf8(&output.stdout)
+        .unwrap()
+        .lines()
+        .collect();
+    vars.sort();
+    assert_eq!(
+        vars,
+        ["APP_NAME=demo", "APP_PORT=8080", "EXTRA=1", "LANG=C"]
+    );
+}
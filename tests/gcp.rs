@@ -0,0 +1,84 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{assert_success, output_text, skit};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Gcp.Pass-123";
+
+/// A stand-in for `gcloud` that lists two secrets and prints a fixed value for each
+fn fake_gcloud(dir: &Path) -> String {
+    let script = r#"#!/bin/sh
+if [ "$2" = "list" ]; then
+  echo '[{"name": "projects/1/secrets/app-API_KEY"}, {"name": "projects/1/secrets/app-db-url"}, {"name": "projects/1/secrets/other"}]'
+else
+  printf 'value-of-%s' "$6"
+fi
+"#;
+    let path = dir.join("gcloud");
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_gcp_pull_stores_encrypted_secrets_and_project() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let gcloud = fake_gcloud(dir);
+    let envs = [("SKIT_SAFEKEY", PASSWORD), ("SKIT_GCLOUD", gcloud.as_str())];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "SKIT_SAFEKEY"],
+        &envs,
+    ));
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "gcp",
+            "pull",
+            "--project",
+            "my-project",
+            "--prefix",
+            "app-",
+        ],
+        &envs,
+    );
+    assert_success(&output);
+    assert!(output_text(&output).contains("Added: 2"));
+
+    let stored = std::fs::read_to_string(dir.join(".env.safe")).unwrap();
+    assert!(stored.contains("#@GCP_PROJECT=my-project\n"), "{}", stored);
+    assert!(stored.contains("API_KEY=ENC~"), "{}", stored);
+    assert!(!stored.contains("other"), "{}", stored);
+
+    let output = skit(dir, &["--no-input", "get", "db_url"], &envs);
+    assert_success(&output);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).ends_with("\nvalue-of-app-db-url\n"),
+        "{}",
+        output_text(&output)
+    );
+}
+
+#[test]
+fn test_gcp_pull_needs_a_project() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let gcloud = fake_gcloud(dir);
+    let envs = [("SKIT_SAFEKEY", PASSWORD), ("SKIT_GCLOUD", gcloud.as_str())];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "SKIT_SAFEKEY"],
+        &envs,
+    ));
+    let output = skit(dir, &["--no-input", "gcp", "pull", "--dry-run"], &envs);
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("--project"));
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::{assert_success, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "History.Pass-123";
+
+/// Actions and keys from `skit -o json history`, oldest first
+fn history(dir: &std::path::Path, args: &[&str]) -> Vec<(String, Option<String>)> {
+    let mut full_args = vec!["-o", "json", "history"];
+    full_args.extend_from_slice(args);
+    let output = skit(dir, &full_args, &[]);
+    assert_success(&output);
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            (
+                e["action"].as_str().unwrap().to_string(),
+                e["key"].as_str().map(|k| k.to_string()),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_history_records_changes_without_values() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_URL", "first-value", "--plain"],
+        &[],
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_URL", "second-value", "--plain"],
+        &[],
+    ));
+    assert_success(&skit(dir, &["set", "DB_HOST", "localhost", "--plain"], &[]));
+    assert_success(&skit(dir, &["rm", "DB_HOST"], &[]));
+
+    let key = |k: &str| Some(k.to_string());
+    assert_eq!(
+        history(dir, &[]),
+        vec![
+            ("init".to_string(), None),
+            ("set".to_string(), key("API_URL")),
+            ("set".to_string(), key("API_URL")),
+            ("set".to_string(), key("DB_HOST")),
+            ("rm".to_string(), key("DB_HOST")),
+        ]
+    );
+    assert_eq!(
+        history(dir, &["--key", "DB_HOST"]),
+        vec![
+            ("set".to_string(), key("DB_HOST")),
+            ("rm".to_string(), key("DB_HOST")),
+        ]
+    );
+    assert!(history(dir, &["--since", "2999-01-01"]).is_empty());
+
+    // The log never contains secret values
+    let audit_dir = dir.join(".config").join("skit").join("audit");
+    for entry in std::fs::read_dir(audit_dir).unwrap() {
+        let log = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        assert!(!log.contains("first-value") && !log.contains("localhost"));
+    }
+}
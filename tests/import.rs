@@ -0,0 +1,223 @@
+mod common;
+
+use common::{assert_success, output_text, skit, skit_with_stdin};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Import.Stdin-123";
+
+#[test]
+fn test_import_from_stdin() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    let output = skit_with_stdin(
+        dir,
+        &[
+            "--no-input",
+            "import",
+            "-f",
+            "-",
+            "--password-env",
+            "IMPORT_PW",
+            "--plain-keys",
+            "DB_HOST",
+        ],
+        &[("IMPORT_PW", PASSWORD)],
+        r#"{"API_KEY": "secret", "db": {"host": "localhost"}}"#,
+    );
+    assert_success(&output);
+
+    let output = skit(
+        dir,
+        &["--no-input", "get", "API_KEY"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    );
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().last(), Some("secret"));
+
+    // Plain keys need no password
+    let output = skit(dir, &["--no-input", "get", "DB_HOST"], &[]);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().last(), Some("localhost"));
+}
+
+#[test]
+fn test_import_into_existing_safe() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let env = [("IMPORT_PW", PASSWORD)];
+
+    std::fs::write(dir.join("first.env"), "API_KEY=old\nDB_HOST=db1\n").unwrap();
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "import",
+            "-f",
+            "first.env",
+            "--password-env",
+            "IMPORT_PW",
+        ],
+        &env,
+    );
+    assert_success(&output);
+
+    // A plain import refuses to touch the safe and points at --into
+    std::fs::write(dir.join("second.env"), "API_KEY=new\nREDIS_URL=redis\n").unwrap();
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "import",
+            "-f",
+            "second.env",
+            "--password-env",
+            "IMPORT_PW",
+        ],
+        &env,
+    );
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("--into"));
+
+    // Without --overwrite and with --no-input, existing keys are kept
+    let output = skit(
+        dir,
+        &["--no-input", "import", "--into", "-f", "second.env"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    );
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Added: 1, Updated: 0, Skipped: 1"),
+        "{}",
+        stdout
+    );
+
+    let output = skit(
+        dir,
+        &["--no-input", "get", "API_KEY"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    );
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().last(), Some("old"));
+
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "import",
+            "--merge",
+            "--overwrite",
+            "-f",
+            "second.env",
+        ],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    );
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Added: 0, Updated: 2, Skipped: 0"),
+        "{}",
+        stdout
+    );
+
+    for (key, expected) in [
+        ("API_KEY", "new"),
+        ("REDIS_URL", "redis"),
+        ("DB_HOST", "db1"),
+    ] {
+        let output = skit(
+            dir,
+            &["--no-input", "get", key],
+            &[("SKIT_SAFEKEY", PASSWORD)],
+        );
+        assert_success(&output);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.lines().last(), Some(expected));
+    }
+}
+
+#[test]
+fn test_import_keeps_source_order() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    std::fs::write(dir.join(".env"), "ZETA=1\nALPHA=2\nMIKE=3\n").unwrap();
+
+    assert_success(&skit(
+        dir,
+        &[
+            "--no-input",
+            "import",
+            "-f",
+            ".env",
+            "--password-env",
+            "IMPORT_PW",
+            "--plain-keys",
+            "ZETA,ALPHA,MIKE",
+        ],
+        &[("IMPORT_PW", PASSWORD)],
+    ));
+
+    let content = std::fs::read_to_string(dir.join(".env.safe")).unwrap();
+    let keys: Vec<&str> = content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split('=').next())
+        .collect();
+    assert_eq!(keys, ["ZETA", "ALPHA", "MIKE"]);
+}
+
+#[test]
+fn test_export_file_round_trips_through_import() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+    let values = [
+        ("MULTI", "line one\nline two"),
+        ("PADDED", "  padded  "),
+        ("HASH", "value #not-a-comment"),
+        ("QUOTED", "\"already quoted\""),
+    ];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "SKIT_SAFEKEY"],
+        &auth,
+    ));
+    for (key, value) in values {
+        assert_success(&skit(dir, &["set", key, value], &auth));
+    }
+    assert_success(&skit(
+        dir,
+        &["--no-input", "export", "--file", "out.env"],
+        &auth,
+    ));
+
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "-s",
+            "./copy.env.safe",
+            "import",
+            "-f",
+            "out.env",
+            "--password-env",
+            "SKIT_SAFEKEY",
+        ],
+        &auth,
+    );
+    assert_success(&output);
+    for (key, value) in values {
+        let output = skit(
+            dir,
+            &["--no-input", "-s", "./copy.env.safe", "cat", key],
+            &auth,
+        );
+        assert_success(&output);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), value, "{}", key);
+    }
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{assert_success, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Init.Pass-123";
+
+#[test]
+fn test_init_from_template_creates_empty_encrypted_keys() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    std::fs::write(dir.join("keys.txt"), "DATABASE_URL\nAPI_KEY\n").unwrap();
+
+    assert_success(&skit(
+        dir,
+        &[
+            "--no-input",
+            "init",
+            "--password-env",
+            "INIT_PW",
+            "--from-template",
+            "keys.txt",
+        ],
+        &[("INIT_PW", PASSWORD)],
+    ));
+
+    let output = skit(
+        dir,
+        &["--no-input", "-o", "json", "print"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    );
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let items = json["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    for item in items {
+        assert_eq!(item["value"], "");
+    }
+    let stored = std::fs::read_to_string(dir.join(".env.safe")).unwrap();
+    assert!(stored.contains("API_KEY=ENC~"), "{}", stored);
+}
+
+#[test]
+fn test_init_from_template_rejects_invalid_keys() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    std::fs::write(dir.join("keys.txt"), "GOOD\nBAD-KEY\n").unwrap();
+
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "init",
+            "--password-env",
+            "INIT_PW",
+            "--from-template",
+            "keys.txt",
+        ],
+        &[("INIT_PW", PASSWORD)],
+    );
+    assert!(!output.status.success());
+    assert!(!dir.join(".env.safe").exists());
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Keys.Pass-123";
+
+#[test]
+fn test_keys_size_without_password() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_KEY", "secret"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "PORT", "3000", "--plain"], &[]));
+
+    // No password is available, so sizes come from what is stored
+    let output = skit(dir, &["--no-input", "-o", "json", "keys", "--size"], &[]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let items = json["keys"].as_array().unwrap();
+    let size = |key: &str| {
+        items.iter().find(|item| item["key"] == key).unwrap()["size"]
+            .as_u64()
+            .unwrap()
+    };
+    assert_eq!(size("PORT"), 4);
+    assert!(size("API_KEY") > "secret".len() as u64);
+
+    let output = skit(dir, &["--no-input", "keys", "--values-length"], &[]);
+    assert_success(&output);
+    assert!(output_text(&output).contains("Size"));
+
+    let output = skit(dir, &["--no-input", "-o", "json", "keys"], &[]);
+    assert!(!output_text(&output).contains("\"size\""));
+}
+
+#[test]
+fn test_keys_env_format_and_count() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+
+    // An empty safe prints no lines and zero counts
+    let output = skit(dir, &["--no-input", "-o", "env", "keys"], &[]);
+    assert_success(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    let output = skit(dir, &["--no-input", "-o", "json", "keys", "--count"], &[]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"total": 0, "encrypted": 0, "plain": 0})
+    );
+
+    assert_success(&skit(
+        dir,
+        &["set", "API_KEY", "secret"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "PORT", "3000", "--plain"], &[]));
+
+    let output = skit(dir, &["--no-input", "-o", "env", "keys"], &[]);
+    assert_success(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "API_KEY\nPORT\n");
+
+    let output = skit(dir, &["--no-input", "-o", "env", "keys", "--types"], &[]);
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "API_KEY\tENC\nPORT\tPLAIN\n"
+    );
+
+    let output = skit(dir, &["--no-input", "-o", "env", "keys", "--count"], &[]);
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "TOTAL=2\nENCRYPTED=1\nPLAIN=1\n"
+    );
+
+    let output = skit(dir, &["--no-input", "keys", "--count"], &[]);
+    assert_success(&output);
+    assert!(output_text(&output).contains("Total: 2, Encrypted: 1, Plain: 1"));
+
+    let output = skit(dir, &["--no-input", "-o", "json", "keys"], &[]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["keys"].as_array().unwrap().len(), 2);
+    assert_eq!(json["summary"]["encrypted"], 1);
+}
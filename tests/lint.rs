@@ -0,0 +1,26 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Lint.Pass-123";
+
+#[test]
+fn test_lint_errors_exit_one_with_clean_json() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "SKIT_SAFEKEY"],
+        &auth,
+    ));
+    assert_success(&skit(dir, &["set", "API_KEY", "changeme"], &auth));
+
+    let output = skit(dir, &["--no-input", "-o", "json", "lint"], &auth);
+    assert_eq!(output.status.code(), Some(1), "{}", output_text(&output));
+    // Nothing follows the findings on stdout, so it still parses
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["findings"][0]["rule"], "placeholder");
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const INITIAL_PASSWORD: &str = "Initial.Pass-123";
+const ROTATED_PASSWORD: &str = "Rotated.Pass-456";
+const COPY_PASSWORD: &str = "Copied.Pass-789";
+
+#[test]
+fn test_init_set_rotate_copy_without_tty() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    let output = skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", INITIAL_PASSWORD)],
+    );
+    assert_success(&output);
+
+    let output = skit(
+        dir,
+        &["--no-input", "set", "API_KEY", "secret-value"],
+        &[("SKIT_SAFEKEY", INITIAL_PASSWORD)],
+    );
+    assert_success(&output);
+
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "--yes",
+            "rotate",
+            "--current-password-env",
+            "OLD_PW",
+            "--new-password-env",
+            "NEW_PW",
+        ],
+        &[("OLD_PW", INITIAL_PASSWORD), ("NEW_PW", ROTATED_PASSWORD)],
+    );
+    assert_success(&output);
+
+    let password_file = dir.join("copy.pw");
+    std::fs::write(&password_file, format!("{}\n", COPY_PASSWORD)).unwrap();
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "copy",
+            "backup",
+            "--password-file",
+            password_file.to_str().unwrap(),
+        ],
+        &[("SKIT_SAFEKEY", ROTATED_PASSWORD)],
+    );
+    assert_success(&output);
+
+    let output = skit(
+        dir,
+        &["--no-input", "-s", "backup", "get", "API_KEY"],
+        &[("SKIT_SAFEKEY", COPY_PASSWORD)],
+    );
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().last(), Some("secret-value"));
+}
+
+#[test]
+fn test_missing_input_fails_fast() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    // CI=true implies --no-input
+    let output = skit(dir, &["init"], &[("CI", "true")]);
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("--password-env"));
+    assert!(!dir.join(".env.safe").exists());
+
+    let output = skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", INITIAL_PASSWORD)],
+    );
+    assert_success(&output);
+
+    let output = skit(
+        dir,
+        &["--no-input", "rotate", "--new-password-env", "NEW_PW"],
+        &[
+            ("SKIT_SAFEKEY", INITIAL_PASSWORD),
+            ("NEW_PW", ROTATED_PASSWORD),
+        ],
+    );
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("--yes"));
+
+    let output = skit(dir, &["--no-input", "set", "API_KEY", "value"], &[]);
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("SKIT_SAFEKEY"));
+}
+
+#[test]
+fn test_global_password_file() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", INITIAL_PASSWORD)],
+    ));
+
+    // Trailing whitespace, as left by `echo` or a mounted secret, is ignored
+    let password_file = dir.join("safe.pw");
+    std::fs::write(&password_file, format!("{}\n", INITIAL_PASSWORD)).unwrap();
+    let password_file = password_file.to_str().unwrap();
+    assert_success(&skit(
+        dir,
+        &[
+            "--no-input",
+            "--password-file",
+            password_file,
+            "set",
+            "API_KEY",
+            "from-file",
+        ],
+        &[],
+    ));
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "--password-file",
+            password_file,
+            "get",
+            "API_KEY",
+        ],
+        &[],
+    );
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().last(), Some("from-file"));
+
+    // A wrong password file is fatal even when SKIT_SAFEKEY is correct
+    let wrong_file = dir.join("wrong.pw");
+    std::fs::write(&wrong_file, ROTATED_PASSWORD).unwrap();
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "--password-file",
+            wrong_file.to_str().unwrap(),
+            "get",
+            "API_KEY",
+        ],
+        &[("SKIT_SAFEKEY", INITIAL_PASSWORD)],
+    );
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("wrong.pw"));
+}
+
+#[test]
+fn test_init_and_rotate_with_passphrase() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    let generated = |output: &std::process::Output| -> String {
+        let text = output_text(output);
+        let line = text
+            .lines()
+            .find(|line| line.contains("keep this safe!): "))
+            .expect("passphrase should be printed");
+        line.rsplit(' ').next().unwrap().to_string()
+    };
+
+    let output = skit(dir, &["--no-input", "init", "--passphrase"], &[]);
+    assert_success(&output);
+    let passphrase = generated(&output);
+
+    let output = skit(
+        dir,
+        &["--no-input", "set", "API_KEY", "secret-value"],
+        &[("SKIT_SAFEKEY", passphrase.as_str())],
+    );
+    assert_success(&output);
+
+    let output = skit(
+        dir,
+        &["--no-input", "--yes", "rotate", "--passphrase"],
+        &[("SKIT_SAFEKEY", passphrase.as_str())],
+    );
+    assert_success(&output);
+    let rotated = generated(&output);
+    assert_ne!(rotated, passphrase);
+
+    let output = skit(
+        dir,
+        &["--no-input", "get", "API_KEY"],
+        &[("SKIT_SAFEKEY", rotated.as_str())],
+    );
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().last(), Some("secret-value"));
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const OLD_PASSWORD: &str = "Passwd.Old-123";
+const NEW_PASSWORD: &str = "Passwd.New-456";
+
+#[test]
+fn test_passwd_from_files_without_prompts() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "OLD_PW"],
+        &[("OLD_PW", OLD_PASSWORD)],
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_KEY", "secret"],
+        &[("SKIT_SAFEKEY", OLD_PASSWORD)],
+    ));
+
+    let old_file = dir.join("old.pw");
+    let new_file = dir.join("new.pw");
+    std::fs::write(&old_file, OLD_PASSWORD).unwrap();
+    std::fs::write(&new_file, format!("{}\n", NEW_PASSWORD)).unwrap();
+
+    // No --yes needed: passwd never asks for confirmation
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "--password-file",
+            old_file.to_str().unwrap(),
+            "passwd",
+            "--new-password-file",
+            new_file.to_str().unwrap(),
+        ],
+        &[],
+    );
+    assert_success(&output);
+
+    let output = skit(
+        dir,
+        &["--no-input", "get", "API_KEY"],
+        &[("SKIT_SAFEKEY", NEW_PASSWORD)],
+    );
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().last(), Some("secret"));
+
+    let output = skit(
+        dir,
+        &["--no-input", "get", "API_KEY"],
+        &[("SKIT_SAFEKEY", OLD_PASSWORD)],
+    );
+    assert!(!output.status.success(), "{}", output_text(&output));
+}
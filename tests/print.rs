@@ -0,0 +1,154 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Print.Pass-123";
+
+#[test]
+fn test_print_raw_shows_ciphertext_without_password() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_KEY", "secret"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    ));
+
+    // Runs without SKIT_SAFEKEY; nothing is decrypted
+    let output = skit(dir, &["--no-input", "-o", "json", "print", "--raw"], &[]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let value = json["items"][0]["value"].as_str().unwrap();
+    assert!(value.starts_with("ENC~"), "{}", value);
+
+    let stored = std::fs::read_to_string(dir.join(".env.safe")).unwrap();
+    assert!(stored.contains(&format!("API_KEY={}", value)));
+
+    // A k8s Secret of ciphertext would be useless to the cluster
+    let output = skit(
+        dir,
+        &["--no-input", "-o", "k8s-secret", "print", "--raw"],
+        &[],
+    );
+    assert!(!output.status.success());
+    let text = output_text(&output);
+    assert!(
+        text.contains("--raw") && !text.contains("kind: Secret"),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn test_cat_prints_without_newline_and_exits_3_when_missing() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "API_KEY", "secret"], &auth));
+
+    let output = skit(dir, &["--no-input", "cat", "API_KEY"], &auth);
+    assert_success(&output);
+    assert_eq!(output.stdout, b"secret");
+
+    let output = skit(dir, &["--no-input", "cat", "MISSING"], &auth);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("MISSING"));
+
+    // JSON mode gets the same error object as every other command
+    let output = skit(dir, &["--no-input", "-o", "json", "cat", "MISSING"], &auth);
+    assert_eq!(output.status.code(), Some(3));
+    let error: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a JSON error");
+    assert_eq!(error["error"]["kind"], "key_not_found");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cat_stays_byte_exact_with_an_open_keys_dir() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &[
+            "--no-input",
+            "init",
+            "--remember",
+            "--password-env",
+            "INIT_PW",
+        ],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "API_KEY", "secret"], &[]));
+
+    // Keys directories created before permissions were enforced are 0755
+    let keys_dir = dir.join(".config").join("skit").join("keys");
+    std::fs::set_permissions(&keys_dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = skit(dir, &["--no-input", "cat", "API_KEY"], &[]);
+    assert_success(&output);
+    assert_eq!(output.stdout, b"secret");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("accessible to other users"));
+}
+
+#[test]
+fn test_sort_orders_for_print_keys_and_export() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    for key in ["ZETA", "ALPHA", "MIKE"] {
+        assert_success(&skit(dir, &["set", key, "v", "--plain"], &[]));
+    }
+
+    let keys_in = |args: &[&str]| -> Vec<String> {
+        let output = skit(dir, args, &[]);
+        assert_success(&output);
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split('=').next())
+            .map(str::to_string)
+            .collect()
+    };
+
+    assert_eq!(keys_in(&["-o", "env", "print"]), ["ALPHA", "MIKE", "ZETA"]);
+    assert_eq!(
+        keys_in(&["-o", "env", "print", "--sort", "desc"]),
+        ["ZETA", "MIKE", "ALPHA"]
+    );
+    assert_eq!(
+        keys_in(&["export", "--sort", "insertion"]),
+        ["ZETA", "ALPHA", "MIKE"]
+    );
+
+    let output = skit(dir, &["-o", "json", "keys", "--sort", "insertion"], &[]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let keys: Vec<&str> = json["keys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["key"].as_str().unwrap())
+        .collect();
+    assert_eq!(keys, ["ZETA", "ALPHA", "MIKE"]);
+}
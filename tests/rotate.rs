@@ -0,0 +1,125 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const OLD_PASSWORD: &str = "Rotate.Old-123";
+const NEW_PASSWORD: &str = "Rotate.New-456";
+
+#[test]
+fn test_rotate_backup_keeps_old_safe() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let passwords = [("OLD_PW", OLD_PASSWORD), ("NEW_PW", NEW_PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "OLD_PW"],
+        &passwords,
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_KEY", "secret"],
+        &[("SKIT_SAFEKEY", OLD_PASSWORD)],
+    ));
+    let original = std::fs::read_to_string(dir.join(".env.safe")).unwrap();
+
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "--yes",
+            "rotate",
+            "--backup",
+            "--current-password-env",
+            "OLD_PW",
+            "--new-password-env",
+            "NEW_PW",
+        ],
+        &passwords,
+    );
+    assert_success(&output);
+
+    let backups: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.starts_with(".env.safe.pre-rotate.") && name.ends_with(".bak"))
+        .collect();
+    assert_eq!(backups.len(), 1, "{}", output_text(&output));
+    assert_eq!(
+        std::fs::read_to_string(dir.join(&backups[0])).unwrap(),
+        original
+    );
+
+    // The live safe takes the new password, the backup still takes the old one
+    let output = skit(dir, &["get", "API_KEY"], &[("SKIT_SAFEKEY", NEW_PASSWORD)]);
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).lines().last(),
+        Some("secret")
+    );
+    let output = skit(
+        dir,
+        &["-s", &format!("./{}", backups[0]), "get", "API_KEY"],
+        &[("SKIT_SAFEKEY", OLD_PASSWORD)],
+    );
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).lines().last(),
+        Some("secret")
+    );
+}
+
+#[test]
+fn test_rotate_cipher_re_encrypts_every_secret() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let passwords = [("OLD_PW", OLD_PASSWORD), ("NEW_PW", NEW_PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "OLD_PW"],
+        &passwords,
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_KEY", "secret"],
+        &[("SKIT_SAFEKEY", OLD_PASSWORD)],
+    ));
+    let before = std::fs::read_to_string(dir.join(".env.safe")).unwrap();
+
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "--yes",
+            "rotate",
+            "--cipher",
+            "xchacha20",
+            "--current-password-env",
+            "OLD_PW",
+            "--new-password-env",
+            "NEW_PW",
+        ],
+        &passwords,
+    );
+    assert_success(&output);
+    assert!(output_text(&output).contains("Re-encrypted: API_KEY"));
+
+    let after = std::fs::read_to_string(dir.join(".env.safe")).unwrap();
+    assert!(after.contains("#@CIPHER=xchacha20\n"), "{}", after);
+    let value = |safe: &str| {
+        safe.lines()
+            .find_map(|line| line.strip_prefix("API_KEY="))
+            .unwrap()
+            .to_string()
+    };
+    assert_ne!(value(&before), value(&after));
+
+    let output = skit(dir, &["get", "API_KEY"], &[("SKIT_SAFEKEY", NEW_PASSWORD)]);
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).lines().last(),
+        Some("secret")
+    );
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Schema.Pass-123";
+
+#[test]
+fn test_schema_export_and_check() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "PORT", "8080"], &auth));
+    assert_success(&skit(dir, &["set", "API_KEY", "sk-live-1"], &auth));
+
+    // Exporting reads no values, so it needs no password
+    let output = skit(dir, &["--no-input", "schema"], &[]);
+    assert_success(&output);
+    let exported = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(exported.lines().any(|line| line == "PORT"), "{}", exported);
+    assert!(
+        exported.lines().any(|line| line == "API_KEY"),
+        "{}",
+        exported
+    );
+
+    std::fs::write(dir.join("schema.txt"), "PORT [0-9]+\nAPI_KEY sk-live-.+\n").unwrap();
+    assert_success(&skit(
+        dir,
+        &["--no-input", "schema", "--check", "schema.txt"],
+        &auth,
+    ));
+
+    std::fs::write(
+        dir.join("schema.txt"),
+        "PORT [0-9]+\nAPI_KEY sk-test-.+\nSENTRY_DSN\n",
+    )
+    .unwrap();
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "-o",
+            "json",
+            "schema",
+            "--check",
+            "schema.txt",
+        ],
+        &auth,
+    );
+    assert_eq!(output.status.code(), Some(1), "{}", output_text(&output));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["ok"], false);
+    let violations: Vec<(&str, &str)> = json["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| (v["key"].as_str().unwrap(), v["rule"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        violations,
+        [("API_KEY", "pattern"), ("SENTRY_DSN", "missing")]
+    );
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("sk-live-1"));
+}
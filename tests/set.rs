@@ -0,0 +1,108 @@
+mod common;
+
+use common::{assert_success, output_text, skit, skit_with_stdin};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Set.Pass-123";
+
+#[test]
+fn test_set_if_not_exists_leaves_safe_untouched() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let safe_path = dir.join(".env.safe");
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(
+        dir,
+        &["set", "API_KEY", "original"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    ));
+    let before = std::fs::read_to_string(&safe_path).unwrap();
+
+    // No password is available, so this only succeeds if nothing is encrypted
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "set",
+            "API_KEY",
+            "replaced",
+            "--if-not-exists",
+        ],
+        &[],
+    );
+    assert_success(&output);
+    assert!(output_text(&output).contains("Key 'API_KEY' already exists, skipping"));
+    assert_eq!(std::fs::read_to_string(&safe_path).unwrap(), before);
+
+    // New keys are still written
+    assert_success(&skit(
+        dir,
+        &["set", "PORT", "3000", "--plain", "--if-not-exists"],
+        &[],
+    ));
+    assert!(
+        std::fs::read_to_string(&safe_path)
+            .unwrap()
+            .contains("PORT=3000")
+    );
+}
+
+#[test]
+fn test_set_stable_keeps_unchanged_ciphertext() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let safe_path = dir.join(".env.safe");
+    let env = [("SKIT_SAFEKEY", PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "API_KEY", "original"], &env));
+    let before = std::fs::read_to_string(&safe_path).unwrap();
+
+    let output = skit(dir, &["set", "API_KEY", "original", "--stable"], &env);
+    assert_success(&output);
+    assert!(output_text(&output).contains("Key 'API_KEY' is unchanged, skipping"));
+    assert_eq!(std::fs::read_to_string(&safe_path).unwrap(), before);
+
+    assert_success(&skit(dir, &["set", "API_KEY", "rotated", "--stable"], &env));
+    assert_ne!(std::fs::read_to_string(&safe_path).unwrap(), before);
+}
+
+#[test]
+fn test_set_value_from_stdin_or_prompt() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let env = [("SKIT_SAFEKEY", PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+
+    let value = "a=b c = d  ";
+    for plain in [false, true] {
+        let mut args = vec!["set", "TOKEN", "--stdin"];
+        if plain {
+            args.push("--plain");
+        }
+        assert_success(&skit_with_stdin(dir, &args, &env, &format!("{}\n", value)));
+
+        let output = skit(dir, &["--no-input", "cat", "TOKEN"], &env);
+        assert_success(&output);
+        assert_eq!(output.stdout, value.as_bytes());
+    }
+
+    // Without a value there is nothing to prompt with under --no-input
+    let output = skit(dir, &["--no-input", "set", "OTHER"], &env);
+    assert!(!output.status.success());
+    assert!(output_text(&output).contains("--stdin"));
+}
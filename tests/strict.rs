@@ -0,0 +1,109 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use std::path::Path;
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Strict.Pass-123";
+const AUTH: [(&str, &str); 1] = [("SKIT_SAFEKEY", PASSWORD)];
+
+/// Create a safe where GOOD decrypts and BAD holds GOOD's ciphertext, which is bound to GOOD
+fn create_safe(dir: &Path) {
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "GOOD", "fine"], &AUTH));
+    assert_success(&skit(dir, &["set", "BAD", "broken"], &AUTH));
+
+    let path = dir.join(".env.safe");
+    let content = std::fs::read_to_string(&path).unwrap();
+    let good = content
+        .lines()
+        .find_map(|line| line.strip_prefix("GOOD="))
+        .unwrap()
+        .to_string();
+    let tampered: String = content
+        .lines()
+        .map(|line| match line.strip_prefix("BAD=") {
+            Some(_) => format!("BAD={}\n", good),
+            None => format!("{}\n", line),
+        })
+        .collect();
+    std::fs::write(&path, tampered).unwrap();
+}
+
+fn assert_strict_failure(output: &std::process::Output) {
+    assert_eq!(output.status.code(), Some(7), "{}", output_text(output));
+    let text = output_text(output);
+    assert!(text.contains("Failed to decrypt 1 key(s): BAD"), "{}", text);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("fine"));
+}
+
+#[test]
+fn test_print_env_export_strict() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    create_safe(dir);
+
+    let output = skit(dir, &["--no-input", "-o", "json", "print"], &AUTH);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let items = json["items"].as_array().unwrap();
+    assert_eq!(items[0]["key"], "BAD");
+    assert_eq!(items[0]["decrypt_failed"], true);
+    assert_eq!(items[1]["value"], "fine");
+    assert!(items[1].get("decrypt_failed").is_none());
+    assert_strict_failure(&skit(dir, &["--no-input", "print", "--strict"], &AUTH));
+
+    let output = skit(dir, &["--no-input", "env", "--shell", "bash"], &AUTH);
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "export GOOD=fine\n"
+    );
+    assert_strict_failure(&skit(
+        dir,
+        &["--no-input", "env", "--shell", "bash", "--strict"],
+        &AUTH,
+    ));
+
+    let output = skit(dir, &["--no-input", "export"], &AUTH);
+    assert_success(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "GOOD=fine\n");
+    assert_strict_failure(&skit(dir, &["--no-input", "export", "--strict"], &AUTH));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_is_strict_by_default() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    create_safe(dir);
+    let print_env = "echo \"[${GOOD-unset}|${BAD-unset}]\"";
+
+    let output = skit(
+        dir,
+        &["--no-input", "exec", "--", "sh", "-c", print_env],
+        &AUTH,
+    );
+    assert_strict_failure(&output);
+    assert!(!output_text(&output).contains("[fine|"));
+
+    let output = skit(
+        dir,
+        &[
+            "--no-input",
+            "exec",
+            "--no-strict",
+            "--",
+            "sh",
+            "-c",
+            print_env,
+        ],
+        &AUTH,
+    );
+    assert_success(&output);
+    assert!(output_text(&output).contains("[fine|unset]"));
+}
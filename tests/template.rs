@@ -0,0 +1,128 @@
+mod common;
+
+use common::{assert_success, output_text, skit, skit_with_stdin};
+use std::path::Path;
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Template.Pass-123";
+
+fn safe_with_secrets(dir: &Path) {
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    for (key, value) in [("DB_PASSWORD", "s3cr3t"), ("DB_HOST", "db.internal")] {
+        assert_success(&skit(
+            dir,
+            &["set", key, value],
+            &[("SKIT_SAFEKEY", PASSWORD)],
+        ));
+    }
+}
+
+#[test]
+fn test_template_renders_stdin_to_stdout() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    safe_with_secrets(dir);
+
+    let output = skit_with_stdin(
+        dir,
+        &["--no-input", "template", "-"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+        "host={{ DB_HOST }} pass={{DB_PASSWORD}} shell=${DB_HOST} literal=\\{{DB_HOST}}\n",
+    );
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "host=db.internal pass=s3cr3t shell=${DB_HOST} literal={{DB_HOST}}\n"
+    );
+
+    let output = skit_with_stdin(
+        dir,
+        &["--no-input", "template", "--dollar", "-"],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+        "${DB_HOST}:\\${DB_HOST}",
+    );
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "db.internal:${DB_HOST}"
+    );
+}
+
+#[test]
+fn test_template_writes_private_file() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    safe_with_secrets(dir);
+    std::fs::write(dir.join("app.conf.tpl"), "password = \"{{DB_PASSWORD}}\"\n").unwrap();
+
+    assert_success(&skit(
+        dir,
+        &[
+            "--no-input",
+            "template",
+            "app.conf.tpl",
+            "--output",
+            "app.conf",
+        ],
+        &[("SKIT_SAFEKEY", PASSWORD)],
+    ));
+    let rendered = dir.join("app.conf");
+    assert_eq!(
+        std::fs::read_to_string(&rendered).unwrap(),
+        "password = \"s3cr3t\"\n"
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&rendered).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn test_template_unknown_keys() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    safe_with_secrets(dir);
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+    let template = "{{DB_HOST}} {{NOPE}}";
+
+    let output = skit_with_stdin(dir, &["--no-input", "template", "-"], &auth, template);
+    assert_eq!(output.status.code(), Some(3));
+    // Reported once, and only on stderr
+    assert!(output.stdout.is_empty(), "{}", output_text(&output));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("NOPE").count(), 1, "{}", stderr);
+
+    let output = skit_with_stdin(
+        dir,
+        &["--no-input", "template", "--allow-missing", "-"],
+        &auth,
+        template,
+    );
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "db.internal {{NOPE}}"
+    );
+
+    let output = skit_with_stdin(
+        dir,
+        &[
+            "--no-input",
+            "template",
+            "--allow-missing",
+            "--missing",
+            "empty",
+            "-",
+        ],
+        &auth,
+        template,
+    );
+    assert_success(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "db.internal ");
+}
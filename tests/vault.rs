@@ -0,0 +1,87 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{assert_success, output_text, skit};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Vault.Pass-123";
+
+/// A stand-in for `vault` that answers `kv get` for `secret/myapp/dev` only
+fn fake_vault(dir: &Path) -> String {
+    let script = r#"#!/bin/sh
+if [ "$4" = "-mount=secret" ] && [ "$5" = "myapp/dev" ]; then
+  echo '{"data": {"data": {"API_KEY": "from-vault", "PORT": "5432"}, "metadata": {"version": 2}}}'
+else
+  echo "No value found at $4 $5" >&2
+  exit 2
+fi
+"#;
+    let path = dir.join("vault");
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_vault_pull_stores_encrypted_secrets_and_path() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let vault = fake_vault(dir);
+    let envs = [("SKIT_SAFEKEY", PASSWORD), ("SKIT_VAULT", vault.as_str())];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "SKIT_SAFEKEY"],
+        &envs,
+    ));
+    assert_success(&skit(dir, &["set", "PORT", "80"], &envs));
+
+    let output = skit(
+        dir,
+        &["--no-input", "vault", "pull", "myapp/dev", "--no-overwrite"],
+        &envs,
+    );
+    assert_success(&output);
+    assert!(
+        output_text(&output).contains("Added: 1, Updated: 0, Skipped: 1"),
+        "{}",
+        output_text(&output)
+    );
+
+    let stored = std::fs::read_to_string(dir.join(".env.safe")).unwrap();
+    assert!(stored.contains("#@VAULT_PATH=myapp/dev\n"), "{}", stored);
+    assert!(stored.contains("API_KEY=ENC~"), "{}", stored);
+
+    // A re-pull without a path uses the stored one
+    let output = skit(dir, &["--no-input", "vault", "pull"], &envs);
+    assert_success(&output);
+    let output = skit(dir, &["--no-input", "get", "PORT"], &envs);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).lines().last(),
+        Some("5432")
+    );
+}
+
+#[test]
+fn test_vault_pull_reports_vault_errors() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let vault = fake_vault(dir);
+    let envs = [("SKIT_SAFEKEY", PASSWORD), ("SKIT_VAULT", vault.as_str())];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "SKIT_SAFEKEY"],
+        &envs,
+    ));
+    let output = skit(
+        dir,
+        &["--no-input", "vault", "pull", "other/path", "--dry-run"],
+        &envs,
+    );
+    assert_eq!(output.status.code(), Some(5));
+    assert!(output_text(&output).contains("No value found"));
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use common::{assert_success, output_text, skit};
+use tempfile::TempDir;
+
+const PASSWORD: &str = "Verify.Pass-123";
+
+#[test]
+fn test_verify_exit_codes() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+
+    let output = skit(dir, &["--no-input", "verify"], &auth);
+    assert_eq!(output.status.code(), Some(2), "{}", output_text(&output));
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "API_KEY", "secret"], &auth));
+    assert_success(&skit(dir, &["--no-input", "verify"], &auth));
+
+    let output = skit(
+        dir,
+        &["--no-input", "verify"],
+        &[("SKIT_SAFEKEY", "Wrong.Pass-000")],
+    );
+    assert_eq!(output.status.code(), Some(3), "{}", output_text(&output));
+
+    // Damage the stored ciphertext
+    let path = dir.join(".env.safe");
+    let content = std::fs::read_to_string(&path).unwrap();
+    let tampered: String = content
+        .lines()
+        .map(|line| match line.strip_prefix("API_KEY=") {
+            Some(value) => format!("API_KEY={}AAAA\n", &value[..value.len() - 4]),
+            None => format!("{}\n", line),
+        })
+        .collect();
+    assert_ne!(tampered, content);
+    std::fs::write(&path, tampered).unwrap();
+
+    let output = skit(dir, &["--no-input", "-o", "json", "verify"], &auth);
+    assert_eq!(output.status.code(), Some(4), "{}", output_text(&output));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["failed_keys"], serde_json::json!(["API_KEY"]));
+}
+
+#[test]
+fn test_status_fails_on_undecryptable_secret() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "API_KEY", "secret"], &auth));
+
+    let output = skit(dir, &["--no-input", "-o", "json", "status"], &auth);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["ok"], true);
+
+    let path = dir.join(".env.safe");
+    let content = std::fs::read_to_string(&path).unwrap();
+    let tampered: String = content
+        .lines()
+        .map(|line| match line.strip_prefix("API_KEY=") {
+            Some(value) => format!("API_KEY={}AAAA\n", &value[..value.len() - 4]),
+            None => format!("{}\n", line),
+        })
+        .collect();
+    assert_ne!(tampered, content);
+    std::fs::write(&path, tampered).unwrap();
+
+    let output = skit(dir, &["--no-input", "-o", "json", "status"], &auth);
+    assert_eq!(output.status.code(), Some(1), "{}", output_text(&output));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["integrity"]["encrypted_secrets_verified"], false);
+
+    // --fast only checks the password, so the damaged value goes unnoticed
+    let output = skit(
+        dir,
+        &["--no-input", "-o", "json", "status", "--fast"],
+        &auth,
+    );
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["ok"], true);
+    assert!(json["integrity"]["encrypted_secrets_verified"].is_null());
+}
+
+#[test]
+fn test_swapped_ciphertexts_fail_verification() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let auth = [("SKIT_SAFEKEY", PASSWORD)];
+
+    assert_success(&skit(
+        dir,
+        &["--no-input", "init", "--password-env", "INIT_PW"],
+        &[("INIT_PW", PASSWORD)],
+    ));
+    assert_success(&skit(dir, &["set", "PROD_DB_URL", "prod"], &auth));
+    assert_success(&skit(dir, &["set", "STAGING_DB_URL", "staging"], &auth));
+
+    // Move the production ciphertext under the staging key
+    let path = dir.join(".env.safe");
+    let content = std::fs::read_to_string(&path).unwrap();
+    let prod = content
+        .lines()
+        .find_map(|line| line.strip_prefix("PROD_DB_URL="))
+        .unwrap()
+        .to_string();
+    assert!(prod.starts_with("ENC~v3k~"), "{}", prod);
+    let swapped: String = content
+        .lines()
+        .map(|line| match line.strip_prefix("STAGING_DB_URL=") {
+            Some(_) => format!("STAGING_DB_URL={}\n", prod),
+            None => format!("{}\n", line),
+        })
+        .collect();
+    std::fs::write(&path, swapped).unwrap();
+
+    let output = skit(dir, &["--no-input", "-o", "json", "verify"], &auth);
+    assert_eq!(output.status.code(), Some(4), "{}", output_text(&output));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["failed_keys"], serde_json::json!(["STAGING_DB_URL"]));
+}
+
+#[test]
+fn test_status_reports_auth_source_and_stale_key_file() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = dir.path();
+    let password = "Status.Auth-123";
+    assert_success(&skit(
+        dir,
+        &[
+            "--no-input",
+            "init",
+            "--remember",
+            "--password-env",
+            "INIT_PW",
+        ],
+        &[("INIT_PW", password)],
+    ));
+    let status = |envs: &[(&str, &str)]| {
+        let output = skit(dir, &["--no-input", "-o", "json", "status"], envs);
+        assert_success(&output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()["auth"].clone()
+    };
+
+    let auth = status(&[]);
+    assert_eq!(auth["source"], "key_file");
+    assert_eq!(auth["key_file_valid"], true);
+    assert!(auth["key_file"].as_str().unwrap().ends_with(".key"));
+    assert!(auth["key_file_last_used"].is_string());
+
+    // The environment wins, but the saved key is still listed
+    let auth = status(&[("SKIT_SAFEKEY", password)]);
+    assert_eq!(auth["source"], "environment");
+    assert_eq!(auth["key_file_valid"], true);
+
+    let keys_dir = dir.join(".config").join("skit").join("keys");
+    let key_file = std::fs::read_dir(&keys_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "key"))
+        .unwrap();
+    std::fs::write(&key_file, "Stale.Pass-123").unwrap();
+    let auth = status(&[("SKIT_SAFEKEY", password)]);
+    assert_eq!(auth["source"], "environment");
+    assert_eq!(auth["key_file_valid"], false);
+}